@@ -1,10 +1,13 @@
 //! Build script to compile parameter database into binary
 use std::env;
 use std::path::Path;
-//use walkdir::WalkDir;
+use walkdir::WalkDir;
 use std::fs::File;
 use std::io::Write;
 
+const DATABASE_DIR: &str = "database/parameters";
+const PROFILE_DIR: &str = "database/profiles";
+
 fn main() -> std::io::Result<()> {
     let out_dir = env::var("OUT_DIR").unwrap();
     let dest_path = Path::new(&out_dir);
@@ -12,19 +15,53 @@ fn main() -> std::io::Result<()> {
     println!("cargo:rerun-if-changed=database/");
 
     // Walk database directory and compile TOML files into Rust code
-    compile_database(&dest_path)
+    compile_database(dest_path)
+}
+
+/// Collect `(parameter path, file)` pairs for every `file_name` below the
+/// database directory, in a stable order.
+fn collect_files(root: &Path, file_name: &str) -> Vec<(String, String)> {
+    let mut files: Vec<(String, String)> = WalkDir::new(root)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file() && entry.file_name() == file_name)
+        .filter_map(|entry| {
+            let dir = entry.path().parent()?;
+            let name = dir.strip_prefix(root).ok()?.to_str()?.replace('\\', "/");
+            let path = entry.path().canonicalize().ok()?;
+            Some((name, path.to_str()?.to_string()))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn write_table(output: &Path, files: &[(String, String)]) -> std::io::Result<()> {
+    let mut file = File::create(output)?;
+    writeln!(file, "HashMap::from([")?;
+    for (name, path) in files {
+        writeln!(file, "    ({:?}, include_str!({:?})),", name, path)?;
+    }
+    writeln!(file, "])")
 }
 
 fn compile_database(output: &Path) -> std::io::Result<()> {
-    // Implementation to read all TOML files and generate embedded data
-    let parameter_names = output.join("parameter_names.rs");
-    let compiled_db = output.join("compiled_db.rs");
+    let root = Path::new(DATABASE_DIR);
+    let definitions = collect_files(root, "definition.toml");
+    let versions = collect_files(root, "versions.toml");
+
+    let mut parameter_file = File::create(output.join("parameter_names.rs"))?;
+    writeln!(parameter_file, "vec![")?;
+    for (name, _) in &definitions {
+        writeln!(parameter_file, "    {:?},", name)?;
+    }
+    writeln!(parameter_file, "]")?;
 
-    let mut parameter_file = File::create(parameter_names)?;
-    parameter_file.write_all(b"// Getting started\n(vec![\"foo\", \"bar\", \"baz\"]).into_iter().map(|i| i.to_string()).collect()\n")?;
+    write_table(&output.join("compiled_db.rs"), &definitions)?;
+    write_table(&output.join("compiled_versions.rs"), &versions)?;
 
-    let mut db_file = File::create(compiled_db)?;
-    db_file.write_all(b"// Getting started\nHashMap::from([(\"foo\", \"bar\"), (\"baz\", \"quux\")])\n")?;
+    let profiles = collect_files(Path::new(PROFILE_DIR), "profile.toml");
+    write_table(&output.join("compiled_profiles.rs"), &profiles)?;
 
     Ok(())
 }
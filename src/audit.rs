@@ -0,0 +1,108 @@
+//! Security auditing of a command line: parameters whose values weaken the
+//! system, such as `mitigations=off` or `init=/bin/bash`, as described by
+//! the `security_impact` field of their definitions.
+
+use serde::Serialize;
+
+use crate::error::Span;
+use crate::parameter::SecuritySeverity;
+use crate::parser::ParsedParameter;
+use crate::query::glob_match;
+use crate::validators::ValidationResult;
+
+/// What a parser checks beyond the syntax of each parameter.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ValidationProfile {
+    /// Catalog lookup, value syntax and whole command line checks.
+    #[default]
+    Standard,
+    /// The standard checks, plus a finding for each parameter that
+    /// weakens security, of a level following its severity.
+    SecurityAudit,
+    /// The standard checks, plus an informational finding for each
+    /// parameter that changes performance.
+    PerformanceReview,
+}
+
+/// A parameter on a command line that weakens security.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SecurityFinding {
+    pub parameter: String,
+    pub value: Option<String>,
+    pub severity: SecuritySeverity,
+    pub rationale: String,
+    pub span: Span,
+}
+
+impl SecurityFinding {
+    /// The validation result the finding is recorded as in a security audit
+    /// profile: critical findings are errors, low ones informational.
+    pub fn validation_result(&self) -> ValidationResult {
+        let message = format!("Weakens security ({}): {}", self.severity.name(), self.rationale);
+        match self.severity {
+            SecuritySeverity::Low => ValidationResult::Info(message),
+            SecuritySeverity::Medium | SecuritySeverity::High => ValidationResult::Warning(message),
+            SecuritySeverity::Critical => ValidationResult::Error(message),
+        }
+    }
+}
+
+/// The parameters of a command line that weaken security, most severe
+/// first.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SecurityAuditReport {
+    pub cmdline: String,
+    pub findings: Vec<SecurityFinding>,
+}
+
+impl SecurityAuditReport {
+    pub(crate) fn new(cmdline: &str, parameters: &[ParsedParameter]) -> Self {
+        let mut findings = security_findings(parameters);
+        findings.sort_by(|a, b| b.severity.cmp(&a.severity).then(a.span.start.cmp(&b.span.start)));
+        Self {
+            cmdline: cmdline.to_string(),
+            findings,
+        }
+    }
+
+    /// Whether nothing on the command line weakens security.
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// The most severe finding's severity, if there are any.
+    pub fn highest_severity(&self) -> Option<SecuritySeverity> {
+        self.findings.iter().map(|finding| finding.severity).max()
+    }
+
+    /// The number of findings of the given severity.
+    pub fn count(&self, severity: SecuritySeverity) -> usize {
+        self.findings.iter().filter(|finding| finding.severity == severity).count()
+    }
+}
+
+/// The effective parameters among `parameters` with a value that their
+/// definition's `security_impact` describes as weakening security, in
+/// command line order. Flags given without a value match impacts with no
+/// value globs.
+pub(crate) fn security_findings(parameters: &[ParsedParameter]) -> Vec<SecurityFinding> {
+    parameters.iter()
+        .filter(|param| param.effective)
+        .flat_map(|param| {
+            let impacts = param.parameter_def.iter().flat_map(|def| &def.security_impact);
+            let value = param.value.as_deref();
+            impacts
+                .filter(move |impact| match value {
+                    None => impact.when.is_empty(),
+                    Some(value) => impact.when.is_empty() || impact.when.iter().any(|pattern| glob_match(pattern, value)),
+                })
+                .map(move |impact| SecurityFinding {
+                    parameter: param.name.clone(),
+                    value: param.value.clone(),
+                    severity: impact.severity,
+                    rationale: impact.rationale.clone(),
+                    span: param.span.clone(),
+                })
+        })
+        .collect()
+}
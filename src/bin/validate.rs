@@ -1,5 +1,28 @@
-use libkcmdline::KCmdlineError;
+use libkcmdline::{KCmdline, KCmdlineError, ValidationResult};
 
 fn main() -> Result<(), KCmdlineError> {
-    Ok(())
+    let (_parsed, summary) = KCmdline::from_current_system()?;
+
+    for finding in &summary.details {
+        let name = &finding.parameter;
+        match &finding.result {
+            ValidationResult::Valid => {}
+            ValidationResult::Info(msg) => println!("info: {}: {}", name, msg),
+            ValidationResult::Warning(msg) => println!("warning: {}: {}", name, msg),
+            ValidationResult::Error(msg) => println!("error: {}: {}", name, msg),
+            ValidationResult::Unknown(msg) => println!("unknown: {}: {}", name, msg),
+        }
+    }
+
+    println!(
+        "{} parameters: {} valid, {} info, {} warnings, {} errors, {} unknown",
+        summary.total_parameters,
+        summary.valid_count,
+        summary.info_count,
+        summary.warning_count,
+        summary.error_count,
+        summary.unknown_count
+    );
+
+    std::process::exit(summary.exit_code());
 }
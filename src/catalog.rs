@@ -1,12 +1,36 @@
-use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::path::Path;
+use std::sync::OnceLock;
 
+use serde::{Deserialize, Serialize};
+
+use crate::database::{DatabaseLoader, LoadedDatabase, MergePolicy, Provenance};
+use crate::diff::CatalogDiff;
+use crate::error::DatabaseError;
+use crate::export::{export_catalog, ExportFormat};
+use crate::lint::{lint_catalog, LintFinding};
 use crate::parameter::Parameter;
-use crate::query::QueryParameters;
+use crate::query::{QueryCondition, QueryExpr, QueryParameters};
 use crate::probe::SystemProbe;
+use crate::search::TextIndex;
+use crate::trust::TrustPolicy;
+use crate::snapshot::{load_snapshot, save_snapshot, SnapshotData, SnapshotRef};
+use crate::suggest::{fuzzy_score, max_edit_distance, SimilarityIndex, MIN_SUGGESTION_LENGTH};
+use crate::version::KernelVersion;
+
+/// The most suggestions offered for a misspelt parameter name.
+const MAX_SUGGESTIONS: usize = 3;
 
 pub struct ParameterCatalog {
     parameters: HashMap<String, Parameter>,
     subparameter_index: HashMap<String, Vec<String>>, // parent -> children
+    similarity_index: OnceLock<SimilarityIndex>,
+    text_index: OnceLock<TextIndex>,
+    version_info: HashMap<String, VersionInfo>,
+    provenance: HashMap<String, Provenance>,
+    alias_index: HashMap<String, String>, // alias key -> canonical key
 }
 
 #[derive(Debug, Clone)]
@@ -21,17 +45,21 @@ pub struct ParameterInfo {
     pub subparameters: Vec<Parameter>,
     pub applicable: bool,
     pub version_info: VersionInfo,
+    /// The source each field of the definition came from; empty for
+    /// parameters that were not loaded from a database.
+    pub provenance: Provenance,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VendorVersion {
     pub introduced: Option<String>,
     pub commit: Option<String>,
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct VersionInfo {
+    /// The mainline kernel release the parameter first appeared in.
     pub introduced: Option<String>,
     pub commit: Option<String>,
     pub last_modified: Option<String>,
@@ -39,23 +67,449 @@ pub struct VersionInfo {
     pub vendors: HashMap<String, HashMap<String, VendorVersion>>,
 }
 
+pub(crate) const MODULE_KEY_PREFIX: &str = "module/";
+
+/// The catalog key of a loadable module's parameter.
+pub fn module_parameter_key(module: &str, parameter: &str) -> String {
+    format!("{}{}/{}", MODULE_KEY_PREFIX, module.replace('-', "_"), parameter)
+}
+
+impl ParameterTree {
+    fn collect_keys(&self, key: &str, keys: &mut HashSet<String>) {
+        keys.insert(key.to_string());
+        for (child_key, child) in &self.children {
+            child.collect_keys(child_key, keys);
+        }
+    }
+}
+
+impl VersionInfo {
+    pub fn introduced_version(&self) -> Option<KernelVersion> {
+        self.introduced.as_deref().and_then(KernelVersion::parse)
+    }
+}
+
+impl From<LoadedDatabase> for ParameterCatalog {
+    /// Re-key a loaded database from its directory paths (`kernel/pci/nomio`)
+    /// to catalog keys: the name of top-level parameters (`pci`), and the
+    /// parent's key and the name for subparameters (`pci/nomio`). Module
+    /// parameters keep their `module/<module>/<parameter>` path.
+    fn from(database: LoadedDatabase) -> Self {
+        let parents: HashMap<&str, &str> = database.subparameter_index.iter()
+            .flat_map(|(parent, children)| children.iter().map(move |child| (child.as_str(), parent.as_str())))
+            .collect();
+
+        fn catalog_key(
+            path: &str,
+            database: &LoadedDatabase,
+            parents: &HashMap<&str, &str>,
+            keys: &mut HashMap<String, String>,
+        ) -> String {
+            if let Some(key) = keys.get(path) {
+                return key.clone();
+            }
+            let name = &database.parameters[path].name;
+            let key = match parents.get(path) {
+                // Module parameters keep their module/<module>/<parameter> path
+                _ if path.starts_with(MODULE_KEY_PREFIX) => path.to_string(),
+                Some(parent) => format!("{}/{}", catalog_key(parent, database, parents, keys), name),
+                None => name.clone(),
+            };
+            keys.insert(path.to_string(), key.clone());
+            key
+        }
+
+        let mut keys = HashMap::new();
+        for path in database.parameters.keys() {
+            catalog_key(path, &database, &parents, &mut keys);
+        }
+
+        let subparameter_index = database.subparameter_index.iter()
+            .map(|(parent, children)| {
+                (keys[parent].clone(), children.iter().map(|child| keys[child].clone()).collect())
+            })
+            .collect();
+        let version_info = database.version_index.into_iter()
+            .filter_map(|(path, info)| keys.get(&path).map(|key| (key.clone(), info)))
+            .collect();
+        let provenance = database.provenance_index.into_iter()
+            .filter_map(|(path, provenance)| keys.get(&path).map(|key| (key.clone(), provenance)))
+            .collect();
+        let parameters = database.parameters.into_iter()
+            .map(|(path, param)| (keys.remove(&path).unwrap(), param))
+            .collect();
+
+        Self::from_snapshot_data(SnapshotData {
+            parameters,
+            subparameter_index,
+            version_info,
+            provenance,
+        })
+    }
+}
+
+/// Builds a catalog in memory, for tests and for programs with their own
+/// small catalogs.
+///
+/// Top-level parameters are keyed by name and subparameters by their
+/// parent's key and their name, as in a loaded database. Duplicate keys and
+/// subparameters whose parent was never added are reported by
+/// [`build`](Self::build).
+#[derive(Debug, Default)]
+pub struct CatalogBuilder {
+    parameters: HashMap<String, Parameter>,
+    subparameter_index: HashMap<String, Vec<String>>,
+    version_info: HashMap<String, VersionInfo>,
+    duplicates: Vec<String>,
+}
+
+impl CatalogBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_parameter(self, parameter: Parameter) -> Self {
+        let key = parameter.name.clone();
+        self.insert(key, parameter)
+    }
+
+    /// Add a subparameter of the parameter catalogued as `parent`, e.g.
+    /// `pci` or `pci/nomio`. The parent may be added before or after it.
+    pub fn add_subparameter(mut self, parent: &str, parameter: Parameter) -> Self {
+        let key = format!("{}/{}", parent, parameter.name);
+        let children = self.subparameter_index.entry(parent.to_string()).or_default();
+        if !children.contains(&key) {
+            children.push(key.clone());
+        }
+        self.insert(key, parameter)
+    }
+
+    /// Record the version history of the parameter catalogued as `key`.
+    pub fn add_version_info(mut self, key: &str, info: VersionInfo) -> Self {
+        self.version_info.insert(key.to_string(), info);
+        self
+    }
+
+    /// Build the catalog and its indexes.
+    pub fn build(self) -> Result<ParameterCatalog, DatabaseError> {
+        if let Some(key) = self.duplicates.first() {
+            return Err(DatabaseError::DuplicateDefinition(key.clone()));
+        }
+        let mut parents: Vec<&String> = self.subparameter_index.keys().collect();
+        parents.sort();
+        if let Some(parent) = parents.into_iter().find(|parent| !self.parameters.contains_key(*parent)) {
+            return Err(DatabaseError::MissingDefinition(parent.clone()));
+        }
+        if let Some(key) = self.version_info.keys().find(|key| !self.parameters.contains_key(*key)) {
+            return Err(DatabaseError::MissingDefinition(key.clone()));
+        }
+
+        Ok(ParameterCatalog::from_snapshot_data(SnapshotData {
+            parameters: self.parameters,
+            subparameter_index: self.subparameter_index,
+            version_info: self.version_info,
+            provenance: HashMap::new(),
+        }))
+    }
+
+    fn insert(mut self, key: String, parameter: Parameter) -> Self {
+        match self.parameters.entry(key) {
+            Entry::Occupied(entry) => self.duplicates.push(entry.key().clone()),
+            Entry::Vacant(entry) => {
+                entry.insert(parameter);
+            }
+        }
+        self
+    }
+}
+
+/// Map the keys parameters were formerly known by to their current key. An
+/// alias replaces the last component of the key, so a subparameter's alias
+/// stays under its parent. Aliases that collide with a current key are
+/// ignored: the parameter of that name wins.
+fn alias_index(parameters: &HashMap<String, Parameter>) -> HashMap<String, String> {
+    let mut index = HashMap::new();
+    for (key, param) in parameters {
+        let prefix = key.rsplit_once('/').map(|(parent, _)| parent);
+        for alias in &param.aliases {
+            let alias_key = match prefix {
+                Some(prefix) => format!("{}/{}", prefix, alias),
+                None => alias.clone(),
+            };
+            if !parameters.contains_key(&alias_key) {
+                index.insert(alias_key, key.clone());
+            }
+        }
+    }
+    index
+}
+
 impl ParameterCatalog {
+    /// Build a catalog from keyed parameters and indexes, deriving the
+    /// alias index; the search indexes are built on first use.
+    fn from_snapshot_data(data: SnapshotData) -> Self {
+        let alias_index = alias_index(&data.parameters);
+        Self {
+            parameters: data.parameters,
+            subparameter_index: data.subparameter_index,
+            similarity_index: OnceLock::new(),
+            text_index: OnceLock::new(),
+            version_info: data.version_info,
+            provenance: data.provenance,
+            alias_index,
+        }
+    }
+
+    /// Load a catalog saved with [`save_snapshot`](Self::save_snapshot).
+    /// Snapshots of another format version, or that fail their checksum,
+    /// are rejected; callers should rebuild them from the database.
+    pub fn load_snapshot<P: AsRef<Path>>(path: P) -> Result<Self, DatabaseError> {
+        Self::load_snapshot_with_policy(path, &TrustPolicy::Unverified)
+    }
+
+    /// Load a snapshot, first checking its detached signature in
+    /// `<path>.sig` if `policy` requires one. The signature is made over
+    /// the whole snapshot file.
+    pub fn load_snapshot_with_policy<P: AsRef<Path>>(path: P, policy: &TrustPolicy) -> Result<Self, DatabaseError> {
+        load_snapshot(path.as_ref(), policy).map(Self::from_snapshot_data)
+    }
+
+    /// Save the catalog as a binary snapshot that loads much faster than
+    /// the TOML database. An existing file at `path` is replaced atomically.
+    pub fn save_snapshot<P: AsRef<Path>>(&self, path: P) -> Result<(), DatabaseError> {
+        let data = SnapshotRef {
+            parameters: &self.parameters,
+            subparameter_index: &self.subparameter_index,
+            version_info: &self.version_info,
+            provenance: &self.provenance,
+        };
+        save_snapshot(&data, path.as_ref())
+    }
+
+    /// Load the parameter database compiled into the library.
     pub fn load_embedded() -> Result<Self, crate::error::KCmdlineError> {
-        // Load from compiled database
-        todo!()
+        Self::from_loader(DatabaseLoader::new().with_embedded())
+    }
+
+    /// Load the embedded database with the site and user overlay
+    /// directories layered over it. Overlay definitions only replace the
+    /// fields they set, so an overlay may add e.g. `distributions` alone.
+    pub fn load_with_overlays() -> Result<Self, crate::error::KCmdlineError> {
+        let loader = DatabaseLoader::new()
+            .with_merge_policy(MergePolicy::FieldMerge)
+            .with_embedded()
+            .with_overlays()?;
+        Self::from_loader(loader)
+    }
+
+    /// Build a catalog from the sources of `loader`, e.g. a filesystem
+    /// database layered over the embedded one.
+    pub fn from_loader(loader: DatabaseLoader) -> Result<Self, crate::error::KCmdlineError> {
+        Ok(Self::from(loader.into_database()?))
     }
 
     pub fn parameters(&self) -> Vec<&Parameter> {
         self.parameters.values().collect()
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = &Parameter> {
+        self.parameters.values()
+    }
+
+    /// Every parameter with the key it is catalogued under.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &Parameter)> {
+        self.parameters.iter().map(|(key, param)| (key.as_str(), param))
+    }
+
+    /// The key `param` is catalogued under, if it belongs to this catalog.
+    pub fn key_of(&self, param: &Parameter) -> Option<&str> {
+        self.entries().find(|(_, p)| std::ptr::eq(*p, param)).map(|(key, _)| key)
+    }
+
+    /// Whether the parameter catalogued as `key` is a subparameter of another.
+    pub fn is_subparameter(&self, key: &str) -> bool {
+        self.subparameter_index.values().any(|children| children.iter().any(|child| child == key))
+    }
+
+    /// The parameters added, removed and modified in `other` relative to
+    /// this catalog.
+    pub fn diff(&self, other: &ParameterCatalog) -> CatalogDiff {
+        CatalogDiff::new(self.entries(), other.entries())
+    }
+
+    /// Write the whole catalog as a JSON or YAML document, in the schema
+    /// described in the `export` module.
+    pub fn export<W: Write>(&self, format: ExportFormat, writer: W) -> Result<(), DatabaseError> {
+        export_catalog(self, format, writer)
+    }
+
+    /// Check the catalog's definitions for unknown validators, examples that
+    /// contradict their validator, malformed selectors and orphaned
+    /// subparameters.
+    pub fn lint(&self) -> Vec<LintFinding> {
+        lint_catalog(self)
+    }
+
+    /// Parent keys in the subparameter index with their children, whether
+    /// or not the parent is catalogued.
+    pub(crate) fn subparameter_parents(&self) -> impl Iterator<Item = (&str, &[String])> {
+        self.subparameter_index.iter().map(|(parent, children)| (parent.as_str(), children.as_slice()))
+    }
+
+    /// The key of the parameter that `key` is a subparameter of.
+    pub fn parent_of(&self, key: &str) -> Option<&str> {
+        self.subparameter_index.iter()
+            .find(|(_, children)| children.iter().any(|child| child == key))
+            .map(|(parent, _)| parent.as_str())
+    }
+
     pub fn get_parameter(&self, name: &str) -> Option<&Parameter> {
         self.parameters.get(name)
     }
 
-    pub fn query_parameters(&self, _query: &QueryParameters) -> Vec<&Parameter> {
-        // Implementation for querying parameters
-        todo!()
+    /// Resolve a former name of a parameter to its current key and
+    /// definition. Current names are not aliases of themselves.
+    pub fn resolve_alias(&self, name: &str) -> Option<(&str, &Parameter)> {
+        let key = self.alias_index.get(name)?;
+        self.parameters.get_key_value(key).map(|(key, param)| (key.as_str(), param))
+    }
+
+    /// Version history of a parameter, when the database records it.
+    pub fn version_info(&self, name: &str) -> Option<&VersionInfo> {
+        self.version_info.get(name)
+    }
+
+    /// The sources that defined each field of a parameter, when it was
+    /// loaded from a database: `embedded`, a database or overlay path, or
+    /// `modinfo`.
+    pub fn provenance(&self, key: &str) -> Option<&Provenance> {
+        self.provenance.get(key)
+    }
+
+    /// Look up a loadable module's parameter, catalogued as
+    /// `module/<module>/<parameter>`.
+    pub fn get_module_parameter(&self, module: &str, parameter: &str) -> Option<&Parameter> {
+        self.parameters.get(&module_parameter_key(module, parameter))
+    }
+
+    /// Catalogued parameter names similar to `name`, closest first, for
+    /// "did you mean" suggestions. Only top-level parameters are offered,
+    /// as subparameters such as the values of `rfkill.default_state` are
+    /// not arguments of their own, and names shorter than three characters
+    /// get no suggestions.
+    pub fn suggest(&self, name: &str) -> Vec<String> {
+        if name.chars().count() < MIN_SUGGESTION_LENGTH {
+            return Vec::new();
+        }
+        let index = self.similarity_index.get_or_init(|| {
+            let subparameters: HashSet<&str> = self.subparameter_index.values().flatten().map(String::as_str).collect();
+            SimilarityIndex::new(self.entries()
+                .filter(|(key, _)| !subparameters.contains(key))
+                .map(|(_, param)| param.name.clone()))
+        });
+
+        index.find(name, max_edit_distance(name))
+            .into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|(candidate, _)| candidate.to_string())
+            .collect()
+    }
+
+    /// Parameters whose name, description, format or documentation links
+    /// contain every word of `text`, with their relevance score, best first.
+    pub fn search(&self, text: &str) -> Vec<(&Parameter, u32)> {
+        self.text_index().search(text)
+            .into_iter()
+            .filter_map(|(key, score)| self.parameters.get(key).map(|param| (param, score)))
+            .collect()
+    }
+
+    /// As `search`, but yielding catalog keys, which unlike names are unique
+    /// for subparameters.
+    pub fn search_keys(&self, text: &str) -> Vec<(&str, u32)> {
+        self.text_index().search(text)
+    }
+
+    /// The full-text index over the catalog's entries. It is built on the
+    /// first search rather than at load time, so that callers who never
+    /// search don't pay for it; the catalog is immutable once built, so the
+    /// index never goes stale.
+    fn text_index(&self) -> &TextIndex {
+        self.text_index.get_or_init(|| {
+            TextIndex::new(self.parameters.iter().map(|(key, param)| (key.as_str(), param)))
+        })
+    }
+
+    /// Parameters whose names fuzzily match a partially typed `term`, with
+    /// their score, best first.
+    pub fn fuzzy_search(&self, term: &str) -> Vec<(&Parameter, u32)> {
+        let mut results: Vec<(&Parameter, u32)> = self.parameters.values()
+            .filter_map(|param| fuzzy_score(term, &param.name).map(|score| (param, score)))
+            .collect();
+        results.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.name.cmp(&b.0.name)));
+        results
+    }
+
+    /// Find the parameters matching `query`. Results of a text search are
+    /// ordered by relevance. Applicability is judged for a system with no
+    /// tags but the architecture this library was built for; use
+    /// [`query_parameters_on`](Self::query_parameters_on) for a probed one.
+    pub fn query_parameters(&self, query: &QueryParameters) -> Vec<&Parameter> {
+        self.query_parameters_on(query, &SystemProbe::builder().build())
+    }
+
+    /// Find the parameters matching `query` on the system described by
+    /// `probe`. Results of a text search are ordered by relevance.
+    pub fn query_parameters_on(&self, query: &QueryParameters, probe: &SystemProbe) -> Vec<&Parameter> {
+        self.query(&query.to_expr(), probe)
+    }
+
+    /// Find the parameters matching a query expression on the system
+    /// described by `probe`. When the expression contains text conditions,
+    /// results are ordered by their combined relevance.
+    pub fn query(&self, expr: &QueryExpr, probe: &SystemProbe) -> Vec<&Parameter> {
+        let text_scores = self.text_scores(expr);
+        let mut results: Vec<(&str, &Parameter)> = self.entries()
+            .filter(|(key, param)| self.matches_expr(key, param, expr, &text_scores, probe))
+            .collect();
+
+        if !text_scores.is_empty() {
+            let score = |key: &str| Self::combined_score(key, &text_scores);
+            results.sort_by(|a, b| score(b.0).cmp(&score(a.0)).then_with(|| a.0.cmp(b.0)));
+        }
+        results.into_iter().map(|(_, param)| param).collect()
+    }
+
+    /// Search the catalog once for each distinct text condition in `expr`.
+    pub(crate) fn text_scores(&self, expr: &QueryExpr) -> HashMap<String, HashMap<&str, u32>> {
+        let mut text_scores = HashMap::new();
+        for condition in expr.conditions() {
+            if let QueryCondition::Text(text) = condition {
+                text_scores.entry(text.clone()).or_insert_with(|| {
+                    self.search_keys(text).into_iter().collect()
+                });
+            }
+        }
+        text_scores
+    }
+
+    pub(crate) fn combined_score(key: &str, text_scores: &HashMap<String, HashMap<&str, u32>>) -> u32 {
+        text_scores.values().filter_map(|scores| scores.get(key)).sum()
+    }
+
+    pub(crate) fn matches_expr(
+        &self,
+        key: &str,
+        param: &Parameter,
+        expr: &QueryExpr,
+        text_scores: &HashMap<String, HashMap<&str, u32>>,
+        probe: &SystemProbe,
+    ) -> bool {
+        expr.evaluate(&mut |condition| match condition {
+            QueryCondition::Text(text) => text_scores[text].contains_key(key),
+            QueryCondition::Subparameter(subparameter) => self.is_subparameter(key) == *subparameter,
+            condition => condition.matches(param, probe, self.version_info(key)),
+        })
     }
 
     pub fn get_applicable_parameters(&self, probe: &SystemProbe) -> Vec<&Parameter> {
@@ -74,8 +528,115 @@ impl ParameterCatalog {
             .unwrap_or_default()
     }
 
-    pub fn build_parameter_tree(&self, _name: &str) -> Option<ParameterTree> {
-        // Build recursive tree structure
-        todo!()
+    /// The subparameters of the parameter catalogued as `parent_key`, with
+    /// their keys.
+    pub fn get_subparameter_entries(&self, parent_key: &str) -> Vec<(&str, &Parameter)> {
+        self.subparameter_index.get(parent_key)
+            .map(|children| {
+                children.iter()
+                    .filter_map(|key| self.parameters.get_key_value(key))
+                    .map(|(key, param)| (key.as_str(), param))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The parameter catalogued as `name` with its subparameters, recursively,
+    /// keyed by catalog key. Children missing from the catalog are skipped,
+    /// and a parameter that is its own ancestor is not expanded again.
+    pub fn build_parameter_tree(&self, name: &str) -> Option<ParameterTree> {
+        self.build_subtree(name, &mut HashSet::new())
+    }
+
+    fn build_subtree<'a>(&'a self, key: &'a str, ancestors: &mut HashSet<&'a str>) -> Option<ParameterTree> {
+        let root = self.parameters.get(key)?;
+        if !ancestors.insert(key) {
+            return None;
+        }
+
+        let children = self.subparameter_index.get(key)
+            .into_iter()
+            .flatten()
+            .filter_map(|child| {
+                self.build_subtree(child, ancestors).map(|tree| (child.clone(), tree))
+            })
+            .collect();
+
+        ancestors.remove(key);
+        Some(ParameterTree {
+            root: root.clone(),
+            children,
+        })
+    }
+
+    /// Trees for every root parameter, sorted by key: parameters that are
+    /// not a subparameter of another catalogued parameter. Parameters only
+    /// reachable through a cycle are rooted at the first of them by key, so
+    /// every parameter appears in the forest.
+    pub fn full_tree(&self) -> Vec<ParameterTree> {
+        let children: HashSet<&str> = self.subparameter_index.iter()
+            .filter(|(parent, _)| self.parameters.contains_key(*parent))
+            .flat_map(|(_, children)| children.iter().map(String::as_str))
+            .collect();
+
+        let mut keys: Vec<&str> = self.parameters.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        let (roots, rest): (Vec<&str>, Vec<&str>) = keys.into_iter()
+            .partition(|key| !children.contains(key));
+
+        let mut forest = Vec::new();
+        let mut placed = HashSet::new();
+        for key in roots.into_iter().chain(rest) {
+            if placed.contains(key) {
+                continue;
+            }
+            if let Some(tree) = self.build_parameter_tree(key) {
+                tree.collect_keys(key, &mut placed);
+                forest.push(tree);
+            }
+        }
+        forest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parameter::ParameterProcessor;
+
+    fn catalog() -> ParameterCatalog {
+        CatalogBuilder::new()
+            .add_parameter(Parameter::new("rfkill.default_state", ParameterProcessor::Kernel))
+            .add_subparameter("rfkill.default_state", Parameter::new("0", ParameterProcessor::Kernel))
+            .add_subparameter("rfkill.default_state", Parameter::new("1", ParameterProcessor::Kernel))
+            .add_parameter(Parameter::new("pci", ParameterProcessor::Kernel))
+            .add_subparameter("pci", Parameter::new("nomio", ParameterProcessor::Kernel))
+            .add_parameter(Parameter::new("quiet", ParameterProcessor::Kernel))
+            .add_parameter(Parameter::new("nosmt", ParameterProcessor::Kernel))
+            .add_parameter(Parameter::new("noapic", ParameterProcessor::Kernel))
+            .add_parameter(Parameter::new("rw", ParameterProcessor::Kernel))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn suggests_misspelt_names() {
+        let catalog = catalog();
+        assert_eq!(catalog.suggest("quite"), ["quiet"]);
+        assert_eq!(catalog.suggest("nosmp"), ["nosmt"]);
+    }
+
+    #[test]
+    fn suggests_no_subparameters() {
+        let catalog = catalog();
+        assert!(catalog.suggest("nomoi").is_empty());
+        assert!(catalog.suggest("1").is_empty());
+    }
+
+    #[test]
+    fn suggests_nothing_for_short_names() {
+        let catalog = catalog();
+        assert!(catalog.suggest("r").is_empty());
+        assert!(catalog.suggest("ro").is_empty());
     }
 }
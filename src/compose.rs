@@ -0,0 +1,262 @@
+//! Composition of whole command lines from typed pieces, for tools that
+//! generate boot lines rather than check them.
+
+use std::fmt;
+
+use crate::catalog::ParameterCatalog;
+use crate::config::{ConsoleSpec, ParameterConfig};
+use crate::error::BuildError;
+use crate::parameter::DuplicatePolicy;
+use crate::parser::{
+    render_argument,
+    split_args,
+    CommandLineParser,
+    NormalizationPolicy,
+    OrderingPolicy,
+    ParameterBuilder,
+};
+use crate::profile::Profile;
+use crate::rules::LintRuleRegistry;
+use crate::validators::ValidationResult;
+
+/// The device the root file system is mounted from, as given to `root=`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RootSpec {
+    /// A device node, e.g. `/dev/sda2`.
+    Device(String),
+    /// A file system UUID.
+    Uuid(String),
+    /// A GPT partition UUID.
+    PartUuid(String),
+    /// A file system label.
+    Label(String),
+    /// A GPT partition label.
+    PartLabel(String),
+}
+
+impl fmt::Display for RootSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Device(device) => write!(f, "{}", device),
+            Self::Uuid(uuid) => write!(f, "UUID={}", uuid),
+            Self::PartUuid(uuid) => write!(f, "PARTUUID={}", uuid),
+            Self::Label(label) => write!(f, "LABEL={}", label),
+            Self::PartLabel(label) => write!(f, "PARTLABEL={}", label),
+        }
+    }
+}
+
+/// A console device, as given to `console=`.
+pub type Console = ConsoleSpec;
+
+/// Assembles a command line from typed pieces.
+///
+/// A piece replaces any earlier occurrence of the same parameter unless
+/// the parameter is additive, such as `console=`, in which case it is
+/// added after them. Mistakes are reported by [`build`](Self::build),
+/// which also validates the command line and arranges it in a
+/// deterministic order, by default that of [`OrderingPolicy::ByProcessor`].
+///
+/// ```no_run
+/// # use libkcmdline::{CommandLineBuilder, Console, ParameterCatalog, ParameterConfig, RootSpec};
+/// let catalog = ParameterCatalog::load_embedded()?;
+/// let cmdline = CommandLineBuilder::new(&catalog)
+///     .root(RootSpec::Uuid("4f1c2d3e-1111-2222-3333-444455556666".to_string()))
+///     .console(Console::tty("tty0"))
+///     .console(Console::serial("ttyS0", 115200))
+///     .add("isolcpus", &ParameterConfig::from_cpu_list(vec![2, 3]))
+///     .merge("quiet splash")
+///     .build()?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct CommandLineBuilder<'a> {
+    catalog: &'a ParameterCatalog,
+    target_arch: Option<String>,
+    ordering: OrderingPolicy,
+    arguments: Vec<(String, Option<String>)>,
+    init_arguments: Vec<String>,
+    error: Option<BuildError>,
+}
+
+impl<'a> CommandLineBuilder<'a> {
+    pub fn new(catalog: &'a ParameterCatalog) -> Self {
+        Self {
+            catalog,
+            target_arch: None,
+            ordering: OrderingPolicy::default(),
+            arguments: Vec::new(),
+            init_arguments: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Format and validate values with the syntax of the given architecture.
+    pub fn with_target_arch<S: Into<String>>(mut self, arch: S) -> Self {
+        self.target_arch = Some(arch.into());
+        self
+    }
+
+    /// Arrange the parameters of the built command line in this order.
+    pub fn with_ordering(mut self, ordering: OrderingPolicy) -> Self {
+        self.ordering = ordering;
+        self
+    }
+
+    /// Mount the root file system from `root`.
+    pub fn root(mut self, root: RootSpec) -> Self {
+        self.insert("root", Some(root.to_string()));
+        self
+    }
+
+    /// Add a console. The last console added becomes `/dev/console`.
+    pub fn console(mut self, console: Console) -> Self {
+        self.insert("console", Some(console.render()));
+        self
+    }
+
+    /// Add the catalog parameter `name`, formatting `config` as its syntax
+    /// expects with a [`ParameterBuilder`].
+    pub fn add(mut self, name: &str, config: &ParameterConfig) -> Self {
+        let parameter = self.catalog.get_parameter(name)
+            .or_else(|| self.catalog.resolve_alias(name).map(|(_, param)| param));
+        let Some(parameter) = parameter else {
+            self.fail(BuildError::UnknownParameter(name.to_string()));
+            return self;
+        };
+
+        let mut builder = ParameterBuilder::new(parameter);
+        if let Some(arch) = &self.target_arch {
+            builder = builder.with_target_arch(arch.as_str());
+        }
+        match builder.build(config) {
+            Ok(argument) => {
+                let value = split_args(&argument).first().and_then(|arg| arg.value.map(str::to_string));
+                self.insert(&parameter.name, value);
+            }
+            Err(error) => self.fail(error),
+        }
+        self
+    }
+
+    /// Add the arguments of a command line fragment as written, e.g. one
+    /// read from `/etc/kernel/cmdline`. Arguments after a `--` separator
+    /// are passed to init.
+    pub fn merge(mut self, fragment: &str) -> Self {
+        let mut arguments = split_args(fragment).into_iter();
+        for arg in arguments.by_ref() {
+            if arg.param == "--" && arg.value.is_none() {
+                break;
+            }
+            self.insert(arg.param, arg.value.map(str::to_string));
+        }
+        self.init_arguments.extend(arguments.map(|arg| arg.text.to_string()));
+        self
+    }
+
+    /// Add the arguments of `profile`, as [`Profile::apply`] does.
+    pub fn profile(mut self, profile: &Profile) -> Self {
+        if let Err(error) = profile.apply(&mut self) {
+            self.fail(error);
+        }
+        self
+    }
+
+    /// Validate the command line and render it, or report the first
+    /// mistake made building it or the errors validation found.
+    pub fn build(self) -> Result<String, BuildError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let mut args: Vec<String> = self.arguments.iter()
+            .map(|(name, value)| render_argument(name, value.as_deref()))
+            .collect();
+        if !self.init_arguments.is_empty() {
+            args.push("--".to_string());
+            args.extend(self.init_arguments.iter().cloned());
+        }
+
+        let parsed = self.parser().parse(&args.join(" "))?;
+        let errors: Vec<_> = parsed.validation_summary.details.iter()
+            .filter(|finding| matches!(finding.result, ValidationResult::Error(_)))
+            .cloned()
+            .collect();
+        if !errors.is_empty() {
+            return Err(BuildError::ValidationFailed(errors));
+        }
+
+        let policy = NormalizationPolicy {
+            ordering: self.ordering,
+            ..NormalizationPolicy::default()
+        };
+        Ok(parsed.normalize_with(&policy).to_cmdline())
+    }
+
+    /// Fail if adding `additions` would conflict with an argument already
+    /// given, or replace or be overridden by one.
+    pub(crate) fn check_additions(&self, additions: &[(String, Option<String>)]) -> Result<(), BuildError> {
+        let given = self.arguments.len();
+        let args: Vec<String> = self.arguments.iter()
+            .chain(additions)
+            .map(|(name, value)| render_argument(name, value.as_deref()))
+            .collect();
+        let parsed = self.parser().parse(&args.join(" "))?;
+
+        let argument = |index: usize| {
+            let param = &parsed.parameters[index];
+            render_argument(&param.name, param.value.as_deref())
+        };
+        let conflict = |(added, present): (usize, usize), reason: String| BuildError::Conflict {
+            param: argument(added),
+            existing: argument(present),
+            reason,
+        };
+        let is_added = |index: usize| index >= given;
+
+        if let Some(found) = parsed.conflicts.iter().find(|c| is_added(c.first) != is_added(c.second)) {
+            return Err(conflict((found.second, found.first), found.reason.clone()));
+        }
+        if let Some(found) = parsed.overrides.iter().find(|o| is_added(o.overridden) != is_added(o.winner)) {
+            let pair = if is_added(found.winner) { (found.winner, found.overridden) } else { (found.overridden, found.winner) };
+            let reason = match &found.setting {
+                Some(setting) => format!("both set {}", setting),
+                None => "only one of the values can take effect".to_string(),
+            };
+            return Err(conflict(pair, reason));
+        }
+        Ok(())
+    }
+
+    fn parser(&self) -> CommandLineParser<'a> {
+        let parser = CommandLineParser::new(self.catalog).with_lint_rules(LintRuleRegistry::with_builtin_rules());
+        match &self.target_arch {
+            Some(arch) => parser.with_target_arch(arch.as_str()),
+            None => parser,
+        }
+    }
+
+    /// Add an argument, replacing earlier occurrences of the parameter
+    /// unless it is additive.
+    pub(crate) fn insert(&mut self, name: &str, value: Option<String>) {
+        let catalog = self.catalog;
+        let current_name = |name: &str| {
+            catalog.resolve_alias(name).map_or(name.to_string(), |(key, _)| key.to_string())
+        };
+        let key = current_name(name);
+        let additive = catalog.get_parameter(&key)
+            .is_some_and(|param| param.duplicate_policy == DuplicatePolicy::Additive);
+
+        if additive {
+            if self.arguments.iter().any(|(other, other_value)| current_name(other) == key && *other_value == value) {
+                return;
+            }
+        } else {
+            self.arguments.retain(|(other, _)| current_name(other) != key);
+        }
+        self.arguments.push((name.to_string(), value));
+    }
+
+    fn fail(&mut self, error: BuildError) {
+        self.error.get_or_insert(error);
+    }
+}
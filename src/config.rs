@@ -1,5 +1,8 @@
 use std::collections::HashMap;
 
+use crate::parameter::SyntaxDefinition;
+use crate::validators::MAX_NR_CPUS;
+
 #[derive(Debug, Clone)]
 pub enum ParameterConfig {
     Boolean(bool),
@@ -37,4 +40,1014 @@ impl ParameterConfig {
         }
         self
     }
+
+    /// Parse a parameter value into a typed configuration according to the
+    /// validator type of its syntax definition. Returns `None` for syntaxes
+    /// without a structured form and for values that do not match the syntax.
+    pub fn parse(value: Option<&str>, syntax: &SyntaxDefinition) -> Option<Self> {
+        let config = &syntax.config;
+        match syntax.validator_type.as_str() {
+            "boolean" => match value {
+                None => Some(Self::Boolean(true)),
+                Some(v) => parse_boolean(v).map(Self::Boolean),
+            },
+            "integer" => value?.parse().ok().map(Self::Integer),
+            "hex" => {
+                let digits = value?.strip_prefix("0x")?;
+                i64::from_str_radix(digits, 16).ok().map(Self::Integer)
+            }
+            "size" => parse_size(value?).map(Self::Integer),
+            "enum" => {
+                let value = value?;
+                let allow_multiple = config.get("allow_multiple")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                if allow_multiple {
+                    Some(Self::List(value.split(',').map(|s| s.trim().to_string()).collect()))
+                } else {
+                    Some(Self::String(value.to_string()))
+                }
+            }
+            "cpu_list" => {
+                let supports_flags = config.get("supports_flags")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                parse_cpu_list(value?, supports_flags)
+            }
+            "key_value" => {
+                let separator = config.get("separator")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("=");
+                parse_key_values(value?, separator)
+            }
+            "dracut_network" => IpConfig::parse(value?).map(Self::from),
+            "crashkernel" => CrashKernelSpec::parse(value?).map(Self::from),
+            "memmap" => MemMapRegion::parse(value?).map(Self::from),
+            "console" => ConsoleSpec::parse(value?).map(Self::from),
+            _ => None,
+        }
+    }
+
+    /// Render the configuration as a value of the given syntax, the
+    /// inverse of [`parse`](Self::parse): `None` for a flag given without a
+    /// value. Returns why the configuration does not fit the syntax
+    /// otherwise.
+    pub(crate) fn render(&self, syntax: &SyntaxDefinition) -> Result<Option<String>, String> {
+        let config = &syntax.config;
+        match (syntax.validator_type.as_str(), self) {
+            ("boolean", Self::Boolean(enabled)) => Ok(Some(if *enabled { "1" } else { "0" }.to_string())),
+            ("hex", Self::Integer(value)) if *value < 0 => Err(format!("negative hex value {}", value)),
+            ("hex", Self::Integer(value)) => Ok(Some(format!("0x{:x}", value))),
+            ("size", Self::Integer(value)) if *value < 0 => Err(format!("negative size {}", value)),
+            ("size", Self::Integer(value)) => Ok(Some(render_size(*value))),
+            ("enum", Self::List(values)) => {
+                let allow_multiple = config.get("allow_multiple")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                match values.as_slice() {
+                    [value] => Ok(Some(value.clone())),
+                    _ if allow_multiple => Ok(Some(values.join(","))),
+                    _ => Err(format!("expected one choice, found {}", values.len())),
+                }
+            }
+            ("cpu_list", Self::CpuList { cpus, flags }) => {
+                let supports_flags = config.get("supports_flags")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                match (flags.is_empty(), supports_flags) {
+                    (true, _) => Ok(Some(render_cpu_ranges(cpus)?)),
+                    (false, true) => Ok(Some(format!("{}:{}", flags.join(","), render_cpu_ranges(cpus)?))),
+                    (false, false) => Err("CPU flags are not supported".to_string()),
+                }
+            }
+            ("key_value", Self::Complex(fields)) => {
+                let separator = config.get("separator")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("=");
+                render_key_values(fields, separator).map(Some)
+            }
+            ("dracut_network", Self::Complex(fields)) => Ok(Some(IpConfig::from_fields(fields)?.render())),
+            ("crashkernel", Self::Complex(fields)) => Ok(Some(CrashKernelSpec::from_fields(fields)?.render())),
+            ("memmap", Self::Complex(fields)) => Ok(Some(MemMapRegion::from_fields(fields)?.render())),
+            ("console", Self::Complex(fields)) => Ok(Some(ConsoleSpec::from_fields(fields)?.render())),
+            (_, Self::Complex(_)) => Err("the syntax has no structured form".to_string()),
+            (_, config) => render_plain(config),
+        }
+    }
+}
+
+/// Render a configuration for a syntax without a form of its own.
+fn render_plain(config: &ParameterConfig) -> Result<Option<String>, String> {
+    match config {
+        ParameterConfig::Boolean(true) => Ok(None),
+        ParameterConfig::Boolean(false) => Err("a flag cannot be switched off; leave it out instead".to_string()),
+        ParameterConfig::Integer(value) => Ok(Some(value.to_string())),
+        ParameterConfig::String(value) => Ok(Some(value.clone())),
+        ParameterConfig::List(values) => Ok(Some(values.join(","))),
+        // The kernel's own form, as in `isolcpus=nohz,domain,1-3`.
+        ParameterConfig::CpuList { cpus, flags } => {
+            let mut parts = flags.clone();
+            parts.push(render_cpu_ranges(cpus)?);
+            Ok(Some(parts.join(",")))
+        }
+        ParameterConfig::Complex(_) => Err("the syntax has no structured form".to_string()),
+    }
+}
+
+/// The value of a field of a structured configuration.
+fn render_field(name: &str, config: &ParameterConfig) -> Result<String, String> {
+    match config {
+        ParameterConfig::Boolean(enabled) => Ok(if *enabled { "1" } else { "0" }.to_string()),
+        ParameterConfig::Integer(value) => Ok(value.to_string()),
+        ParameterConfig::String(value) => Ok(value.clone()),
+        _ => Err(format!("field '{}' must be a single value", name)),
+    }
+}
+
+/// A size in bytes with the largest suffix that divides it exactly.
+fn render_size(bytes: i64) -> String {
+    u64::try_from(bytes).map_or_else(|_| bytes.to_string(), format_size)
+}
+
+/// A byte count in the `<n>[KMG]` form of kernel size parameters.
+pub(crate) fn format_size(bytes: u64) -> String {
+    [(30, "G"), (20, "M"), (10, "K")].into_iter()
+        .find(|&(shift, _)| bytes != 0 && bytes.is_multiple_of(1 << shift))
+        .map_or_else(|| bytes.to_string(), |(shift, unit)| format!("{}{}", bytes >> shift, unit))
+}
+
+/// Render CPUs as a list of ranges, e.g. `0-3,6`.
+fn render_cpu_ranges(cpus: &[u32]) -> Result<String, String> {
+    if cpus.is_empty() {
+        return Err("no CPUs given".to_string());
+    }
+    let mut cpus = cpus.to_vec();
+    cpus.sort_unstable();
+    cpus.dedup();
+
+    let mut ranges = Vec::new();
+    let mut start = cpus[0];
+    for (i, &cpu) in cpus.iter().enumerate() {
+        if cpus.get(i + 1).copied() != cpu.checked_add(1) {
+            ranges.push(if start == cpu { cpu.to_string() } else { format!("{}-{}", start, cpu) });
+            if let Some(&next) = cpus.get(i + 1) {
+                start = next;
+            }
+        }
+    }
+    Ok(ranges.join(","))
+}
+
+/// Render `key<separator>value` pairs, ordered by key.
+fn render_key_values(fields: &HashMap<String, ParameterConfig>, separator: &str) -> Result<String, String> {
+    if fields.is_empty() {
+        return Err("no fields given".to_string());
+    }
+    let mut keys: Vec<&String> = fields.keys().collect();
+    keys.sort();
+    let pairs = keys.into_iter()
+        .map(|key| {
+            if key.is_empty() || key.contains(separator) || key.contains(',') {
+                return Err(format!("invalid key '{}'", key));
+            }
+            Ok(format!("{}{}{}", key, separator, render_field(key, &fields[key])?))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(pairs.join(","))
+}
+
+fn parse_boolean(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "1" | "y" | "yes" | "on" | "true" => Some(true),
+        "0" | "n" | "no" | "off" | "false" => Some(false),
+        _ => None,
+    }
+}
+
+pub(crate) fn parse_size(value: &str) -> Option<i64> {
+    let (number, multiplier) = match value.char_indices().last()? {
+        (i, 'K') => (&value[..i], 1i64 << 10),
+        (i, 'M') => (&value[..i], 1i64 << 20),
+        (i, 'G') => (&value[..i], 1i64 << 30),
+        _ => (value, 1),
+    };
+    number.parse::<i64>().ok()?.checked_mul(multiplier)
+}
+
+/// Expand a CPU list such as `nohz,domain:0-3,^2,6` into its flags and the
+/// sorted set of selected CPUs, applying any `^` exclusions. Lists naming a
+/// CPU at or past [`MAX_NR_CPUS`] are rejected.
+pub(crate) fn parse_cpu_list(value: &str, supports_flags: bool) -> Option<ParameterConfig> {
+    let (flags, cpu_part) = match value.split_once(':') {
+        Some((flags, cpus)) if supports_flags => {
+            (flags.split(',').map(|f| f.trim().to_string()).collect(), cpus)
+        }
+        _ => (Vec::new(), value),
+    };
+
+    let mut included = Vec::new();
+    let mut excluded = Vec::new();
+    for part in cpu_part.split(',') {
+        let part = part.trim();
+        let (target, part) = match part.strip_prefix('^') {
+            Some(rest) => (&mut excluded, rest),
+            None => (&mut included, part),
+        };
+        let (start, end) = match part.split_once('-') {
+            Some((start, end)) => (start.parse::<u32>().ok()?, end.parse::<u32>().ok()?),
+            None => {
+                let cpu = part.parse::<u32>().ok()?;
+                (cpu, cpu)
+            }
+        };
+        if end < start || end >= MAX_NR_CPUS {
+            return None;
+        }
+        target.push((start, end));
+    }
+
+    let cpus = subtract_ranges(merge_ranges(included), &merge_ranges(excluded))
+        .into_iter()
+        .flat_map(|(start, end)| start..=end)
+        .collect();
+    Some(ParameterConfig::CpuList { cpus, flags })
+}
+
+/// Sort inclusive ranges and join those that overlap or touch.
+fn merge_ranges(mut ranges: Vec<(u32, u32)>) -> Vec<(u32, u32)> {
+    ranges.sort_unstable();
+    let mut merged: Vec<(u32, u32)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1.saturating_add(1) => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// Remove the merged inclusive ranges `excluded` from the merged inclusive
+/// ranges `included`.
+fn subtract_ranges(included: Vec<(u32, u32)>, excluded: &[(u32, u32)]) -> Vec<(u32, u32)> {
+    let mut remaining = Vec::with_capacity(included.len());
+    for (start, end) in included {
+        let mut next = start;
+        for &(cut_start, cut_end) in excluded {
+            if cut_end < next {
+                continue;
+            }
+            if cut_start > end {
+                break;
+            }
+            if cut_start > next {
+                remaining.push((next, cut_start - 1));
+            }
+            next = cut_end + 1;
+        }
+        if next <= end {
+            remaining.push((next, end));
+        }
+    }
+    remaining
+}
+
+/// Parse a comma separated list of `key<separator>value` pairs.
+fn parse_key_values(value: &str, separator: &str) -> Option<ParameterConfig> {
+    let mut fields = HashMap::new();
+    for pair in value.split(',') {
+        let (key, val) = pair.split_once(separator)?;
+        if key.is_empty() {
+            return None;
+        }
+        fields.insert(key.to_string(), ParameterConfig::String(val.to_string()));
+    }
+    Some(ParameterConfig::Complex(fields))
+}
+
+/// Field names of the full dracut `ip=` form:
+/// `<client-IP>:[<peer>]:<gateway-IP>:<netmask>:<hostname>:<interface>:<autoconf>[:[<mtu>][:<macaddr>]]`
+const IP_CONFIG_FIELDS: [&str; 7] = [
+    "client_ip", "peer", "gateway", "netmask", "hostname", "interface", "autoconf",
+];
+
+/// Autoconfiguration methods accepted in the `<autoconf>` field of `ip=`.
+pub(crate) const IP_AUTOCONF_METHODS: [&str; 8] = [
+    "none", "off", "dhcp", "on", "any", "dhcp6", "auto6", "ibft",
+];
+
+/// Split a dracut `ip=` value into its colon separated fields and locate the
+/// `<autoconf>` field. Besides the full form this accepts `ip=<autoconf>` and
+/// `ip=<interface>:<autoconf>[:[<mtu>][:<macaddr>]]`.
+pub(crate) fn split_ip_config(value: &str) -> Option<(Vec<String>, usize)> {
+    let parts = split_ip_fields(value);
+    let is_method = |i: usize| parts.get(i).is_some_and(|p| IP_AUTOCONF_METHODS.contains(&p.as_str()));
+    let autoconf = if parts.len() == 1 {
+        0
+    } else if parts.len() >= IP_CONFIG_FIELDS.len() && is_method(IP_CONFIG_FIELDS.len() - 1) {
+        IP_CONFIG_FIELDS.len() - 1
+    } else if is_method(1) {
+        1
+    } else if parts.len() >= IP_CONFIG_FIELDS.len() {
+        IP_CONFIG_FIELDS.len() - 1
+    } else {
+        return None;
+    };
+    Some((parts, autoconf))
+}
+
+fn split_ip_fields(value: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_brackets = false;
+    for c in value.chars() {
+        match c {
+            '[' => in_brackets = true,
+            ']' => in_brackets = false,
+            ':' if !in_brackets => {
+                fields.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    fields.push(current);
+    fields
+}
+
+/// A dracut `ip=` value, in the full form
+/// `<client-IP>:[<peer>]:<gateway-IP>:<netmask>:<hostname>:<interface>:<autoconf>[:[<mtu>][:<macaddr>]]`
+/// or one of the short forms `<autoconf>` and
+/// `<interface>:<autoconf>[:[<mtu>][:<macaddr>]]`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IpConfig {
+    pub client_ip: Option<String>,
+    pub peer: Option<String>,
+    pub gateway: Option<String>,
+    pub netmask: Option<String>,
+    pub hostname: Option<String>,
+    pub interface: Option<String>,
+    /// The autoconfiguration method, e.g. `dhcp`, or `none` for a static
+    /// address.
+    pub autoconf: String,
+    pub mtu: Option<u32>,
+    pub macaddr: Option<String>,
+}
+
+impl IpConfig {
+    /// Configure every interface with the given method, e.g. `dhcp`.
+    pub fn new(autoconf: &str) -> Self {
+        Self {
+            autoconf: autoconf.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Parse an `ip=` value. Empty fields are left unset and bracketed IPv6
+    /// addresses are kept intact.
+    pub fn parse(value: &str) -> Option<Self> {
+        let (parts, autoconf) = split_ip_config(value)?;
+        let mut fields: HashMap<&str, String> = HashMap::new();
+        let names: &[&str] = match autoconf {
+            0 => &["autoconf"],
+            1 => &["interface", "autoconf"],
+            _ => &IP_CONFIG_FIELDS,
+        };
+        for (name, field) in names.iter().zip(&parts) {
+            fields.insert(name, field.clone());
+        }
+
+        // Dracut accepts either `<mtu>:<macaddr>` or a bare `<macaddr>`, whose
+        // own colons have split it into six parts.
+        let rest = &parts[autoconf + 1..];
+        match rest.len() {
+            0 => {}
+            1 => {
+                fields.insert("mtu", rest[0].clone());
+            }
+            6 => {
+                fields.insert("macaddr", rest.join(":"));
+            }
+            _ => {
+                fields.insert("mtu", rest[0].clone());
+                fields.insert("macaddr", rest[1..].join(":"));
+            }
+        }
+
+        let mut field = |name: &str| fields.remove(name).filter(|field| !field.is_empty());
+        Some(Self {
+            client_ip: field("client_ip"),
+            peer: field("peer"),
+            gateway: field("gateway"),
+            netmask: field("netmask"),
+            hostname: field("hostname"),
+            interface: field("interface"),
+            autoconf: field("autoconf")?,
+            mtu: field("mtu").map(|mtu| mtu.parse()).transpose().ok()?,
+            macaddr: field("macaddr"),
+        })
+    }
+
+    /// The value in the shortest form that holds every field set.
+    pub fn render(&self) -> String {
+        let addressed = [&self.client_ip, &self.peer, &self.gateway, &self.netmask, &self.hostname]
+            .iter()
+            .any(|field| field.is_some());
+        let has_options = self.mtu.is_some() || self.macaddr.is_some();
+
+        let mut parts: Vec<String> = if addressed || (self.interface.is_none() && has_options) {
+            [&self.client_ip, &self.peer, &self.gateway, &self.netmask, &self.hostname, &self.interface]
+                .iter()
+                .map(|field| field.as_deref().unwrap_or("").to_string())
+                .collect()
+        } else {
+            self.interface.iter().cloned().collect()
+        };
+        parts.push(self.autoconf.clone());
+        match (self.mtu, &self.macaddr) {
+            (None, None) => {}
+            (Some(mtu), None) => parts.push(mtu.to_string()),
+            (mtu, Some(macaddr)) => parts.extend([mtu.map(|mtu| mtu.to_string()).unwrap_or_default(), macaddr.clone()]),
+        }
+        parts.join(":")
+    }
+
+    /// The value a [`ParameterConfig::Complex`] of its fields describes.
+    pub fn from_config(config: &ParameterConfig) -> Option<Self> {
+        match config {
+            ParameterConfig::Complex(fields) => Self::from_fields(fields).ok(),
+            _ => None,
+        }
+    }
+
+    fn from_fields(fields: &HashMap<String, ParameterConfig>) -> Result<Self, String> {
+        let fields = Fields::new(fields, &["client_ip", "peer", "gateway", "netmask", "hostname", "interface", "autoconf", "mtu", "macaddr"])?;
+        Ok(Self {
+            client_ip: fields.string("client_ip")?,
+            peer: fields.string("peer")?,
+            gateway: fields.string("gateway")?,
+            netmask: fields.string("netmask")?,
+            hostname: fields.string("hostname")?,
+            interface: fields.string("interface")?,
+            autoconf: fields.string("autoconf")?.ok_or("missing field 'autoconf'")?,
+            mtu: fields.number("mtu")?,
+            macaddr: fields.string("macaddr")?,
+        })
+    }
+}
+
+impl From<IpConfig> for ParameterConfig {
+    fn from(ip: IpConfig) -> Self {
+        let mut fields = HashMap::new();
+        let strings = [
+            ("client_ip", ip.client_ip),
+            ("peer", ip.peer),
+            ("gateway", ip.gateway),
+            ("netmask", ip.netmask),
+            ("hostname", ip.hostname),
+            ("interface", ip.interface),
+            ("autoconf", Some(ip.autoconf)),
+            ("macaddr", ip.macaddr),
+        ];
+        for (name, value) in strings {
+            if let Some(value) = value {
+                fields.insert(name.to_string(), Self::String(value));
+            }
+        }
+        if let Some(mtu) = ip.mtu {
+            fields.insert("mtu".to_string(), Self::Integer(mtu.into()));
+        }
+        Self::Complex(fields)
+    }
+}
+
+/// Where a fixed size `crashkernel=` reservation is placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrashKernelPlacement {
+    /// Above 4 GiB where possible, `,high`.
+    High,
+    /// Below 4 GiB, for DMA, `,low`.
+    Low,
+    /// From the contiguous memory allocator, `,cma`.
+    Cma,
+}
+
+impl CrashKernelPlacement {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::High => "high",
+            Self::Low => "low",
+            Self::Cma => "cma",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "high" => Some(Self::High),
+            "low" => Some(Self::Low),
+            "cma" => Some(Self::Cma),
+            _ => None,
+        }
+    }
+}
+
+/// The size a `crashkernel=` value reserves when system memory is from
+/// `start` up to, but not including, `end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrashKernelRange {
+    pub start: u64,
+    /// `None` for no upper bound.
+    pub end: Option<u64>,
+    pub size: u64,
+}
+
+/// A `crashkernel=` value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CrashKernelSpec {
+    /// `<size>[@<offset>]`
+    Size { size: u64, offset: Option<u64> },
+    /// `<size>,high`, `<size>,low` or `<size>,cma`
+    Placed { size: u64, placement: CrashKernelPlacement },
+    /// `<start>-[<end>]:<size>[,...][@<offset>]`: a size depending on how
+    /// much memory the system has.
+    Ranges { ranges: Vec<CrashKernelRange>, offset: Option<u64> },
+}
+
+impl CrashKernelSpec {
+    pub fn parse(value: &str) -> Option<Self> {
+        let parse_bytes = |size: &str| u64::try_from(parse_size(size)?).ok();
+        let (value, offset) = match value.split_once('@') {
+            Some((value, offset)) => (value, Some(parse_bytes(offset)?)),
+            None => (value, None),
+        };
+
+        if value.contains(':') {
+            let ranges = value.split(',')
+                .map(|range| {
+                    let (range, size) = range.split_once(':')?;
+                    let (start, end) = range.split_once('-')?;
+                    Some(CrashKernelRange {
+                        start: parse_bytes(start)?,
+                        end: if end.is_empty() { None } else { Some(parse_bytes(end)?) },
+                        size: parse_bytes(size)?,
+                    })
+                })
+                .collect::<Option<Vec<_>>>()?;
+            return Some(Self::Ranges { ranges, offset });
+        }
+        match value.split_once(',') {
+            Some(_) if offset.is_some() => None,
+            Some((size, placement)) => Some(Self::Placed {
+                size: parse_bytes(size)?,
+                placement: CrashKernelPlacement::from_name(placement)?,
+            }),
+            None => Some(Self::Size { size: parse_bytes(value)?, offset }),
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let at = |offset: &Option<u64>| offset.map(|offset| format!("@{}", format_size(offset))).unwrap_or_default();
+        match self {
+            Self::Size { size, offset } => format!("{}{}", format_size(*size), at(offset)),
+            Self::Placed { size, placement } => format!("{},{}", format_size(*size), placement.as_str()),
+            Self::Ranges { ranges, offset } => {
+                let ranges: Vec<String> = ranges.iter()
+                    .map(|range| format!(
+                        "{}-{}:{}",
+                        format_size(range.start),
+                        range.end.map(format_size).unwrap_or_default(),
+                        format_size(range.size),
+                    ))
+                    .collect();
+                format!("{}{}", ranges.join(","), at(offset))
+            }
+        }
+    }
+
+    /// The bytes reserved on a system with `memory` bytes of memory.
+    pub fn size_for_memory(&self, memory: u64) -> u64 {
+        match self {
+            Self::Size { size, .. } | Self::Placed { size, .. } => *size,
+            Self::Ranges { ranges, .. } => ranges.iter()
+                .find(|range| range.start <= memory && range.end.is_none_or(|end| memory < end))
+                .map_or(0, |range| range.size),
+        }
+    }
+
+    /// The value a [`ParameterConfig::Complex`] of its fields describes.
+    pub fn from_config(config: &ParameterConfig) -> Option<Self> {
+        match config {
+            ParameterConfig::Complex(fields) => Self::from_fields(fields).ok(),
+            _ => None,
+        }
+    }
+
+    fn from_fields(fields: &HashMap<String, ParameterConfig>) -> Result<Self, String> {
+        let fields = Fields::new(fields, &["size", "offset", "placement", "ranges"])?;
+        let offset = fields.number("offset")?;
+        match (fields.number("size")?, fields.string("placement")?, fields.list("ranges")?) {
+            (Some(size), None, None) => Ok(Self::Size { size, offset }),
+            (Some(_), Some(_), _) if offset.is_some() => Err("a placed reservation cannot have an offset".to_string()),
+            (Some(size), Some(placement), None) => Ok(Self::Placed {
+                size,
+                placement: CrashKernelPlacement::from_name(&placement)
+                    .ok_or_else(|| format!("invalid placement '{}'", placement))?,
+            }),
+            (None, None, Some(ranges)) => {
+                let joined = ranges.join(",");
+                match Self::parse(&joined) {
+                    Some(Self::Ranges { ranges, .. }) => Ok(Self::Ranges { ranges, offset }),
+                    _ => Err(format!("invalid ranges '{}'", joined)),
+                }
+            }
+            _ => Err("expected either 'size' or 'ranges'".to_string()),
+        }
+    }
+}
+
+impl From<CrashKernelSpec> for ParameterConfig {
+    fn from(spec: CrashKernelSpec) -> Self {
+        let mut fields = HashMap::new();
+        let mut number = |name: &str, bytes: u64| {
+            fields.insert(name.to_string(), Self::Integer(i64::try_from(bytes).unwrap_or(i64::MAX)));
+        };
+        match &spec {
+            CrashKernelSpec::Size { size, offset } => {
+                number("size", *size);
+                if let Some(offset) = offset {
+                    number("offset", *offset);
+                }
+            }
+            CrashKernelSpec::Placed { size, placement } => {
+                number("size", *size);
+                fields.insert("placement".to_string(), Self::String(placement.as_str().to_string()));
+            }
+            CrashKernelSpec::Ranges { ranges, offset } => {
+                if let Some(offset) = offset {
+                    number("offset", *offset);
+                }
+                let ranges = CrashKernelSpec::Ranges { ranges: ranges.clone(), offset: None }.render();
+                fields.insert("ranges".to_string(), Self::List(ranges.split(',').map(str::to_string).collect()));
+            }
+        }
+        Self::Complex(fields)
+    }
+}
+
+/// What a `memmap=` region marks memory as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemMapKind {
+    /// Usable memory, `@`.
+    Usable,
+    /// ACPI data, `#`.
+    AcpiData,
+    /// Reserved, `$`. Boot loaders such as GRUB need the `$` escaped.
+    Reserved,
+    /// Persistent memory, `!`.
+    Persistent,
+}
+
+impl MemMapKind {
+    const ALL: [(char, MemMapKind, &'static str); 4] = [
+        ('@', MemMapKind::Usable, "usable"),
+        ('#', MemMapKind::AcpiData, "acpi_data"),
+        ('$', MemMapKind::Reserved, "reserved"),
+        ('!', MemMapKind::Persistent, "persistent"),
+    ];
+
+    fn symbol(self) -> char {
+        Self::ALL.iter().find(|(_, kind, _)| *kind == self).map_or('@', |(symbol, _, _)| *symbol)
+    }
+
+    fn name(self) -> &'static str {
+        Self::ALL.iter().find(|(_, kind, _)| *kind == self).map_or("usable", |(_, _, name)| name)
+    }
+}
+
+/// A region of a `memmap=` value, `<size><kind><start>`, e.g.
+/// `64M$0x10000000`. The `%` form, which changes the type of existing
+/// memory, and `exactmap` are not regions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemMapRegion {
+    pub size: u64,
+    pub start: u64,
+    pub kind: MemMapKind,
+}
+
+impl MemMapRegion {
+    pub fn parse(value: &str) -> Option<Self> {
+        let (index, symbol) = value.char_indices().find(|(_, c)| "@#$!".contains(*c))?;
+        let (_, kind, _) = MemMapKind::ALL.iter().find(|(s, _, _)| *s == symbol)?;
+        Some(Self {
+            size: parse_memory(&value[..index])?,
+            start: parse_memory(&value[index + 1..])?,
+            kind: *kind,
+        })
+    }
+
+    /// Parse the comma separated regions of a `memmap=` value.
+    pub fn parse_list(value: &str) -> Option<Vec<Self>> {
+        value.split(',').map(Self::parse).collect()
+    }
+
+    pub fn render(&self) -> String {
+        format!("{}{}{}", format_size(self.size), self.kind.symbol(), format_address(self.start))
+    }
+
+    /// The value a [`ParameterConfig::Complex`] of its fields describes.
+    pub fn from_config(config: &ParameterConfig) -> Option<Self> {
+        match config {
+            ParameterConfig::Complex(fields) => Self::from_fields(fields).ok(),
+            _ => None,
+        }
+    }
+
+    fn from_fields(fields: &HashMap<String, ParameterConfig>) -> Result<Self, String> {
+        let fields = Fields::new(fields, &["size", "start", "kind"])?;
+        let kind = fields.string("kind")?.unwrap_or_else(|| "usable".to_string());
+        Ok(Self {
+            size: fields.number("size")?.ok_or("missing field 'size'")?,
+            start: fields.number("start")?.ok_or("missing field 'start'")?,
+            kind: MemMapKind::ALL.iter()
+                .find(|(_, _, name)| *name == kind)
+                .map(|(_, kind, _)| *kind)
+                .ok_or_else(|| format!("invalid kind '{}'", kind))?,
+        })
+    }
+}
+
+impl From<MemMapRegion> for ParameterConfig {
+    fn from(region: MemMapRegion) -> Self {
+        let number = |bytes: u64| Self::Integer(i64::try_from(bytes).unwrap_or(i64::MAX));
+        Self::Complex(HashMap::from([
+            ("size".to_string(), number(region.size)),
+            ("start".to_string(), number(region.start)),
+            ("kind".to_string(), Self::String(region.kind.name().to_string())),
+        ]))
+    }
+}
+
+/// An amount of memory or an address as the kernel's `memparse()` reads
+/// it: decimal or `0x` hexadecimal, with an optional `K`, `M`, `G` or `T`
+/// suffix in either case.
+fn parse_memory(value: &str) -> Option<u64> {
+    let shift = match value.chars().last()?.to_ascii_uppercase() {
+        'K' => 10,
+        'M' => 20,
+        'G' => 30,
+        'T' => 40,
+        _ => 0,
+    };
+    let number = if shift == 0 { value } else { &value[..value.len() - 1] };
+    let number = match number.strip_prefix("0x").or_else(|| number.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16).ok()?,
+        None => number.parse().ok()?,
+    };
+    number.checked_mul(1 << shift)
+}
+
+/// An address with a size suffix if it has one, in hexadecimal otherwise.
+fn format_address(address: u64) -> String {
+    if address != 0 && address.is_multiple_of(1 << 10) {
+        format_size(address)
+    } else {
+        format!("0x{:x}", address)
+    }
+}
+
+/// The parity of a serial console line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parity {
+    None,
+    Odd,
+    Even,
+}
+
+impl Parity {
+    fn symbol(self) -> char {
+        match self {
+            Self::None => 'n',
+            Self::Odd => 'o',
+            Self::Even => 'e',
+        }
+    }
+
+    fn from_symbol(symbol: char) -> Option<Self> {
+        match symbol {
+            'n' => Some(Self::None),
+            'o' => Some(Self::Odd),
+            'e' => Some(Self::Even),
+            _ => None,
+        }
+    }
+}
+
+/// A `console=` value: a device with optional line settings, as in
+/// `ttyS0,115200n8r`, or an early UART given by address, as in
+/// `uart8250,io,0x3f8,115200n8`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsoleSpec {
+    /// The device, e.g. `ttyS0`, or for an early UART its driver, address
+    /// space and address, e.g. `uart8250,io,0x3f8`.
+    pub device: String,
+    pub speed: Option<u32>,
+    pub parity: Option<Parity>,
+    pub data_bits: Option<u8>,
+    /// RTS/CTS flow control.
+    pub flow_control: bool,
+}
+
+impl ConsoleSpec {
+    /// A device without line settings, e.g. the virtual terminal `tty0`.
+    pub fn tty(device: &str) -> Self {
+        Self {
+            device: device.to_string(),
+            speed: None,
+            parity: None,
+            data_bits: None,
+            flow_control: false,
+        }
+    }
+
+    /// A serial port at the given speed, e.g. `ttyS0,115200`.
+    pub fn serial(device: &str, baud: u32) -> Self {
+        Self {
+            speed: Some(baud),
+            ..Self::tty(device)
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let parts: Vec<&str> = value.split(',').collect();
+        let device_parts = if parts[0].starts_with("uart") && parts.len() >= 3 { 3 } else { 1 };
+        let device = parts[..device_parts].join(",");
+        if device.is_empty() {
+            return None;
+        }
+        let mut spec = Self::tty(&device);
+        let Some(options) = parts.get(device_parts) else {
+            return Some(spec);
+        };
+        if parts.len() > device_parts + 1 {
+            return None;
+        }
+
+        let digits = options.find(|c: char| !c.is_ascii_digit()).unwrap_or(options.len());
+        let (speed, mut rest) = options.split_at(digits);
+        spec.speed = if speed.is_empty() { None } else { Some(speed.parse().ok()?) };
+        if let Some(parity) = rest.chars().next().and_then(Parity::from_symbol) {
+            spec.parity = Some(parity);
+            rest = &rest[1..];
+        }
+        if let Some(bits) = rest.chars().next().and_then(|c| c.to_digit(10)) {
+            spec.data_bits = Some(bits as u8);
+            rest = &rest[1..];
+        }
+        match rest {
+            "" => {}
+            "r" => spec.flow_control = true,
+            _ => return None,
+        }
+        Some(spec)
+    }
+
+    pub fn render(&self) -> String {
+        let mut options = self.speed.map(|speed| speed.to_string()).unwrap_or_default();
+        options.extend(self.parity.map(Parity::symbol));
+        options.extend(self.data_bits.map(|bits| char::from(b'0' + bits % 10)));
+        if self.flow_control {
+            options.push('r');
+        }
+        if options.is_empty() {
+            self.device.clone()
+        } else {
+            format!("{},{}", self.device, options)
+        }
+    }
+
+    /// The value a [`ParameterConfig::Complex`] of its fields describes.
+    pub fn from_config(config: &ParameterConfig) -> Option<Self> {
+        match config {
+            ParameterConfig::Complex(fields) => Self::from_fields(fields).ok(),
+            _ => None,
+        }
+    }
+
+    fn from_fields(fields: &HashMap<String, ParameterConfig>) -> Result<Self, String> {
+        let fields = Fields::new(fields, &["device", "speed", "parity", "data_bits", "flow_control"])?;
+        let parity = fields.string("parity")?
+            .map(|parity| {
+                let mut symbols = parity.chars();
+                match (symbols.next().and_then(Parity::from_symbol), symbols.next()) {
+                    (Some(parity), None) => Ok(parity),
+                    _ => Err(format!("invalid parity '{}'", parity)),
+                }
+            })
+            .transpose()?;
+        let data_bits = fields.number::<u8>("data_bits")?;
+        if let Some(bits) = data_bits.filter(|bits| !(5..=8).contains(bits)) {
+            return Err(format!("invalid data bits {}", bits));
+        }
+        Ok(Self {
+            device: fields.string("device")?.ok_or("missing field 'device'")?,
+            speed: fields.number("speed")?,
+            parity,
+            data_bits,
+            flow_control: fields.boolean("flow_control")?.unwrap_or(false),
+        })
+    }
+}
+
+impl From<ConsoleSpec> for ParameterConfig {
+    fn from(spec: ConsoleSpec) -> Self {
+        let mut fields = HashMap::from([("device".to_string(), Self::String(spec.device))]);
+        if let Some(speed) = spec.speed {
+            fields.insert("speed".to_string(), Self::Integer(speed.into()));
+        }
+        if let Some(parity) = spec.parity {
+            fields.insert("parity".to_string(), Self::String(parity.symbol().to_string()));
+        }
+        if let Some(bits) = spec.data_bits {
+            fields.insert("data_bits".to_string(), Self::Integer(bits.into()));
+        }
+        if spec.flow_control {
+            fields.insert("flow_control".to_string(), Self::Boolean(true));
+        }
+        Self::Complex(fields)
+    }
+}
+
+/// The fields of a [`ParameterConfig::Complex`] describing a typed value.
+struct Fields<'a>(&'a HashMap<String, ParameterConfig>);
+
+impl<'a> Fields<'a> {
+    fn new(fields: &'a HashMap<String, ParameterConfig>, known: &[&str]) -> Result<Self, String> {
+        match fields.keys().find(|name| !known.contains(&name.as_str())) {
+            Some(unknown) => Err(format!("unknown field '{}'", unknown)),
+            None => Ok(Self(fields)),
+        }
+    }
+
+    fn string(&self, name: &str) -> Result<Option<String>, String> {
+        self.0.get(name).map(|config| render_field(name, config)).transpose()
+    }
+
+    fn number<T: TryFrom<i64> + std::str::FromStr>(&self, name: &str) -> Result<Option<T>, String> {
+        let invalid = || format!("field '{}' must be a non-negative number", name);
+        match self.0.get(name) {
+            None => Ok(None),
+            Some(ParameterConfig::Integer(value)) => T::try_from(*value).map(Some).map_err(|_| invalid()),
+            Some(ParameterConfig::String(value)) => value.parse().map(Some).map_err(|_| invalid()),
+            Some(_) => Err(invalid()),
+        }
+    }
+
+    fn boolean(&self, name: &str) -> Result<Option<bool>, String> {
+        match self.0.get(name) {
+            None => Ok(None),
+            Some(ParameterConfig::Boolean(value)) => Ok(Some(*value)),
+            Some(_) => Err(format!("field '{}' must be a boolean", name)),
+        }
+    }
+
+    fn list(&self, name: &str) -> Result<Option<Vec<String>>, String> {
+        match self.0.get(name) {
+            None => Ok(None),
+            Some(ParameterConfig::List(values)) => Ok(Some(values.clone())),
+            Some(ParameterConfig::String(value)) => Ok(Some(value.split(',').map(str::to_string).collect())),
+            Some(_) => Err(format!("field '{}' must be a list", name)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cpus(value: &str) -> Option<Vec<u32>> {
+        match parse_cpu_list(value, true)? {
+            ParameterConfig::CpuList { cpus, .. } => Some(cpus),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn expands_cpu_ranges() {
+        assert_eq!(cpus("0-3,6"), Some(vec![0, 1, 2, 3, 6]));
+        assert_eq!(cpus("6,2-3,3-4"), Some(vec![2, 3, 4, 6]));
+    }
+
+    #[test]
+    fn subtracts_excluded_cpus() {
+        assert_eq!(cpus("0-9,^2-3,^7"), Some(vec![0, 1, 4, 5, 6, 8, 9]));
+        assert_eq!(cpus("0-3,^0-5"), Some(vec![]));
+        assert_eq!(cpus("nohz,domain:0-3,^1"), Some(vec![0, 2, 3]));
+    }
+
+    #[test]
+    fn rejects_cpus_past_the_largest_nr_cpus() {
+        assert_eq!(cpus("0-4000000000"), None);
+        assert_eq!(cpus("8192"), None);
+        assert_eq!(cpus("8191").map(|cpus| cpus.len()), Some(1));
+    }
 }
@@ -1,122 +1,585 @@
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::fs;
-use serde::{Serialize, Deserialize};
-use crate::parameter::{Parameter, ParameterProcessor};
-use crate::catalog::VersionInfo;
-use crate::error::DatabaseError;
+use std::io::Write;
+use std::time::UNIX_EPOCH;
+use serde::de::DeserializeOwned;
+use serde::{Serialize, Serializer, Deserialize};
+use crate::parameter::{
+    ConfigRequirement,
+    DuplicatePolicy,
+    Parameter,
+    ParameterConflict,
+    ParameterProcessor,
+    ParameterRequirement,
+    PerformanceImpact,
+    PerformanceRating,
+    SecurityImpact,
+    SecuritySeverity,
+    SyntaxDefinition,
+};
+use crate::catalog::{VendorVersion, VersionInfo};
+use crate::error::{DatabaseError, DocumentContext, SourceKind};
+use crate::export::to_yaml;
+use crate::integrity::{check_integrity, IntegrityReport};
+use crate::schema::{self, SchemaPolicy, DEFINITION_SCHEMA_VERSION};
+use crate::snapshot::checksum;
+use crate::yaml::parse_yaml;
+use crate::trust::{SignatureVerifier, TrustPolicy, PAYLOAD_HEADER, SIGNATURE_FILE};
+use crate::version::compare_versions;
 
 pub trait ParameterSource {
+    /// A name identifying the source in provenance records and errors.
+    fn source_name(&self) -> String;
     fn list_parameters(&self) -> Result<Vec<String>, DatabaseError>;
     fn get_parameter_definition(&self, name: &str) -> Result<Option<ParameterDefinitionRaw>, DatabaseError>;
     fn get_parameter_versions(&self, name: &str) -> Result<Option<ParameterVersionsRaw>, DatabaseError>;
     fn get_subparameters(&self, parent: &str) -> Result<Vec<String>, DatabaseError>;
+
+    /// Directories whose contents the source reads, for watching changes.
+    fn watch_paths(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    /// A value that changes whenever the source's documents for a parameter
+    /// change, so that reloading can skip parameters that have not. `None`
+    /// means the source cannot tell, and the parameter is always reloaded.
+    fn parameter_stamp(&self, _name: &str) -> Option<u64> {
+        None
+    }
+
+    /// Check the source's detached signature with `verifier`. Sources that
+    /// cannot be signed are untrusted.
+    fn verify(&self, _verifier: &dyn SignatureVerifier) -> Result<(), DatabaseError> {
+        Err(DatabaseError::UntrustedSource {
+            name: self.source_name(),
+            reason: "source cannot be signed".to_string(),
+        })
+    }
+}
+
+/// System-wide overlay directory of site-local parameter definitions.
+pub const SYSTEM_OVERLAY_DIR: &str = "/etc/kcmdline/parameters.d";
+
+/// Overlay directories searched for parameter definitions, in increasing
+/// order of precedence: [`SYSTEM_OVERLAY_DIR`], then the user's
+/// `$XDG_CONFIG_HOME/kcmdline/parameters.d` (`~/.config/kcmdline/...`).
+pub fn overlay_search_path() -> Vec<PathBuf> {
+    let mut dirs = vec![PathBuf::from(SYSTEM_OVERLAY_DIR)];
+    if let Some(config_home) = xdg_home("XDG_CONFIG_HOME", ".config") {
+        dirs.push(config_home.join("kcmdline").join("parameters.d"));
+    }
+    dirs
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// The user's cache directory for this library, `$XDG_CACHE_HOME/kcmdline`
+/// or `~/.cache/kcmdline`, under which each cache has a subdirectory.
+pub(crate) fn user_cache_dir() -> Option<PathBuf> {
+    xdg_home("XDG_CACHE_HOME", ".cache").map(|cache| cache.join("kcmdline"))
+}
+
+/// The XDG base directory named by the environment variable `var`, or
+/// `default` under the home directory when it is unset or empty.
+fn xdg_home(var: &str, default: &str) -> Option<PathBuf> {
+    std::env::var_os(var)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(default)))
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct ParameterDefinitionRaw {
+    /// Set to [`DEFINITION_SCHEMA_VERSION`](crate::DEFINITION_SCHEMA_VERSION)
+    /// once the document is upgraded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<u32>,
     pub name: String,
     pub processor: String,
+    /// May be omitted by overlays that only change other fields.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub deprecated: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecated_since: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replacement: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aliases: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub selectors: Option<Vec<String>>,
+    #[serde(default)]
     pub syntax: SyntaxDefinitionRaw,
+    /// Take the syntax from another parameter, overridden by the fields
+    /// set in `syntax`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inherit_syntax: Option<InheritSyntaxRaw>,
+    /// Overrides of `syntax` on particular architectures.
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "ordered_option")]
+    pub arch_syntax: Option<HashMap<String, SyntaxDefinitionRaw>>,
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "ordered_option")]
     pub distributions: Option<HashMap<String, DistributionSupportRaw>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub examples: Option<ExamplesRaw>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub documentation: Option<DocumentationLinksRaw>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duplicate_policy: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conflicts_with: Option<Vec<ParameterConflictRaw>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requires: Option<Vec<ParameterRequirementRaw>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub controls: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub security_impact: Option<Vec<SecurityImpactRaw>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub performance_impact: Option<Vec<PerformanceImpactRaw>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required_config: Option<Vec<ConfigRequirementRaw>>,
+    /// Extension fields of downstream consumers, named `x-<namespace>`, that
+    /// the library carries through without interpreting.
+    #[serde(flatten, serialize_with = "ordered")]
+    pub extra: HashMap<String, toml::Value>,
+    /// Fields of the document that the schema does not know.
+    #[serde(skip)]
+    pub unknown_fields: Vec<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct SyntaxDefinitionRaw {
-    #[serde(rename = "type")]
+    /// May be omitted by definitions that inherit their syntax.
+    #[serde(rename = "type", default, skip_serializing_if = "String::is_empty")]
     pub validator_type: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub format: String,
-    #[serde(flatten)]
+    #[serde(flatten, serialize_with = "ordered")]
     pub config: HashMap<String, toml::Value>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// The parameter a definition inherits its syntax from: `true` for the
+/// parent of a subparameter, or the key of any other parameter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum InheritSyntaxRaw {
+    Parent(bool),
+    From(String),
+}
+
+impl From<SyntaxDefinitionRaw> for SyntaxDefinition {
+    fn from(raw: SyntaxDefinitionRaw) -> Self {
+        Self {
+            validator_type: raw.validator_type,
+            format: raw.format,
+            config: raw.config,
+        }
+    }
+}
+
+impl From<&SyntaxDefinition> for SyntaxDefinitionRaw {
+    fn from(syntax: &SyntaxDefinition) -> Self {
+        Self {
+            validator_type: syntax.validator_type.clone(),
+            format: syntax.format.clone(),
+            config: syntax.config.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VendorVersionRaw {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub introduced: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub commit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ComponentVersionRaw {
     pub name: String,
     pub version: String,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VersionInfoRaw {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub introduced: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub commit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub last_modified: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub last_modified_commit: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ParameterVersionsRaw {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub mainline: Option<VersionInfoRaw>,
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "ordered_vendors")]
     pub vendors: Option<HashMap<String, HashMap<String, VendorVersionRaw>>>,
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "ordered_option")]
     pub components: Option<HashMap<String, ComponentVersionRaw>>,
+    /// Fields of the document that the schema does not know.
+    #[serde(skip)]
+    pub unknown_fields: Vec<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DistributionSupportRaw {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub min_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub max_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub component_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct ExamplesRaw {
+    #[serde(default)]
     pub valid: Vec<String>,
+    #[serde(default)]
     pub invalid: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParameterConflictRaw {
+    pub parameter: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<String>,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParameterRequirementRaw {
+    pub parameters: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub when: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SecurityImpactRaw {
+    pub severity: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub when: Vec<String>,
+    pub rationale: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PerformanceImpactRaw {
+    pub rating: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub when: Vec<String>,
+    pub effect: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigRequirementRaw {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cpu_flags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub when: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub arch: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DocumentationLinksRaw {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub kernel_org: Option<String>,
+    #[serde(default)]
     pub man_pages: Vec<String>,
+    #[serde(default, serialize_with = "ordered")]
     pub distribution_docs: HashMap<String, String>,
 }
 
 
 // Other Raw types follow similar pattern...
 
+/// Serialize a map in key order, so that written documents are stable.
+fn ordered<S: Serializer, V: Serialize>(map: &HashMap<String, V>, serializer: S) -> Result<S::Ok, S::Error> {
+    map.iter().collect::<BTreeMap<_, _>>().serialize(serializer)
+}
+
+fn ordered_option<S: Serializer, V: Serialize>(
+    map: &Option<HashMap<String, V>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    map.as_ref().map(|map| map.iter().collect::<BTreeMap<_, _>>()).serialize(serializer)
+}
+
+fn ordered_vendors<S: Serializer>(
+    vendors: &Option<HashMap<String, HashMap<String, VendorVersionRaw>>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    vendors.as_ref()
+        .map(|vendors| vendors.iter()
+            .map(|(vendor, releases)| (vendor, releases.iter().collect::<BTreeMap<_, _>>()))
+            .collect::<BTreeMap<_, _>>())
+        .serialize(serializer)
+}
+
+/// The processor name used in definitions, the inverse of
+/// `DatabaseLoader::parse_processor`.
+fn processor_name(processor: &ParameterProcessor) -> &'static str {
+    match processor {
+        ParameterProcessor::Kernel => "kernel",
+        ParameterProcessor::Systemd { .. } => "systemd",
+        ParameterProcessor::Dracut { .. } => "dracut",
+        ParameterProcessor::InitramfsTools => "initramfs-tools",
+        ParameterProcessor::Plymouth => "plymouth",
+        ParameterProcessor::Grub => "grub",
+    }
+}
+
+/// The definition of a parameter in the current schema, omitting fields
+/// that have their default value. The minimum version of a systemd or
+/// dracut processor is not part of the definition; it comes from the
+/// parameter's versions document.
+impl From<&Parameter> for ParameterDefinitionRaw {
+    fn from(parameter: &Parameter) -> Self {
+        let duplicate_policy = match parameter.duplicate_policy {
+            DuplicatePolicy::LastWins => None,
+            DuplicatePolicy::FirstWins => Some("first-wins".to_string()),
+            DuplicatePolicy::Additive => Some("additive".to_string()),
+        };
+        let examples = &parameter.examples;
+
+        Self {
+            schema_version: Some(DEFINITION_SCHEMA_VERSION),
+            name: parameter.name.clone(),
+            processor: processor_name(&parameter.processor).to_string(),
+            description: parameter.description.clone(),
+            deprecated: parameter.deprecated.then_some(true),
+            deprecated_since: parameter.deprecated_since.clone(),
+            replacement: parameter.replacement.clone(),
+            aliases: (!parameter.aliases.is_empty()).then(|| parameter.aliases.clone()),
+            selectors: (!parameter.selectors.is_empty()).then(|| parameter.selectors.clone()),
+            syntax: SyntaxDefinitionRaw::from(&parameter.syntax),
+            inherit_syntax: None,
+            arch_syntax: (!parameter.arch_syntax.is_empty()).then(|| {
+                parameter.arch_syntax.iter()
+                    .map(|(arch, syntax)| (arch.clone(), SyntaxDefinitionRaw::from(syntax)))
+                    .collect()
+            }),
+            distributions: (!parameter.distributions.is_empty()).then(|| {
+                parameter.distributions.iter()
+                    .map(|(distro, support)| (distro.clone(), DistributionSupportRaw {
+                        min_version: support.min_version.clone(),
+                        max_version: support.max_version.clone(),
+                        component_version: support.component_version.clone(),
+                        notes: support.notes.clone(),
+                    }))
+                    .collect()
+            }),
+            examples: (!examples.valid.is_empty() || !examples.invalid.is_empty()).then(|| ExamplesRaw {
+                valid: examples.valid.clone(),
+                invalid: examples.invalid.clone(),
+            }),
+            documentation: parameter.documentation.as_ref().map(|links| DocumentationLinksRaw {
+                kernel_org: links.kernel_org.clone(),
+                man_pages: links.man_pages.clone(),
+                distribution_docs: links.distribution_docs.clone(),
+            }),
+            duplicate_policy,
+            conflicts_with: (!parameter.conflicts_with.is_empty()).then(|| {
+                parameter.conflicts_with.iter()
+                    .map(|conflict| ParameterConflictRaw {
+                        parameter: conflict.parameter.clone(),
+                        values: conflict.values.clone(),
+                        reason: conflict.reason.clone(),
+                    })
+                    .collect()
+            }),
+            requires: (!parameter.requires.is_empty()).then(|| {
+                parameter.requires.iter()
+                    .map(|requirement| ParameterRequirementRaw {
+                        parameters: requirement.parameters.clone(),
+                        when: requirement.when.clone(),
+                        suggestion: requirement.suggestion.clone(),
+                        reason: requirement.reason.clone(),
+                    })
+                    .collect()
+            }),
+            controls: (!parameter.controls.is_empty()).then(|| parameter.controls.clone()),
+            security_impact: (!parameter.security_impact.is_empty()).then(|| {
+                parameter.security_impact.iter()
+                    .map(|impact| SecurityImpactRaw {
+                        severity: impact.severity.name().to_string(),
+                        when: impact.when.clone(),
+                        rationale: impact.rationale.clone(),
+                    })
+                    .collect()
+            }),
+            performance_impact: (!parameter.performance_impact.is_empty()).then(|| {
+                parameter.performance_impact.iter()
+                    .map(|impact| PerformanceImpactRaw {
+                        rating: impact.rating.name().to_string(),
+                        when: impact.when.clone(),
+                        effect: impact.effect.clone(),
+                    })
+                    .collect()
+            }),
+            required_config: (!parameter.required_config.is_empty()).then(|| {
+                parameter.required_config.iter()
+                    .map(|requirement| ConfigRequirementRaw {
+                        options: requirement.options.clone(),
+                        cpu_flags: requirement.cpu_flags.clone(),
+                        when: requirement.when.clone(),
+                        arch: requirement.arch.clone(),
+                    })
+                    .collect()
+            }),
+            extra: parameter.extra.clone(),
+            unknown_fields: Vec::new(),
+        }
+    }
+}
+
+/// How the loader combines definitions of the same parameter from several
+/// sources, which are applied in the order they were added.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub enum MergePolicy {
+    /// A later definition replaces an earlier one entirely.
+    #[default]
+    Override,
+    /// A later definition replaces only the fields it sets, so an overlay
+    /// can change e.g. `distributions` alone.
+    FieldMerge,
+    /// As `FieldMerge`, but two sources setting a field to different values
+    /// is an error.
+    ErrorOnConflict,
+}
+
+/// The source each field of a merged parameter definition came from, keyed
+/// by definition field name (`description`, `distributions`, ...).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    pub fields: BTreeMap<String, String>,
+}
+
+impl Provenance {
+    pub fn source_of(&self, field: &str) -> Option<&str> {
+        self.fields.get(field).map(String::as_str)
+    }
+}
+
+/// A parameter loaded from the sources, with its version history and
+/// provenance and the parameter it inherits its syntax from.
+type LoadedParameter = (Parameter, Option<VersionInfo>, Provenance, Option<String>);
+
+/// A parameter whose source stamps changed, with the new stamps and the
+/// parameter as now loaded, if it is still defined.
+type ChangedParameter = (String, Vec<Option<u64>>, Option<LoadedParameter>);
+
 pub struct DatabaseLoader {
     sources: Vec<Box<dyn ParameterSource>>,
+    merge_policy: MergePolicy,
+    trust_policy: TrustPolicy,
+    schema_policy: SchemaPolicy,
     cache: Option<LoadedDatabase>,
 }
 
+#[derive(Clone, Default)]
 pub struct LoadedDatabase {
-    parameters: HashMap<String, Parameter>,
-    subparameter_index: HashMap<String, Vec<String>>,
+    pub(crate) parameters: HashMap<String, Parameter>,
+    pub(crate) subparameter_index: HashMap<String, Vec<String>>,
     processor_index: HashMap<ParameterProcessor, Vec<String>>,
+    pub(crate) version_index: HashMap<String, VersionInfo>,
+    pub(crate) provenance_index: HashMap<String, Provenance>,
+    /// The source stamps of every listed parameter when it was loaded.
+    stamps: HashMap<String, Vec<Option<u64>>>,
+    /// The parameter each inheriting parameter takes its syntax from, and
+    /// the syntax it declares itself.
+    syntax_bases: HashMap<String, (String, SyntaxDefinition)>,
+}
+
+impl Default for DatabaseLoader {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl DatabaseLoader {
     pub fn new() -> Self {
         Self {
             sources: Vec::new(),
+            merge_policy: MergePolicy::default(),
+            trust_policy: TrustPolicy::default(),
+            schema_policy: SchemaPolicy::default(),
             cache: None,
         }
     }
 
+    pub fn with_merge_policy(mut self, policy: MergePolicy) -> Self {
+        self.merge_policy = policy;
+        self
+    }
+
+    /// Only load sources whose signatures the policy accepts; loading fails
+    /// if any source is rejected.
+    pub fn with_trust_policy(mut self, policy: TrustPolicy) -> Self {
+        self.trust_policy = policy;
+        self
+    }
+
+    /// Whether definitions with fields the schema does not know are
+    /// rejected.
+    pub fn with_schema_policy(mut self, policy: SchemaPolicy) -> Self {
+        self.schema_policy = policy;
+        self
+    }
+
     pub fn with_embedded(mut self) -> Self {
         self.sources.push(Box::new(EmbeddedDatabase::new()));
         self
     }
 
+    /// Add another source of parameter definitions, such as module
+    /// parameters harvested with `ModuleParameterSource`.
+    pub fn with_source<S: ParameterSource + 'static>(mut self, source: S) -> Self {
+        self.sources.push(Box::new(source));
+        self
+    }
+
     pub fn with_filesystem<P: AsRef<Path>>(mut self, path: P) -> Result<Self, DatabaseError> {
         let fs_source = FilesystemDatabase::new(path)?;
         self.sources.push(Box::new(fs_source));
         Ok(self)
     }
 
+    /// Layer the definitions in an overlay directory over the sources added
+    /// so far.
+    pub fn with_overlay<P: AsRef<Path>>(mut self, path: P) -> Result<Self, DatabaseError> {
+        self.sources.push(Box::new(FilesystemDatabase::overlay(path)?));
+        Ok(self)
+    }
+
+    /// Layer each existing directory of [`overlay_search_path`] over the
+    /// sources added so far.
+    pub fn with_overlays(mut self) -> Result<Self, DatabaseError> {
+        for dir in overlay_search_path().into_iter().filter(|dir| dir.is_dir()) {
+            self = self.with_overlay(dir)?;
+        }
+        Ok(self)
+    }
+
+    /// Directories read by the loader's sources.
+    pub fn watch_paths(&self) -> Vec<PathBuf> {
+        self.sources.iter().flat_map(|source| source.watch_paths()).collect()
+    }
+
     pub fn load(&mut self) -> Result<&LoadedDatabase, DatabaseError> {
         if self.cache.is_none() {
             self.cache = Some(self.build_database()?);
@@ -124,100 +587,315 @@ impl DatabaseLoader {
         Ok(self.cache.as_ref().unwrap())
     }
 
+    /// Consume the loader, returning the loaded database.
+    pub fn into_database(mut self) -> Result<LoadedDatabase, DatabaseError> {
+        match self.cache.take() {
+            Some(database) => Ok(database),
+            None => self.build_database(),
+        }
+    }
+
+    /// Reload the parameters whose documents changed since the last load,
+    /// keeping the rest of the loaded database. If the changed definitions
+    /// fail to load, the error is returned and the previous database is
+    /// kept.
     pub fn reload(&mut self) -> Result<&LoadedDatabase, DatabaseError> {
-        self.cache = None;
-        self.load()
+        let Some(mut database) = self.cache.take() else {
+            return self.load();
+        };
+        let result = self.update_database(&mut database);
+        self.cache = Some(database);
+        result?;
+        Ok(self.cache.as_ref().unwrap())
     }
 
     fn build_database(&self) -> Result<LoadedDatabase, DatabaseError> {
-        let mut all_param_names = std::collections::HashSet::new();
-        let mut parameters = HashMap::new();
-        let mut subparameter_index = HashMap::new();
+        let mut database = LoadedDatabase::default();
+        self.update_database(&mut database)?;
+        Ok(database)
+    }
+
+    /// Bring `database` up to date with the sources, loading only the
+    /// parameters whose [stamps](ParameterSource::parameter_stamp) changed.
+    /// Returns the number of parameters reloaded or removed.
+    fn update_database(&self, database: &mut LoadedDatabase) -> Result<usize, DatabaseError> {
+        if let TrustPolicy::RequireSigned(verifier) = &self.trust_policy {
+            for source in &self.sources {
+                source.verify(verifier.as_ref())?;
+            }
+        }
 
         // Collect all parameter names from all sources
+        let mut all_param_names = HashSet::new();
         for source in &self.sources {
             for name in source.list_parameters()? {
                 all_param_names.insert(name);
             }
         }
 
-        // Load each parameter, with later sources overriding earlier ones
-        for param_name in all_param_names {
-            if let Some(parameter) = self.load_parameter(&param_name)? {
-                // Build subparameter relationships
-                if let Some(parent) = self.get_parent_parameter(&param_name) {
-                    subparameter_index
-                        .entry(parent)
-                        .or_insert_with(Vec::new)
-                        .push(param_name.clone());
+        // Load every changed parameter before touching the database, so that
+        // it is left as it was if one fails
+        let mut changed = Vec::new();
+        for param_name in &all_param_names {
+            let stamp: Vec<Option<u64>> = self.sources.iter()
+                .map(|source| source.parameter_stamp(param_name))
+                .collect();
+            let unchanged = stamp.iter().all(Option::is_some) && database.stamps.get(param_name) == Some(&stamp);
+            if !unchanged {
+                changed.push((param_name.clone(), stamp, self.load_parameter(param_name)?));
+            }
+        }
+        let removed: Vec<String> = database.stamps.keys()
+            .filter(|name| !all_param_names.contains(*name))
+            .cloned()
+            .collect();
+        let inherited = database.resolve_syntax(&removed, &changed)?;
+
+        let mut affected_parents = HashSet::new();
+        for name in removed.iter().chain(changed.iter().map(|(name, _, _)| name)) {
+            database.remove_parameter(name);
+            affected_parents.insert(name.clone());
+            affected_parents.extend(parent_path(name).map(str::to_string));
+        }
+        let count = removed.len() + changed.len();
+        for (name, stamp, loaded) in changed {
+            if let Some((parameter, versions, provenance, syntax_base)) = loaded {
+                if let Some(base) = syntax_base {
+                    database.syntax_bases.insert(name.clone(), (base, parameter.syntax.clone()));
                 }
-
-                parameters.insert(param_name, parameter);
+                database.insert_parameter(&name, parameter, versions, provenance);
             }
+            database.stamps.insert(name, stamp);
         }
-
-        // Build processor index
-        let mut processor_index: HashMap<ParameterProcessor, Vec<String>> = HashMap::new();
-        for (name, param) in &parameters {
-            processor_index
-                .entry(param.processor.clone())
-                .or_insert_with(Vec::new)
-                .push(name.clone());
+        for (name, syntax) in inherited {
+            if let Some(parameter) = database.parameters.get_mut(&name) {
+                parameter.syntax = syntax;
+            }
         }
-
-        Ok(LoadedDatabase {
-            parameters,
-            subparameter_index,
-            processor_index,
-        })
+        database.index_subparameters(&affected_parents);
+        Ok(count)
     }
 
-    fn load_parameter(&self, name: &str) -> Result<Option<Parameter>, DatabaseError> {
-        let mut definition: Option<ParameterDefinitionRaw> = None;
+    fn load_parameter(
+        &self,
+        name: &str,
+    ) -> Result<Option<LoadedParameter>, DatabaseError> {
+        let mut definition: Option<(ParameterDefinitionRaw, Provenance)> = None;
         let mut versions: Option<ParameterVersionsRaw> = None;
 
         // Load from sources in order, later sources override earlier ones
         for source in &self.sources {
             if let Some(def) = source.get_parameter_definition(name)? {
-                definition = Some(def);
+                self.check_unknown_fields(&def.unknown_fields, || {
+                    format!("definition of {} from {}", name, source.source_name())
+                })?;
+                let (merged, provenance) = match definition.take() {
+                    Some(previous) if self.merge_policy != MergePolicy::Override => previous,
+                    _ => Default::default(),
+                };
+                definition = Some(self.merge_definition(name, merged, provenance, def, &source.source_name())?);
             }
             if let Some(ver) = source.get_parameter_versions(name)? {
+                self.check_unknown_fields(&ver.unknown_fields, || {
+                    format!("versions of {} from {}", name, source.source_name())
+                })?;
                 versions = Some(ver);
             }
         }
 
-        if let Some(def) = definition {
-            let parameter = self.convert_raw_parameter(def, versions)?;
-            Ok(Some(parameter))
+        if let Some((def, provenance)) = definition {
+            let syntax_base = match &def.inherit_syntax {
+                None | Some(InheritSyntaxRaw::Parent(false)) => None,
+                Some(InheritSyntaxRaw::Parent(true)) => Some(parent_path(name)
+                    .ok_or_else(|| DatabaseError::FormatError(format!(
+                        "{} inherits the syntax of its parent but is not a subparameter", name,
+                    )))?
+                    .to_string()),
+                Some(InheritSyntaxRaw::From(base)) => Some(base.clone()),
+            };
+            let mut parameter = self.convert_raw_parameter(def)?;
+            if let Some(raw) = &versions {
+                Self::apply_component_version(&mut parameter.processor, raw);
+            }
+            let versions = versions.map(|raw| self.convert_versions(raw));
+            Ok(Some((parameter, versions, provenance, syntax_base)))
         } else {
             Ok(None)
         }
     }
 
+    fn check_unknown_fields<F: FnOnce() -> String>(&self, fields: &[String], document: F) -> Result<(), DatabaseError> {
+        if fields.is_empty() || self.schema_policy == SchemaPolicy::Lenient {
+            return Ok(());
+        }
+        Err(DatabaseError::UnknownFields {
+            document: document(),
+            fields: fields.to_vec(),
+        })
+    }
+
+    /// Apply the fields set by `def` on top of `merged`, recording their
+    /// source.
+    fn merge_definition(
+        &self,
+        name: &str,
+        mut merged: ParameterDefinitionRaw,
+        mut provenance: Provenance,
+        def: ParameterDefinitionRaw,
+        source: &str,
+    ) -> Result<(ParameterDefinitionRaw, Provenance), DatabaseError> {
+        let mut merger = FieldMerger {
+            policy: self.merge_policy,
+            param: name,
+            source,
+            provenance: &mut provenance,
+        };
+
+        merger.merge("name", &mut merged.name, def.name, true)?;
+        merger.merge("processor", &mut merged.processor, def.processor, true)?;
+        let set = !def.description.is_empty();
+        merger.merge("description", &mut merged.description, def.description, set)?;
+        let set = def.deprecated.is_some();
+        merger.merge("deprecated", &mut merged.deprecated, def.deprecated, set)?;
+        let set = def.deprecated_since.is_some();
+        merger.merge("deprecated_since", &mut merged.deprecated_since, def.deprecated_since, set)?;
+        let set = def.replacement.is_some();
+        merger.merge("replacement", &mut merged.replacement, def.replacement, set)?;
+        let set = def.aliases.is_some();
+        merger.merge("aliases", &mut merged.aliases, def.aliases, set)?;
+        let set = def.selectors.is_some();
+        merger.merge("selectors", &mut merged.selectors, def.selectors, set)?;
+        let set = def.syntax != SyntaxDefinitionRaw::default();
+        merger.merge("syntax", &mut merged.syntax, def.syntax, set)?;
+        let set = def.inherit_syntax.is_some();
+        merger.merge("inherit_syntax", &mut merged.inherit_syntax, def.inherit_syntax, set)?;
+        let set = def.arch_syntax.is_some();
+        merger.merge("arch_syntax", &mut merged.arch_syntax, def.arch_syntax, set)?;
+        let set = def.distributions.is_some();
+        merger.merge("distributions", &mut merged.distributions, def.distributions, set)?;
+        let set = def.examples.is_some();
+        merger.merge("examples", &mut merged.examples, def.examples, set)?;
+        let set = def.documentation.is_some();
+        merger.merge("documentation", &mut merged.documentation, def.documentation, set)?;
+        let set = def.duplicate_policy.is_some();
+        merger.merge("duplicate_policy", &mut merged.duplicate_policy, def.duplicate_policy, set)?;
+        let set = def.conflicts_with.is_some();
+        merger.merge("conflicts_with", &mut merged.conflicts_with, def.conflicts_with, set)?;
+        let set = def.requires.is_some();
+        merger.merge("requires", &mut merged.requires, def.requires, set)?;
+        let set = def.controls.is_some();
+        merger.merge("controls", &mut merged.controls, def.controls, set)?;
+        let set = def.security_impact.is_some();
+        merger.merge("security_impact", &mut merged.security_impact, def.security_impact, set)?;
+        let set = def.performance_impact.is_some();
+        merger.merge("performance_impact", &mut merged.performance_impact, def.performance_impact, set)?;
+        let set = def.required_config.is_some();
+        merger.merge("required_config", &mut merged.required_config, def.required_config, set)?;
+        // Each extension namespace merges as a field of its own.
+        for (namespace, value) in def.extra.into_iter().collect::<BTreeMap<_, _>>() {
+            let current = merged.extra.entry(namespace.clone()).or_insert_with(|| value.clone());
+            merger.merge(&namespace, current, value, true)?;
+        }
+
+        Ok((merged, provenance))
+    }
+
     fn convert_raw_parameter(
         &self,
         raw_def: ParameterDefinitionRaw,
-        _raw_versions: Option<ParameterVersionsRaw>,
     ) -> Result<Parameter, DatabaseError> {
         let processor = self.parse_processor(&raw_def.processor)?;
+        let duplicate_policy = self.parse_duplicate_policy(raw_def.duplicate_policy.as_deref())?;
 
         Ok(Parameter {
             name: raw_def.name,
             processor,
             description: raw_def.description,
             deprecated: raw_def.deprecated.unwrap_or(false),
+            deprecated_since: raw_def.deprecated_since,
+            replacement: raw_def.replacement,
+            aliases: raw_def.aliases.unwrap_or_default(),
             selectors: raw_def.selectors.unwrap_or_default(),
-            syntax: crate::parameter::SyntaxDefinition {
-                validator_type: raw_def.syntax.validator_type,
-                format: raw_def.syntax.format,
-                config: raw_def.syntax.config,
-            },
+            syntax: raw_def.syntax.into(),
+            arch_syntax: raw_def.arch_syntax.unwrap_or_default()
+                .into_iter()
+                .map(|(arch, syntax)| (arch, syntax.into()))
+                .collect(),
             distributions: self.convert_distributions(raw_def.distributions)?,
             examples: self.convert_examples(raw_def.examples)?,
             documentation: self.convert_documentation(raw_def.documentation)?,
+            duplicate_policy,
+            conflicts_with: raw_def.conflicts_with.unwrap_or_default()
+                .into_iter()
+                .map(|conflict| ParameterConflict {
+                    parameter: conflict.parameter,
+                    values: conflict.values,
+                    reason: conflict.reason,
+                })
+                .collect(),
+            requires: raw_def.requires.unwrap_or_default()
+                .into_iter()
+                .map(|requirement| ParameterRequirement {
+                    parameters: requirement.parameters,
+                    when: requirement.when,
+                    suggestion: requirement.suggestion,
+                    reason: requirement.reason,
+                })
+                .collect(),
+            controls: raw_def.controls.unwrap_or_default(),
+            security_impact: self.convert_security_impact(raw_def.security_impact)?,
+            performance_impact: self.convert_performance_impact(raw_def.performance_impact)?,
+            required_config: raw_def.required_config.unwrap_or_default()
+                .into_iter()
+                .map(|requirement| ConfigRequirement {
+                    options: requirement.options,
+                    cpu_flags: requirement.cpu_flags,
+                    when: requirement.when,
+                    arch: requirement.arch,
+                })
+                .collect(),
+            extra: raw_def.extra,
         })
     }
 
+    fn convert_versions(&self, raw: ParameterVersionsRaw) -> VersionInfo {
+        let mainline = raw.mainline;
+        let vendors = raw.vendors.unwrap_or_default()
+            .into_iter()
+            .map(|(vendor, releases)| {
+                let releases = releases.into_iter()
+                    .map(|(release, v)| (release, VendorVersion {
+                        introduced: v.introduced,
+                        commit: v.commit,
+                        notes: v.notes,
+                    }))
+                    .collect();
+                (vendor, releases)
+            })
+            .collect();
+
+        VersionInfo {
+            introduced: mainline.as_ref().and_then(|m| m.introduced.clone()),
+            commit: mainline.as_ref().and_then(|m| m.commit.clone()),
+            last_modified: mainline.as_ref().and_then(|m| m.last_modified.clone()),
+            last_modified_commit: mainline.and_then(|m| m.last_modified_commit),
+            vendors,
+        }
+    }
+
+    /// Use the release of systemd or dracut a parameter first appeared in,
+    /// when the versions file records it, as the processor's minimum version.
+    fn apply_component_version(processor: &mut ParameterProcessor, versions: &ParameterVersionsRaw) {
+        let (component, min_version) = match processor {
+            ParameterProcessor::Systemd { min_version } => ("systemd", min_version),
+            ParameterProcessor::Dracut { min_version } => ("dracut", min_version),
+            _ => return,
+        };
+        if let Some(version) = versions.components.as_ref().and_then(|c| c.get(component)) {
+            *min_version = version.version.clone();
+        }
+    }
+
     fn parse_processor(&self, processor_str: &str) -> Result<ParameterProcessor, DatabaseError> {
         match processor_str {
             "kernel" => Ok(ParameterProcessor::Kernel),
@@ -230,29 +908,387 @@ impl DatabaseLoader {
         }
     }
 
-    fn get_parent_parameter(&self, param_name: &str) -> Option<String> {
-        // Extract parent from path like "pci/resource_alignment" -> "pci"
-        param_name.rfind('/').map(|idx| param_name[..idx].to_string())
+    fn parse_duplicate_policy(&self, policy_str: Option<&str>) -> Result<DuplicatePolicy, DatabaseError> {
+        match policy_str {
+            None | Some("last-wins") => Ok(DuplicatePolicy::LastWins),
+            Some("first-wins") => Ok(DuplicatePolicy::FirstWins),
+            Some("additive") => Ok(DuplicatePolicy::Additive),
+            Some(other) => Err(DatabaseError::FormatError(format!("Unknown duplicate policy: {}", other))),
+        }
+    }
+
+    fn convert_security_impact(&self, raw: Option<Vec<SecurityImpactRaw>>) -> Result<Vec<SecurityImpact>, DatabaseError> {
+        raw.unwrap_or_default()
+            .into_iter()
+            .map(|impact| Ok(SecurityImpact {
+                severity: SecuritySeverity::from_name(&impact.severity).ok_or_else(|| {
+                    DatabaseError::FormatError(format!("Unknown security severity: {}", impact.severity))
+                })?,
+                when: impact.when,
+                rationale: impact.rationale,
+            }))
+            .collect()
+    }
+
+    fn convert_performance_impact(&self, raw: Option<Vec<PerformanceImpactRaw>>) -> Result<Vec<PerformanceImpact>, DatabaseError> {
+        raw.unwrap_or_default()
+            .into_iter()
+            .map(|impact| Ok(PerformanceImpact {
+                rating: PerformanceRating::from_name(&impact.rating).ok_or_else(|| {
+                    DatabaseError::FormatError(format!("Unknown performance rating: {}", impact.rating))
+                })?,
+                when: impact.when,
+                effect: impact.effect,
+            }))
+            .collect()
     }
 
     // Helper methods for converting raw types to final types...
-    fn convert_distributions(&self, _raw: Option<HashMap<String, DistributionSupportRaw>>) -> Result<HashMap<String, crate::parameter::DistributionSupport>, DatabaseError> {
-        // Implementation
-        todo!()
+    fn convert_distributions(&self, raw: Option<HashMap<String, DistributionSupportRaw>>) -> Result<HashMap<String, crate::parameter::DistributionSupport>, DatabaseError> {
+        // Empty strings are treated as absent, as generated documents
+        // leave unknown versions blank
+        let present = |value: Option<String>| value.filter(|value| !value.trim().is_empty());
+
+        raw.unwrap_or_default()
+            .into_iter()
+            .map(|(distro, support)| {
+                let min_version = present(support.min_version);
+                let max_version = present(support.max_version);
+                if let (Some(min), Some(max)) = (&min_version, &max_version) {
+                    if compare_versions(min, max) == Ordering::Greater {
+                        return Err(DatabaseError::FormatError(format!(
+                            "Distribution {}: min_version {} is later than max_version {}", distro, min, max,
+                        )));
+                    }
+                }
+                Ok((distro, crate::parameter::DistributionSupport {
+                    min_version,
+                    max_version,
+                    component_version: present(support.component_version),
+                    notes: present(support.notes),
+                }))
+            })
+            .collect()
+    }
+
+    fn convert_examples(&self, raw: Option<ExamplesRaw>) -> Result<crate::parameter::Examples, DatabaseError> {
+        let raw = raw.unwrap_or_default();
+        Ok(crate::parameter::Examples {
+            valid: raw.valid,
+            invalid: raw.invalid,
+        })
     }
 
-    fn convert_examples(&self, _raw: Option<ExamplesRaw>) -> Result<crate::parameter::Examples, DatabaseError> {
-        // Implementation
-        todo!()
+    /// Links are `None` when the section is absent or empty.
+    fn convert_documentation(&self, raw: Option<DocumentationLinksRaw>) -> Result<Option<crate::parameter::DocumentationLinks>, DatabaseError> {
+        let Some(raw) = raw else {
+            return Ok(None);
+        };
+        let kernel_org = raw.kernel_org.filter(|url| !url.trim().is_empty());
+        if kernel_org.is_none() && raw.man_pages.is_empty() && raw.distribution_docs.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(crate::parameter::DocumentationLinks {
+            kernel_org,
+            man_pages: raw.man_pages,
+            distribution_docs: raw.distribution_docs,
+        }))
     }
+}
 
-    fn convert_documentation(&self, _raw: Option<DocumentationLinksRaw>) -> Result<Option<crate::parameter::DocumentationLinks>, DatabaseError> {
-        // Implementation
-        todo!()
+/// Parse `content` in `format`, pointing syntax errors at their position.
+fn parse_document(
+    content: &str,
+    format: DocumentFormat,
+    context: &DocumentContext,
+) -> Result<serde_json::Value, DatabaseError> {
+    match format {
+        DocumentFormat::Toml => toml::from_str(content).map_err(|e| toml_error(e, content, context)),
+        DocumentFormat::Json => serde_json::from_str(content).map_err(|e| json_error(e, content, context)),
+        DocumentFormat::Yaml => parse_yaml(content).map_err(|e| {
+            let context = match e.line {
+                Some(line) => context.clone().at(content, line, e.column),
+                None => context.clone(),
+            };
+            DatabaseError::InvalidDocument {
+                context: Box::new(context),
+                message: e.message,
+            }
+        }),
+    }
+}
+
+fn parse_definition_document(
+    content: &str,
+    format: DocumentFormat,
+    context: &DocumentContext,
+) -> Result<ParameterDefinitionRaw, DatabaseError> {
+    let value = parse_document(content, format, context)?;
+    schema::parse_definition(context, value).map_err(|e| locate::<ParameterDefinitionRaw>(e, content, format))
+}
+
+fn parse_versions_document(
+    content: &str,
+    format: DocumentFormat,
+    context: &DocumentContext,
+) -> Result<ParameterVersionsRaw, DatabaseError> {
+    let value = parse_document(content, format, context)?;
+    schema::parse_versions(context, value).map_err(|e| locate::<ParameterVersionsRaw>(e, content, format))
+}
+
+/// Point an error the schema reports without a position, such as a value
+/// of the wrong type, at its position by deserializing the text directly.
+/// The position is only used if that reports the same error.
+fn locate<T: DeserializeOwned>(error: DatabaseError, content: &str, format: DocumentFormat) -> DatabaseError {
+    let DatabaseError::InvalidDocument { context, message } = &error else {
+        return error;
+    };
+    if context.line.is_some() {
+        return error;
+    }
+    let located = match format {
+        DocumentFormat::Toml => toml::from_str::<T>(content).err().map(|e| toml_error(e, content, context)),
+        DocumentFormat::Json => serde_json::from_str::<T>(content).err().map(|e| json_error(e, content, context)),
+        DocumentFormat::Yaml => None,
+    };
+    match located {
+        Some(DatabaseError::InvalidDocument { message: located_message, .. }) if located_message != *message => error,
+        Some(located) => located,
+        None => error,
+    }
+}
+
+fn toml_error(error: toml::de::Error, content: &str, context: &DocumentContext) -> DatabaseError {
+    let context = match error.span() {
+        Some(span) => context.clone().at_offset(content, span.start),
+        None => context.clone(),
+    };
+    DatabaseError::InvalidDocument {
+        context: Box::new(context),
+        message: error.message().trim_end().to_string(),
+    }
+}
+
+fn json_error(error: serde_json::Error, content: &str, context: &DocumentContext) -> DatabaseError {
+    let message = error.to_string();
+    if error.line() == 0 {
+        return DatabaseError::InvalidDocument { context: Box::new(context.clone()), message };
+    }
+    let position = format!(" at line {} column {}", error.line(), error.column());
+    DatabaseError::InvalidDocument {
+        context: Box::new(context.clone().at(content, error.line(), Some(error.column()))),
+        message: message.strip_suffix(&position).unwrap_or(&message).to_string(),
+    }
+}
+
+/// Update `existing` to the entries of `updated`, keeping the formatting
+/// and comments of the entries whose values do not change.
+fn merge_toml(existing: &mut toml_edit::Table, updated: &toml_edit::Table) {
+    existing.retain(|key, _| updated.contains_key(key));
+    for (key, item) in updated.iter() {
+        match (existing.get_mut(key), item) {
+            (Some(toml_edit::Item::Table(current)), toml_edit::Item::Table(item)) => merge_toml(current, item),
+            (Some(current), _) => {
+                if toml_value(current).is_none() || toml_value(current) != toml_value(item) {
+                    *current = item.clone();
+                }
+            }
+            (None, _) => {
+                existing.insert(key, item.clone());
+            }
+        }
+    }
+}
+
+/// The value of a key/value item, ignoring its formatting.
+fn toml_value(item: &toml_edit::Item) -> Option<toml::Value> {
+    let value = item.as_value()?;
+    let mut table: toml::Table = toml::from_str(&format!("value = {}", value)).ok()?;
+    table.remove("value")
+}
+
+/// Write `path`, creating its directory, and replace any existing file
+/// atomically so that a loader never reads a partial document.
+fn write_replacing(path: &Path, content: &[u8]) -> Result<(), DatabaseError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+
+    fs::write(&temp_path, content)
+        .and_then(|_| fs::rename(&temp_path, path))
+        .map_err(|e| {
+            let _ = fs::remove_file(&temp_path);
+            DatabaseError::from(e)
+        })
+}
+
+/// The parent of a parameter path, e.g. `kernel/pci` for
+/// `kernel/pci/resource_alignment`.
+fn parent_path(param_name: &str) -> Option<&str> {
+    param_name.rfind('/').map(|idx| &param_name[..idx])
+}
+
+/// Resolve the syntax of `name` through the parameters it inherits from,
+/// following `chain` to detect cycles.
+fn inherit_syntax<'a>(
+    name: &'a str,
+    declared: &HashMap<&'a str, (Option<&'a str>, &SyntaxDefinition)>,
+    resolved: &mut HashMap<String, SyntaxDefinition>,
+    chain: &mut Vec<&'a str>,
+) -> Result<SyntaxDefinition, DatabaseError> {
+    if let Some(syntax) = resolved.get(name) {
+        return Ok(syntax.clone());
+    }
+    let (base, syntax) = declared[name];
+    let Some(base) = base else {
+        return Ok(syntax.clone());
+    };
+    if let Some(start) = chain.iter().position(|other| *other == name) {
+        let mut cycle: Vec<String> = chain[start..].iter().map(|other| other.to_string()).collect();
+        cycle.push(name.to_string());
+        return Err(DatabaseError::SyntaxInheritanceCycle(cycle));
+    }
+    if !declared.contains_key(base) {
+        return Err(DatabaseError::MissingSyntaxBase {
+            param: name.to_string(),
+            base: base.to_string(),
+        });
+    }
+
+    chain.push(name);
+    let inherited = inherit_syntax(base, declared, resolved, chain)?;
+    chain.pop();
+    let syntax = syntax.overriding(&inherited);
+    resolved.insert(name.to_string(), syntax.clone());
+    Ok(syntax)
+}
+
+/// Applies the fields of one definition to a merged definition under a
+/// merge policy.
+struct FieldMerger<'a> {
+    policy: MergePolicy,
+    param: &'a str,
+    source: &'a str,
+    provenance: &'a mut Provenance,
+}
+
+impl FieldMerger<'_> {
+    fn merge<T: PartialEq>(&mut self, field: &str, current: &mut T, incoming: T, set: bool) -> Result<(), DatabaseError> {
+        if !set {
+            return Ok(());
+        }
+        if self.policy == MergePolicy::ErrorOnConflict {
+            if let Some(first) = self.provenance.fields.get(field) {
+                if *current != incoming {
+                    return Err(DatabaseError::MergeConflict {
+                        param: self.param.to_string(),
+                        field: field.to_string(),
+                        first: first.clone(),
+                        second: self.source.to_string(),
+                    });
+                }
+            }
+        }
+
+        *current = incoming;
+        self.provenance.fields.insert(field.to_string(), self.source.to_string());
+        Ok(())
     }
 }
 
 impl LoadedDatabase {
+    fn insert_parameter(
+        &mut self,
+        name: &str,
+        parameter: Parameter,
+        versions: Option<VersionInfo>,
+        provenance: Provenance,
+    ) {
+        self.processor_index.entry(parameter.processor.clone()).or_default().push(name.to_string());
+        if let Some(versions) = versions {
+            self.version_index.insert(name.to_string(), versions);
+        }
+        self.provenance_index.insert(name.to_string(), provenance);
+        self.parameters.insert(name.to_string(), parameter);
+    }
+
+    fn remove_parameter(&mut self, name: &str) {
+        if let Some(parameter) = self.parameters.remove(name) {
+            if let Some(names) = self.processor_index.get_mut(&parameter.processor) {
+                names.retain(|other| other != name);
+                if names.is_empty() {
+                    self.processor_index.remove(&parameter.processor);
+                }
+            }
+        }
+        self.version_index.remove(name);
+        self.provenance_index.remove(name);
+        self.stamps.remove(name);
+        self.syntax_bases.remove(name);
+    }
+
+    /// The syntax of every inheriting parameter once the `removed` and
+    /// `changed` parameters are applied, checked before the database is
+    /// modified so that it is left as it was if inheritance is broken.
+    fn resolve_syntax(
+        &self,
+        removed: &[String],
+        changed: &[ChangedParameter],
+    ) -> Result<HashMap<String, SyntaxDefinition>, DatabaseError> {
+        let replaced: HashSet<&str> = removed.iter()
+            .chain(changed.iter().map(|(name, _, _)| name))
+            .map(String::as_str)
+            .collect();
+
+        let mut declared: HashMap<&str, (Option<&str>, &SyntaxDefinition)> = HashMap::new();
+        for (name, parameter) in &self.parameters {
+            if replaced.contains(name.as_str()) {
+                continue;
+            }
+            let syntax = match self.syntax_bases.get(name) {
+                Some((base, syntax)) => (Some(base.as_str()), syntax),
+                None => (None, &parameter.syntax),
+            };
+            declared.insert(name, syntax);
+        }
+        for (name, _, loaded) in changed {
+            if let Some((parameter, _, _, base)) = loaded {
+                declared.insert(name, (base.as_deref(), &parameter.syntax));
+            }
+        }
+
+        let mut inheriting: Vec<&str> = declared.iter()
+            .filter(|(_, (base, _))| base.is_some())
+            .map(|(name, _)| *name)
+            .collect();
+        inheriting.sort_unstable();
+
+        let mut resolved = HashMap::new();
+        for name in inheriting {
+            inherit_syntax(name, &declared, &mut resolved, &mut Vec::new())?;
+        }
+        Ok(resolved)
+    }
+
+    /// Rebuild the subparameter lists of `parents`. Only listed parameters
+    /// are parents: the top-level directories group parameters by processor
+    /// and are not parameters themselves.
+    fn index_subparameters(&mut self, parents: &HashSet<String>) {
+        for parent in parents {
+            self.subparameter_index.remove(parent);
+        }
+        for name in self.parameters.keys() {
+            if let Some(parent) = parent_path(name)
+                .filter(|parent| parents.contains(*parent) && self.stamps.contains_key(*parent))
+            {
+                self.subparameter_index.entry(parent.to_string()).or_default().push(name.clone());
+            }
+        }
+    }
+
     pub fn get_parameter(&self, name: &str) -> Option<&Parameter> {
         self.parameters.get(name)
     }
@@ -282,109 +1318,365 @@ impl LoadedDatabase {
     pub fn all_parameters(&self) -> impl Iterator<Item = &Parameter> {
         self.parameters.values()
     }
+
+    pub fn version_info(&self, name: &str) -> Option<&VersionInfo> {
+        self.version_index.get(name)
+    }
+
+    /// Which source each field of a parameter's definition came from.
+    pub fn provenance(&self, name: &str) -> Option<&Provenance> {
+        self.provenance_index.get(name)
+    }
+
+    /// Find subparameters without a parent, selectors in unknown namespaces
+    /// and aliases that do not resolve.
+    pub fn integrity_report(&self) -> IntegrityReport {
+        check_integrity(self)
+    }
 }
 
 // Embedded database source (uses build.rs generated data)
 pub struct EmbeddedDatabase {
-    // This would be populated by build.rs
+    names: Vec<&'static str>,
+    definitions: HashMap<&'static str, &'static str>,
+    versions: HashMap<&'static str, &'static str>,
 }
 
 impl EmbeddedDatabase {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            names: include!(concat!(env!("OUT_DIR"), "/parameter_names.rs")),
+            definitions: include!(concat!(env!("OUT_DIR"), "/compiled_db.rs")),
+            versions: include!(concat!(env!("OUT_DIR"), "/compiled_versions.rs")),
+        }
+    }
+}
+
+impl EmbeddedDatabase {
+    /// Errors name the file in the source tree the document was built from.
+    fn context(name: &str, file: &str) -> DocumentContext {
+        DocumentContext::new(SourceKind::Embedded)
+            .with_path(format!("database/parameters/{}/{}", name, file))
+            .with_parameter(name)
     }
 }
 
 impl ParameterSource for EmbeddedDatabase {
+    fn source_name(&self) -> String {
+        "embedded".to_string()
+    }
+
+    /// Compiled into the library, so as trusted as the library itself.
+    fn verify(&self, _verifier: &dyn SignatureVerifier) -> Result<(), DatabaseError> {
+        Ok(())
+    }
+
     fn list_parameters(&self) -> Result<Vec<String>, DatabaseError> {
-        // Return compiled parameter list
-        Ok(include!(concat!(env!("OUT_DIR"), "/parameter_names.rs")))
+        Ok(self.names.iter().map(|name| name.to_string()).collect())
     }
 
-    fn get_parameter_definition(&self, name: &str) -> Result<Option<ParameterDefinitionRaw>, DatabaseError> {
-        // Load from embedded data
-        let definitions: HashMap<&str, &str> = include!(concat!(env!("OUT_DIR"), "/compiled_db.rs"));
+    /// Compiled in, so never changes.
+    fn parameter_stamp(&self, _name: &str) -> Option<u64> {
+        Some(0)
+    }
 
-        if let Some(toml_str) = definitions.get(name) {
-            let def: ParameterDefinitionRaw = toml::from_str(toml_str)
-                .map_err(|e| DatabaseError::FormatError(format!("Parse error for {}: {}", name, e)))?;
-            Ok(Some(def))
+    fn get_parameter_definition(&self, name: &str) -> Result<Option<ParameterDefinitionRaw>, DatabaseError> {
+        if let Some(toml_str) = self.definitions.get(name) {
+            let context = Self::context(name, "definition.toml");
+            parse_definition_document(toml_str, DocumentFormat::Toml, &context).map(Some)
         } else {
             Ok(None)
         }
     }
 
-    fn get_parameter_versions(&self, _name: &str) -> Result<Option<ParameterVersionsRaw>, DatabaseError> {
-        // Similar to definitions but for versions
-        todo!()
+    fn get_parameter_versions(&self, name: &str) -> Result<Option<ParameterVersionsRaw>, DatabaseError> {
+        if let Some(toml_str) = self.versions.get(name) {
+            let context = Self::context(name, "versions.toml");
+            parse_versions_document(toml_str, DocumentFormat::Toml, &context).map(Some)
+        } else {
+            Ok(None)
+        }
     }
 
-    fn get_subparameters(&self, _parent: &str) -> Result<Vec<String>, DatabaseError> {
-        // Return embedded subparameter list
-        todo!()
+    fn get_subparameters(&self, parent: &str) -> Result<Vec<String>, DatabaseError> {
+        let prefix = format!("{}/", parent);
+        Ok(self.names.iter()
+            .filter_map(|name| name.strip_prefix(&prefix))
+            .filter(|rest| !rest.contains('/'))
+            .map(|rest| format!("{}{}", prefix, rest))
+            .collect())
     }
 }
 
+/// Version of the JSON database bundle format written by
+/// [`FilesystemDatabase::write_bundle`].
+pub const BUNDLE_SCHEMA_VERSION: u32 = 1;
+
 // Filesystem database source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DocumentFormat {
+    Toml,
+    Json,
+    Yaml,
+}
+
+/// Extensions of definition and versions documents, which share the same
+/// schema in every format.
+const DOCUMENT_FORMATS: &[(&str, DocumentFormat)] = &[
+    ("toml", DocumentFormat::Toml),
+    ("json", DocumentFormat::Json),
+    ("yaml", DocumentFormat::Yaml),
+    ("yml", DocumentFormat::Yaml),
+];
+
 pub struct FilesystemDatabase {
     root_path: PathBuf,
+    parameters_path: PathBuf,
 }
 
 impl FilesystemDatabase {
+    /// A database tree whose definitions are under `<path>/parameters`.
     pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, DatabaseError> {
         let root_path = path.as_ref().to_path_buf();
         if !root_path.exists() {
             return Err(DatabaseError::LoadError(format!("Database path does not exist: {:?}", root_path)));
         }
-        Ok(Self { root_path })
+        let parameters_path = root_path.join("parameters");
+        Ok(Self { root_path, parameters_path })
+    }
+
+    /// An overlay directory, holding `<processor>/<name>/definition.toml`
+    /// (or `.json`, `.yaml`)
+    /// directly rather than under `parameters/`.
+    pub fn overlay<P: AsRef<Path>>(path: P) -> Result<Self, DatabaseError> {
+        let root_path = path.as_ref().to_path_buf();
+        if !root_path.is_dir() {
+            return Err(DatabaseError::LoadError(format!("Overlay directory does not exist: {:?}", root_path)));
+        }
+        Ok(Self { parameters_path: root_path.clone(), root_path })
     }
 
     fn get_parameter_path(&self, name: &str) -> PathBuf {
-        self.root_path.join("parameters").join(name)
+        self.parameters_path.join(name)
+    }
+
+    /// The `<stem>.toml`, `.json` or `.yaml` document of a parameter. A
+    /// parameter may only have one document of each kind.
+    fn find_document(&self, name: &str, stem: &str) -> Result<Option<(PathBuf, DocumentFormat)>, DatabaseError> {
+        let dir = self.get_parameter_path(name);
+        let mut found = DOCUMENT_FORMATS.iter()
+            .map(|&(extension, format)| (dir.join(format!("{}.{}", stem, extension)), format))
+            .filter(|(path, _)| path.exists());
+
+        let document = found.next();
+        if let Some((other, _)) = found.next() {
+            return Err(DatabaseError::FormatError(format!(
+                "Conflicting {} documents in {:?}: {:?} and {:?}",
+                stem, dir, document.unwrap().0.file_name().unwrap_or_default(), other.file_name().unwrap_or_default()
+            )));
+        }
+        Ok(document)
+    }
+
+    fn read_document(path: &Path) -> Result<String, DatabaseError> {
+        fs::read_to_string(path).map_err(|e| DatabaseError::LoadError(format!("Failed to read {:?}: {}", path, e)))
+    }
+
+    fn context(name: &str, path: &Path) -> DocumentContext {
+        DocumentContext::new(SourceKind::Filesystem)
+            .with_path(path.display().to_string())
+            .with_parameter(name)
+    }
+
+    /// Write `parameter` in the canonical layout, as the definition of
+    /// `<processor>/<name>`, returning the path written.
+    pub fn write_parameter(&self, parameter: &Parameter) -> Result<PathBuf, DatabaseError> {
+        let name = &parameter.name;
+        if name.is_empty() || name.contains('/') || name == "." || name == ".." {
+            return Err(DatabaseError::WriteError(format!("{:?}: invalid parameter name", name)));
+        }
+        let key = format!("{}/{}", processor_name(&parameter.processor), name);
+        self.write_definition(&key, &ParameterDefinitionRaw::from(parameter))
+    }
+
+    /// Write the definition of the parameter at `name`, e.g. `kernel/pci/nomio`
+    /// for a subparameter, returning the path written.
+    ///
+    /// A new definition is written as TOML. An existing definition keeps its
+    /// format, and an existing TOML definition keeps the comments and layout
+    /// of the entries that do not change.
+    pub fn write_definition(&self, name: &str, definition: &ParameterDefinitionRaw) -> Result<PathBuf, DatabaseError> {
+        let write_error = |e: String| DatabaseError::WriteError(format!("{}: {}", name, e));
+        let (path, format) = match self.find_document(name, "definition")? {
+            Some(existing) => existing,
+            None => (self.get_parameter_path(name).join("definition.toml"), DocumentFormat::Toml),
+        };
+
+        let content = match format {
+            DocumentFormat::Toml => {
+                let updated: toml_edit::DocumentMut = toml::to_string(definition)
+                    .map_err(|e| write_error(e.to_string()))?
+                    .parse()
+                    .map_err(|e: toml_edit::TomlError| write_error(e.to_string()))?;
+                if path.exists() {
+                    let mut document: toml_edit::DocumentMut = fs::read_to_string(&path)?
+                        .parse()
+                        .map_err(|e: toml_edit::TomlError| write_error(e.to_string()))?;
+                    merge_toml(document.as_table_mut(), updated.as_table());
+                    document.to_string()
+                } else {
+                    updated.to_string()
+                }
+            }
+            DocumentFormat::Json => {
+                let mut json = serde_json::to_string_pretty(definition).map_err(|e| write_error(e.to_string()))?;
+                json.push('\n');
+                json
+            }
+            DocumentFormat::Yaml => {
+                let value = serde_json::to_value(definition).map_err(|e| write_error(e.to_string()))?;
+                to_yaml(&value)
+            }
+        };
+
+        write_replacing(&path, content.as_bytes())?;
+        Ok(path)
+    }
+
+    /// Write the database as a single JSON bundle, the format served to
+    /// `RemoteDatabase`:
+    ///
+    /// ```text
+    /// {"schema_version": 1,
+    ///  "parameters": {"kernel/pci": {"definition": {...}, "versions": {...}}}}
+    /// ```
+    ///
+    /// Documents keep the definition schema whatever format they are in on
+    /// disk; `versions` is omitted for parameters without one.
+    pub fn write_bundle<W: Write>(&self, writer: W) -> Result<(), DatabaseError> {
+        let mut parameters = serde_json::Map::new();
+        let mut names = self.list_parameters()?;
+        names.sort();
+        for name in names {
+            let mut entry = serde_json::Map::new();
+            for stem in ["definition", "versions"] {
+                if let Some((path, format)) = self.find_document(&name, stem)? {
+                    let content = Self::read_document(&path)?;
+                    let context = Self::context(&name, &path);
+                    // Catch definitions that would not load before publishing them
+                    if stem == "definition" {
+                        parse_definition_document(&content, format, &context)?;
+                    }
+                    entry.insert(stem.to_string(), parse_document(&content, format, &context)?);
+                }
+            }
+            parameters.insert(name, serde_json::Value::Object(entry));
+        }
+
+        let bundle = serde_json::json!({
+            "schema_version": BUNDLE_SCHEMA_VERSION,
+            "parameters": parameters,
+        });
+        serde_json::to_writer(writer, &bundle).map_err(|e| DatabaseError::ExportError(e.to_string()))
+    }
+
+    /// The bytes a database's detached signature is made over: a header
+    /// line, then for each definition and versions file in path order its
+    /// path relative to the definitions directory, its length and its
+    /// content, separated by NUL bytes.
+    pub fn signing_payload(&self) -> Result<Vec<u8>, DatabaseError> {
+        let mut files = Vec::new();
+        for name in self.list_parameters()? {
+            for stem in ["definition", "versions"] {
+                if let Some((path, _)) = self.find_document(&name, stem)? {
+                    let file = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+                    files.push(format!("{}/{}", name, file));
+                }
+            }
+        }
+        files.sort();
+
+        let mut payload = PAYLOAD_HEADER.to_vec();
+        for file in files {
+            let content = fs::read(self.parameters_path.join(&file))?;
+            payload.extend_from_slice(file.as_bytes());
+            payload.push(0);
+            payload.extend_from_slice(content.len().to_string().as_bytes());
+            payload.push(0);
+            payload.extend_from_slice(&content);
+        }
+        Ok(payload)
     }
 }
 
 impl ParameterSource for FilesystemDatabase {
+    fn source_name(&self) -> String {
+        self.root_path.display().to_string()
+    }
+
+    fn watch_paths(&self) -> Vec<PathBuf> {
+        vec![self.parameters_path.clone()]
+    }
+
+    /// The size and modification time of each of the parameter's documents.
+    fn parameter_stamp(&self, name: &str) -> Option<u64> {
+        let dir = self.get_parameter_path(name);
+        let mut state = Vec::new();
+        for stem in ["definition", "versions"] {
+            for (extension, _) in DOCUMENT_FORMATS {
+                let metadata = match fs::metadata(dir.join(format!("{}.{}", stem, extension))) {
+                    Ok(metadata) => metadata,
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                        state.push(0);
+                        continue;
+                    }
+                    Err(_) => return None,
+                };
+                let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+                state.push(1);
+                state.extend_from_slice(&metadata.len().to_le_bytes());
+                state.extend_from_slice(&modified.as_nanos().to_le_bytes());
+            }
+        }
+        Some(checksum(&state))
+    }
+
+    fn verify(&self, verifier: &dyn SignatureVerifier) -> Result<(), DatabaseError> {
+        let untrusted = |reason: String| DatabaseError::UntrustedSource {
+            name: self.source_name(),
+            reason,
+        };
+        let signature_path = self.root_path.join(SIGNATURE_FILE);
+        let signature = fs::read(&signature_path)
+            .map_err(|e| untrusted(format!("cannot read {:?}: {}", signature_path, e)))?;
+        verifier.verify(&self.signing_payload()?, &signature).map_err(untrusted)
+    }
+
     fn list_parameters(&self) -> Result<Vec<String>, DatabaseError> {
         let mut parameters = Vec::new();
-        let params_dir = self.root_path.join("parameters");
 
-        if params_dir.exists() {
-            self.collect_parameters_recursive(&params_dir, "", &mut parameters)?;
+        if self.parameters_path.exists() {
+            self.collect_parameters_recursive(&self.parameters_path, "", &mut parameters)?;
         }
 
         Ok(parameters)
     }
 
     fn get_parameter_definition(&self, name: &str) -> Result<Option<ParameterDefinitionRaw>, DatabaseError> {
-        let def_path = self.get_parameter_path(name).join("definition.toml");
-
-        if def_path.exists() {
-            let content = fs::read_to_string(&def_path)
-                .map_err(|e| DatabaseError::LoadError(format!("Failed to read {:?}: {}", def_path, e)))?;
-
-            let def: ParameterDefinitionRaw = toml::from_str(&content)
-                .map_err(|e| DatabaseError::FormatError(format!("Parse error in {:?}: {}", def_path, e)))?;
-
-            Ok(Some(def))
-        } else {
-            Ok(None)
+        match self.find_document(name, "definition")? {
+            Some((path, format)) => {
+                parse_definition_document(&Self::read_document(&path)?, format, &Self::context(name, &path)).map(Some)
+            }
+            None => Ok(None),
         }
     }
 
     fn get_parameter_versions(&self, name: &str) -> Result<Option<ParameterVersionsRaw>, DatabaseError> {
-        let versions_path = self.get_parameter_path(name).join("versions.toml");
-
-        if versions_path.exists() {
-            let content = fs::read_to_string(&versions_path)
-                .map_err(|e| DatabaseError::LoadError(format!("Failed to read {:?}: {}", versions_path, e)))?;
-
-            let versions: ParameterVersionsRaw = toml::from_str(&content)
-                .map_err(|e| DatabaseError::FormatError(format!("Parse error in {:?}: {}", versions_path, e)))?;
-
-            Ok(Some(versions))
-        } else {
-            Ok(None)
+        match self.find_document(name, "versions")? {
+            Some((path, format)) => {
+                parse_versions_document(&Self::read_document(&path)?, format, &Self::context(name, &path)).map(Some)
+            }
+            None => Ok(None),
         }
     }
 
@@ -431,8 +1723,8 @@ impl FilesystemDatabase {
                         format!("{}/{}", prefix, name)
                     };
 
-                    // Check if this directory has a definition.toml
-                    if path.join("definition.toml").exists() {
+                    // Check if this directory has a definition document
+                    if DOCUMENT_FORMATS.iter().any(|(extension, _)| path.join(format!("definition.{}", extension)).exists()) {
                         parameters.push(full_name.clone());
                     }
 
@@ -444,3 +1736,167 @@ impl FilesystemDatabase {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A definition fixture for each syntax type, named by the type.
+    const SYNTAX_FIXTURES: &[(&str, &str)] = &[
+        ("flag", include_str!("../tests/fixtures/definitions/flag.toml")),
+        ("boolean", include_str!("../tests/fixtures/definitions/boolean.toml")),
+        ("integer", include_str!("../tests/fixtures/definitions/integer.toml")),
+        ("enum", include_str!("../tests/fixtures/definitions/enum.toml")),
+        ("size", include_str!("../tests/fixtures/definitions/size.toml")),
+        ("hex", include_str!("../tests/fixtures/definitions/hex.toml")),
+        ("key_value", include_str!("../tests/fixtures/definitions/key_value.toml")),
+        ("cpu_list", include_str!("../tests/fixtures/definitions/cpu_list.toml")),
+        ("memory_range", include_str!("../tests/fixtures/definitions/memory_range.toml")),
+        ("pci_device", include_str!("../tests/fixtures/definitions/pci_device.toml")),
+        ("module_param", include_str!("../tests/fixtures/definitions/module_param.toml")),
+        ("dracut_luks_name", include_str!("../tests/fixtures/definitions/dracut_luks_name.toml")),
+        ("dracut_network", include_str!("../tests/fixtures/definitions/dracut_network.toml")),
+        ("complex", include_str!("../tests/fixtures/definitions/complex.toml")),
+    ];
+    const COMPLEX: &str = include_str!("../tests/fixtures/definitions/complex.toml");
+    const VERSIONS: &str = include_str!("../tests/fixtures/definitions/versions.toml");
+
+    fn context() -> DocumentContext {
+        DocumentContext::new(SourceKind::Filesystem).with_path("fixture.toml")
+    }
+
+    fn fixture(validator_type: &str) -> &'static str {
+        SYNTAX_FIXTURES.iter().find(|(name, _)| *name == validator_type).unwrap().1
+    }
+
+    fn convert(document: &str) -> Result<Parameter, DatabaseError> {
+        let raw = parse_definition_document(document, DocumentFormat::Toml, &context())?;
+        DatabaseLoader::new().convert_raw_parameter(raw)
+    }
+
+    fn format_error(document: &str) -> String {
+        match convert(document) {
+            Err(DatabaseError::FormatError(message)) => message,
+            other => panic!("expected a format error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn converts_each_syntax_type() {
+        for (validator_type, document) in SYNTAX_FIXTURES {
+            let parameter = convert(document).unwrap_or_else(|e| panic!("{}: {}", validator_type, e));
+            assert_eq!(parameter.syntax.validator_type, *validator_type);
+            assert!(!parameter.syntax.format.is_empty(), "{}", validator_type);
+            assert!(!parameter.description.is_empty(), "{}", validator_type);
+        }
+    }
+
+    #[test]
+    fn keeps_syntax_configuration() {
+        let parameter = convert(fixture("integer")).unwrap();
+        assert_eq!(parameter.syntax.config.get("min"), Some(&toml::Value::Integer(0)));
+        assert_eq!(parameter.syntax.config.get("max"), Some(&toml::Value::Integer(7)));
+
+        let parameter = convert(fixture("cpu_list")).unwrap();
+        let flags = parameter.syntax.config["flags"].as_array().unwrap();
+        assert_eq!(flags.len(), 3);
+    }
+
+    #[test]
+    fn converts_processors_with_default_versions() {
+        let processors: HashMap<&str, ParameterProcessor> = SYNTAX_FIXTURES.iter()
+            .map(|(validator_type, document)| (*validator_type, convert(document).unwrap().processor))
+            .collect();
+        assert_eq!(processors["flag"], ParameterProcessor::Kernel);
+        assert_eq!(processors["enum"], ParameterProcessor::Systemd { min_version: "219".to_string() });
+        assert_eq!(processors["boolean"], ParameterProcessor::Dracut { min_version: "011".to_string() });
+    }
+
+    #[test]
+    fn converts_every_section() {
+        let parameter = convert(COMPLEX).unwrap();
+        assert_eq!(parameter.aliases, ["cons"]);
+        assert_eq!(parameter.selectors, ["arch:x86_64", "arch:aarch64"]);
+        assert_eq!(parameter.duplicate_policy, DuplicatePolicy::Additive);
+        assert_eq!(parameter.controls, ["console"]);
+        assert_eq!(parameter.arch_syntax["s390x"].validator_type, "enum");
+
+        let fedora = &parameter.distributions["fedora"];
+        assert_eq!(fedora.min_version.as_deref(), Some("38"));
+        assert_eq!(fedora.max_version, None);
+        assert_eq!(fedora.notes, None);
+        assert_eq!(parameter.distributions["ubuntu"].max_version.as_deref(), Some("24.04"));
+
+        assert_eq!(parameter.examples.valid, ["ttyS0,115200n8", "tty0"]);
+        assert_eq!(parameter.examples.invalid, [""]);
+        let documentation = parameter.documentation.as_ref().unwrap();
+        assert!(documentation.kernel_org.is_some());
+        assert_eq!(documentation.man_pages, ["bootparam(7)"]);
+
+        assert_eq!(parameter.conflicts_with[0].parameter, "earlycon");
+        assert_eq!(parameter.conflicts_with[0].values, ["off"]);
+        assert_eq!(parameter.requires[0].parameters, ["earlyprintk"]);
+        assert_eq!(parameter.security_impact[0].severity, SecuritySeverity::Low);
+        assert_eq!(parameter.performance_impact[0].rating, PerformanceRating::Medium);
+        assert_eq!(parameter.required_config[0].options, ["CONFIG_SERIAL_8250_CONSOLE"]);
+        assert_eq!(parameter.required_config[0].arch, ["x86_64"]);
+        assert!(parameter.extra.contains_key("x-distro"));
+    }
+
+    #[test]
+    fn omits_empty_documentation() {
+        let document = format!("{}\n[documentation]\nkernel_org = \" \"\n", fixture("flag"));
+        assert!(convert(&document).unwrap().documentation.is_none());
+        assert!(convert(fixture("flag")).unwrap().documentation.is_none());
+    }
+
+    #[test]
+    fn converts_versions() {
+        let loader = DatabaseLoader::new();
+        let raw = parse_versions_document(VERSIONS, DocumentFormat::Toml, &context()).unwrap();
+
+        let mut processor = ParameterProcessor::Systemd { min_version: "219".to_string() };
+        DatabaseLoader::apply_component_version(&mut processor, &raw);
+        assert_eq!(processor, ParameterProcessor::Systemd { min_version: "250".to_string() });
+        let mut processor = ParameterProcessor::Dracut { min_version: "011".to_string() };
+        DatabaseLoader::apply_component_version(&mut processor, &raw);
+        assert_eq!(processor, ParameterProcessor::Dracut { min_version: "011".to_string() });
+
+        let versions = loader.convert_versions(raw);
+        assert_eq!(versions.introduced.as_deref(), Some("2.6.12"));
+        assert_eq!(versions.last_modified.as_deref(), Some("6.1"));
+        assert_eq!(versions.last_modified_commit, None);
+        let rhel = &versions.vendors["rhel"]["9.2"];
+        assert_eq!(rhel.introduced.as_deref(), Some("5.14.0-284"));
+        assert_eq!(rhel.notes.as_deref(), Some("backported"));
+    }
+
+    #[test]
+    fn rejects_unknown_processor() {
+        let document = fixture("flag").replace("processor = \"kernel\"", "processor = \"upstart\"");
+        assert_eq!(format_error(&document), "Unknown processor: upstart");
+    }
+
+    #[test]
+    fn rejects_unknown_duplicate_policy() {
+        let document = COMPLEX.replace("\"additive\"", "\"sometimes\"");
+        assert_eq!(format_error(&document), "Unknown duplicate policy: sometimes");
+    }
+
+    #[test]
+    fn rejects_unknown_impact_levels() {
+        let document = COMPLEX.replace("severity = \"low\"", "severity = \"dire\"");
+        assert_eq!(format_error(&document), "Unknown security severity: dire");
+        let document = COMPLEX.replace("rating = \"medium\"", "rating = \"extreme\"");
+        assert_eq!(format_error(&document), "Unknown performance rating: extreme");
+    }
+
+    #[test]
+    fn rejects_inverted_distribution_range() {
+        let document = COMPLEX.replace("max_version = \"24.04\"", "max_version = \"18.04\"");
+        assert_eq!(
+            format_error(&document),
+            "Distribution ubuntu: min_version 20.04 is later than max_version 18.04",
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! Deprecated parameters on a command line, and what to use instead: those
+//! whose definitions are marked `deprecated`, and those given under a former
+//! name.
+
+use serde::Serialize;
+
+use crate::error::Span;
+use crate::parameter::Parameter;
+use crate::parser::{render_argument, ParsedParameter};
+use crate::validators::ValidationResult;
+
+/// A deprecated parameter on a command line.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DeprecatedUsage {
+    /// The parameter as given, which may be a former name.
+    pub parameter: String,
+    pub value: Option<String>,
+    /// The release the parameter was deprecated in, if known.
+    pub since: Option<String>,
+    /// The exact argument to use instead, if there is one.
+    pub replacement: Option<String>,
+    /// Whether the parameter itself is deprecated, rather than only the
+    /// name it was given under.
+    pub deprecated: bool,
+    /// Whether the parameter was given under a former name.
+    pub renamed: bool,
+    pub span: Span,
+}
+
+impl DeprecatedUsage {
+    /// The validation result recorded for a deprecated parameter; former
+    /// names are reported as the parameter is parsed.
+    pub fn validation_result(&self) -> ValidationResult {
+        let mut message = match &self.since {
+            Some(since) => format!("Deprecated since {}", since),
+            None => "Deprecated".to_string(),
+        };
+        match &self.replacement {
+            Some(replacement) => message.push_str(&format!("; use '{}' instead", replacement)),
+            None => message.push_str("; it has no replacement and can be removed"),
+        }
+        ValidationResult::Warning(message)
+    }
+}
+
+/// The deprecated parameters of a command line, in command line order.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DeprecationReport {
+    pub cmdline: String,
+    pub deprecated: Vec<DeprecatedUsage>,
+}
+
+impl DeprecationReport {
+    pub(crate) fn new(cmdline: &str, parameters: &[ParsedParameter]) -> Self {
+        Self {
+            cmdline: cmdline.to_string(),
+            deprecated: deprecated_usages(parameters),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.deprecated.is_empty()
+    }
+
+    /// The command line with every deprecated parameter that has a
+    /// replacement replaced by it.
+    pub fn migrated_cmdline(&self) -> String {
+        let mut cmdline = self.cmdline.clone();
+        for usage in self.deprecated.iter().rev() {
+            if let Some(replacement) = &usage.replacement {
+                cmdline.replace_range(usage.span.start..usage.span.end, replacement);
+            }
+        }
+        cmdline
+    }
+}
+
+/// Whether a parameter was given under one of the former names of its
+/// definition, bare or after a module prefix.
+pub(crate) fn is_renamed(param: &ParsedParameter) -> bool {
+    param.parameter_def.as_ref().is_some_and(|def| {
+        def.aliases.iter().any(|alias| {
+            param.name == *alias || param.name.strip_suffix(alias.as_str()).is_some_and(|prefix| prefix.ends_with('.'))
+        })
+    })
+}
+
+/// A parameter's argument under the current name of its definition.
+pub(crate) fn current_argument(param: &ParsedParameter, def: &Parameter) -> String {
+    let name = match &param.module {
+        Some(module) => format!("{}.{}", module, def.name),
+        None => def.name.clone(),
+    };
+    render_argument(&name, param.value.as_deref())
+}
+
+/// The deprecated and renamed parameters among `parameters`, with the
+/// argument to replace each with. A `{value}` placeholder in a definition's
+/// replacement is filled in with the value given.
+pub(crate) fn deprecated_usages(parameters: &[ParsedParameter]) -> Vec<DeprecatedUsage> {
+    parameters.iter()
+        .filter_map(|param| {
+            let def = param.parameter_def.as_ref()?;
+            let renamed = is_renamed(param);
+            if !def.deprecated && !renamed {
+                return None;
+            }
+            let replacement = if def.deprecated {
+                def.replacement.as_ref().map(|replacement| {
+                    replacement.replace("{value}", param.value.as_deref().unwrap_or(""))
+                })
+            } else {
+                Some(current_argument(param, def))
+            };
+            Some(DeprecatedUsage {
+                parameter: param.name.clone(),
+                value: param.value.clone(),
+                since: def.deprecated.then(|| def.deprecated_since.clone()).flatten(),
+                replacement,
+                deprecated: def.deprecated,
+                renamed,
+                span: param.span.clone(),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,142 @@
+//! Comparison of two parameter catalogs, e.g. successive releases of the
+//! database, to review what an update changes.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::parameter::Parameter;
+
+/// Fields compared as a whole rather than field by field.
+const ATOMIC_FIELDS: &[&str] = &["processor", "duplicate_policy"];
+
+/// A change to one field of a parameter definition. Nested fields are named
+/// by their dotted path, e.g. `syntax.config.choices`; `old` is `None` for
+/// fields that were added and `new` is `None` for fields that were removed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: Option<Value>,
+    pub new: Option<Value>,
+}
+
+/// The changed fields of a parameter present in both catalogs.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParameterDiff {
+    pub key: String,
+    pub changes: Vec<FieldChange>,
+}
+
+/// The differences between two catalogs, by catalog key, sorted by key.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CatalogDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub modified: Vec<ParameterDiff>,
+}
+
+impl CatalogDiff {
+    /// Compare `old` and `new` catalog entries, given as `(key, parameter)`
+    /// pairs.
+    pub fn new<'a, O, N>(old: O, new: N) -> Self
+    where
+        O: IntoIterator<Item = (&'a str, &'a Parameter)>,
+        N: IntoIterator<Item = (&'a str, &'a Parameter)>,
+    {
+        let old: BTreeMap<&str, &Parameter> = old.into_iter().collect();
+        let new: BTreeMap<&str, &Parameter> = new.into_iter().collect();
+
+        let added = new.keys()
+            .filter(|key| !old.contains_key(*key))
+            .map(|key| key.to_string())
+            .collect();
+        let removed = old.keys()
+            .filter(|key| !new.contains_key(*key))
+            .map(|key| key.to_string())
+            .collect();
+        let modified = old.iter()
+            .filter_map(|(key, old_param)| {
+                let new_param = new.get(key)?;
+                let changes = diff_parameters(old_param, new_param);
+                (!changes.is_empty()).then(|| ParameterDiff {
+                    key: key.to_string(),
+                    changes,
+                })
+            })
+            .collect();
+
+        Self { added, removed, modified }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// The field-level changes between two definitions of a parameter.
+pub fn diff_parameters(old: &Parameter, new: &Parameter) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    // Parameter serialization cannot fail: all map keys are strings
+    let old = serde_json::to_value(old).unwrap_or(Value::Null);
+    let new = serde_json::to_value(new).unwrap_or(Value::Null);
+    diff_values("", Some(&old), Some(&new), &mut changes);
+    changes
+}
+
+fn diff_values(field: &str, old: Option<&Value>, new: Option<&Value>, changes: &mut Vec<FieldChange>) {
+    if old == new {
+        return;
+    }
+
+    match (old, new) {
+        (Some(Value::Object(old)), Some(Value::Object(new))) if !ATOMIC_FIELDS.contains(&field) => {
+            let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let path = if field.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", field, key)
+                };
+                diff_values(&path, old.get(key), new.get(key), changes);
+            }
+        }
+        _ => changes.push(FieldChange {
+            field: field.to_string(),
+            old: old.filter(|v| !v.is_null()).cloned(),
+            new: new.filter(|v| !v.is_null()).cloned(),
+        }),
+    }
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.old, &self.new) {
+            (Some(old), Some(new)) => write!(f, "{}: {} -> {}", self.field, old, new),
+            (None, Some(new)) => write!(f, "{}: added {}", self.field, new),
+            (Some(old), None) => write!(f, "{}: removed {}", self.field, old),
+            (None, None) => write!(f, "{}: unchanged", self.field),
+        }
+    }
+}
+
+impl fmt::Display for CatalogDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for key in &self.added {
+            writeln!(f, "+ {}", key)?;
+        }
+        for key in &self.removed {
+            writeln!(f, "- {}", key)?;
+        }
+        for diff in &self.modified {
+            writeln!(f, "~ {}", diff.key)?;
+            for change in &diff.changes {
+                writeln!(f, "    {}", change)?;
+            }
+        }
+        Ok(())
+    }
+}
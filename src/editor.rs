@@ -0,0 +1,189 @@
+use std::fmt;
+
+use crate::parser::{render_argument, span_within, split_args};
+
+#[derive(Debug, Clone, PartialEq)]
+enum ArgumentEdit {
+    Keep,
+    Replace(String),
+    Remove,
+}
+
+#[derive(Debug, Clone)]
+struct EditorArgument {
+    name: String,
+    value: Option<String>,
+    start: usize,
+    end: usize,
+    edit: ArgumentEdit,
+}
+
+/// Edits a command line while preserving the ordering, whitespace and quoting
+/// of every argument that is not changed.
+///
+/// Arguments after a `--` separator belong to init and are never matched by
+/// name; appended parameters are inserted before the separator.
+#[derive(Debug, Clone)]
+pub struct CommandLineEditor {
+    original: String,
+    arguments: Vec<EditorArgument>,
+    init_separator: Option<usize>,
+    appended: Vec<String>,
+}
+
+impl CommandLineEditor {
+    pub fn new(cmdline: &str) -> Self {
+        let mut init_separator = None;
+        let arguments = split_args(cmdline)
+            .iter()
+            .enumerate()
+            .map(|(index, arg)| {
+                if init_separator.is_none() && arg.param == "--" && arg.value.is_none() {
+                    init_separator = Some(index);
+                }
+                let span = span_within(cmdline, arg.text);
+                EditorArgument {
+                    name: arg.param.to_string(),
+                    value: arg.value.map(|v| v.to_string()),
+                    start: span.start,
+                    end: span.end,
+                    edit: ArgumentEdit::Keep,
+                }
+            })
+            .collect();
+
+        Self {
+            original: cmdline.to_string(),
+            arguments,
+            init_separator,
+            appended: Vec::new(),
+        }
+    }
+
+    /// Indices of the live kernel arguments called `name`.
+    fn occurrences(&self, name: &str) -> Vec<usize> {
+        let kernel_args = self.init_separator.unwrap_or(self.arguments.len());
+        self.arguments[..kernel_args]
+            .iter()
+            .enumerate()
+            .filter(|(_, arg)| arg.name == name && arg.edit != ArgumentEdit::Remove)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// The current value of the last occurrence of `name`, or `None` if the
+    /// parameter is absent. A present flag has the value `Some(None)`.
+    pub fn get(&self, name: &str) -> Option<Option<String>> {
+        if let Some(index) = self.occurrences(name).last() {
+            return Some(self.arguments[*index].value.clone());
+        }
+        self.appended
+            .iter()
+            .rev()
+            .map(|text| split_args(text))
+            .find_map(|args| {
+                args.into_iter()
+                    .find(|arg| arg.param == name)
+                    .map(|arg| arg.value.map(|v| v.to_string()))
+            })
+    }
+
+    /// Set `name` to `value`, replacing the first occurrence in place and
+    /// removing any later occurrences, or appending it if it is absent.
+    pub fn set(&mut self, name: &str, value: Option<&str>) -> &mut Self {
+        let occurrences = self.occurrences(name);
+        match occurrences.split_first() {
+            Some((first, rest)) => {
+                let arg = &mut self.arguments[*first];
+                arg.value = value.map(|v| v.to_string());
+                arg.edit = ArgumentEdit::Replace(render_argument(name, value));
+                for index in rest {
+                    self.arguments[*index].edit = ArgumentEdit::Remove;
+                }
+                self.remove_appended(name);
+            }
+            None => {
+                self.remove_appended(name);
+                self.appended.push(render_argument(name, value));
+            }
+        }
+        self
+    }
+
+    /// Remove every occurrence of `name`, returning how many were removed.
+    pub fn remove(&mut self, name: &str) -> usize {
+        let occurrences = self.occurrences(name);
+        for index in &occurrences {
+            self.arguments[*index].edit = ArgumentEdit::Remove;
+        }
+        occurrences.len() + self.remove_appended(name)
+    }
+
+    /// Add a new occurrence of `name`, even if it is already present.
+    pub fn append(&mut self, name: &str, value: Option<&str>) -> &mut Self {
+        self.appended.push(render_argument(name, value));
+        self
+    }
+
+    fn remove_appended(&mut self, name: &str) -> usize {
+        let before = self.appended.len();
+        self.appended.retain(|text| split_args(text).first().map(|arg| arg.param) != Some(name));
+        before - self.appended.len()
+    }
+
+    pub fn is_modified(&self) -> bool {
+        !self.appended.is_empty() || self.arguments.iter().any(|arg| arg.edit != ArgumentEdit::Keep)
+    }
+}
+
+impl fmt::Display for CommandLineEditor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut output = String::new();
+        let mut cursor = 0;
+        let mut emitted = false;
+        // Leading whitespace of a removed first argument is kept for the next one
+        let mut leading: Option<&str> = None;
+
+        fn push(output: &mut String, emitted: &mut bool, gap: &str, leading: &mut Option<&str>, text: &str) {
+            if *emitted {
+                output.push_str(if gap.is_empty() { " " } else { gap });
+            } else {
+                output.push_str(leading.take().unwrap_or(gap));
+            }
+            output.push_str(text);
+            *emitted = true;
+        }
+
+        for (index, arg) in self.arguments.iter().enumerate() {
+            let gap = &self.original[cursor..arg.start];
+            cursor = arg.end;
+
+            if Some(index) == self.init_separator {
+                for text in &self.appended {
+                    push(&mut output, &mut emitted, "", &mut leading, text);
+                }
+            }
+
+            match &arg.edit {
+                ArgumentEdit::Keep => {
+                    push(&mut output, &mut emitted, gap, &mut leading, &self.original[arg.start..arg.end]);
+                }
+                ArgumentEdit::Replace(text) => push(&mut output, &mut emitted, gap, &mut leading, text),
+                ArgumentEdit::Remove => {
+                    if !emitted && leading.is_none() {
+                        leading = Some(gap);
+                    }
+                }
+            }
+        }
+
+        if self.init_separator.is_none() {
+            for text in &self.appended {
+                push(&mut output, &mut emitted, "", &mut leading, text);
+            }
+        }
+        output.push_str(&self.original[cursor..]);
+
+        f.write_str(&output)
+    }
+}
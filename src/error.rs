@@ -1,5 +1,181 @@
+use std::fmt;
+use serde::Serialize;
 use thiserror::Error;
 
+/// A byte range within a command line, optionally naming the source it was
+/// read from, used to point diagnostics at the exact text they refer to.
+///
+/// Offsets are relative to the command line string handed to the parser,
+/// which for configuration file sources is the extracted command line.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct Span {
+    pub source: Option<String>,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { source: None, start, end }
+    }
+
+    pub fn with_source<S: Into<String>>(mut self, source: S) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Render `line` with a caret marker underneath this span.
+    pub fn annotate(&self, line: &str) -> String {
+        let indent = line.get(..self.start).map_or(self.start, |prefix| prefix.chars().count());
+        let width = line.get(self.start..self.end).map_or(self.len(), |text| text.chars().count());
+        format!("{}\n{}{}", line, " ".repeat(indent), "^".repeat(width.max(1)))
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.source {
+            Some(source) => write!(f, "{}:{}-{}", source, self.start, self.end),
+            None => write!(f, "{}-{}", self.start, self.end),
+        }
+    }
+}
+
+/// The kind of parameter source a database document was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SourceKind {
+    Embedded,
+    Filesystem,
+    Remote,
+}
+
+impl fmt::Display for SourceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Embedded => write!(f, "embedded database"),
+            Self::Filesystem => write!(f, "filesystem database"),
+            Self::Remote => write!(f, "remote database"),
+        }
+    }
+}
+
+/// Where a parameter database document came from and, when known, the
+/// position in it that an error refers to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentContext {
+    pub source_kind: SourceKind,
+    /// The file the document was read from, or the URL of a remote bundle.
+    pub path: Option<String>,
+    /// The database path of the parameter, e.g. `kernel/pci`.
+    pub parameter: Option<String>,
+    /// 1-based line and column.
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    /// The text of the line, to show the error in context.
+    pub source_line: Option<String>,
+}
+
+impl DocumentContext {
+    pub fn new(source_kind: SourceKind) -> Self {
+        Self {
+            source_kind,
+            path: None,
+            parameter: None,
+            line: None,
+            column: None,
+            source_line: None,
+        }
+    }
+
+    pub fn with_path<S: Into<String>>(mut self, path: S) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    pub fn with_parameter<S: Into<String>>(mut self, parameter: S) -> Self {
+        self.parameter = Some(parameter.into());
+        self
+    }
+
+    /// Point at a 1-based `line`, and `column` in characters, of `text`.
+    pub fn at(mut self, text: &str, line: usize, column: Option<usize>) -> Self {
+        self.line = Some(line);
+        self.column = column;
+        self.source_line = text.lines().nth(line.saturating_sub(1)).map(str::to_string);
+        self
+    }
+
+    /// Point at a byte offset of `text`. Offsets past the last line, as for
+    /// an unexpected end of the document, point at the end of the last line.
+    pub fn at_offset(self, text: &str, offset: usize) -> Self {
+        let offset = offset.min(text.trim_end().len());
+        let before: String = text.char_indices()
+            .take_while(|(index, _)| *index < offset)
+            .map(|(_, c)| c)
+            .collect();
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().unwrap_or_default().chars().count() + 1;
+        self.at(text, line, Some(column))
+    }
+
+    /// The line the error refers to with a caret under its column, on lines
+    /// of their own, or nothing if the position is not known.
+    pub fn snippet(&self) -> String {
+        let (Some(line), Some(text)) = (self.line, &self.source_line) else {
+            return String::new();
+        };
+        let gutter = " ".repeat(line.to_string().len());
+        let start = self.column
+            .and_then(|column| text.char_indices().nth(column.saturating_sub(1)))
+            .map_or(text.len(), |(index, _)| index);
+        let end = text[start..].chars().next().map_or(start, |c| start + c.len_utf8());
+        let annotated = Span::new(start, end).annotate(text);
+        let (text, caret) = annotated.split_once('\n').unwrap_or_default();
+        if self.column.is_none() {
+            return format!("\n{} | {}", line, text);
+        }
+        format!("\n{} | {}\n{} | {}", line, text, gutter, caret)
+    }
+}
+
+impl fmt::Display for DocumentContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.path, &self.parameter) {
+            (Some(path), parameter) => {
+                write!(f, "{}", path)?;
+                if let Some(line) = self.line {
+                    write!(f, ":{}", line)?;
+                }
+                if let Some(column) = self.column {
+                    write!(f, ":{}", column)?;
+                }
+                if let Some(parameter) = parameter {
+                    write!(f, " ({})", parameter)?;
+                }
+                Ok(())
+            }
+            (None, parameter) => {
+                write!(f, "{} in the {}", parameter.as_deref().unwrap_or("document"), self.source_kind)?;
+                if let Some(line) = self.line {
+                    write!(f, ", line {}", line)?;
+                }
+                if let Some(column) = self.column {
+                    write!(f, ", column {}", column)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum KCmdlineError {
     #[error("Database loading failed: {0}")]
@@ -10,6 +186,8 @@ pub enum KCmdlineError {
     ParseError(#[from] ParseError),
     #[error("Hardware probe error: {0}")]
     ProbeError(#[from] ProbeError),
+    #[error("Query error: {0}")]
+    QueryError(#[from] QueryError),
 }
 
 #[derive(Error, Debug)]
@@ -30,6 +208,12 @@ pub enum ParseError {
     InvalidFormat(String),
     #[error("Missing required value for parameter: {0}")]
     MissingValue(String),
+    #[error("I/O error reading command line source: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Truncated GRUB environment block: {0}")]
+    TruncatedEnvironmentBlock(String),
+    #[error("Corrupt GRUB environment block: {0}")]
+    CorruptEnvironmentBlock(String),
 }
 
 #[derive(Error, Debug)]
@@ -40,6 +224,13 @@ pub enum BuildError {
     InvalidConfig { param: String, error: String },
     #[error("Missing required configuration: {0}")]
     MissingConfig(String),
+    #[error("{param} conflicts with {existing} already on the command line: {reason}")]
+    Conflict { param: String, existing: String, reason: String },
+    #[error(transparent)]
+    ParseError(#[from] ParseError),
+    /// The findings of the built command line that are errors.
+    #[error("Command line failed validation with {} error(s)", .0.len())]
+    ValidationFailed(Vec<crate::validators::Finding>),
 }
 
 #[derive(Error, Debug)]
@@ -62,6 +253,30 @@ pub enum DatabaseError {
     FormatError(String),
     #[error("Missing required parameter definition: {0}")]
     MissingDefinition(String),
+    #[error("Parameter {0} is defined more than once")]
+    DuplicateDefinition(String),
+    #[error("Conflicting values for {field} of {param} from {first} and {second}")]
+    MergeConflict { param: String, field: String, first: String, second: String },
+    #[error("Failed to export parameter database: {0}")]
+    ExportError(String),
+    #[error("Failed to write parameter definition {0}")]
+    WriteError(String),
+    #[error("Catalog snapshot error: {0}")]
+    SnapshotError(String),
+    #[error("Unsupported catalog snapshot version {found} (supported {supported})")]
+    UnsupportedSnapshotVersion { found: u32, supported: u32 },
+    #[error("Invalid document {context}: {message}{}", .context.snippet())]
+    InvalidDocument { context: Box<DocumentContext>, message: String },
+    #[error("Unsupported definition schema version {found} (supported up to {supported}) in {context}")]
+    UnsupportedSchemaVersion { context: Box<DocumentContext>, found: u32, supported: u32 },
+    #[error("Unknown fields in {document}: {}", fields.join(", "))]
+    UnknownFields { document: String, fields: Vec<String> },
+    #[error("Cyclic syntax inheritance: {}", .0.join(" -> "))]
+    SyntaxInheritanceCycle(Vec<String>),
+    #[error("Parameter {param} inherits its syntax from undefined parameter {base}")]
+    MissingSyntaxBase { param: String, base: String },
+    #[error("Untrusted parameter source {name}: {reason}")]
+    UntrustedSource { name: String, reason: String },
 }
 
 #[derive(Error, Debug)]
@@ -69,3 +284,21 @@ pub enum RegistryError {
     #[error("Name {0} is already in use")]
     NameError(String),
 }
+
+#[derive(Error, Debug)]
+pub enum ReportError {
+    #[error("Failed to serialize validation report: {0}")]
+    SerializationError(String),
+    #[error("I/O error reading or writing baseline: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Unsupported baseline schema version {found} (supported {supported})")]
+    UnsupportedSchemaVersion { found: u32, supported: u32 },
+}
+
+#[derive(Error, Debug)]
+pub enum QueryError {
+    #[error("Failed to (de)serialize query: {0}")]
+    SerializationError(String),
+    #[error("Unsupported query schema version {found} (supported up to {supported})")]
+    UnsupportedSchemaVersion { found: u32, supported: u32 },
+}
@@ -0,0 +1,372 @@
+//! Plain-language explanations of a command line: what each parameter
+//! does and what its value means, with structured values decoded, for
+//! answering "what does my boot line actually do?".
+
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::config::{parse_cpu_list, parse_size, ConsoleSpec, CrashKernelPlacement, CrashKernelSpec, ParameterConfig, Parity};
+use crate::error::Span;
+use crate::parameter::{Parameter, ParameterProcessor};
+use crate::parser::{quote_argument, render_argument, ParsedParameter};
+
+/// Where parameters without documentation links of their own are
+/// documented, by the component that consumes them.
+const KERNEL_DOCS: &str = "https://docs.kernel.org/admin-guide/kernel-parameters.html";
+const SYSTEMD_DOCS: &str = "https://www.freedesktop.org/software/systemd/man/latest/kernel-command-line.html";
+const DRACUT_DOCS: &str = "https://man7.org/linux/man-pages/man7/dracut.cmdline.7.html";
+
+/// Parameters whose value is a CPU list, and whether it may be preceded by
+/// flags, as in `isolcpus=nohz,domain,1-3`.
+const CPU_LIST_PARAMETERS: &[(&str, bool)] = &[
+    ("isolcpus", true),
+    ("nohz_full", false),
+    ("rcu_nocbs", false),
+    ("irqaffinity", false),
+    ("kthread_cpus", false),
+];
+
+/// Parameters whose value is a size in `memparse` form, e.g. `512M`.
+const SIZE_PARAMETERS: &[&str] = &["mem", "hugepagesz", "default_hugepagesz", "cma"];
+
+/// Kernel log levels, from most to least severe.
+const LOG_LEVELS: [&str; 8] = ["emerg", "alert", "crit", "err", "warning", "notice", "info", "debug"];
+
+/// What one argument of a command line does.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParameterExplanation {
+    pub parameter: String,
+    pub value: Option<String>,
+    /// What the parameter does, from its catalog description.
+    pub summary: Option<String>,
+    /// What the value means, one statement each.
+    pub details: Vec<String>,
+    /// False when a repeated occurrence elsewhere on the line supersedes
+    /// this one.
+    pub effective: bool,
+    /// Where the parameter is documented.
+    pub documentation: Vec<String>,
+    pub span: Span,
+}
+
+/// What each argument of a command line does, in command line order.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CommandLineExplanation {
+    pub cmdline: String,
+    pub parameters: Vec<ParameterExplanation>,
+    /// Arguments following `--`, passed to init instead of the kernel.
+    pub init_arguments: Vec<String>,
+}
+
+impl CommandLineExplanation {
+    pub(crate) fn new(cmdline: &str, parameters: &[ParsedParameter], init_arguments: &[String]) -> Self {
+        let last_console = parameters.iter().rposition(|param| param.effective && param.name == "console");
+        let parameters = parameters.iter()
+            .enumerate()
+            .map(|(index, param)| {
+                let mut explanation = explain_parameter(param);
+                if Some(index) == last_console {
+                    explanation.details.push("Last console given, so it becomes /dev/console".to_string());
+                }
+                explanation
+            })
+            .collect();
+
+        Self {
+            cmdline: cmdline.to_string(),
+            parameters,
+            init_arguments: init_arguments.to_vec(),
+        }
+    }
+}
+
+fn explain_parameter(param: &ParsedParameter) -> ParameterExplanation {
+    let def = param.parameter_def.as_ref();
+    let mut details = Vec::new();
+    if !param.effective {
+        details.push("Superseded by another occurrence, so it has no effect".to_string());
+    }
+    match (def, &param.module) {
+        (None, Some(module)) => details.push(format!(
+            "Parameter '{}' of module '{}'",
+            param.name.strip_prefix(&format!("{}.", module)).unwrap_or(&param.name), module
+        )),
+        (None, None) => details.push("Not in the catalog; the kernel passes it on to init".to_string()),
+        (Some(def), _) if def.deprecated => details.push(match &def.replacement {
+            Some(replacement) => format!("Deprecated; use '{}' instead", replacement),
+            None => "Deprecated".to_string(),
+        }),
+        _ => {}
+    }
+    details.extend(value_details(param));
+
+    ParameterExplanation {
+        parameter: param.name.clone(),
+        value: param.value.clone(),
+        summary: def.and_then(|def| summary(&def.description)),
+        details,
+        effective: param.effective,
+        documentation: def.map(documentation).unwrap_or_default(),
+        span: param.span.clone(),
+    }
+}
+
+/// The first sentence of a description, joined onto one line.
+fn summary(description: &str) -> Option<String> {
+    let paragraph: Vec<&str> = description.lines()
+        .map(str::trim)
+        .skip_while(|line| line.is_empty())
+        .take_while(|line| !line.is_empty())
+        .collect();
+    let paragraph = paragraph.join(" ");
+    let sentence = match paragraph.find(". ") {
+        Some(end) => &paragraph[..=end],
+        None => &paragraph,
+    };
+    (!sentence.is_empty()).then(|| sentence.to_string())
+}
+
+/// The documentation links of a parameter, or the reference of the
+/// component that consumes it when it has none.
+fn documentation(def: &Parameter) -> Vec<String> {
+    if let Some(links) = &def.documentation {
+        let mut documentation: Vec<String> = links.kernel_org.iter().chain(&links.man_pages).cloned().collect();
+        let mut distributions: Vec<(&String, &String)> = links.distribution_docs.iter().collect();
+        distributions.sort();
+        documentation.extend(distributions.into_iter().map(|(_, link)| link.clone()));
+        return documentation;
+    }
+    let reference = match def.processor {
+        ParameterProcessor::Kernel => KERNEL_DOCS,
+        ParameterProcessor::Systemd { .. } => SYSTEMD_DOCS,
+        ParameterProcessor::Dracut { .. } => DRACUT_DOCS,
+        _ => return Vec::new(),
+    };
+    vec![reference.to_string()]
+}
+
+/// What the value of a parameter means, decoded for the parameters with
+/// well-known structured values and from the parsed configuration of the
+/// others.
+fn value_details(param: &ParsedParameter) -> Vec<String> {
+    let Some(value) = param.value.as_deref() else {
+        return match &param.parameter_def {
+            Some(_) => vec!["Given as a flag".to_string()],
+            None => Vec::new(),
+        };
+    };
+    let name = param.parameter_def.as_ref().map_or(param.name.as_str(), |def| def.name.as_str());
+
+    if name == "console" {
+        return console_details(value);
+    }
+    if name == "loglevel" {
+        if let Some(details) = loglevel_details(value) {
+            return details;
+        }
+    }
+    if name == "crashkernel" {
+        if let Some(details) = crashkernel_details(value) {
+            return details;
+        }
+    }
+    if let Some((_, supports_flags)) = CPU_LIST_PARAMETERS.iter().find(|(cpu_list, _)| *cpu_list == name) {
+        // Flags lead the list, as in `nohz,domain,1-3`
+        let (flags, cpus) = match value.find(|c: char| c.is_ascii_digit() || c == '^') {
+            Some(start) if *supports_flags && start > 0 => (value[..start].trim_end_matches(','), &value[start..]),
+            _ => ("", value),
+        };
+        if let Some(config) = parse_cpu_list(cpus, false) {
+            let flags = flags.split(',').filter(|flag| !flag.is_empty()).map(str::to_string).collect();
+            return config_details(&config.with_flags(flags));
+        }
+    }
+    let is_size = SIZE_PARAMETERS.contains(&name)
+        || param.parameter_def.as_ref().is_some_and(|def| def.syntax.validator_type == "size");
+    if is_size {
+        if let Some(bytes) = parse_size(value) {
+            return vec![format!("Size: {} ({} bytes)", describe_size(bytes), bytes)];
+        }
+    }
+
+    param.config.as_ref().map(config_details).unwrap_or_default()
+}
+
+fn config_details(config: &ParameterConfig) -> Vec<String> {
+    match config {
+        ParameterConfig::Boolean(true) => vec!["Enabled".to_string()],
+        ParameterConfig::Boolean(false) => vec!["Disabled".to_string()],
+        ParameterConfig::Integer(value) => vec![format!("Value: {}", value)],
+        ParameterConfig::String(value) => vec![format!("Value: {}", value)],
+        ParameterConfig::List(values) => vec![format!("Values: {}", values.join(", "))],
+        ParameterConfig::CpuList { cpus, flags } => {
+            let list: Vec<String> = cpus.iter().map(u32::to_string).collect();
+            let noun = if cpus.len() == 1 { "CPU" } else { "CPUs" };
+            let mut details = vec![format!("{} {} ({} in all)", noun, list.join(", "), cpus.len())];
+            if !flags.is_empty() {
+                details.push(format!("Flags: {}", flags.join(", ")));
+            }
+            details
+        }
+        ParameterConfig::Complex(fields) => {
+            let mut fields: Vec<(&String, &ParameterConfig)> = fields.iter().collect();
+            fields.sort_by(|a, b| a.0.cmp(b.0));
+            fields.into_iter()
+                .map(|(key, value)| match value {
+                    ParameterConfig::String(value) => format!("{}: {}", key, value),
+                    value => format!("{}: {}", key, config_details(value).join("; ")),
+                })
+                .collect()
+        }
+    }
+}
+
+/// A size in bytes in the largest binary unit that divides it exactly.
+fn describe_size(bytes: i64) -> String {
+    let unit = [(1i64 << 40, "TiB"), (1 << 30, "GiB"), (1 << 20, "MiB"), (1 << 10, "KiB")]
+        .into_iter()
+        .find(|(unit, _)| bytes != 0 && bytes % unit == 0);
+    match unit {
+        Some((unit, name)) => format!("{} {}", bytes / unit, name),
+        None => format!("{} bytes", bytes),
+    }
+}
+
+/// The device and line settings of a `console=` value such as
+/// `ttyS0,115200n8r`, `tty0` or `uart8250,io,0x3f8,115200n8`.
+fn console_details(value: &str) -> Vec<String> {
+    let Some(console) = ConsoleSpec::parse(value) else {
+        return vec![format!("Device: {}", console_device(value.split(',').next().unwrap_or("")))];
+    };
+    let mut details = match console.device.split(',').collect::<Vec<_>>()[..] {
+        [_, space, address] => vec![format!(
+            "Device: early UART at {} address {}", if space == "io" { "I/O port" } else { space }, address
+        )],
+        _ => vec![format!("Device: {}", console_device(&console.device))],
+    };
+    if let Some(speed) = console.speed {
+        details.push(format!("Speed: {} baud", speed));
+    }
+    if let Some(parity) = console.parity {
+        let parity = match parity {
+            Parity::None => "none",
+            Parity::Odd => "odd",
+            Parity::Even => "even",
+        };
+        details.push(format!("Parity: {}", parity));
+    }
+    if let Some(bits) = console.data_bits {
+        details.push(format!("Data bits: {}", bits));
+    }
+    if console.flow_control {
+        details.push("Flow control: RTS/CTS".to_string());
+    }
+    details
+}
+
+/// A description of a console device name.
+fn console_device(device: &str) -> String {
+    let numbered = |prefix: &str| device.strip_prefix(prefix).filter(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+    if device == "tty0" {
+        return "the foreground virtual terminal (tty0)".to_string();
+    }
+    let described = [
+        ("ttyS", "serial port"),
+        ("ttyAMA", "ARM PL011 serial port"),
+        ("ttyUSB", "USB serial adapter"),
+        ("ttyACM", "USB modem serial port"),
+        ("hvc", "hypervisor console"),
+        ("lp", "parallel printer port"),
+        ("tty", "virtual terminal"),
+    ];
+    for (prefix, description) in described {
+        if let Some(number) = numbered(prefix) {
+            return format!("{} {} ({})", description, number, device);
+        }
+    }
+    match device {
+        "ttysclp0" => "s390 SCLP console (ttysclp0)".to_string(),
+        "null" => "none: console output is discarded".to_string(),
+        _ => device.to_string(),
+    }
+}
+
+/// The log levels a `loglevel=` value lets through to the console.
+fn loglevel_details(value: &str) -> Option<Vec<String>> {
+    let level: usize = value.parse().ok()?;
+    let shown = &LOG_LEVELS[..level.min(LOG_LEVELS.len())];
+    Some(vec![match shown.len() {
+        0 => "Console shows no kernel messages".to_string(),
+        n if n == LOG_LEVELS.len() => "Console shows kernel messages of every level".to_string(),
+        _ => format!(
+            "Console shows kernel messages more severe than level {} ({}): {}",
+            level, LOG_LEVELS[level], shown.join(", ")
+        ),
+    }])
+}
+
+/// The memory a `crashkernel=` value reserves: a size, a size per range of
+/// system memory, or a size placed high or low or in CMA, optionally at an
+/// offset.
+fn crashkernel_details(value: &str) -> Option<Vec<String>> {
+    let describe = |bytes: u64| describe_size(i64::try_from(bytes).unwrap_or(i64::MAX));
+    let mut details = Vec::new();
+    let offset = match CrashKernelSpec::parse(value)? {
+        CrashKernelSpec::Size { size, offset } => {
+            details.push(format!("Reserves {} for the crash kernel", describe(size)));
+            offset
+        }
+        CrashKernelSpec::Placed { size, placement } => {
+            details.push(format!("Reserves {} for the crash kernel", describe(size)));
+            details.push(match placement {
+                CrashKernelPlacement::High => "Placed above 4 GiB where possible",
+                CrashKernelPlacement::Low => "Reserved below 4 GiB, for DMA",
+                CrashKernelPlacement::Cma => "Taken from the contiguous memory allocator",
+            }.to_string());
+            None
+        }
+        CrashKernelSpec::Ranges { ranges, offset } => {
+            for range in ranges {
+                details.push(match range.end {
+                    None => format!(
+                        "Reserves {} when the system has at least {} of memory",
+                        describe(range.size), describe(range.start)
+                    ),
+                    Some(end) => format!(
+                        "Reserves {} when the system has from {} to {} of memory",
+                        describe(range.size), describe(range.start), describe(end)
+                    ),
+                });
+            }
+            offset
+        }
+    };
+    if let Some(offset) = offset {
+        details.push(format!("At physical offset {}", describe(offset)));
+    }
+    Some(details)
+}
+
+impl fmt::Display for CommandLineExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for explanation in &self.parameters {
+            writeln!(f, "{}", render_argument(&explanation.parameter, explanation.value.as_deref()))?;
+            if let Some(summary) = &explanation.summary {
+                writeln!(f, "    {}", summary)?;
+            }
+            for detail in &explanation.details {
+                writeln!(f, "    - {}", detail)?;
+            }
+            for link in &explanation.documentation {
+                writeln!(f, "    see {}", link)?;
+            }
+        }
+        if !self.init_arguments.is_empty() {
+            let arguments: Vec<String> = self.init_arguments.iter().map(|arg| quote_argument(arg)).collect();
+            writeln!(f, "-- {}", arguments.join(" "))?;
+            writeln!(f, "    Passed to init")?;
+        }
+        Ok(())
+    }
+}
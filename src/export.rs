@@ -0,0 +1,218 @@
+//! Export of the full parameter catalog for tools that do not link the
+//! library.
+//!
+//! Both formats carry the same document, versioned by
+//! [`CATALOG_SCHEMA_VERSION`]:
+//!
+//! ```text
+//! schema_version: 1
+//! parameters:                  # sorted by key
+//!   - key: pci/nomio           # unique catalog key
+//!     parent: pci              # key of the parent, subparameters only
+//!     subparameters: []        # keys of the direct subparameters
+//!     name: nomio
+//!     processor: Kernel        # or {Systemd: {min_version: "219"}}, ...
+//!     description: ...
+//!     deprecated: false
+//!     deprecated_since: "5.11"    # or null
+//!     replacement: "ip={value}"   # or null; {value} is the value given
+//!     aliases: [nopti]         # former names, still accepted
+//!     selectors: [arch:s390x]
+//!     syntax: {validator_type, format, config}
+//!     arch_syntax: {<arch>: {validator_type, format, config}}  # overrides of syntax
+//!     distributions: {<distro>: {min_version, max_version, component_version, notes}}
+//!     examples: {valid, invalid}
+//!     documentation: {kernel_org, man_pages, distribution_docs} or null
+//!     duplicate_policy: LastWins | FirstWins | Additive
+//!     conflicts_with: [{parameter, values, reason}]
+//!     requires: [{parameters, when, suggestion, reason}]
+//!     controls: [console_loglevel]  # settings the last parameter setting wins
+//!     security_impact: [{severity: low | medium | high | critical, when, rationale}]
+//!     performance_impact: [{rating: low | medium | high, when, effect}]
+//!     required_config: [{options, cpu_flags, when, arch}]
+//!     extra: {x-<namespace>: ...}  # downstream metadata, passed through
+//!     versions: {introduced, commit, last_modified, last_modified_commit, vendors}
+//! ```
+//!
+//! `versions` is omitted when the database has no version history for the
+//! parameter. Fields are only ever added within a schema version.
+
+use std::io::Write;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::catalog::{ParameterCatalog, VersionInfo};
+use crate::error::DatabaseError;
+use crate::parameter::Parameter;
+
+/// Version of the catalog export schema; bumped on incompatible changes.
+pub const CATALOG_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExportFormat {
+    Json,
+    Yaml,
+}
+
+#[derive(Serialize)]
+struct CatalogExport<'a> {
+    schema_version: u32,
+    parameters: Vec<ExportedParameter<'a>>,
+}
+
+#[derive(Serialize)]
+struct ExportedParameter<'a> {
+    key: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent: Option<&'a str>,
+    subparameters: Vec<&'a str>,
+    #[serde(flatten)]
+    parameter: &'a Parameter,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    versions: Option<&'a VersionInfo>,
+}
+
+impl<'a> CatalogExport<'a> {
+    fn new(catalog: &'a ParameterCatalog) -> Self {
+        let mut parameters: Vec<ExportedParameter> = catalog.entries()
+            .map(|(key, parameter)| {
+                let mut subparameters: Vec<&str> = catalog.get_subparameter_entries(key)
+                    .into_iter()
+                    .map(|(child, _)| child)
+                    .collect();
+                subparameters.sort_unstable();
+                ExportedParameter {
+                    key,
+                    parent: catalog.parent_of(key),
+                    subparameters,
+                    parameter,
+                    versions: catalog.version_info(key),
+                }
+            })
+            .collect();
+        parameters.sort_by(|a, b| a.key.cmp(b.key));
+
+        Self {
+            schema_version: CATALOG_SCHEMA_VERSION,
+            parameters,
+        }
+    }
+}
+
+/// Write the catalog to `writer` in `format`.
+pub fn export_catalog<W: Write>(
+    catalog: &ParameterCatalog,
+    format: ExportFormat,
+    mut writer: W,
+) -> Result<(), DatabaseError> {
+    let export = CatalogExport::new(catalog);
+    let to_export_error = |e: serde_json::Error| DatabaseError::ExportError(e.to_string());
+
+    match format {
+        ExportFormat::Json => {
+            serde_json::to_writer_pretty(&mut writer, &export).map_err(to_export_error)?;
+            writeln!(writer)?;
+        }
+        ExportFormat::Yaml => {
+            let value = serde_json::to_value(&export).map_err(to_export_error)?;
+            writer.write_all(to_yaml(&value).as_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+/// A YAML document holding `value`.
+pub(crate) fn to_yaml(value: &Value) -> String {
+    let mut yaml = String::from("---\n");
+    write_yaml_block(&mut yaml, value, 0, false);
+    yaml
+}
+
+/// Whether a value is written as an indented block rather than inline.
+fn is_block(value: &Value) -> bool {
+    match value {
+        Value::Object(map) => !map.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        _ => false,
+    }
+}
+
+/// Write a non-empty mapping or sequence at `indent`. With `inline_first`
+/// the first line continues the current one, as after a sequence dash.
+fn write_yaml_block(out: &mut String, value: &Value, indent: usize, mut inline_first: bool) {
+    let mut pad = |out: &mut String| {
+        if !inline_first {
+            out.push_str(&" ".repeat(indent));
+        }
+        inline_first = false;
+    };
+
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                pad(out);
+                out.push_str(&yaml_string(key));
+                out.push(':');
+                write_yaml_child(out, child, indent + 2);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                pad(out);
+                out.push('-');
+                if let Value::Object(_) = item {
+                    if is_block(item) {
+                        out.push(' ');
+                        write_yaml_block(out, item, indent + 2, true);
+                        continue;
+                    }
+                }
+                write_yaml_child(out, item, indent + 2);
+            }
+        }
+        scalar => {
+            pad(out);
+            out.push_str(&yaml_scalar(scalar));
+            out.push('\n');
+        }
+    }
+}
+
+fn write_yaml_child(out: &mut String, value: &Value, indent: usize) {
+    if is_block(value) {
+        out.push('\n');
+        write_yaml_block(out, value, indent, false);
+    } else {
+        out.push(' ');
+        out.push_str(&yaml_scalar(value));
+        out.push('\n');
+    }
+}
+
+fn yaml_scalar(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => yaml_string(s),
+        Value::Array(_) => "[]".to_string(),
+        Value::Object(_) => "{}".to_string(),
+    }
+}
+
+/// Plain scalars for simple strings, JSON-style double quoting (which is
+/// valid YAML) for anything that could be read as another type or as
+/// YAML syntax.
+fn yaml_string(s: &str) -> String {
+    const RESERVED: &[&str] = &["null", "true", "false", "yes", "no", "on", "off", "y", "n"];
+    let plain = s.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '/')
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || "_./-".contains(c))
+        && !RESERVED.contains(&s.to_ascii_lowercase().as_str());
+
+    if plain {
+        s.to_string()
+    } else {
+        Value::String(s.to_string()).to_string()
+    }
+}
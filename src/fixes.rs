@@ -0,0 +1,96 @@
+//! Machine-applicable fixes for findings whose correction is obvious: a
+//! boolean or enum value in the wrong spelling, a size with a unit suffix
+//! the kernel does not accept, or a deprecated parameter with a direct
+//! replacement.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::error::Span;
+
+/// Replace the text at `span` with `replacement`. The span is that of a
+/// whole argument, which an empty replacement removes, or of a value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Fix {
+    pub span: Span,
+    pub replacement: String,
+}
+
+impl Fix {
+    pub fn new(span: Span, replacement: &str) -> Self {
+        Self {
+            span,
+            replacement: replacement.to_string(),
+        }
+    }
+}
+
+/// Spellings of boolean values the kernel does not accept, and the value
+/// meant by each.
+const BOOLEAN_SPELLINGS: &[(&str, &str)] = &[
+    ("true", "1"),
+    ("yes", "1"),
+    ("on", "1"),
+    ("y", "1"),
+    ("enable", "1"),
+    ("enabled", "1"),
+    ("false", "0"),
+    ("no", "0"),
+    ("off", "0"),
+    ("n", "0"),
+    ("disable", "0"),
+    ("disabled", "0"),
+];
+
+/// Size suffixes written the way other tools write them, e.g. `512MB`,
+/// `2GiB` or `64k`, and the suffix the kernel expects.
+const SIZE_SUFFIXES: &[(&[&str], &str)] = &[
+    (&["k", "kb", "kib"], "K"),
+    (&["m", "mb", "mib"], "M"),
+    (&["g", "gb", "gib"], "G"),
+];
+
+/// The value a rejected `value` was evidently meant as, for the validators
+/// whose mistakes have a single obvious correction.
+pub(crate) fn value_fix(validator_type: &str, config: &HashMap<String, toml::Value>, value: &str) -> Option<String> {
+    match validator_type {
+        "boolean" => boolean_meaning(value).map(str::to_string),
+        "size" => {
+            let digits = value.find(|c: char| !c.is_ascii_digit()).filter(|&end| end > 0)?;
+            let (number, suffix) = value.split_at(digits);
+            let suffix = suffix.to_ascii_lowercase();
+            SIZE_SUFFIXES.iter()
+                .find(|(spellings, _)| spellings.contains(&suffix.as_str()))
+                .map(|(_, unit)| format!("{}{}", number, unit))
+        }
+        "enum" => {
+            let choices: Vec<&str> = config.get("choices")?.as_array()?.iter().filter_map(|v| v.as_str()).collect();
+            let allow_multiple = config.get("allow_multiple").and_then(|v| v.as_bool()).unwrap_or(false);
+            let choice = |part: &str| choices.iter().find(|choice| choice.eq_ignore_ascii_case(part.trim())).copied();
+            let fixed = if allow_multiple {
+                value.split(',').map(choice).collect::<Option<Vec<_>>>()?.join(",")
+            } else {
+                choice(value).or_else(|| boolean_choice(&choices, value))?.to_string()
+            };
+            (fixed != value).then_some(fixed)
+        }
+        _ => None,
+    }
+}
+
+/// `1` or `0` for a value that spells out a boolean.
+fn boolean_meaning(value: &str) -> Option<&'static str> {
+    let value = value.to_ascii_lowercase();
+    BOOLEAN_SPELLINGS.iter().find(|(spelling, _)| *spelling == value).map(|(_, meant)| *meant)
+}
+
+/// The choice of an enum with boolean choices, such as `true|false|auto`,
+/// that a boolean spelled another way means.
+fn boolean_choice<'a>(choices: &[&'a str], value: &str) -> Option<&'a str> {
+    let spellings: &[&str] = match boolean_meaning(value)? {
+        "1" => &["1", "true", "on", "yes", "y"],
+        _ => &["0", "false", "off", "no", "n"],
+    };
+    spellings.iter().find_map(|spelling| choices.iter().find(|choice| *choice == spelling).copied())
+}
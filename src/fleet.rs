@@ -0,0 +1,183 @@
+//! Validation of many command lines at once, such as those of every host
+//! in a fleet, with the results rolled up by host and by finding so that
+//! configuration audits need not aggregate them by hand.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use serde::Serialize;
+
+use crate::catalog::ParameterCatalog;
+use crate::error::ReportError;
+use crate::parser::CommandLineParser;
+use crate::probe::SystemProbe;
+use crate::report::{ReportSeverity, ValidationReport};
+use crate::rules::LintRuleRegistry;
+use crate::suppress::Suppressions;
+
+/// Identifies a command line in a batch, e.g. the name of the host it
+/// boots.
+pub type SourceId = String;
+
+/// A command line to validate in a batch, with the probe of the system it
+/// is for. Without a probe, the validating system's probe is used.
+#[derive(Debug, Clone)]
+pub struct FleetHost {
+    pub id: SourceId,
+    pub cmdline: String,
+    pub probe: Option<SystemProbe>,
+}
+
+impl FleetHost {
+    pub fn new(id: &str, cmdline: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            cmdline: cmdline.to_string(),
+            probe: None,
+        }
+    }
+
+    /// Validate against `probe`, e.g. one the host saved with
+    /// [`SystemProbe::save`], rather than the validating system.
+    pub fn with_probe(mut self, probe: SystemProbe) -> Self {
+        self.probe = Some(probe);
+        self
+    }
+}
+
+/// The outcome of validating one command line of a batch.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HostReport {
+    pub id: SourceId,
+    pub cmdline: String,
+    /// Whether the command line parsed and no finding is an error.
+    pub passed: bool,
+    pub report: Option<ValidationReport>,
+    /// Why the command line could not be validated, if it could not.
+    pub error: Option<String>,
+}
+
+/// A finding reported for one or more command lines of a batch.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FindingRollup {
+    pub parameter: String,
+    pub severity: ReportSeverity,
+    pub message: String,
+    pub rule: Option<String>,
+    /// The command lines the finding was reported for, in batch order.
+    pub hosts: Vec<SourceId>,
+}
+
+/// The results of validating a batch of command lines.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FleetReport {
+    /// One report per command line, in batch order.
+    pub hosts: Vec<HostReport>,
+    /// Every finding other than a valid result, reported for most hosts
+    /// first.
+    pub findings: Vec<FindingRollup>,
+}
+
+impl FleetReport {
+    fn new(hosts: Vec<HostReport>) -> Self {
+        let mut rollups: BTreeMap<(String, String, Option<String>), FindingRollup> = BTreeMap::new();
+        for host in &hosts {
+            let details = host.report.iter().flat_map(|report| &report.findings);
+            for finding in details {
+                let Some(message) = &finding.message else {
+                    continue;
+                };
+                let key = (finding.parameter.clone(), message.clone(), finding.rule.clone());
+                let rollup = rollups.entry(key).or_insert_with(|| FindingRollup {
+                    parameter: finding.parameter.clone(),
+                    severity: finding.severity,
+                    message: message.clone(),
+                    rule: finding.rule.clone(),
+                    hosts: Vec::new(),
+                });
+                if !rollup.hosts.contains(&host.id) {
+                    rollup.hosts.push(host.id.clone());
+                }
+            }
+        }
+        let mut findings: Vec<FindingRollup> = rollups.into_values().collect();
+        findings.sort_by(|a, b| b.hosts.len().cmp(&a.hosts.len()).then_with(|| a.parameter.cmp(&b.parameter)));
+
+        Self { hosts, findings }
+    }
+
+    pub fn passed_count(&self) -> usize {
+        self.hosts.iter().filter(|host| host.passed).count()
+    }
+
+    /// The command lines that failed to parse or have errors.
+    pub fn failed_hosts(&self) -> Vec<&str> {
+        self.hosts.iter().filter(|host| !host.passed).map(|host| host.id.as_str()).collect()
+    }
+
+    /// The exit code for a command line tool to report the batch with: 0
+    /// when every command line passed, 1 otherwise.
+    pub fn exit_code(&self) -> i32 {
+        if self.hosts.iter().all(|host| host.passed) { 0 } else { 1 }
+    }
+
+    pub fn to_json(&self) -> Result<String, ReportError> {
+        serde_json::to_string_pretty(self).map_err(|e| ReportError::SerializationError(e.to_string()))
+    }
+}
+
+/// Validate `hosts` on as many threads as the system has processors,
+/// against their own probes or `default_probe`. Module parameters are only
+/// checked against the catalog, as `modinfo` describes the validating
+/// system's modules rather than the hosts'. Findings `suppressions` match
+/// are left out.
+pub(crate) fn validate_fleet(
+    catalog: &ParameterCatalog,
+    default_probe: &SystemProbe,
+    suppressions: &Suppressions,
+    hosts: Vec<FleetHost>,
+) -> FleetReport {
+    let workers = thread::available_parallelism().map_or(1, |n| n.get()).min(hosts.len());
+    let next = AtomicUsize::new(0);
+
+    let mut results: Vec<(usize, HostReport)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..workers)
+            .map(|_| scope.spawn(|| {
+                let parser = CommandLineParser::new(catalog)
+                    .with_lint_rules(LintRuleRegistry::with_builtin_rules())
+                    .with_suppressions(suppressions.clone());
+                let mut results = Vec::new();
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(host) = hosts.get(index) else {
+                        break;
+                    };
+                    let probe = host.probe.as_ref().unwrap_or(default_probe);
+                    let report = match parser.parse_for_system(&host.cmdline, probe) {
+                        Ok(parsed) => HostReport {
+                            id: host.id.clone(),
+                            cmdline: host.cmdline.clone(),
+                            passed: parsed.validation_summary.is_valid(),
+                            report: Some(parsed.validation_summary.report()),
+                            error: None,
+                        },
+                        Err(error) => HostReport {
+                            id: host.id.clone(),
+                            cmdline: host.cmdline.clone(),
+                            passed: false,
+                            report: None,
+                            error: Some(error.to_string()),
+                        },
+                    };
+                    results.push((index, report));
+                }
+                results
+            }))
+            .collect();
+        workers.into_iter().flat_map(|worker| worker.join().expect("validation worker panicked")).collect()
+    });
+
+    results.sort_by_key(|(index, _)| *index);
+    FleetReport::new(results.into_iter().map(|(_, report)| report).collect())
+}
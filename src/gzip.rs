@@ -0,0 +1,79 @@
+//! Decompression of gzip files such as /proc/config.gz, capped so that a
+//! corrupt or hostile file cannot exhaust memory.
+
+use std::io::Read;
+
+use flate2::read::MultiGzDecoder;
+
+/// The most a gzip file may decompress to. Kernel configurations are a few
+/// hundred kilobytes.
+pub(crate) const MAX_DECOMPRESSED_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Decompress a gzip file, which may hold several concatenated members.
+pub(crate) fn gunzip(data: &[u8]) -> Result<Vec<u8>, String> {
+    gunzip_limited(data, MAX_DECOMPRESSED_SIZE)
+}
+
+/// Decompress a gzip file, failing if it holds more than `limit` bytes.
+fn gunzip_limited(data: &[u8], limit: u64) -> Result<Vec<u8>, String> {
+    let mut output = Vec::new();
+    MultiGzDecoder::new(data)
+        .take(limit + 1)
+        .read_to_end(&mut output)
+        .map_err(|e| e.to_string())?;
+    if output.len() as u64 > limit {
+        return Err(format!("decompresses to more than {} bytes", limit));
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    const CONFIG: &[u8] = b"CONFIG_SMP=y\nCONFIG_NR_CPUS=64\n# CONFIG_KASAN is not set\n";
+
+    fn gzip(data: &[u8], level: Compression) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), level);
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn round_trips_each_level() {
+        let large: Vec<u8> = CONFIG.iter().copied().cycle().take(200_000).collect();
+        for level in [Compression::none(), Compression::fast(), Compression::best()] {
+            assert_eq!(gunzip(&gzip(CONFIG, level)).unwrap(), CONFIG);
+            assert_eq!(gunzip(&gzip(&large, level)).unwrap(), large);
+        }
+    }
+
+    #[test]
+    fn joins_members() {
+        let mut data = gzip(b"CONFIG_A=y\n", Compression::default());
+        data.extend(gzip(b"CONFIG_B=m\n", Compression::none()));
+        assert_eq!(gunzip(&data).unwrap(), b"CONFIG_A=y\nCONFIG_B=m\n");
+    }
+
+    #[test]
+    fn caps_decompressed_size() {
+        let data = gzip(&vec![b'x'; 4096], Compression::best());
+        assert!(gunzip_limited(&data, 4096).is_ok());
+        let error = gunzip_limited(&data, 4095).unwrap_err();
+        assert!(error.contains("more than 4095 bytes"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_corrupt_data() {
+        let mut data = gzip(CONFIG, Compression::default());
+        let crc = data.len() - 8;
+        data[crc] ^= 0xff;
+        assert!(gunzip(&data).is_err());
+        assert!(gunzip(&data[..data.len() / 2]).is_err());
+        assert!(gunzip(b"CONFIG_SMP=y\n").is_err());
+    }
+}
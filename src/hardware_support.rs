@@ -0,0 +1,45 @@
+//! Checks that parameters enabling IOMMU and confidential computing
+//! features are given on hardware that has them, using the `iommu:` and
+//! `cc:` tags of the system probe.
+
+use crate::parser::ParsedParameter;
+use crate::probe::SystemProbe;
+use crate::taxonomy;
+use crate::validators::{Finding, ValidationResult};
+
+/// Parameters that enable a hardware feature, the values that leave it
+/// disabled, the tag of the hardware it needs and how to describe that
+/// hardware.
+const FEATURE_PARAMETERS: &[(&str, &[&str], &str, &str)] = &[
+    ("intel_iommu", &["off"], "iommu:hw:intel", "an Intel IOMMU (ACPI DMAR table)"),
+    ("amd_iommu", &["off"], "iommu:hw:amd", "an AMD IOMMU (ACPI IVRS table)"),
+    ("kvm_amd.sev", &["0", "N", "n"], "cc:sev", "a CPU with AMD SEV"),
+    ("kvm_amd.sev_es", &["0", "N", "n"], "cc:sev-es", "a CPU with AMD SEV-ES"),
+    ("kvm_amd.sev_snp", &["0", "N", "n"], "cc:sev-snp", "a CPU with AMD SEV-SNP"),
+    ("kvm_intel.tdx", &["0", "N", "n"], "cc:tdx", "a CPU with Intel TDX"),
+];
+
+/// Findings for the effective parameters among `parameters` that enable a
+/// feature the probed hardware lacks. Systems probed without a sysfs or
+/// /proc/cpuinfo, whose hardware is unknown, are not checked.
+pub(crate) fn hardware_support_findings(parameters: &[ParsedParameter], probe: &SystemProbe) -> Vec<Finding> {
+    let has_tag_prefix = |prefix: &str| probe.hardware_tags.iter().any(|tag| tag.starts_with(prefix));
+    let devices_known = has_tag_prefix("pci:");
+    let cpu_known = has_tag_prefix("cpu:vendor:");
+
+    parameters.iter()
+        .filter(|p| p.effective)
+        .filter_map(|param| {
+            let (_, disabled, tag, hardware) = FEATURE_PARAMETERS.iter().find(|(name, ..)| *name == param.name)?;
+            let value = param.value.as_deref().unwrap_or("");
+            let known = if tag.starts_with("cc:") { cpu_known } else { devices_known };
+            if !known || disabled.contains(&value) || probe.hardware_tags.contains(*tag) {
+                return None;
+            }
+            let arg = if param.value.is_some() { format!("{}={}", param.name, value) } else { param.name.clone() };
+            let message = format!("{} needs {}, which this system does not have", arg, hardware);
+            let finding = Finding::new(&param.name, ValidationResult::Warning(message), Some(param.span.clone()));
+            Some(finding.with_rule_info(&taxonomy::UNSUPPORTED_HARDWARE))
+        })
+        .collect()
+}
@@ -0,0 +1,121 @@
+//! References between the definitions of a loaded database that do not
+//! resolve, found after loading rather than when a broken tree is walked.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::catalog::MODULE_KEY_PREFIX;
+use crate::database::LoadedDatabase;
+use crate::parameter::SELECTOR_NAMESPACES;
+
+/// A reference in a definition that does not resolve. Parameters are named
+/// by their database path, e.g. `kernel/pci/nomio`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityIssue {
+    /// A subparameter whose parent has no definition.
+    OrphanedSubparameter { param: String, parent: String },
+    /// A selector whose namespace is not one the system probe produces,
+    /// which therefore never matches.
+    UnknownSelectorNamespace { param: String, selector: String },
+    /// An alias that names a defined parameter, so it never resolves to the
+    /// parameter declaring it.
+    ShadowedAlias { param: String, alias: String },
+    /// An alias declared by more than one parameter.
+    AmbiguousAlias { alias: String, params: Vec<String> },
+}
+
+impl fmt::Display for IntegrityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OrphanedSubparameter { param, parent } => {
+                write!(f, "{}: parent parameter {} is not defined", param, parent)
+            }
+            Self::UnknownSelectorNamespace { param, selector } => {
+                write!(f, "{}: selector '{}' has an unknown namespace", param, selector)
+            }
+            Self::ShadowedAlias { param, alias } => {
+                write!(f, "{}: alias {} is shadowed by the parameter of that name", param, alias)
+            }
+            Self::AmbiguousAlias { alias, params } => {
+                write!(f, "alias {} is declared by {}", alias, params.join(", "))
+            }
+        }
+    }
+}
+
+/// The broken references of a loaded database, sorted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct IntegrityReport {
+    pub issues: Vec<IntegrityIssue>,
+}
+
+impl IntegrityReport {
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl fmt::Display for IntegrityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for issue in &self.issues {
+            writeln!(f, "{}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn check_integrity(database: &LoadedDatabase) -> IntegrityReport {
+    let mut issues = Vec::new();
+    // Alias paths, like subparameter paths, are relative to the parent
+    let mut aliases: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for (path, param) in &database.parameters {
+        let parent = path.rsplit_once('/').map(|(parent, _)| parent);
+
+        // Top-level parameters sit directly under their processor directory
+        if let Some(parent) = parent.filter(|parent| parent.contains('/')) {
+            if !path.starts_with(MODULE_KEY_PREFIX) && !database.parameters.contains_key(parent) {
+                issues.push(IntegrityIssue::OrphanedSubparameter {
+                    param: path.clone(),
+                    parent: parent.to_string(),
+                });
+            }
+        }
+
+        for selector in &param.selectors {
+            let namespace = selector.split_once(':').map_or(selector.as_str(), |(namespace, _)| namespace);
+            if !SELECTOR_NAMESPACES.contains(&namespace) {
+                issues.push(IntegrityIssue::UnknownSelectorNamespace {
+                    param: path.clone(),
+                    selector: selector.clone(),
+                });
+            }
+        }
+
+        for alias in &param.aliases {
+            let alias_path = match parent {
+                Some(parent) => format!("{}/{}", parent, alias),
+                None => alias.clone(),
+            };
+            aliases.entry(alias_path).or_default().push(path.clone());
+        }
+    }
+
+    for (alias, mut params) in aliases {
+        if database.parameters.contains_key(&alias) {
+            issues.extend(params.into_iter().map(|param| IntegrityIssue::ShadowedAlias {
+                param,
+                alias: alias.clone(),
+            }));
+        } else if params.len() > 1 {
+            params.sort();
+            issues.push(IntegrityIssue::AmbiguousAlias { alias, params });
+        }
+    }
+
+    issues.sort();
+    IntegrityReport { issues }
+}
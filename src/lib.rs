@@ -3,90 +3,535 @@
 //! command line parameters across multiple boot components including the
 //! kernel, systemd, dracut, and others.
 
+mod audit;
 mod catalog;
+mod compose;
 mod config;
 mod database;
+mod deprecation;
+mod diff;
+mod editor;
 mod error;
+mod explain;
+mod export;
+mod fixes;
+mod fleet;
+mod gzip;
+mod hardware_support;
+mod integrity;
+mod lint;
+mod mitigations;
+mod modinfo;
 mod parameter;
 mod parser;
+mod performance;
+mod policy;
 mod probe;
+mod profile;
 mod query;
+mod report;
+#[cfg(feature = "remote")]
+mod remote;
+mod rules;
+mod schema;
+mod search;
+mod simulate;
+mod snapshot;
+mod sources;
+mod suggest;
+mod suppress;
+mod system_checks;
+mod taxonomy;
+mod trust;
 mod validators;
 mod version;
+#[cfg(target_os = "linux")]
+mod watch;
+mod yaml;
 
-pub use parameter::{Parameter, ParameterProcessor, SyntaxDefinition};
-pub use query::{QueryParameters, QueryMode};
+
+pub use parameter::{
+    ConfigRequirement,
+    DuplicatePolicy,
+    Parameter,
+    ParameterConflict,
+    ParameterProcessor,
+    ParameterRequirement,
+    PerformanceImpact,
+    PerformanceRating,
+    SecurityImpact,
+    SecuritySeverity,
+    SyntaxDefinition,
+    DRACUT_MODULE_SELECTOR_PREFIX,
+    FLAG_SELECTOR_PREFIX,
+    SELECTOR_NAMESPACES
+};
+pub use query::{
+    glob_match,
+    ConditionExplanation,
+    DistributionQuery,
+    QueryCondition,
+    QueryExpr,
+    QueryExplanation,
+    QueryParameters,
+    QueryMode,
+    QueryResults,
+    SavedQuery,
+    QUERY_SCHEMA_VERSION,
+};
+pub use audit::{SecurityAuditReport, SecurityFinding, ValidationProfile};
+pub use deprecation::{DeprecatedUsage, DeprecationReport};
+pub use performance::{PerformanceFinding, PerformanceReport};
+pub use fixes::Fix;
+pub use fleet::{FindingRollup, FleetHost, FleetReport, HostReport, SourceId};
+pub use policy::{ApplicabilityMode, FindingSeverity, SeverityPolicy};
+pub use report::{ReportCounts, ReportFinding, ReportSeverity, ValidationReport, VALIDATION_REPORT_SCHEMA_VERSION};
 pub use catalog::{
+    CatalogBuilder,
     ParameterCatalog,
     ParameterTree,
     ParameterInfo,
     VendorVersion,
     VersionInfo
 };
-pub use parser::{CommandLineParser, ParsedParameter};
-pub use validators::{ValidationResult, ParameterValidator, ValidationSummary};
-pub use probe::{SystemProbe, HardwareProbe};
-pub use error::{KCmdlineError, ValidationError, RegistryError};
-pub use version::{KernelVersion, ComponentVersion};
+pub use compose::{CommandLineBuilder, Console, RootSpec};
+pub use profile::Profile;
+pub use config::{
+    ConsoleSpec,
+    CrashKernelPlacement,
+    CrashKernelRange,
+    CrashKernelSpec,
+    IpConfig,
+    MemMapKind,
+    MemMapRegion,
+    ParameterConfig,
+    Parity
+};
+pub use database::{
+    overlay_search_path,
+    DatabaseLoader,
+    FilesystemDatabase,
+    LoadedDatabase,
+    MergePolicy,
+    ParameterSource,
+    Provenance,
+    BUNDLE_SCHEMA_VERSION,
+    SYSTEM_OVERLAY_DIR
+};
+pub use schema::{SchemaPolicy, DEFINITION_SCHEMA_VERSION};
+#[cfg(feature = "remote")]
+pub use remote::{
+    default_cache_dir,
+    FetchRequest,
+    FetchResponse,
+    HttpFetcher,
+    RemoteDatabase,
+    UreqFetcher,
+    DEFAULT_MAX_RESPONSE_SIZE,
+    DEFAULT_TIMEOUT
+};
+pub use diff::{diff_parameters, CatalogDiff, FieldChange, ParameterDiff};
+pub use explain::{CommandLineExplanation, ParameterExplanation};
+pub use export::{ExportFormat, CATALOG_SCHEMA_VERSION};
+pub use snapshot::SNAPSHOT_FORMAT_VERSION;
+pub use trust::{snapshot_signature_path, SignatureVerifier, TrustPolicy, SIGNATURE_FILE};
+#[cfg(feature = "ed25519")]
+pub use trust::Ed25519Verifier;
+pub use editor::CommandLineEditor;
+pub use integrity::{IntegrityIssue, IntegrityReport};
+pub use lint::{LintCheck, LintFinding};
+pub use rules::{
+    CmdlineLintRule,
+    ConsoleOrderRule,
+    DuplicateRootRule,
+    EncryptedRootRule,
+    LintContext,
+    LintRuleRegistry,
+    QuietDebugRule,
+};
+pub use modinfo::ModuleParameterSource;
+pub use parser::{
+    CommandLineParser,
+    ConflictingParameters,
+    DuplicateParameter,
+    MissingRequirement,
+    NormalizationPolicy,
+    OrderingPolicy,
+    OverriddenParameter,
+    ParameterBuilder,
+    ParameterParser,
+    ParsedCommandLine,
+    ParsedParameter,
+    UnknownParameter
+};
+#[cfg(target_os = "linux")]
+pub use watch::CatalogWatcher;
+pub use validators::{
+    command_line_size,
+    MAX_NR_CPUS,
+    Finding,
+    FindingCategory,
+    ValidationResult,
+    ParameterValidator,
+    ValidationSummary,
+    COMMAND_LINE_FINDING
+};
+pub use sources::{
+    CommandLineSource,
+    SourcedCommandLine,
+    BlsEntry,
+    BlsEntrySource,
+    DracutCmdlineSource,
+    GrubDefaults,
+    GrubDefaultSource,
+    GrubEnv,
+    GrubEnvSource,
+    IpxeScriptSource,
+    KickstartSource,
+    PxelinuxConfigSource,
+    RaspberryPiCmdline,
+    RaspberryPiCmdlineSource,
+    UbootEnv,
+    UbootEnvSource,
+    UkiCmdlineExtractor,
+    ZiplConfig,
+    ZiplConfigSource,
+    ZiplSection,
+    ZIPL_LEGACY_PARAMETERS_LIMIT
+};
+pub use simulate::{BehaviorChange, CmdlineEdit, ImpactReport};
+pub use suppress::{Suppression, Suppressions, BASELINE_SCHEMA_VERSION};
+pub use taxonomy::{rule_info, RuleInfo, BUILTIN_RULES};
+pub use search::{tokenize, SearchField, TextIndex};
+pub use suggest::{edit_distance, fuzzy_score, SimilarityIndex};
+pub use probe::{
+    SystemProbe,
+    SystemProbeBuilder,
+    HardwareProbe,
+    KernelConfigProbe,
+    ModuleState,
+    VulnerabilityStatus,
+    Bootloader,
+    DetectedBootloader,
+    DistributionInfo,
+    CrashKernelState,
+    default_probe_cache_dir,
+    PROBE_SCHEMA_VERSION,
+};
+pub use error::{
+    BuildError,
+    DatabaseError,
+    DocumentContext,
+    KCmdlineError,
+    ValidationError,
+    ParseError,
+    ProbeError,
+    QueryError,
+    RegistryError,
+    ReportError,
+    SourceKind,
+    Span
+};
+pub use version::{compare_versions, KernelVersion, ComponentVersion};
 
 /// Main library interface
 pub struct KCmdline {
     catalog: ParameterCatalog,
     probe: SystemProbe,
+    suppressions: Suppressions,
 }
 
 impl KCmdline {
-    /// Unified parameter querying interface
-    pub fn query_parameters(&self, query: &QueryParameters) -> Vec<&Parameter> {
-        self.catalog.parameters().into_iter()
-            .filter(|param| self.matches_query(param, query))
-            .collect()
+    pub fn new(catalog: ParameterCatalog, probe: SystemProbe) -> Self {
+        Self {
+            catalog,
+            probe,
+            suppressions: Suppressions::default(),
+        }
+    }
+
+    /// Leave the findings `suppressions` match, such as those of a baseline
+    /// saved with [`Suppressions::save`], out of validation.
+    pub fn with_suppressions(mut self, suppressions: Suppressions) -> Self {
+        self.suppressions = suppressions;
+        self
+    }
+
+    /// Load the embedded parameter catalog and probe the running system,
+    /// reusing the probe cached earlier in this boot; see
+    /// [`SystemProbe::cached`]. A new probe is written to the cache in
+    /// [`default_probe_cache_dir`], under `$XDG_CACHE_HOME` or
+    /// `$HOME/.cache`; use [`KCmdline::new`] with [`SystemProbe::new`] to
+    /// leave the home directory untouched.
+    pub fn load() -> Result<Self, KCmdlineError> {
+        Ok(Self::new(ParameterCatalog::load_embedded()?, SystemProbe::cached()?))
+    }
+
+    /// Read, parse and validate the running kernel's command line from
+    /// /proc/cmdline against the embedded catalog and a probe of this system.
+    /// Like [`KCmdline::load`], this may write the probe cache under
+    /// `$XDG_CACHE_HOME` or `$HOME/.cache`.
+    pub fn from_current_system() -> Result<(ParsedCommandLine, ValidationSummary), KCmdlineError> {
+        let kcmdline = Self::load()?;
+        let cmdline = SystemProbe::current_cmdline()?;
+        kcmdline.validate_cmdline(&cmdline)
+    }
+
+    /// Parse and validate a command line against this system.
+    pub fn validate_cmdline(&self, cmdline: &str) -> Result<(ParsedCommandLine, ValidationSummary), KCmdlineError> {
+        let mut parsed = CommandLineParser::new(&self.catalog)
+            .with_lint_rules(LintRuleRegistry::with_builtin_rules())
+            .with_suppressions(self.suppressions.clone())
+            .parse_for_system(cmdline, &self.probe)?;
+        let modules = parsed.present_modules(&self.probe);
+        if !modules.is_empty() {
+            // Without modinfo, module parameters are checked against the catalog only
+            if let Ok(source) = ModuleParameterSource::from_modinfo(&modules, None) {
+                parsed.check_module_parameters(&source, &self.probe);
+            }
+        }
+        let summary = parsed.validation_summary.clone();
+        Ok((parsed, summary))
+    }
+
+    /// Validate many command lines in parallel against this system, e.g.
+    /// those collected from a fleet, rolling the results up by command line
+    /// and by finding.
+    pub fn validate_many<I>(&self, inputs: I) -> FleetReport
+    where
+        I: IntoIterator<Item = (SourceId, String)>,
+    {
+        self.validate_fleet(inputs.into_iter().map(|(id, cmdline)| FleetHost { id, cmdline, probe: None }))
+    }
+
+    /// Validate many command lines in parallel, each against the probe of
+    /// its own host where one is given and against this system otherwise.
+    pub fn validate_fleet<I>(&self, hosts: I) -> FleetReport
+    where
+        I: IntoIterator<Item = FleetHost>,
+    {
+        fleet::validate_fleet(&self.catalog, &self.probe, &self.suppressions, hosts.into_iter().collect())
+    }
+
+    /// Explain what each parameter of a command line does and what its
+    /// value means, with links to its documentation.
+    pub fn explain(&self, cmdline: &str) -> Result<CommandLineExplanation, KCmdlineError> {
+        let mut parser = CommandLineParser::new(&self.catalog);
+        if let Some(arch) = self.probe.arch() {
+            parser = parser.with_target_arch(arch);
+        }
+        Ok(parser.explain(cmdline)?)
+    }
+
+    /// Simulate `edits` to `base_cmdline`, reporting the findings they would
+    /// introduce or resolve and the parameters whose effect they change.
+    pub fn simulate_change(&self, base_cmdline: &str, edits: &[CmdlineEdit]) -> Result<ImpactReport, KCmdlineError> {
+        let edited = simulate::apply_edits(base_cmdline, edits);
+        let (before, _) = self.validate_cmdline(base_cmdline)?;
+        let (after, _) = self.validate_cmdline(&edited)?;
+        Ok(ImpactReport::new(&before, &after, (base_cmdline.to_string(), edited)))
+    }
+
+    /// Audit a command line for parameters that weaken security, such as
+    /// `mitigations=off` or `selinux=0`, most severe first.
+    pub fn security_audit(&self, cmdline: &str) -> Result<SecurityAuditReport, KCmdlineError> {
+        let mut parser = CommandLineParser::new(&self.catalog);
+        if let Some(arch) = self.probe.arch() {
+            parser = parser.with_target_arch(arch);
+        }
+        Ok(parser.security_audit(cmdline)?)
+    }
+
+    /// Summarize the expected performance consequences of a command line,
+    /// such as those of `nosmt` or `idle=poll`, largest first.
+    pub fn performance_review(&self, cmdline: &str) -> Result<PerformanceReport, KCmdlineError> {
+        let mut parser = CommandLineParser::new(&self.catalog);
+        if let Some(arch) = self.probe.arch() {
+            parser = parser.with_target_arch(arch);
+        }
+        Ok(parser.performance_review(cmdline)?)
     }
 
-    pub fn check_name_condition(&self, _param: &Parameter, _query: &QueryParameters) -> bool {
-        todo!()
+    /// List the deprecated parameters of a command line, with the exact
+    /// argument to use instead of each.
+    pub fn deprecation_report(&self, cmdline: &str) -> Result<DeprecationReport, KCmdlineError> {
+        Ok(CommandLineParser::new(&self.catalog).deprecation_report(cmdline)?)
     }
 
-    pub fn check_processor_condition(&self, _param: &Parameter, _query: &QueryParameters) -> bool {
-        todo!()
+    pub fn catalog(&self) -> &ParameterCatalog {
+        &self.catalog
     }
 
-    pub fn check_hardware_condition(&self, _param: &Parameter, _query: &QueryParameters) -> bool {
-        todo!()
+    pub fn probe(&self) -> &SystemProbe {
+        &self.probe
     }
 
-    pub fn check_applicability_condition(&self, _param: &Parameter, _query: &QueryParameters) -> bool {
-        todo!()
+    /// Unified parameter querying interface. Results of a text search are
+    /// ordered by relevance.
+    pub fn query_parameters(&self, query: &QueryParameters) -> Vec<&Parameter> {
+        self.catalog.query_parameters_on(query, &self.probe)
     }
 
-    pub fn check_distribution_condition(&self, _param: &Parameter, _query: &QueryParameters) -> bool {
-        todo!()
+    /// Run a query and collect owned, serializable results.
+    pub fn query_results(&self, query: &QueryParameters) -> QueryResults {
+        let parameters = self.query_parameters(query).into_iter().cloned().collect();
+        QueryResults::new(query.clone(), parameters)
     }
 
-    pub fn check_deprecated_condition(&self, _param: &Parameter, _query: &QueryParameters) -> bool {
-        todo!()
+    /// Find the parameters matching a query expression. When the expression
+    /// contains text conditions, results are ordered by their combined
+    /// relevance.
+    pub fn query(&self, expr: &QueryExpr) -> Vec<&Parameter> {
+        self.catalog.query(expr, &self.probe)
     }
 
-    pub fn check_flags_condition(&self, _param: &Parameter, _query: &QueryParameters) -> bool {
-        todo!()
+    /// Lazily yield the parameters matching `query`, evaluating conditions
+    /// only as the iterator is advanced. Results come in catalog order, not
+    /// ranked by relevance as with `query_parameters`.
+    pub fn query_iter<'a>(&'a self, query: &QueryParameters) -> impl Iterator<Item = &'a Parameter> + 'a {
+        self.query_expr_iter(query.to_expr())
     }
 
-    fn matches_query(&self, param: &Parameter, query: &QueryParameters) -> bool {
-        let conditions = vec![
-            self.check_name_condition(param, &query),
-            self.check_processor_condition(param, &query),
-            self.check_hardware_condition(param, query),
-            self.check_applicability_condition(param, &query),
-            self.check_distribution_condition(param, &query),
-            self.check_deprecated_condition(param, &query),
-            self.check_flags_condition(param, &query),
-        ];
+    /// Lazily yield the parameters matching a query expression, in catalog
+    /// order.
+    pub fn query_expr_iter(&self, expr: QueryExpr) -> impl Iterator<Item = &Parameter> + '_ {
+        let text_scores = self.catalog.text_scores(&expr);
+        self.catalog.entries()
+            .filter(move |(key, param)| self.catalog.matches_expr(key, param, &expr, &text_scores, &self.probe))
+            .map(|(_, param)| param)
+    }
+
+    /// Match the query against parameters and subparameters alike and group
+    /// the results by top-level parameter: each entry holds a parent that
+    /// matched or has matching children, with only the matching children.
+    pub fn query_tree(&self, query: &QueryParameters) -> Vec<ParameterInfo> {
+        let expr = query.filter_expr();
+        let text_scores = self.catalog.text_scores(&expr);
+        let matches = |key: &str, param: &Parameter| self.catalog.matches_expr(key, param, &expr, &text_scores, &self.probe);
+
+        let mut groups: Vec<(u32, &str, ParameterInfo)> = Vec::new();
+        for (key, param) in self.catalog.entries().filter(|(key, _)| !self.catalog.is_subparameter(key)) {
+            let children: Vec<(&str, &Parameter)> = self.catalog.get_subparameter_entries(key)
+                .into_iter()
+                .filter(|(child_key, child)| matches(child_key, child))
+                .collect();
+            if children.is_empty() && !matches(key, param) {
+                continue;
+            }
 
-        match query.query_mode {
-            QueryMode::And => conditions.iter().all(|&c| c),
-            QueryMode::Or => conditions.iter().any(|&c| c),
+            let score = std::iter::once(key)
+                .chain(children.iter().map(|(child_key, _)| *child_key))
+                .map(|k| ParameterCatalog::combined_score(k, &text_scores))
+                .max()
+                .unwrap_or(0);
+            groups.push((score, key, ParameterInfo {
+                parameter: param.clone(),
+                subparameters: children.into_iter().map(|(_, child)| child.clone()).collect(),
+                applicable: param.is_applicable(&self.probe),
+                version_info: self.catalog.version_info(key).cloned().unwrap_or_default(),
+                provenance: self.catalog.provenance(key).cloned().unwrap_or_default(),
+            }));
         }
+
+        groups.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+        groups.into_iter().map(|(_, _, info)| info).collect()
+    }
+
+    /// Fuzzy, ranked lookup of parameter names for as-you-type completion.
+    pub fn search(&self, term: &str) -> Vec<(&Parameter, u32)> {
+        self.catalog.fuzzy_search(term)
     }
+
+    /// Report, condition by condition, why `param` is or is not returned by
+    /// `query`.
+    pub fn explain_query(&self, param: &Parameter, query: &QueryParameters) -> QueryExplanation {
+        let expr = query.to_expr();
+        let conditions = expr.conditions();
+        let key = self.catalog.key_of(param).unwrap_or(&param.name);
+        let versions = self.catalog.version_info(key);
+
+        let explanations: Vec<ConditionExplanation> = conditions.iter()
+            .map(|condition| match condition {
+                QueryCondition::Text(text) => {
+                    let score = self.catalog.search_keys(text)
+                        .into_iter()
+                        .find(|(hit, _)| *hit == key)
+                        .map(|(_, score)| score);
+                    ConditionExplanation {
+                        condition: condition.to_string(),
+                        matched: score.is_some(),
+                        reason: match score {
+                            Some(score) => format!("found with relevance {}", score),
+                            None => "not every word was found".to_string(),
+                        },
+                    }
+                }
+                QueryCondition::Subparameter(subparameter) => {
+                    let is_subparameter = self.catalog.is_subparameter(key);
+                    ConditionExplanation {
+                        condition: condition.to_string(),
+                        matched: is_subparameter == *subparameter,
+                        reason: format!(
+                            "{} is {}",
+                            key,
+                            if is_subparameter { "a subparameter" } else { "a top-level parameter" }
+                        ),
+                    }
+                }
+                condition => condition.explain(param, &self.probe, versions),
+            })
+            .collect();
+
+        let matched = expr.evaluate(&mut |leaf| {
+            conditions.iter()
+                .position(|condition| std::ptr::eq(*condition, leaf))
+                .is_some_and(|i| explanations[i].matched)
+        });
+
+        QueryExplanation {
+            parameter: param.name.clone(),
+            matched,
+            conditions: explanations,
+        }
+    }
+
+    pub fn check_name_condition(&self, param: &Parameter, query: &QueryParameters) -> bool {
+        query.name.as_ref().is_none_or(|regex| self.check_condition(param, &QueryCondition::Name(regex.clone())))
+            && query.name_glob.as_ref().is_none_or(|glob| self.check_condition(param, &QueryCondition::NameGlob(glob.clone())))
+    }
+
+    pub fn check_processor_condition(&self, param: &Parameter, query: &QueryParameters) -> bool {
+        query.processor.as_ref().is_none_or(|processor| query::processor_matches(param, processor))
+    }
+
+    pub fn check_hardware_condition(&self, param: &Parameter, query: &QueryParameters) -> bool {
+        (query.pci_ids.is_empty() || query::ids_match(param, "pci:", &query.pci_ids))
+            && (query.usb_ids.is_empty() || query::ids_match(param, "usb:", &query.usb_ids))
+            && query.arch.as_deref().is_none_or(|arch| query::arch_matches(param, arch))
+    }
+
+    pub fn check_applicability_condition(&self, param: &Parameter, query: &QueryParameters) -> bool {
+        query.applicable.is_none_or(|applicable| self.check_condition(param, &QueryCondition::Applicable(applicable)))
+    }
+
+    pub fn check_distribution_condition(&self, param: &Parameter, query: &QueryParameters) -> bool {
+        query.distribution.as_ref().is_none_or(|distribution| {
+            self.check_condition(param, &QueryCondition::Distribution(distribution.clone()))
+        })
+    }
+
+    pub fn check_deprecated_condition(&self, param: &Parameter, query: &QueryParameters) -> bool {
+        query.deprecated.is_none_or(|deprecated| self.check_condition(param, &QueryCondition::Deprecated(deprecated)))
+    }
+
+    pub fn check_flags_condition(&self, param: &Parameter, query: &QueryParameters) -> bool {
+        query::flags_match(param, &query.flags)
+    }
+
+    fn check_condition(&self, param: &Parameter, condition: &QueryCondition) -> bool {
+        condition.matches(param, &self.probe, None)
+    }
+
     //
     // Convenience methods that build QueryParameters
     pub fn find_parameters(&self, pattern: &str) -> Vec<&Parameter> {
@@ -104,3 +549,23 @@ impl KCmdline {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_against_the_embedded_catalog_and_a_probe() {
+        let root = std::env::temp_dir().join(format!("libkcmdline-kcmdline-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        let probe = SystemProbe::from_sysroot(&root).unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+
+        let kcmdline = KCmdline::new(ParameterCatalog::load_embedded().unwrap(), probe);
+        let (parsed, summary) = kcmdline.validate_cmdline("root=/dev/sda1 ro quiet -- single").unwrap();
+        assert_eq!(parsed.parameters.len(), 3);
+        assert_eq!(parsed.init_arguments, ["single"]);
+        assert_eq!(summary.total_parameters, 3);
+        assert!(summary.is_valid());
+    }
+}
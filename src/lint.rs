@@ -0,0 +1,142 @@
+//! Consistency checks over the parameter database itself, so that broken
+//! definitions are found when the database changes rather than when a
+//! command line happens to use them.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::catalog::ParameterCatalog;
+use crate::parameter::Parameter;
+use crate::validators::{StandardValidatorRegistry, ValidationResult, ValidatorRegistry};
+
+/// The check a lint finding comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintCheck {
+    /// The syntax names a validator the registry does not provide.
+    UnknownValidator,
+    /// An enum parameter lists no choices.
+    EmptyChoices,
+    /// An example of a valid value fails validation.
+    ValidExampleRejected,
+    /// An example of an invalid value passes validation.
+    InvalidExampleAccepted,
+    /// A selector is not of the form `category:value`, or has a malformed
+    /// PCI or USB id.
+    MalformedSelector,
+    /// A subparameter's parent is not in the catalog.
+    MissingParent,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LintFinding {
+    /// Catalog key of the offending parameter.
+    pub key: String,
+    pub check: LintCheck,
+    pub message: String,
+}
+
+impl fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.message)
+    }
+}
+
+/// Check every definition in `catalog`, returning findings sorted by key.
+pub fn lint_catalog(catalog: &ParameterCatalog) -> Vec<LintFinding> {
+    let registry = StandardValidatorRegistry::new();
+    let mut findings = Vec::new();
+
+    for (key, param) in catalog.entries() {
+        let mut report = |check: LintCheck, message: String| {
+            findings.push(LintFinding {
+                key: key.to_string(),
+                check,
+                message,
+            });
+        };
+        lint_syntax(param, &registry, &mut report);
+        lint_selectors(param, &mut report);
+    }
+
+    for (parent, children) in catalog.subparameter_parents() {
+        if catalog.get_parameter(parent).is_none() {
+            findings.extend(children.iter().map(|child| LintFinding {
+                key: child.to_string(),
+                check: LintCheck::MissingParent,
+                message: format!("parent parameter '{}' is not in the catalog", parent),
+            }));
+        }
+    }
+
+    findings.sort_by(|a, b| a.key.cmp(&b.key).then(a.check.cmp(&b.check)));
+    findings
+}
+
+fn lint_syntax(param: &Parameter, registry: &dyn ValidatorRegistry, report: &mut impl FnMut(LintCheck, String)) {
+    let syntax = &param.syntax;
+    if syntax.validator_type == "enum" {
+        let choices = syntax.config.get("choices").and_then(|v| v.as_array());
+        if choices.is_none_or(|choices| choices.is_empty()) {
+            report(LintCheck::EmptyChoices, "enum parameter has no choices".to_string());
+        }
+    }
+
+    let Some(validator) = registry.get_validator(&param.processor, &syntax.validator_type) else {
+        let message = if syntax.validator_type.is_empty() {
+            "no validator type".to_string()
+        } else {
+            format!("unknown validator type '{}'", syntax.validator_type)
+        };
+        report(LintCheck::UnknownValidator, message);
+        return;
+    };
+
+    for example in &param.examples.valid {
+        if let ValidationResult::Error(error) = validator.validate(example, &syntax.config) {
+            report(
+                LintCheck::ValidExampleRejected,
+                format!("valid example '{}' is rejected: {}", example, error),
+            );
+        }
+    }
+    for example in &param.examples.invalid {
+        if !matches!(validator.validate(example, &syntax.config), ValidationResult::Error(_)) {
+            report(
+                LintCheck::InvalidExampleAccepted,
+                format!("invalid example '{}' is accepted", example),
+            );
+        }
+    }
+}
+
+fn lint_selectors(param: &Parameter, report: &mut impl FnMut(LintCheck, String)) {
+    for selector in &param.selectors {
+        if let Some(problem) = selector_problem(selector) {
+            report(LintCheck::MalformedSelector, format!("selector '{}' {}", selector, problem));
+        }
+    }
+}
+
+fn selector_problem(selector: &str) -> Option<&'static str> {
+    let Some((category, value)) = selector.split_once(':') else {
+        return Some("has no category");
+    };
+    if category.is_empty() || !category.chars().all(|c| c.is_ascii_lowercase() || c == '_' || c == '-') {
+        return Some("has a malformed category");
+    }
+    if value.is_empty() || value.chars().any(char::is_whitespace) {
+        return Some("has an empty or malformed value");
+    }
+
+    if category == "pci" || category == "usb" {
+        // <vendor>[:<device>], four hex digits or a wildcard each
+        let ids: Vec<&str> = value.split(':').collect();
+        let id_ok = |id: &str| id == "*" || (id.len() == 4 && id.chars().all(|c| c.is_ascii_hexdigit()));
+        if ids.len() > 2 || !ids.iter().all(|id| id_ok(id)) {
+            return Some("is not a <vendor>[:<device>] id");
+        }
+    }
+    None
+}
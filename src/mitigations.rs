@@ -0,0 +1,110 @@
+//! Advice on CPU vulnerability mitigation parameters, from the kernel's own
+//! assessment of the CPU: mitigations for vulnerabilities the CPU does not
+//! have only cost performance, and disabling those it does have leaves the
+//! system exposed.
+
+use crate::parser::ParsedParameter;
+use crate::probe::{SystemProbe, VulnerabilityStatus};
+use crate::taxonomy;
+use crate::validators::{Finding, ValidationResult};
+
+/// Parameters selecting the mitigation of a vulnerability, named as in
+/// /sys/devices/system/cpu/vulnerabilities. `off` disables the mitigation.
+const MITIGATION_PARAMETERS: &[(&str, &str)] = &[
+    ("gather_data_sampling", "gather_data_sampling"),
+    ("l1tf", "l1tf"),
+    ("mds", "mds"),
+    ("mmio_stale_data", "mmio_stale_data"),
+    ("pti", "meltdown"),
+    ("reg_file_data_sampling", "reg_file_data_sampling"),
+    ("retbleed", "retbleed"),
+    ("spec_rstack_overflow", "spec_rstack_overflow"),
+    ("spec_store_bypass_disable", "spec_store_bypass"),
+    ("spectre_bhi", "spectre_v2"),
+    ("spectre_v2", "spectre_v2"),
+    ("spectre_v2_user", "spectre_v2"),
+    ("srbds", "srbds"),
+    ("tsx_async_abort", "tsx_async_abort"),
+];
+
+/// Parameters whose presence disables the mitigation of a vulnerability.
+const DISABLING_PARAMETERS: &[(&str, &str)] = &[
+    ("nopti", "meltdown"),
+    ("nospec_store_bypass_disable", "spec_store_bypass"),
+    ("nospectre_bhb", "spectre_v2"),
+    ("nospectre_v1", "spectre_v1"),
+    ("nospectre_v2", "spectre_v2"),
+];
+
+/// Values that leave the choice of mitigation to the kernel, which applies
+/// none on CPUs that are not affected.
+const KERNEL_CHOICE_VALUES: &[&str] = &["auto", "prctl", "seccomp"];
+
+/// How vulnerabilities are usually written, for messages.
+const VULNERABILITY_NAMES: &[(&str, &str)] = &[
+    ("gather_data_sampling", "GDS"),
+    ("itlb_multihit", "iTLB multihit"),
+    ("l1tf", "L1TF"),
+    ("mds", "MDS"),
+    ("meltdown", "Meltdown"),
+    ("mmio_stale_data", "MMIO stale data"),
+    ("reg_file_data_sampling", "RFDS"),
+    ("retbleed", "Retbleed"),
+    ("spec_rstack_overflow", "SRSO"),
+    ("spec_store_bypass", "Speculative Store Bypass"),
+    ("spectre_v1", "Spectre v1"),
+    ("spectre_v2", "Spectre v2"),
+    ("srbds", "SRBDS"),
+    ("tsx_async_abort", "TAA"),
+];
+
+/// Findings about the effective mitigation parameters among `parameters`
+/// for vulnerabilities the probe has the kernel's assessment of.
+pub(crate) fn mitigation_findings(parameters: &[ParsedParameter], probe: &SystemProbe) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let affected = |status: Option<VulnerabilityStatus>| {
+        matches!(status, Some(VulnerabilityStatus::Mitigated | VulnerabilityStatus::Vulnerable))
+    };
+
+    for param in parameters.iter().filter(|p| p.effective && p.module.is_none()) {
+        let value = param.value.as_deref().unwrap_or("");
+        let arg = if param.value.is_some() { format!("{}={}", param.name, value) } else { param.name.clone() };
+        let mut report = |message: String| {
+            let finding = Finding::new(&param.name, ValidationResult::Warning(message), Some(param.span.clone()));
+            findings.push(finding.with_rule_info(&taxonomy::MITIGATION_MISMATCH));
+        };
+
+        if param.name == "mitigations" && value == "off" {
+            let exposed: Vec<&str> = probe.vulnerabilities().into_iter()
+                .filter(|(_, status)| affected(Some(*status)))
+                .map(|(name, _)| display_name(name))
+                .collect();
+            if !exposed.is_empty() {
+                let pronoun = if exposed.len() == 1 { "it" } else { "them" };
+                report(format!("This CPU is affected by {}; {} leaves {} unmitigated", exposed.join(", "), arg, pronoun));
+            }
+        } else if let Some((_, vulnerability)) = DISABLING_PARAMETERS.iter().find(|(name, _)| *name == param.name) {
+            if affected(probe.vulnerability_status(vulnerability)) {
+                report(format!("This CPU is affected by {}; {} leaves it unmitigated", display_name(vulnerability), arg));
+            }
+        } else if let Some((_, vulnerability)) = MITIGATION_PARAMETERS.iter().find(|(name, _)| *name == param.name) {
+            let status = probe.vulnerability_status(vulnerability);
+            if value == "off" {
+                if affected(status) {
+                    report(format!("This CPU is affected by {}; {} leaves it unmitigated", display_name(vulnerability), arg));
+                }
+            } else if status == Some(VulnerabilityStatus::NotAffected)
+                && !KERNEL_CHOICE_VALUES.iter().any(|choice| value.starts_with(choice))
+            {
+                report(format!("This CPU is not affected by {}; {} only costs performance", display_name(vulnerability), arg));
+            }
+        }
+    }
+    findings
+}
+
+fn display_name(vulnerability: &str) -> &str {
+    VULNERABILITY_NAMES.iter()
+        .find(|(name, _)| *name == vulnerability)
+        .map_or(vulnerability, |(_, display)| display)
+}
@@ -0,0 +1,324 @@
+//! Catalog source for the parameters of kernel modules, harvested from
+//! `modinfo` and the kernel's `modules.builtin.modinfo`.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use crate::catalog::module_parameter_key;
+use crate::database::{ParameterDefinitionRaw, ParameterSource, ParameterVersionsRaw, SyntaxDefinitionRaw};
+use crate::error::DatabaseError;
+
+const MODINFO: &str = "modinfo";
+const MODULES_DIR: &str = "/lib/modules";
+const OSRELEASE_PATH: &str = "/proc/sys/kernel/osrelease";
+
+/// Validator for module parameters, checking values against `parmtype`.
+const MODULE_PARAM_VALIDATOR: &str = "module_param";
+
+/// Compression suffixes of module files in `modules.dep`.
+const MODULE_SUFFIXES: &[&str] = &[".ko", ".ko.xz", ".ko.zst", ".ko.gz"];
+
+#[derive(Debug, Clone, Default)]
+struct ModuleParameter {
+    description: Option<String>,
+    parmtype: Option<String>,
+}
+
+/// Parameters of kernel modules, catalogued as `module/<module>/<parameter>`
+/// with a `module_param` validator derived from their declared type.
+#[derive(Debug, Clone, Default)]
+pub struct ModuleParameterSource {
+    // (module, parameter) -> details
+    parameters: BTreeMap<(String, String), ModuleParameter>,
+    // Every module described, including those without parameters
+    modules: BTreeSet<String>,
+}
+
+impl ModuleParameterSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Harvest the parameters of the modules built into and installed for
+    /// the running kernel.
+    pub fn system() -> Result<Self, DatabaseError> {
+        let release = fs::read_to_string(OSRELEASE_PATH)?;
+        Self::for_release(Path::new(MODULES_DIR).join(release.trim()))
+    }
+
+    /// Harvest the parameters of the modules in a kernel's module directory,
+    /// e.g. `/lib/modules/6.8.0`: built-in modules from
+    /// `modules.builtin.modinfo` and loadable ones listed in `modules.dep`
+    /// through `modinfo`.
+    pub fn for_release<P: AsRef<Path>>(module_dir: P) -> Result<Self, DatabaseError> {
+        let module_dir = module_dir.as_ref();
+        let mut source = Self::new();
+
+        let builtin = module_dir.join("modules.builtin.modinfo");
+        if builtin.exists() {
+            source.merge(Self::from_builtin_modinfo(&fs::read(builtin)?));
+        }
+
+        let dep = module_dir.join("modules.dep");
+        if dep.exists() {
+            let modules = Self::modules_in_dep(&fs::read_to_string(dep)?);
+            let release = module_dir.file_name().and_then(|name| name.to_str());
+            source.merge(Self::from_modinfo(&modules, release)?);
+        }
+
+        Ok(source)
+    }
+
+    /// Run `modinfo` on the named modules, optionally for another kernel
+    /// release than the running one.
+    pub fn from_modinfo<S: AsRef<str>>(modules: &[S], release: Option<&str>) -> Result<Self, DatabaseError> {
+        if modules.is_empty() {
+            return Ok(Self::new());
+        }
+
+        let mut command = Command::new(MODINFO);
+        if let Some(release) = release {
+            command.args(["-k", release]);
+        }
+        command.args(modules.iter().map(|module| module.as_ref()));
+
+        let output = command.output()
+            .map_err(|e| DatabaseError::LoadError(format!("Failed to run {}: {}", MODINFO, e)))?;
+        // modinfo fails if any module is missing, but still describes the rest
+        if output.stdout.is_empty() && !output.status.success() {
+            return Err(DatabaseError::LoadError(format!(
+                "{} failed: {}", MODINFO, String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(Self::parse_modinfo(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// Parse `modinfo` output for one or more modules. Each module's record
+    /// starts with its `filename:` line; parameters are listed as
+    /// `parm: <name>:<description> (<type>)`.
+    pub fn parse_modinfo(output: &str) -> Self {
+        let mut source = Self::new();
+        let mut module: Option<String> = None;
+        let mut pending: Vec<(String, ModuleParameter)> = Vec::new();
+
+        let mut flush = |module: &Option<String>, pending: &mut Vec<(String, ModuleParameter)>| {
+            if let Some(module) = module {
+                source.modules.insert(module.replace('-', "_"));
+                for (name, param) in pending.drain(..) {
+                    source.insert(module, &name, param);
+                }
+            }
+            pending.clear();
+        };
+
+        for line in output.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key {
+                "filename" => {
+                    flush(&module, &mut pending);
+                    module = Self::module_from_filename(value);
+                }
+                "name" => module = Some(value.to_string()),
+                "parm" => {
+                    if let Some((name, rest)) = value.split_once(':') {
+                        pending.push((name.to_string(), Self::parse_parm(rest)));
+                    }
+                }
+                _ => {}
+            }
+        }
+        flush(&module, &mut pending);
+        source
+    }
+
+    /// Parse the NUL-separated `module.key=value` records of
+    /// `modules.builtin.modinfo`.
+    pub fn from_builtin_modinfo(data: &[u8]) -> Self {
+        let mut source = Self::new();
+
+        for record in data.split(|&b| b == 0) {
+            let record = String::from_utf8_lossy(record);
+            let Some((module, entry)) = record.split_once('.') else {
+                continue;
+            };
+            source.modules.insert(module.replace('-', "_"));
+            let Some((key, value)) = entry.split_once('=') else {
+                continue;
+            };
+            let Some((name, detail)) = value.split_once(':') else {
+                continue;
+            };
+            if key != "parm" && key != "parmtype" {
+                continue;
+            }
+            let param = source.parameters
+                .entry((module.replace('-', "_"), name.to_string()))
+                .or_default();
+            let detail = Some(detail.trim().to_string());
+            if key == "parm" {
+                param.description = detail;
+            } else {
+                param.parmtype = detail;
+            }
+        }
+        source
+    }
+
+    /// Add the parameters of `other`, whose details take precedence.
+    pub fn merge(&mut self, other: Self) {
+        for (key, param) in other.parameters {
+            let existing = self.parameters.entry(key).or_default();
+            existing.description = param.description.or(existing.description.take());
+            existing.parmtype = param.parmtype.or(existing.parmtype.take());
+        }
+        self.modules.extend(other.modules);
+    }
+
+    /// Whether `module` was described, whether or not it has parameters.
+    pub fn has_module(&self, module: &str) -> bool {
+        self.modules.contains(&module.replace('-', "_"))
+    }
+
+    /// The parameters of `module`, in name order.
+    pub fn parameters_of(&self, module: &str) -> Vec<&str> {
+        let module = module.replace('-', "_");
+        self.parameters.keys()
+            .filter(|(m, _)| *m == module)
+            .map(|(_, name)| name.as_str())
+            .collect()
+    }
+
+    /// The module parameter named `parameter`, spelt with underscores or
+    /// dashes as the kernel allows, and its declared type if modinfo gave
+    /// one.
+    pub fn parameter(&self, module: &str, parameter: &str) -> Option<(&str, Option<&str>)> {
+        let module = module.replace('-', "_");
+        let parameter = parameter.replace('-', "_");
+        self.parameters.iter()
+            .find(|((m, name), _)| *m == module && name.replace('-', "_") == parameter)
+            .map(|((_, name), details)| (name.as_str(), details.parmtype.as_deref()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.parameters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parameters.is_empty()
+    }
+
+    fn insert(&mut self, module: &str, name: &str, param: ModuleParameter) {
+        self.parameters.insert((module.replace('-', "_"), name.to_string()), param);
+    }
+
+    /// Split `<description> (<type>)`; a bare type is printed for parameters
+    /// without a description.
+    fn parse_parm(text: &str) -> ModuleParameter {
+        let text = text.trim();
+        let typed = text.strip_suffix(')')
+            .and_then(|t| t.rsplit_once(" ("))
+            .filter(|(_, parmtype)| Self::is_parmtype(parmtype));
+        if let Some((description, parmtype)) = typed {
+            return ModuleParameter {
+                description: Some(description.trim().to_string()),
+                parmtype: Some(parmtype.to_string()),
+            };
+        }
+        if Self::is_parmtype(text) {
+            return ModuleParameter {
+                description: None,
+                parmtype: Some(text.to_string()),
+            };
+        }
+        ModuleParameter {
+            description: Some(text.to_string()),
+            parmtype: None,
+        }
+    }
+
+    fn is_parmtype(text: &str) -> bool {
+        const TYPES: &[&str] = &[
+            "bool", "invbool", "charp", "string", "byte", "short", "ushort",
+            "int", "uint", "hexint", "long", "ulong", "llong", "ullong",
+        ];
+        let element = text.strip_prefix("array of ").unwrap_or(text);
+        TYPES.contains(&element)
+    }
+
+    fn module_from_filename(filename: &str) -> Option<String> {
+        let file = Path::new(filename).file_name()?.to_str()?;
+        MODULE_SUFFIXES.iter()
+            .find_map(|suffix| file.strip_suffix(suffix))
+            .map(|name| name.replace('-', "_"))
+    }
+
+    /// Module names from `modules.dep`, whose lines are `<path>: <deps>`.
+    fn modules_in_dep(content: &str) -> Vec<String> {
+        content.lines()
+            .filter_map(|line| line.split_once(':'))
+            .filter_map(|(path, _)| Self::module_from_filename(path))
+            .collect()
+    }
+
+    fn definition(module: &str, name: &str, param: &ModuleParameter) -> ParameterDefinitionRaw {
+        let mut config = HashMap::new();
+        if let Some(parmtype) = &param.parmtype {
+            config.insert("parmtype".to_string(), toml::Value::String(parmtype.clone()));
+        }
+
+        ParameterDefinitionRaw {
+            name: format!("{}.{}", module, name),
+            processor: "kernel".to_string(),
+            description: param.description.clone().unwrap_or_default(),
+            syntax: SyntaxDefinitionRaw {
+                validator_type: MODULE_PARAM_VALIDATOR.to_string(),
+                format: param.parmtype.clone().unwrap_or_default(),
+                config,
+            },
+            ..Default::default()
+        }
+    }
+
+    fn split_key(key: &str) -> Option<(&str, &str)> {
+        key.strip_prefix("module/")?.split_once('/')
+    }
+}
+
+impl ParameterSource for ModuleParameterSource {
+    fn source_name(&self) -> String {
+        MODINFO.to_string()
+    }
+
+    fn list_parameters(&self) -> Result<Vec<String>, DatabaseError> {
+        Ok(self.parameters.keys()
+            .map(|(module, name)| module_parameter_key(module, name))
+            .collect())
+    }
+
+    /// Harvested once, so never changes.
+    fn parameter_stamp(&self, _name: &str) -> Option<u64> {
+        Some(0)
+    }
+
+    fn get_parameter_definition(&self, name: &str) -> Result<Option<ParameterDefinitionRaw>, DatabaseError> {
+        Ok(Self::split_key(name).and_then(|(module, param)| {
+            self.parameters
+                .get(&(module.to_string(), param.to_string()))
+                .map(|details| Self::definition(module, param, details))
+        }))
+    }
+
+    fn get_parameter_versions(&self, _name: &str) -> Result<Option<ParameterVersionsRaw>, DatabaseError> {
+        Ok(None)
+    }
+
+    fn get_subparameters(&self, _parent: &str) -> Result<Vec<String>, DatabaseError> {
+        Ok(Vec::new())
+    }
+}
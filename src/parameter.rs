@@ -1,17 +1,61 @@
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::collections::HashMap;
 
+use crate::query::glob_match;
+use crate::version::compare_versions;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Parameter {
     pub name: String,
     pub processor: ParameterProcessor,
     pub description: String,
     pub deprecated: bool,
+    /// The release the parameter was deprecated in, e.g. `5.11`.
+    #[serde(default)]
+    pub deprecated_since: Option<String>,
+    /// The argument to use instead of a deprecated parameter, in which
+    /// `{value}` stands for the value given to it, e.g. `ip={value}`.
+    #[serde(default)]
+    pub replacement: Option<String>,
+    /// Former names of the parameter that are still accepted.
+    #[serde(default)]
+    pub aliases: Vec<String>,
     pub selectors: Vec<String>,
     pub syntax: SyntaxDefinition,
+    /// Overrides of `syntax` on particular architectures, keyed by the
+    /// architecture name used in `arch:` selectors.
+    #[serde(default)]
+    pub arch_syntax: HashMap<String, SyntaxDefinition>,
     pub distributions: HashMap<String, DistributionSupport>,
     pub examples: Examples,
     pub documentation: Option<DocumentationLinks>,
+    pub duplicate_policy: DuplicatePolicy,
+    /// Parameters that must not be given together with this one.
+    #[serde(default)]
+    pub conflicts_with: Vec<ParameterConflict>,
+    /// Parameters without which this one has no effect.
+    #[serde(default)]
+    pub requires: Vec<ParameterRequirement>,
+    /// Settings this parameter sets, e.g. `console_loglevel`. Of the
+    /// parameters on a command line that set the same one, the last takes
+    /// effect and overrides the others.
+    #[serde(default)]
+    pub controls: Vec<String>,
+    /// How values of this parameter weaken the security of the system.
+    #[serde(default)]
+    pub security_impact: Vec<SecurityImpact>,
+    /// How values of this parameter change the performance of the system.
+    #[serde(default)]
+    pub performance_impact: Vec<PerformanceImpact>,
+    /// Kernel options and CPU features without which the kernel ignores
+    /// this parameter.
+    #[serde(default)]
+    pub required_config: Vec<ConfigRequirement>,
+    /// Metadata attached by downstream consumers, keyed by `x-<namespace>`.
+    #[serde(default)]
+    pub extra: HashMap<String, toml::Value>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
@@ -24,6 +68,18 @@ pub enum ParameterProcessor {
     Grub,
 }
 
+/// How the consumer of a parameter treats repeated occurrences of it.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum DuplicatePolicy {
+    /// Each occurrence replaces the previous value.
+    #[default]
+    LastWins,
+    /// The first occurrence is used and later ones are ignored.
+    FirstWins,
+    /// Every occurrence takes effect (e.g. multiple console= devices).
+    Additive,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyntaxDefinition {
     pub validator_type: String,
@@ -31,6 +87,24 @@ pub struct SyntaxDefinition {
     pub config: HashMap<String, toml::Value>,
 }
 
+impl SyntaxDefinition {
+    /// This syntax as a partial override of `base`: the validator type and
+    /// format are those of `base` unless set here, and config keys set here
+    /// replace those of `base`.
+    pub fn overriding(&self, base: &SyntaxDefinition) -> SyntaxDefinition {
+        let pick = |own: &String, inherited: &String| {
+            if own.is_empty() { inherited.clone() } else { own.clone() }
+        };
+        let mut config = base.config.clone();
+        config.extend(self.config.iter().map(|(key, value)| (key.clone(), value.clone())));
+        SyntaxDefinition {
+            validator_type: pick(&self.validator_type, &base.validator_type),
+            format: pick(&self.format, &base.format),
+            config,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DistributionSupport {
     pub min_version: Option<String>,
@@ -45,6 +119,127 @@ pub struct Examples {
     pub invalid: Vec<String>,
 }
 
+/// A parameter, or some of its values, that contradicts the parameter
+/// declaring the conflict when both are on a command line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParameterConflict {
+    /// The conflicting parameter as written on the command line, e.g.
+    /// `i915.modeset`. A parameter naming itself is ignored; its repeats
+    /// are reported as duplicates.
+    pub parameter: String,
+    /// The values of `parameter` that conflict; any value when empty.
+    pub values: Vec<String>,
+    /// Why the two cannot be combined.
+    pub reason: String,
+}
+
+/// Companion parameters that the parameter declaring the requirement only
+/// works with, at least one of which must be on the command line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParameterRequirement {
+    /// The companions, as written on the command line; any one will do.
+    pub parameters: Vec<String>,
+    /// Globs for the values of the declaring parameter that need a
+    /// companion, e.g. `iscsi:*`; every value when empty.
+    pub when: Vec<String>,
+    /// An argument to suggest adding, e.g. `ip=dhcp`.
+    pub suggestion: Option<String>,
+    /// What the parameter needs the companion for.
+    pub reason: String,
+}
+
+/// How seriously a parameter value weakens the security of a system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SecuritySeverity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl SecuritySeverity {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+            Self::Critical => "critical",
+        }
+    }
+
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "low" => Some(Self::Low),
+            "medium" => Some(Self::Medium),
+            "high" => Some(Self::High),
+            "critical" => Some(Self::Critical),
+            _ => None,
+        }
+    }
+}
+
+/// Values of a parameter that weaken the security of the system, and why.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SecurityImpact {
+    pub severity: SecuritySeverity,
+    /// Globs for the weakening values; any value, or none, when empty.
+    pub when: Vec<String>,
+    pub rationale: String,
+}
+
+/// How much a parameter value changes the performance of a system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PerformanceRating {
+    Low,
+    Medium,
+    High,
+}
+
+impl PerformanceRating {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "low" => Some(Self::Low),
+            "medium" => Some(Self::Medium),
+            "high" => Some(Self::High),
+            _ => None,
+        }
+    }
+}
+
+/// Values of a parameter that change performance, and what to expect.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PerformanceImpact {
+    pub rating: PerformanceRating,
+    /// Globs for the values with this impact; any value, or none, when
+    /// empty.
+    pub when: Vec<String>,
+    pub effect: String,
+}
+
+/// Kernel build options and CPU flags that a parameter, or some of its
+/// values, needs in order to take effect.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigRequirement {
+    /// Options that must be built in or modular, e.g. `CONFIG_NO_HZ_FULL`.
+    pub options: Vec<String>,
+    /// CPU flags, as in /proc/cpuinfo, that must be present.
+    pub cpu_flags: Vec<String>,
+    /// Globs for the values with this requirement; every value when empty.
+    pub when: Vec<String>,
+    /// The architectures the requirement applies on; all when empty.
+    pub arch: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DocumentationLinks {
     pub kernel_org: Option<String>,
@@ -52,14 +247,166 @@ pub struct DocumentationLinks {
     pub distribution_docs: HashMap<String, String>,
 }
 
+/// Selector prefix for the documentation flags of a parameter (e.g.
+/// `flag:EARLY`), which describe it rather than the systems it applies to.
+pub const FLAG_SELECTOR_PREFIX: &str = "flag:";
+
+/// Selector prefix naming the dracut module that reads a parameter (e.g.
+/// `dracut_module:crypt`). The parameter has no effect unless the module is
+/// included in the initramfs.
+pub const DRACUT_MODULE_SELECTOR_PREFIX: &str = "dracut_module:";
+
+/// Whether `selector` describes the parameter, as `flag:` and
+/// `dracut_module:` selectors do, rather than the systems it applies to.
+pub(crate) fn is_descriptive_selector(selector: &str) -> bool {
+    selector.starts_with(FLAG_SELECTOR_PREFIX) || selector.starts_with(DRACUT_MODULE_SELECTOR_PREFIX)
+}
+
+/// The namespaces of the tags selectors match: those the system probe
+/// produces, and the descriptive `flag:` and `dracut_module:` selectors.
+pub const SELECTOR_NAMESPACES: &[&str] = &[
+    "arch", "cpu", "pci", "pci-class", "usb", "dmi", "dt", "tpm", "iommu", "cc", "config",
+    "module", "flag", "dracut_module",
+];
+
 impl Parameter {
-    pub fn is_applicable(&self, _probe: &crate::probe::SystemProbe) -> bool {
-        // Implementation for checking applicability
-        todo!()
+    /// A parameter with no description, selectors or syntax, to be filled
+    /// in by the caller, e.g. for a [`CatalogBuilder`](crate::CatalogBuilder).
+    pub fn new<S: Into<String>>(name: S, processor: ParameterProcessor) -> Self {
+        Self {
+            name: name.into(),
+            processor,
+            description: String::new(),
+            deprecated: false,
+            deprecated_since: None,
+            replacement: None,
+            aliases: Vec::new(),
+            selectors: Vec::new(),
+            syntax: SyntaxDefinition {
+                validator_type: String::new(),
+                format: String::new(),
+                config: HashMap::new(),
+            },
+            arch_syntax: HashMap::new(),
+            distributions: HashMap::new(),
+            examples: Examples {
+                valid: Vec::new(),
+                invalid: Vec::new(),
+            },
+            documentation: None,
+            duplicate_policy: DuplicatePolicy::default(),
+            conflicts_with: Vec::new(),
+            requires: Vec::new(),
+            controls: Vec::new(),
+            security_impact: Vec::new(),
+            performance_impact: Vec::new(),
+            required_config: Vec::new(),
+            extra: HashMap::new(),
+        }
+    }
+
+    /// The syntax of the parameter on `arch`, with the architecture's
+    /// overrides applied. Without an architecture, or for architectures
+    /// with no overrides, this is the common syntax.
+    pub fn syntax_for_arch(&self, arch: Option<&str>) -> Cow<'_, SyntaxDefinition> {
+        match arch.and_then(|arch| self.arch_syntax.get(arch)) {
+            Some(syntax) => Cow::Owned(syntax.overriding(&self.syntax)),
+            None => Cow::Borrowed(&self.syntax),
+        }
+    }
+
+    /// The values of the selectors with the given category prefix, e.g.
+    /// `"arch:"` yields `"s390x"` for `arch:s390x`.
+    pub fn selector_values<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.selectors.iter().filter_map(move |s| s.strip_prefix(prefix))
+    }
+
+    /// The dracut modules that read the parameter, e.g. `crypt` for
+    /// `rd.luks.uuid`.
+    pub fn dracut_modules(&self) -> impl Iterator<Item = &str> {
+        self.selector_values(DRACUT_MODULE_SELECTOR_PREFIX)
+    }
+
+    /// Whether any selector of the parameter matches `pattern`, which may use
+    /// shell-style wildcards (e.g. `pci:8086:*`).
+    pub fn has_selector(&self, pattern: &str) -> bool {
+        self.selectors.iter().any(|selector| glob_match(pattern, selector))
+    }
+
+    /// A parameter applies to a system when, for every category of selector
+    /// it has (`arch:`, `pci:`, ...), at least one selector of that category
+    /// is among the probed tags. Parameters without selectors apply everywhere.
+    pub fn is_applicable(&self, probe: &crate::probe::SystemProbe) -> bool {
+        self.is_applicable_to_tags(probe.hardware_tags.iter().chain(&probe.software_tags))
+    }
+
+    /// Applicability against an explicit set of tags. Selectors may contain
+    /// wildcards, so `pci:8086:*` applies to any Intel PCI device.
+    pub fn is_applicable_to_tags<'a, I>(&self, tags: I) -> bool
+    where
+        I: IntoIterator<Item = &'a String> + Clone,
+    {
+        self.unmatched_selectors(tags).is_empty()
+    }
+
+    /// Why the parameter does not apply to a system with `tags`: the
+    /// selectors of each category none of which is among them, by category
+    /// in selector order. Empty when the parameter applies.
+    pub fn unmatched_selectors<'a, I>(&self, tags: I) -> Vec<(&str, Vec<&str>)>
+    where
+        I: IntoIterator<Item = &'a String> + Clone,
+    {
+        let mut categories: Vec<(&str, Vec<&str>, bool)> = Vec::new();
+        for selector in self.selectors.iter().filter(|selector| !is_descriptive_selector(selector)) {
+            let category = selector.split_once(':').map_or(selector.as_str(), |(c, _)| c);
+            let present = tags.clone().into_iter().any(|tag| glob_match(selector, tag));
+            match categories.iter_mut().find(|(c, ..)| *c == category) {
+                Some((_, selectors, matched)) => {
+                    selectors.push(selector);
+                    *matched |= present;
+                }
+                None => categories.push((category, vec![selector], present)),
+            }
+        }
+        categories.into_iter()
+            .filter(|(_, _, matched)| !matched)
+            .map(|(category, selectors, _)| (category, selectors))
+            .collect()
+    }
+
+    /// Whether the parameter is supported by a distribution release. An empty
+    /// version matches any release; parameters without distribution data are
+    /// assumed to be available everywhere.
+    pub fn is_available_in_distribution(&self, distro: &str, version: &str) -> bool {
+        if self.distributions.is_empty() {
+            return true;
+        }
+        let Some(support) = self.distributions.get(distro) else {
+            return false;
+        };
+        if version.is_empty() {
+            return true;
+        }
+        let after_min = support.min_version.as_deref()
+            .is_none_or(|min| compare_versions(min, version) != Ordering::Greater);
+        let before_max = support.max_version.as_deref()
+            .is_none_or(|max| compare_versions(version, max) != Ordering::Greater);
+        after_min && before_max
     }
 
-    pub fn is_available_in_distribution(&self, _distro: &str, _version: &str) -> bool {
-        // Implementation for distribution checking
-        todo!()
+    /// Whether the parameter is supported by an installed distribution. The
+    /// support data is looked up under the distribution's own id and then
+    /// those it is like, so that data for `rhel` applies on Rocky Linux and
+    /// AlmaLinux. Release bounds only apply to the distribution's own id,
+    /// as a derivative may number its releases independently.
+    pub fn is_available_on(&self, distribution: &crate::probe::DistributionInfo) -> bool {
+        if self.distributions.is_empty() {
+            return true;
+        }
+        match distribution.resolve(|id| self.distributions.contains_key(id)) {
+            Some((id, true)) => self.is_available_in_distribution(id, &distribution.version_id),
+            Some((id, false)) => self.is_available_in_distribution(id, ""),
+            None => false,
+        }
     }
 }
@@ -1,70 +1,1444 @@
-use crate::parameter::Parameter;
-use crate::validators::ValidationResult;
-use crate::catalog::ParameterCatalog;
+use std::collections::HashSet;
+
+use crate::audit::{security_findings, SecurityAuditReport, ValidationProfile};
+use crate::deprecation::{current_argument, deprecated_usages, is_renamed, DeprecationReport};
+use crate::parameter::{DuplicatePolicy, Parameter, ParameterProcessor};
+use crate::explain::CommandLineExplanation;
+use crate::performance::{performance_findings, PerformanceReport};
+use crate::fixes::{value_fix, Fix};
+use crate::policy::{ApplicabilityMode, SeverityPolicy};
+use crate::rules::{LintContext, LintRuleRegistry};
+use crate::suppress::Suppressions;
+use crate::taxonomy;
+use crate::validators::{
+    validate_command_line_length,
+    StandardValidatorRegistry,
+    COMMAND_LINE_FINDING,
+    Finding,
+    ValidationResult,
+    ValidationSummary,
+    ValidatorRegistry,
+};
+use crate::catalog::{module_parameter_key, ParameterCatalog};
+use crate::config::ParameterConfig;
+use crate::error::Span;
+use crate::modinfo::ModuleParameterSource;
 use crate::probe::SystemProbe;
+use crate::query::glob_match;
+use crate::sources::{CommandLineSource, SourcedCommandLine};
+use crate::system_checks::{applicability_findings, module_parameter_findings, system_findings};
 
 #[derive(Debug, Clone)]
 pub struct ParsedParameter {
     pub name: String,
     pub value: Option<String>,
+    /// The module a `module.parameter` argument is addressed to.
+    pub module: Option<String>,
+    pub source: Option<String>,
+    /// The whole argument, including any quotes, in the parsed line.
+    pub span: Span,
+    /// The value with surrounding quotes removed, if a value was given.
+    pub value_span: Option<Span>,
+    /// False when a repeated occurrence elsewhere on the line supersedes this one.
+    pub effective: bool,
     pub subparameters: Vec<ParsedParameter>,
     pub validation: ValidationResult,
     pub parameter_def: Option<Parameter>,
+    /// The value parsed according to the parameter's syntax, for parameters
+    /// with a structured syntax whose value validated.
+    pub config: Option<ParameterConfig>,
+    /// The value a rejected value was evidently meant as, e.g. `1` for
+    /// `True`.
+    pub value_fix: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ParsedCommandLine {
+    pub source: Option<String>,
     pub parameters: Vec<ParsedParameter>,
-    pub unknown_parameters: Vec<String>,
+    pub unknown_parameters: Vec<UnknownParameter>,
+    /// Uncatalogued parameters shaped like `module.parameter`, which the
+    /// kernel hands to the named module rather than to init.
+    pub module_parameters: Vec<String>,
+    /// Arguments following `--`, passed to init instead of the kernel, as
+    /// init receives them: with the kernel's quotes removed.
+    pub init_arguments: Vec<String>,
+    pub duplicates: Vec<DuplicateParameter>,
+    pub conflicts: Vec<ConflictingParameters>,
+    pub overrides: Vec<OverriddenParameter>,
+    pub missing_requirements: Vec<MissingRequirement>,
     pub validation_summary: crate::validators::ValidationSummary,
 }
 
+/// A parameter that is not in the catalog, with similarly named parameters
+/// that it may be a misspelling of.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownParameter {
+    pub name: String,
+    pub suggestions: Vec<String>,
+}
+
+/// A parameter that occurs more than once on a command line.
+#[derive(Debug, Clone)]
+pub struct DuplicateParameter {
+    pub name: String,
+    pub policy: DuplicatePolicy,
+    /// Indices into `ParsedCommandLine::parameters` of every occurrence.
+    pub occurrences: Vec<usize>,
+    /// Indices of the occurrences that take effect under `policy`.
+    pub effective: Vec<usize>,
+}
+
+/// Two parameters on a command line that a `conflicts_with` declaration
+/// rules out together.
+#[derive(Debug, Clone)]
+pub struct ConflictingParameters {
+    /// Indices into `ParsedCommandLine::parameters` of the earlier and the
+    /// later of the two.
+    pub first: usize,
+    pub second: usize,
+    pub reason: String,
+}
+
+/// A parameter whose effect another on the same command line nullifies:
+/// an occurrence superseded by a repeat of the same parameter with another
+/// value, or a parameter setting what a later one sets too, as declared by
+/// the `controls` field of their definitions.
+#[derive(Debug, Clone)]
+pub struct OverriddenParameter {
+    /// Indices into `ParsedCommandLine::parameters` of the overridden
+    /// parameter and of the one that takes effect instead.
+    pub overridden: usize,
+    pub winner: usize,
+    /// The setting both parameters set, for different parameters.
+    pub setting: Option<String>,
+}
+
+/// A parameter whose `requires` declaration names companions none of
+/// which are on the command line.
+#[derive(Debug, Clone)]
+pub struct MissingRequirement {
+    /// Index into `ParsedCommandLine::parameters` of the parameter.
+    pub parameter: usize,
+    /// The companions, any one of which would do.
+    pub requires: Vec<String>,
+    pub suggestion: Option<String>,
+    pub reason: String,
+}
+
+/// The order parameters are arranged in by `ParsedCommandLine::normalize_with`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OrderingPolicy {
+    /// Keep the original order.
+    Preserve,
+    /// Sort by parameter name.
+    Alphabetical,
+    /// Group kernel, module, and then userspace parameters by processor,
+    /// with unknown parameters last, sorting by name within each group.
+    #[default]
+    ByProcessor,
+}
+
+/// Controls how a command line is rewritten into canonical form.
+#[derive(Debug, Clone)]
+pub struct NormalizationPolicy {
+    pub ordering: OrderingPolicy,
+    /// Drop occurrences that have no effect under the parameter's duplicate
+    /// policy, and exact repeats of additive parameters.
+    pub deduplicate: bool,
+    /// Rewrite the values of boolean parameters as `1` or `0`.
+    pub canonical_booleans: bool,
+}
+
+impl Default for NormalizationPolicy {
+    fn default() -> Self {
+        Self {
+            ordering: OrderingPolicy::default(),
+            deduplicate: true,
+            canonical_booleans: true,
+        }
+    }
+}
+
 pub struct CommandLineParser<'a> {
     catalog: &'a ParameterCatalog,
+    registry: StandardValidatorRegistry,
+    target_arch: Option<String>,
+    profile: ValidationProfile,
+    severity_policy: SeverityPolicy,
+    suppressions: Suppressions,
+    lint_rules: LintRuleRegistry,
+    applicability: ApplicabilityMode,
 }
 
+/// Resolves and validates a single argument, such as one typed into an
+/// editor, without the checks that need the whole command line.
 pub struct ParameterParser<'a> {
     catalog: &'a ParameterCatalog,
+    registry: StandardValidatorRegistry,
+    target_arch: Option<String>,
 }
 
 pub struct ParameterBuilder<'a> {
     parameter: &'a Parameter,
+    registry: StandardValidatorRegistry,
+    target_arch: Option<String>,
+}
+
+/// A single argument split from a command line, before catalog lookup.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RawArgument<'s> {
+    pub(crate) text: &'s str,
+    pub(crate) param: &'s str,
+    pub(crate) value: Option<&'s str>,
+}
+
+/// Whitespace as understood by the kernel's isspace().
+fn is_space(c: u8) -> bool {
+    matches!(c, b' ' | b'\t' | b'\n' | 0x0b | 0x0c | b'\r')
+}
+
+fn skip_spaces(args: &str) -> &str {
+    let start = args.bytes().position(|c| !is_space(c)).unwrap_or(args.len());
+    &args[start..]
+}
+
+/// Split the next argument from `args`, returning it together with the rest
+/// of the line (leading whitespace removed).
+///
+/// This follows next_arg() in the kernel's lib/cmdline.c: double quotes
+/// protect whitespace, a leading quote on the value (or on the whole
+/// argument) is dropped along with a matching trailing quote, and quotes
+/// elsewhere are kept as part of the text.
+fn next_arg(line: &str) -> (RawArgument<'_>, &str) {
+    let (args, quoted) = match line.strip_prefix('"') {
+        Some(rest) => (rest, true),
+        None => (line, false),
+    };
+    let bytes = args.as_bytes();
+    let mut in_quote = quoted;
+    let mut equals = None;
+
+    let mut i = 0;
+    while i < bytes.len() {
+        if is_space(bytes[i]) && !in_quote {
+            break;
+        }
+        // The kernel records the first '=' past the start of the argument
+        if equals.is_none() && i > 0 && bytes[i] == b'=' {
+            equals = Some(i);
+        }
+        if bytes[i] == b'"' {
+            in_quote = !in_quote;
+        }
+        i += 1;
+    }
+
+    let trailing_quote = i > 0 && bytes[i - 1] == b'"';
+    let (param, value) = match equals {
+        None => {
+            let end = if quoted && trailing_quote { i - 1 } else { i };
+            (&args[..end], None)
+        }
+        Some(eq) => {
+            let mut start = eq + 1;
+            let mut end = i;
+            // Don't include quotes in value.
+            if bytes.get(start) == Some(&b'"') {
+                start += 1;
+                if trailing_quote {
+                    end = i - 1;
+                }
+            }
+            if quoted && trailing_quote {
+                end = i - 1;
+            }
+            (&args[..eq], Some(&args[start..end.max(start)]))
+        }
+    };
+
+    let rest = if i < bytes.len() { &args[i + 1..] } else { &args[i..] };
+    let text = &line[..i + usize::from(quoted)];
+    (RawArgument { text, param, value }, skip_spaces(rest))
+}
+
+/// Namespaces used by userspace components that read the kernel command line,
+/// whose dotted parameters are not module parameters.
+const USERSPACE_NAMESPACES: &[&str] = &[
+    "rd", "systemd", "udev", "plymouth", "vconsole", "locale", "luks", "fstab",
+];
+
+/// Split a parameter with the `module.parameter` shape the kernel uses to
+/// route parameters to loadable modules into its module and parameter names.
+///
+/// The kernel treats '-' and '_' in module names as equivalent, so the module
+/// name is returned with dashes normalized to underscores.
+fn split_module_parameter(name: &str) -> Option<(String, &str)> {
+    let (module, param) = name.split_once('.')?;
+    let module_shaped = !module.is_empty()
+        && !param.is_empty()
+        && module.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        && !USERSPACE_NAMESPACES.contains(&module);
+
+    module_shaped.then(|| (module.replace('-', "_"), param))
+}
+
+/// Render a parameter as it would be written on a command line, quoting the
+/// value if it contains whitespace.
+pub(crate) fn render_argument(param: &str, value: Option<&str>) -> String {
+    match value {
+        Some(value) if value.contains(char::is_whitespace) => format!("{}=\"{}\"", param, value),
+        Some(value) => format!("{}={}", param, value),
+        None => param.to_string(),
+    }
+}
+
+impl RawArgument<'_> {
+    /// The argument as the kernel hands it to init, without its quotes.
+    fn unquoted(&self) -> String {
+        match self.value {
+            Some(value) => format!("{}={}", self.param, value),
+            None => self.param.to_string(),
+        }
+    }
+}
+
+/// An unquoted argument, such as one for init, as it would be written on a
+/// command line: a value holding whitespace is quoted, or the whole
+/// argument when the name holds it too.
+pub(crate) fn quote_argument(argument: &str) -> String {
+    if !argument.contains(char::is_whitespace) {
+        return argument.to_string();
+    }
+    match argument.char_indices().skip(1).find(|&(_, c)| c == '=') {
+        Some((eq, _)) if !argument[..eq].contains(char::is_whitespace) => {
+            render_argument(&argument[..eq], Some(&argument[eq + 1..]))
+        }
+        _ => format!("\"{}\"", argument),
+    }
+}
+
+/// Split a complete command line into its arguments.
+pub(crate) fn split_args(cmdline: &str) -> Vec<RawArgument<'_>> {
+    let mut arguments = Vec::new();
+    let mut args = skip_spaces(cmdline);
+
+    while !args.is_empty() {
+        let (arg, rest) = next_arg(args);
+        arguments.push(arg);
+        args = rest;
+    }
+
+    arguments
+}
+
+/// The span of `text`, which must be a slice of `line`.
+pub(crate) fn span_within(line: &str, text: &str) -> Span {
+    let start = text.as_ptr() as usize - line.as_ptr() as usize;
+    Span::new(start, start + text.len())
+}
+
+/// Look up and validate a single split argument from `line` against the
+/// catalog, using the syntax of the parameter on `arch` when given.
+fn resolve_argument(
+    catalog: &ParameterCatalog,
+    registry: &dyn ValidatorRegistry,
+    arch: Option<&str>,
+    line: &str,
+    arg: &RawArgument<'_>,
+) -> ParsedParameter {
+    let module = split_module_parameter(arg.param);
+    let mut renamed = false;
+    let parameter_def = catalog.get_parameter(arg.param)
+        .or_else(|| {
+            module.as_ref().and_then(|(module, param)| catalog.get_module_parameter(module, param))
+        })
+        .or_else(|| {
+            let alias = catalog.resolve_alias(arg.param).or_else(|| {
+                module.as_ref().and_then(|(module, param)| {
+                    catalog.resolve_alias(&module_parameter_key(module, param))
+                })
+            });
+            renamed = alias.is_some();
+            alias.map(|(_, param)| param)
+        })
+        .cloned();
+
+    let syntax = parameter_def.as_ref().map(|param| param.syntax_for_arch(arch));
+    let validation = match (&parameter_def, &syntax) {
+        (Some(param), Some(syntax)) => {
+            let validation = match registry.get_validator(&param.processor, &syntax.validator_type) {
+                Some(validator) => validator.validate(arg.value.unwrap_or(""), &syntax.config),
+                None => ValidationResult::Valid,
+            };
+            match validation {
+                // Problems with the value take precedence over the old spelling
+                ValidationResult::Valid | ValidationResult::Info(_) if renamed => ValidationResult::Warning(format!(
+                    "'{}' has been renamed; use '{}' instead", arg.param, param.name
+                )),
+                validation => validation,
+            }
+        }
+        _ => match &module {
+            Some((module, _)) => ValidationResult::Info(format!(
+                "Parameter for module '{}' is not in the catalog", module
+            )),
+            None => {
+                let suggestions = catalog.suggest(arg.param);
+                match suggestions.first() {
+                    Some(suggestion) => ValidationResult::Unknown(format!(
+                        "Unknown parameter: {} (did you mean '{}'?)", arg.param, suggestion
+                    )),
+                    None => ValidationResult::Unknown(format!("Unknown parameter: {}", arg.param)),
+                }
+            }
+        },
+    };
+
+    let config = match (&syntax, &validation) {
+        (_, ValidationResult::Error(_)) => None,
+        (Some(syntax), _) => ParameterConfig::parse(arg.value, syntax),
+        (None, _) => None,
+    };
+    let value_fix = match (&syntax, &validation, arg.value) {
+        (Some(syntax), ValidationResult::Error(_), Some(value)) => value_fix(&syntax.validator_type, &syntax.config, value),
+        _ => None,
+    };
+
+    ParsedParameter {
+        name: arg.param.to_string(),
+        value: arg.value.map(|v| v.to_string()),
+        module: module.map(|(module, _)| module),
+        source: None,
+        span: span_within(line, arg.text),
+        value_span: arg.value.map(|value| span_within(line, value)),
+        effective: true,
+        subparameters: Vec::new(),
+        validation,
+        parameter_def,
+        config,
+        value_fix,
+    }
+}
+
+impl ParsedParameter {
+    /// The span diagnostics about this parameter should point at: the value
+    /// of a known parameter when there is one, otherwise the whole argument.
+    pub fn diagnostic_span(&self) -> Span {
+        match (&self.parameter_def, &self.value_span) {
+            (Some(_), Some(value_span)) => value_span.clone(),
+            _ => self.span.clone(),
+        }
+    }
+
+    /// The finding for the parameter's own validation result, with the fix
+    /// for its value if there is one. The warning for a parameter given
+    /// under a former name is a deprecation, fixed by the current name.
+    pub(crate) fn finding(&self) -> Finding {
+        let finding = Finding::new(&self.name, self.validation.clone(), Some(self.diagnostic_span()));
+        let rule = match (&self.validation, &self.parameter_def) {
+            (ValidationResult::Valid, _) => return finding,
+            (ValidationResult::Unknown(_), _) => &taxonomy::UNKNOWN_PARAMETER,
+            (_, None) => &taxonomy::UNCATALOGUED_MODULE_PARAMETER,
+            (_, Some(_)) => &taxonomy::INVALID_VALUE,
+        };
+        let finding = finding.with_rule_info(rule);
+        if let (Some(fix), Some(value_span)) = (&self.value_fix, &self.value_span) {
+            return finding.with_fix(Fix::new(value_span.clone(), fix));
+        }
+        match &self.parameter_def {
+            Some(def) if matches!(self.validation, ValidationResult::Warning(_)) && is_renamed(self) => finding
+                .with_rule_info(&taxonomy::RENAMED_PARAMETER)
+                .with_fix(Fix::new(self.span.clone(), &current_argument(self, def))),
+            _ => finding,
+        }
+    }
+}
+
+impl ParsedCommandLine {
+    /// Attribute the command line, its parameters and their spans to `source`.
+    pub fn set_source(&mut self, source: &str) {
+        self.source = Some(source.to_string());
+        for param in &mut self.parameters {
+            param.source = Some(source.to_string());
+            param.span.source = Some(source.to_string());
+            if let Some(span) = &mut param.value_span {
+                span.source = Some(source.to_string());
+            }
+        }
+        for finding in &mut self.validation_summary.details {
+            if let Some(span) = &mut finding.span {
+                span.source = Some(source.to_string());
+            }
+            if let Some(fix) = &mut finding.fix {
+                fix.span.source = Some(source.to_string());
+            }
+        }
+    }
+
+    /// Check the parameters of modules present on `probe`'s system against
+    /// the `modinfo` descriptions in `modules`, adding a warning for each
+    /// parameter a module does not have and an error for each value that
+    /// does not match its parameter's type.
+    pub fn check_module_parameters(&mut self, modules: &ModuleParameterSource, probe: &SystemProbe) {
+        for finding in module_parameter_findings(&self.parameters, modules, probe) {
+            self.validation_summary.push(finding);
+        }
+    }
+
+    /// The modules present on `probe`'s system that parameters are
+    /// addressed to, for harvesting their `modinfo` descriptions.
+    pub fn present_modules(&self, probe: &SystemProbe) -> Vec<String> {
+        let mut modules: Vec<String> = self.parameters.iter()
+            .filter_map(|param| param.module.clone())
+            .filter(|module| probe.module_state(module).is_some())
+            .collect();
+        modules.sort();
+        modules.dedup();
+        modules
+    }
+
+    /// Render the parameters and init arguments back into a command line.
+    pub fn to_cmdline(&self) -> String {
+        let args = self.parameters
+            .iter()
+            .map(|param| render_argument(&param.name, param.value.as_deref()))
+            .collect();
+        self.join_arguments(args)
+    }
+
+    /// The fixes attached to the findings of the validation summary.
+    pub fn fixes(&self) -> Vec<&Fix> {
+        self.validation_summary.details.iter().filter_map(|finding| finding.fix.as_ref()).collect()
+    }
+
+    /// The command line with every fix applied: arguments replaced or
+    /// removed, and values corrected. Where a parameter has fixes for both
+    /// the argument and its value, the argument's is applied.
+    pub fn apply_fixes(&self) -> String {
+        let fixes = self.fixes();
+        let fix_at = |span: &Span| fixes.iter().find(|fix| fix.span == *span);
+        let args = self.parameters
+            .iter()
+            .filter_map(|param| {
+                let argument = match (fix_at(&param.span), param.value_span.as_ref().and_then(fix_at)) {
+                    (Some(fix), _) => fix.replacement.clone(),
+                    (None, Some(fix)) => render_argument(&param.name, Some(&fix.replacement)),
+                    (None, None) => render_argument(&param.name, param.value.as_deref()),
+                };
+                (!argument.is_empty()).then_some(argument)
+            })
+            .collect();
+        self.join_arguments(args)
+    }
+
+    fn join_arguments(&self, mut args: Vec<String>) -> String {
+        if !self.init_arguments.is_empty() {
+            args.push("--".to_string());
+            args.extend(self.init_arguments.iter().map(|arg| quote_argument(arg)));
+        }
+        args.join(" ")
+    }
+
+    /// Rewrite the command line into canonical form using the default policy.
+    pub fn normalize(&self) -> ParsedCommandLine {
+        self.normalize_with(&NormalizationPolicy::default())
+    }
+
+    /// Rewrite the command line into canonical form, so that command lines
+    /// with the same effect compare equal via `to_cmdline()`.
+    ///
+    /// Spans on the returned parameters still refer to the original text.
+    pub fn normalize_with(&self, policy: &NormalizationPolicy) -> ParsedCommandLine {
+        let mut parameters: Vec<ParsedParameter> = Vec::new();
+
+        for (index, param) in self.parameters.iter().enumerate() {
+            if policy.deduplicate {
+                let repeated_later = self.parameters[index + 1..]
+                    .iter()
+                    .any(|later| later.name == param.name && later.value == param.value);
+                if !param.effective || repeated_later {
+                    continue;
+                }
+            }
+
+            let mut param = param.clone();
+            param.effective = true;
+            if policy.canonical_booleans {
+                param.value = canonical_boolean(&param);
+            }
+            parameters.push(param);
+        }
+
+        match policy.ordering {
+            OrderingPolicy::Preserve => {}
+            OrderingPolicy::Alphabetical => parameters.sort_by(|a, b| a.name.cmp(&b.name)),
+            OrderingPolicy::ByProcessor => {
+                parameters.sort_by(|a, b| (processor_rank(a), &a.name).cmp(&(processor_rank(b), &b.name)))
+            }
+        }
+
+        let mut validation_summary = ValidationSummary::with_policy(self.validation_summary.policy.clone())
+            .with_suppressions(self.validation_summary.suppressions.clone());
+        for param in &parameters {
+            validation_summary.record_finding(parameter_finding(param, &self.unknown_parameters));
+        }
+
+        let mut normalized = ParsedCommandLine {
+            source: self.source.clone(),
+            parameters,
+            unknown_parameters: self.unknown_parameters.clone(),
+            module_parameters: self.module_parameters.clone(),
+            init_arguments: self.init_arguments.clone(),
+            duplicates: Vec::new(),
+            conflicts: Vec::new(),
+            overrides: Vec::new(),
+            missing_requirements: Vec::new(),
+            validation_summary,
+        };
+        detect_duplicates(&mut normalized);
+        detect_conflicts(&mut normalized);
+        detect_overrides(&mut normalized);
+        detect_missing_requirements(&mut normalized);
+        detect_deprecations(&mut normalized);
+
+        normalized
+    }
+}
+
+/// The value of a boolean parameter spelled as `1` or `0`.
+fn canonical_boolean(param: &ParsedParameter) -> Option<String> {
+    let is_boolean = param.parameter_def
+        .as_ref()
+        .is_some_and(|def| def.syntax.validator_type == "boolean");
+
+    match param.value.as_deref() {
+        Some("1" | "true" | "on" | "yes" | "y") if is_boolean => Some("1".to_string()),
+        Some("0" | "false" | "off" | "no" | "n") if is_boolean => Some("0".to_string()),
+        _ => param.value.clone(),
+    }
+}
+
+/// Sort group for `OrderingPolicy::ByProcessor`.
+fn processor_rank(param: &ParsedParameter) -> u8 {
+    match param.parameter_def.as_ref().map(|def| &def.processor) {
+        Some(ParameterProcessor::Kernel) if param.module.is_none() => 0,
+        Some(ParameterProcessor::Kernel) => 1,
+        Some(ParameterProcessor::Systemd { .. }) => 2,
+        Some(ParameterProcessor::Dracut { .. }) => 3,
+        Some(ParameterProcessor::InitramfsTools) => 4,
+        Some(ParameterProcessor::Plymouth) => 5,
+        Some(ParameterProcessor::Grub) => 6,
+        None if param.module.is_some() => 1,
+        None => 7,
+    }
+}
+
+/// Report a command line longer than the boot loader it was read from
+/// accepts, unless it was already reported as too long for the
+/// architecture, and warn of one longer than older versions accept.
+fn check_source_length(parsed: &mut ParsedCommandLine, length: usize, source: &dyn CommandLineSource) {
+    let too_long = parsed.validation_summary.details.iter().any(|finding| {
+        finding.parameter == COMMAND_LINE_FINDING && matches!(finding.result, ValidationResult::Error(_))
+    });
+    let result = match (source.length_limit(), source.legacy_length_limit()) {
+        (Some(limit), _) if length > limit && !too_long => ValidationResult::Error(format!(
+            "Command line is {} bytes; the boot loader accepts at most {}", length, limit
+        )),
+        (_, Some(legacy)) if length > legacy && !too_long => ValidationResult::Warning(format!(
+            "Command line is {} bytes; older kernels and boot loaders accept at most {}", length, legacy
+        )),
+        _ => return,
+    };
+    parsed.validation_summary.push(
+        Finding::new(COMMAND_LINE_FINDING, result, Some(Span::new(0, length)))
+            .with_rule_info(&taxonomy::COMMAND_LINE_LENGTH)
+    );
+}
+
+/// Make the spans of a command line joined from fragments relative to
+/// the fragment each falls within. Spans crossing fragments, such as that
+/// of the whole command line, are left as they are.
+fn attribute_fragments(parsed: &mut ParsedCommandLine, fragments: &[Span]) {
+    let locate = |span: &mut Span| {
+        let fragment = fragments.iter().find(|f| f.start <= span.start && span.end <= f.end)?;
+        span.start -= fragment.start;
+        span.end -= fragment.start;
+        span.source = fragment.source.clone();
+        fragment.source.clone()
+    };
+    for param in &mut parsed.parameters {
+        param.source = locate(&mut param.span);
+        if let Some(span) = &mut param.value_span {
+            locate(span);
+        }
+    }
+    for finding in &mut parsed.validation_summary.details {
+        if let Some(span) = &mut finding.span {
+            locate(span);
+        }
+        if let Some(fix) = &mut finding.fix {
+            locate(&mut fix.span);
+        }
+    }
+}
+
+/// Find repeated parameters, mark superseded occurrences as not effective and
+/// report each duplicate according to the parameter's duplicate policy.
+fn detect_duplicates(parsed: &mut ParsedCommandLine) {
+    let mut occurrences: indexmap::IndexMap<&str, Vec<usize>> = indexmap::IndexMap::new();
+    for (index, param) in parsed.parameters.iter().enumerate() {
+        // Occurrences under a former name are the same parameter
+        let name = param.parameter_def.as_ref().map_or(param.name.as_str(), |def| def.name.as_str());
+        occurrences.entry(name).or_default().push(index);
+    }
+
+    let mut duplicates = Vec::new();
+    for (name, indices) in occurrences.into_iter().filter(|(_, indices)| indices.len() > 1) {
+        let policy = parsed.parameters[indices[0]].parameter_def
+            .as_ref()
+            .map(|def| def.duplicate_policy)
+            .unwrap_or_default();
+
+        let effective = match policy {
+            DuplicatePolicy::LastWins => vec![indices[indices.len() - 1]],
+            DuplicatePolicy::FirstWins => vec![indices[0]],
+            DuplicatePolicy::Additive => indices.clone(),
+        };
+
+        duplicates.push(DuplicateParameter {
+            name: name.to_string(),
+            policy,
+            occurrences: indices,
+            effective,
+        });
+    }
+
+    for duplicate in &duplicates {
+        for &index in &duplicate.occurrences {
+            parsed.parameters[index].effective = duplicate.effective.contains(&index);
+        }
+
+        let values: Vec<&Option<String>> = duplicate.occurrences
+            .iter()
+            .map(|&index| &parsed.parameters[index].value)
+            .collect();
+        let identical = values.windows(2).all(|pair| pair[0] == pair[1]);
+        let count = duplicate.occurrences.len();
+
+        let finding = match duplicate.policy {
+            DuplicatePolicy::Additive => ValidationResult::Info(format!(
+                "Specified {} times; all occurrences take effect", count
+            )),
+            _ if identical => ValidationResult::Info(format!(
+                "Specified {} times with the same value", count
+            )),
+            DuplicatePolicy::LastWins => ValidationResult::Warning(format!(
+                "Specified {} times; only the last occurrence takes effect", count
+            )),
+            DuplicatePolicy::FirstWins => ValidationResult::Warning(format!(
+                "Specified {} times; only the first occurrence takes effect", count
+            )),
+        };
+        let last = duplicate.occurrences[count - 1];
+        let span = parsed.parameters[last].span.clone();
+        let finding = Finding::new(&duplicate.name, finding, Some(span)).with_rule_info(&taxonomy::DUPLICATE_PARAMETER);
+        parsed.validation_summary.push(finding);
+    }
+
+    parsed.duplicates = duplicates;
+}
+
+/// The finding for a parameter's own validation result, suggesting the
+/// names an unknown parameter may have been meant as.
+fn parameter_finding(param: &ParsedParameter, unknown_parameters: &[UnknownParameter]) -> Finding {
+    let finding = param.finding();
+    match unknown_parameters.iter().find(|unknown| unknown.name == param.name) {
+        Some(unknown) if param.parameter_def.is_none() => finding.with_suggestions(unknown.suggestions.clone()),
+        _ => finding,
+    }
+}
+
+/// The name of a parameter as `conflicts_with` and `requires` declarations
+/// refer to it:
+/// its current name, or `module.parameter` for module parameters.
+fn reference_name(param: &ParsedParameter) -> &str {
+    match (&param.module, &param.parameter_def) {
+        (None, Some(def)) => &def.name,
+        _ => &param.name,
+    }
+}
+
+/// Find pairs of parameters that a `conflicts_with` declaration of either
+/// one rules out together, and report each pair at the later of the two.
+/// Occurrences superseded by a repeat of the same parameter do not
+/// conflict with others. A declaration naming the parameter itself is
+/// ignored, as repeats are reported as duplicates and overrides already.
+fn detect_conflicts(parsed: &mut ParsedCommandLine) {
+    let parameters = &parsed.parameters;
+    let mut conflicts: Vec<ConflictingParameters> = Vec::new();
+
+    for (index, param) in parameters.iter().enumerate() {
+        let Some(def) = &param.parameter_def else {
+            continue;
+        };
+        for conflict in def.conflicts_with.iter().filter(|conflict| conflict.parameter != reference_name(param)) {
+            for (other_index, other) in parameters.iter().enumerate() {
+                if other_index == index || reference_name(other) != conflict.parameter {
+                    continue;
+                }
+                let conflicting = param.effective && other.effective && (conflict.values.is_empty()
+                    || other.value.as_ref().is_some_and(|value| conflict.values.contains(value)));
+                let (first, second) = (index.min(other_index), index.max(other_index));
+                if conflicting && !conflicts.iter().any(|c| c.first == first && c.second == second) {
+                    conflicts.push(ConflictingParameters {
+                        first,
+                        second,
+                        reason: conflict.reason.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    conflicts.sort_by_key(|conflict| (conflict.second, conflict.first));
+    for conflict in &conflicts {
+        let (first, second) = (&parsed.parameters[conflict.first], &parsed.parameters[conflict.second]);
+        let finding = ValidationResult::Warning(format!(
+            "Conflicts with '{}' earlier on the command line: {}",
+            render_argument(&first.name, first.value.as_deref()), conflict.reason
+        ));
+        let finding = Finding::new(&second.name, finding, Some(second.span.clone()))
+            .with_rule_info(&taxonomy::CONFLICTING_PARAMETERS);
+        parsed.validation_summary.push(finding);
+    }
+
+    parsed.conflicts = conflicts;
+}
+
+/// Find parameters whose effect a later one nullifies, and report each at
+/// the overridden occurrence with the argument that wins. Repeats of the
+/// same parameter are informational, as the duplicate is reported already;
+/// a parameter overridden by a different one is a warning, unless the two
+/// are reported as conflicting.
+fn detect_overrides(parsed: &mut ParsedCommandLine) {
+    let parameters = &parsed.parameters;
+    let mut overrides = Vec::new();
+
+    for duplicate in parsed.duplicates.iter().filter(|d| d.policy != DuplicatePolicy::Additive) {
+        let winner = duplicate.effective[0];
+        for &index in duplicate.occurrences.iter().filter(|&&index| index != winner) {
+            if parameters[index].value != parameters[winner].value {
+                overrides.push(OverriddenParameter { overridden: index, winner, setting: None });
+            }
+        }
+    }
+
+    let mut settings: indexmap::IndexMap<&str, Vec<usize>> = indexmap::IndexMap::new();
+    for (index, param) in parameters.iter().enumerate().filter(|(_, p)| p.effective) {
+        for setting in param.parameter_def.iter().flat_map(|def| &def.controls) {
+            settings.entry(setting.as_str()).or_default().push(index);
+        }
+    }
+    for (setting, indices) in settings {
+        let Some((&winner, earlier)) = indices.split_last() else {
+            continue;
+        };
+        for &index in earlier {
+            let conflicting = parsed.conflicts.iter().any(|c| c.first == index && c.second == winner);
+            if reference_name(&parameters[index]) != reference_name(&parameters[winner]) && !conflicting {
+                overrides.push(OverriddenParameter { overridden: index, winner, setting: Some(setting.to_string()) });
+            }
+        }
+    }
+
+    overrides.sort_by_key(|entry| (entry.overridden, entry.winner));
+    for entry in &overrides {
+        let (param, winner) = (&parsed.parameters[entry.overridden], &parsed.parameters[entry.winner]);
+        let argument = render_argument(&winner.name, winner.value.as_deref());
+        let position = if entry.winner > entry.overridden { "later" } else { "earlier" };
+        let finding = match &entry.setting {
+            None => ValidationResult::Info(format!(
+                "Overridden by '{}' {} on the command line", argument, position
+            )),
+            Some(setting) => ValidationResult::Warning(format!(
+                "Overridden by '{}' {} on the command line, which also sets {}, so this has no effect",
+                argument, position, setting
+            )),
+        };
+        let finding = Finding::new(&param.name, finding, Some(param.span.clone()))
+            .with_rule_info(&taxonomy::OVERRIDDEN_PARAMETER);
+        parsed.validation_summary.push(finding);
+    }
+
+    parsed.overrides = overrides;
+}
+
+/// Find effective parameters whose `requires` declarations apply to their
+/// value but name no companion on the command line, and report each with
+/// the argument to add.
+fn detect_missing_requirements(parsed: &mut ParsedCommandLine) {
+    let present: HashSet<&str> = parsed.parameters.iter().map(reference_name).collect();
+    let mut missing = Vec::new();
+
+    for (index, param) in parsed.parameters.iter().enumerate().filter(|(_, p)| p.effective) {
+        let Some(def) = &param.parameter_def else {
+            continue;
+        };
+        let value = param.value.as_deref().unwrap_or("");
+        for requirement in &def.requires {
+            let applies = requirement.when.is_empty()
+                || requirement.when.iter().any(|pattern| glob_match(pattern, value));
+            if applies && !requirement.parameters.iter().any(|name| present.contains(name.as_str())) {
+                missing.push(MissingRequirement {
+                    parameter: index,
+                    requires: requirement.parameters.clone(),
+                    suggestion: requirement.suggestion.clone(),
+                    reason: requirement.reason.clone(),
+                });
+            }
+        }
+    }
+
+    for requirement in &missing {
+        let param = &parsed.parameters[requirement.parameter];
+        let companions: Vec<String> = requirement.requires.iter().map(|name| format!("'{}'", name)).collect();
+        let mut message = format!(
+            "Requires {}, which is not on the command line: {}",
+            companions.join(" or "), requirement.reason
+        );
+        if let Some(suggestion) = &requirement.suggestion {
+            message.push_str(&format!("; add e.g. '{}'", suggestion));
+        }
+        let finding = Finding::new(&param.name, ValidationResult::Warning(message), Some(param.span.clone()))
+            .with_rule_info(&taxonomy::MISSING_REQUIREMENT)
+            .with_suggestions(requirement.suggestion.iter().cloned().collect());
+        parsed.validation_summary.push(finding);
+    }
+
+    parsed.missing_requirements = missing;
+}
+
+/// Report the parameters whose definitions are deprecated, with their
+/// replacements.
+fn detect_deprecations(parsed: &mut ParsedCommandLine) {
+    for usage in deprecated_usages(&parsed.parameters).into_iter().filter(|usage| usage.deprecated) {
+        let mut finding = Finding::new(&usage.parameter, usage.validation_result(), Some(usage.span.clone()))
+            .with_rule_info(&taxonomy::DEPRECATED);
+        if let Some(replacement) = usage.replacement {
+            finding = finding.with_fix(Fix::new(usage.span, &replacement)).with_suggestions(vec![replacement]);
+        }
+        parsed.validation_summary.push(finding);
+    }
 }
 
 impl<'a> CommandLineParser<'a> {
     pub fn new(catalog: &'a ParameterCatalog) -> Self {
-        Self { catalog }
+        Self {
+            catalog,
+            registry: StandardValidatorRegistry::new(),
+            target_arch: None,
+            profile: ValidationProfile::default(),
+            severity_policy: SeverityPolicy::default(),
+            suppressions: Suppressions::default(),
+            lint_rules: LintRuleRegistry::new(),
+            applicability: ApplicabilityMode::default(),
+        }
+    }
+
+    /// Check command lines with the given profile's checks.
+    pub fn with_profile(mut self, profile: ValidationProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Reclassify the findings of validation summaries by `policy`.
+    pub fn with_severity_policy(mut self, policy: SeverityPolicy) -> Self {
+        self.severity_policy = policy;
+        self
+    }
+
+    /// Leave the findings `suppressions` match, such as those of a
+    /// baseline, out of validation summaries.
+    pub fn with_suppressions(mut self, suppressions: Suppressions) -> Self {
+        self.suppressions = suppressions;
+        self
+    }
+
+    /// Run the given whole command line lint rules, such as those of
+    /// [`LintRuleRegistry::with_builtin_rules`], after the other checks.
+    pub fn with_lint_rules(mut self, rules: LintRuleRegistry) -> Self {
+        self.lint_rules = rules;
+        self
+    }
+
+    /// What validation against a system does with parameters that cannot
+    /// have any effect on it.
+    pub fn with_applicability(mut self, mode: ApplicabilityMode) -> Self {
+        self.applicability = mode;
+        self
+    }
+
+    /// Check command lines against the limits of the given architecture,
+    /// and values against the syntax of their parameters on it.
+    pub fn with_target_arch<S: Into<String>>(mut self, arch: S) -> Self {
+        self.target_arch = Some(arch.into());
+        self
+    }
+
+    pub fn parse(&self, cmdline: &str) -> Result<ParsedCommandLine, crate::error::ParseError> {
+        let mut parsed = self.parse_for_arch(cmdline, self.target_arch.as_deref())?;
+        self.apply_lint_rules(&mut parsed, None);
+        Ok(parsed)
+    }
+
+    fn parse_for_arch(&self, cmdline: &str, arch: Option<&str>) -> Result<ParsedCommandLine, crate::error::ParseError> {
+        let mut parameters = Vec::new();
+        let mut unknown_parameters = Vec::new();
+        let mut module_parameters = Vec::new();
+        let mut init_arguments = Vec::new();
+        let mut validation_summary = self.summary();
+
+        let mut args = split_args(cmdline).into_iter();
+        while let Some(arg) = args.next() {
+            // Everything after "--" belongs to init
+            if arg.param == "--" && arg.value.is_none() {
+                init_arguments.extend(args.map(|arg| arg.unquoted()));
+                break;
+            }
+
+            let parsed = resolve_argument(self.catalog, &self.registry, arch, cmdline, &arg);
+            if parsed.parameter_def.is_none() {
+                if parsed.module.is_some() {
+                    module_parameters.push(parsed.name.clone());
+                } else {
+                    unknown_parameters.push(UnknownParameter {
+                        name: parsed.name.clone(),
+                        suggestions: self.catalog.suggest(&parsed.name),
+                    });
+                }
+            }
+            validation_summary.record_finding(parameter_finding(&parsed, &unknown_parameters));
+            parameters.push(parsed);
+        }
+
+        if let Some(arch) = arch {
+            match validate_command_line_length(cmdline, arch) {
+                ValidationResult::Valid => {}
+                result => validation_summary.push(
+                    Finding::new(COMMAND_LINE_FINDING, result, Some(Span::new(0, cmdline.len())))
+                        .with_rule_info(&taxonomy::COMMAND_LINE_LENGTH)
+                ),
+            }
+        }
+
+        let mut parsed = ParsedCommandLine {
+            source: None,
+            parameters,
+            unknown_parameters,
+            module_parameters,
+            init_arguments,
+            duplicates: Vec::new(),
+            conflicts: Vec::new(),
+            overrides: Vec::new(),
+            missing_requirements: Vec::new(),
+            validation_summary,
+        };
+        detect_duplicates(&mut parsed);
+        detect_conflicts(&mut parsed);
+        detect_overrides(&mut parsed);
+        detect_missing_requirements(&mut parsed);
+        detect_deprecations(&mut parsed);
+        self.apply_profile(&mut parsed);
+
+        Ok(parsed)
+    }
+
+    /// Parse every command line provided by `source`, recording where each
+    /// one came from. Fragmented sources produce a single merged result.
+    pub fn parse_source(&self, source: &dyn CommandLineSource) -> Result<Vec<ParsedCommandLine>, crate::error::ParseError> {
+        let command_lines = source.command_lines()?;
+        let source_findings = source.source_findings()?;
+
+        if source.is_fragmented() {
+            let mut parsed = self.parse_fragments(&command_lines)?;
+            let length = command_lines.iter().map(|sourced| sourced.cmdline.len()).sum::<usize>()
+                + command_lines.len().saturating_sub(1);
+            check_source_length(&mut parsed, length, source);
+            for finding in source_findings {
+                parsed.validation_summary.push(finding);
+            }
+            return Ok(vec![parsed]);
+        }
+
+        command_lines
+            .into_iter()
+            .map(|sourced| {
+                let mut parsed = self.parse(&sourced.cmdline)?;
+                parsed.set_source(&sourced.source);
+                check_source_length(&mut parsed, sourced.cmdline.len(), source);
+                for finding in &source_findings {
+                    parsed.validation_summary.push(finding.clone());
+                }
+                Ok(parsed)
+            })
+            .collect()
+    }
+
+    /// Parse command line fragments in order as the single command line
+    /// they make up, so that each check sees every fragment at once,
+    /// recording the fragment each parameter came from. Spans are given
+    /// relative to the fragment they fall in.
+    pub fn parse_fragments(&self, fragments: &[SourcedCommandLine]) -> Result<ParsedCommandLine, crate::error::ParseError> {
+        let mut cmdline = String::new();
+        let mut spans = Vec::with_capacity(fragments.len());
+        for (index, fragment) in fragments.iter().enumerate() {
+            if index > 0 {
+                cmdline.push(' ');
+            }
+            let start = cmdline.len();
+            cmdline.push_str(&fragment.cmdline);
+            spans.push(Span::new(start, cmdline.len()).with_source(&fragment.source));
+        }
+
+        let mut parsed = self.parse(&cmdline)?;
+        attribute_fragments(&mut parsed, &spans);
+        Ok(parsed)
+    }
+
+    /// An empty validation summary with this parser's policy and
+    /// suppressions.
+    fn summary(&self) -> ValidationSummary {
+        ValidationSummary::with_policy(self.severity_policy.clone()).with_suppressions(self.suppressions.clone())
+    }
+
+    /// Add the findings of the checks of this parser's profile.
+    fn apply_profile(&self, parsed: &mut ParsedCommandLine) {
+        match self.profile {
+            ValidationProfile::Standard => {}
+            ValidationProfile::SecurityAudit => {
+                for finding in security_findings(&parsed.parameters) {
+                    let result = finding.validation_result();
+                    let finding = Finding::new(&finding.parameter, result, Some(finding.span))
+                        .with_rule_info(&taxonomy::WEAKENS_SECURITY);
+                    parsed.validation_summary.push(finding);
+                }
+            }
+            ValidationProfile::PerformanceReview => {
+                for finding in performance_findings(&parsed.parameters) {
+                    let result = finding.validation_result();
+                    let finding = Finding::new(&finding.parameter, result, Some(finding.span))
+                        .with_rule_info(&taxonomy::PERFORMANCE_IMPACT);
+                    parsed.validation_summary.push(finding);
+                }
+            }
+        }
+    }
+
+    /// Flag the parameters that cannot have any effect on `probe`'s system,
+    /// and downgrade the other findings about them if asked to.
+    fn apply_applicability(&self, parsed: &mut ParsedCommandLine, probe: &SystemProbe) {
+        if self.applicability == ApplicabilityMode::Off {
+            return;
+        }
+        for finding in applicability_findings(&parsed.parameters, probe) {
+            if let (ApplicabilityMode::Downgrade, Some(span)) = (self.applicability, &finding.span) {
+                parsed.validation_summary.downgrade_within(span, "no effect on this system");
+            }
+            parsed.validation_summary.push(finding);
+        }
+    }
+
+    /// Add the findings of this parser's lint rules.
+    fn apply_lint_rules(&self, parsed: &mut ParsedCommandLine, probe: Option<&SystemProbe>) {
+        if self.lint_rules.is_empty() {
+            return;
+        }
+        let context = LintContext { parsed, catalog: self.catalog, probe };
+        let findings = self.lint_rules.check(&context);
+        for finding in findings {
+            parsed.validation_summary.push(finding);
+        }
+    }
+
+    /// Audit a command line for parameters that weaken security, whatever
+    /// this parser's profile.
+    pub fn security_audit(&self, cmdline: &str) -> Result<SecurityAuditReport, crate::error::ParseError> {
+        let parsed = self.parse(cmdline)?;
+        Ok(SecurityAuditReport::new(cmdline, &parsed.parameters))
+    }
+
+    /// Summarize the expected performance consequences of a command line,
+    /// whatever this parser's profile.
+    pub fn performance_review(&self, cmdline: &str) -> Result<PerformanceReport, crate::error::ParseError> {
+        let parsed = self.parse(cmdline)?;
+        Ok(PerformanceReport::new(cmdline, &parsed.parameters))
+    }
+
+    /// List the deprecated parameters of a command line, with the exact
+    /// argument to use instead of each.
+    pub fn deprecation_report(&self, cmdline: &str) -> Result<DeprecationReport, crate::error::ParseError> {
+        let parsed = self.parse(cmdline)?;
+        Ok(DeprecationReport::new(cmdline, &parsed.parameters))
+    }
+
+    /// Explain what each parameter of a command line does and what its
+    /// value means.
+    pub fn explain(&self, cmdline: &str) -> Result<CommandLineExplanation, crate::error::ParseError> {
+        let parsed = self.parse(cmdline)?;
+        Ok(CommandLineExplanation::new(cmdline, &parsed.parameters, &parsed.init_arguments))
     }
 
-    pub fn parse(&self, _cmdline: &str) -> Result<ParsedCommandLine, crate::error::ParseError> {
-        // Split command line and parse each parameter
-        todo!()
+    /// Parse a command line and check it against a probed system: values
+    /// are validated with the syntax of each parameter on the system's
+    /// architecture, unless a target architecture was set, and the findings
+    /// that depend on the system's devices, CPU and crash kernel
+    /// reservation are added to the validation summary.
+    pub fn parse_for_system(&self, cmdline: &str, probe: &SystemProbe) -> Result<ParsedCommandLine, crate::error::ParseError> {
+        let arch = self.target_arch.as_deref().or(probe.arch());
+        let mut parsed = self.parse_for_arch(cmdline, arch)?;
+        for finding in system_findings(&parsed.parameters, probe) {
+            parsed.validation_summary.push(finding);
+        }
+        self.apply_applicability(&mut parsed, probe);
+        self.apply_lint_rules(&mut parsed, Some(probe));
+        Ok(parsed)
     }
 
-    pub fn validate(&self, _cmdline: &str, _probe: &SystemProbe) -> ValidationResult {
-        // Parse and validate entire command line
-        todo!()
+    /// Validate a whole command line against a probed system, counting the
+    /// result for each parameter. Parameters missing from the catalog are
+    /// counted as unknown and listed by
+    /// [`ValidationSummary::unknown_parameters`].
+    pub fn validate(&self, cmdline: &str, probe: &SystemProbe) -> Result<ValidationSummary, crate::error::ParseError> {
+        Ok(self.parse_for_system(cmdline, probe)?.validation_summary)
     }
 }
 
 impl<'a> ParameterParser<'a> {
     pub fn new(catalog: &'a ParameterCatalog) -> Self {
-        Self { catalog }
+        Self {
+            catalog,
+            registry: StandardValidatorRegistry::new(),
+            target_arch: None,
+        }
+    }
+
+    /// Validate values against the syntax of the given architecture.
+    pub fn with_target_arch<S: Into<String>>(mut self, arch: S) -> Self {
+        self.target_arch = Some(arch.into());
+        self
     }
 
-    pub fn parse_single(&self, _input: &str) -> Result<ParsedParameter, crate::error::ParseError> {
-        // Parse single parameter=value
-        todo!()
+    pub fn parse_single(&self, input: &str) -> Result<ParsedParameter, crate::error::ParseError> {
+        let args = split_args(input);
+        match args.as_slice() {
+            [arg] => Ok(resolve_argument(self.catalog, &self.registry, self.target_arch.as_deref(), input, arg)),
+            [] => Err(crate::error::ParseError::InvalidFormat("Empty parameter".to_string())),
+            _ => Err(crate::error::ParseError::InvalidFormat(format!(
+                "Expected a single parameter, found {}: '{}'", args.len(), input
+            ))),
+        }
     }
 }
 
 impl<'a> ParameterBuilder<'a> {
     pub fn new(parameter: &'a Parameter) -> Self {
-        Self { parameter }
+        Self {
+            parameter,
+            registry: StandardValidatorRegistry::new(),
+            target_arch: None,
+        }
+    }
+
+    /// Format values with the syntax of the given architecture.
+    pub fn with_target_arch<S: Into<String>>(mut self, arch: S) -> Self {
+        self.target_arch = Some(arch.into());
+        self
+    }
+
+    /// Render `config` as an argument for the parameter, formatted as its
+    /// syntax expects, e.g. `isolcpus=0-3,6` from a list of CPUs. Values
+    /// containing whitespace are quoted. Fails if the configuration does
+    /// not fit the syntax or the rendered value does not validate.
+    pub fn build(&self, config: &ParameterConfig) -> Result<String, crate::error::BuildError> {
+        let name = &self.parameter.name;
+        let invalid = |error: String| crate::error::BuildError::InvalidConfig { param: name.clone(), error };
+        let syntax = self.parameter.syntax_for_arch(self.target_arch.as_deref());
+        let value = config.render(&syntax).map_err(invalid)?;
+
+        if let Some(value) = &value {
+            if value.contains('"') {
+                return Err(invalid(format!("value '{}' contains a quote, which cannot be written on a command line", value)));
+            }
+            if let Some(validator) = self.registry.get_validator(&self.parameter.processor, &syntax.validator_type) {
+                if let ValidationResult::Error(error) = validator.validate(value, &syntax.config) {
+                    return Err(invalid(error));
+                }
+            }
+        }
+        Ok(render_argument(name, value.as_deref()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::CatalogBuilder;
+    use crate::parameter::{ParameterProcessor, SyntaxDefinition};
+
+    fn syntax(validator_type: &str, config: &[(&str, toml::Value)]) -> SyntaxDefinition {
+        SyntaxDefinition {
+            validator_type: validator_type.to_string(),
+            format: String::new(),
+            config: config.iter().map(|(key, value)| (key.to_string(), value.clone())).collect(),
+        }
+    }
+
+    fn catalog() -> ParameterCatalog {
+        let mut timeout = Parameter::new("vmhalt_timeout", ParameterProcessor::Kernel);
+        timeout.syntax = syntax("integer", &[("min", toml::Value::Integer(0)), ("max", toml::Value::Integer(10))]);
+        timeout.arch_syntax.insert("s390x".to_string(), syntax("", &[("max", toml::Value::Integer(100))]));
+        CatalogBuilder::new()
+            .add_parameter(timeout)
+            .add_parameter(Parameter::new("quiet", ParameterProcessor::Kernel))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn parses_a_single_argument() {
+        let catalog = catalog();
+        let parsed = ParameterParser::new(&catalog).parse_single("quiet").unwrap();
+        assert_eq!(parsed.name, "quiet");
+        assert!(parsed.parameter_def.is_some());
+        assert_eq!(parsed.validation, ValidationResult::Valid);
+    }
+
+    #[test]
+    fn rejects_anything_but_one_argument() {
+        let catalog = catalog();
+        let parser = ParameterParser::new(&catalog);
+        assert!(parser.parse_single("").is_err());
+        assert!(parser.parse_single("quiet splash").is_err());
+    }
+
+    #[test]
+    fn validates_with_the_syntax_of_the_target_arch() {
+        let catalog = catalog();
+        let parsed = ParameterParser::new(&catalog).parse_single("vmhalt_timeout=50").unwrap();
+        assert!(matches!(parsed.validation, ValidationResult::Error(_)));
+        let parsed = ParameterParser::new(&catalog)
+            .with_target_arch("s390x")
+            .parse_single("vmhalt_timeout=50")
+            .unwrap();
+        assert_eq!(parsed.validation, ValidationResult::Valid);
+    }
+
+    fn fragments(fragments: &[(&str, &str)]) -> Vec<SourcedCommandLine> {
+        fragments.iter()
+            .map(|(source, cmdline)| SourcedCommandLine { source: source.to_string(), cmdline: cmdline.to_string() })
+            .collect()
+    }
+
+    #[test]
+    fn detects_duplicates_across_fragments_once() {
+        let catalog = catalog();
+        let parsed = CommandLineParser::new(&catalog)
+            .parse_fragments(&fragments(&[("01.conf", "quiet"), ("02.conf", "rw quiet")]))
+            .unwrap();
+        assert_eq!(parsed.duplicates.len(), 1);
+        assert_eq!(parsed.duplicates[0].occurrences, [0, 2]);
+        assert!(!parsed.parameters[0].effective);
+    }
+
+    #[test]
+    fn gives_spans_within_each_fragment() {
+        let catalog = catalog();
+        let parsed = CommandLineParser::new(&catalog)
+            .parse_fragments(&fragments(&[("01.conf", "quiet"), ("02.conf", "rw vmhalt_timeout=50")]))
+            .unwrap();
+        let timeout = &parsed.parameters[2];
+        assert_eq!(timeout.source.as_deref(), Some("02.conf"));
+        assert_eq!(timeout.span, Span::new(3, 20).with_source("02.conf"));
+        assert_eq!(timeout.value_span, Some(Span::new(18, 20).with_source("02.conf")));
+        let finding = parsed.validation_summary.details.iter()
+            .find(|finding| finding.parameter == "vmhalt_timeout")
+            .unwrap();
+        assert_eq!(finding.span, Some(Span::new(18, 20).with_source("02.conf")));
+    }
+
+    #[test]
+    fn checks_the_length_of_the_joined_fragments() {
+        let catalog = catalog();
+        let fragment = "quiet ".repeat(200);
+        let parsed = CommandLineParser::new(&catalog)
+            .with_target_arch("x86_64")
+            .parse_fragments(&fragments(&[("01.conf", &fragment), ("02.conf", &fragment)]))
+            .unwrap();
+        let length_findings: Vec<_> = parsed.validation_summary.details.iter()
+            .filter(|finding| finding.parameter == COMMAND_LINE_FINDING)
+            .collect();
+        assert_eq!(length_findings.len(), 1);
+        assert!(matches!(length_findings[0].result, ValidationResult::Error(_)));
+    }
+
+    #[test]
+    fn leaves_repeats_to_duplicate_detection() {
+        let mut root = Parameter::new("root", ParameterProcessor::Kernel);
+        root.conflicts_with.push(crate::parameter::ParameterConflict {
+            parameter: "root".to_string(),
+            values: Vec::new(),
+            reason: "only one root".to_string(),
+        });
+        let catalog = CatalogBuilder::new().add_parameter(root).build().unwrap();
+        let parsed = CommandLineParser::new(&catalog).parse("root=/dev/sda1 root=UUID=x").unwrap();
+        assert!(parsed.conflicts.is_empty());
+        assert_eq!(parsed.duplicates.len(), 1);
+    }
+
+    fn split(cmdline: &str) -> Vec<(&str, Option<&str>)> {
+        split_args(cmdline).into_iter().map(|arg| (arg.param, arg.value)).collect()
+    }
+
+    #[test]
+    fn splits_quoted_values_like_the_kernel() {
+        assert_eq!(split("a=\"b c\" quiet"), vec![("a", Some("b c")), ("quiet", None)]);
+        assert_eq!(split("a=b\"c d\""), vec![("a", Some("b\"c d\""))]);
+    }
+
+    #[test]
+    fn keeps_the_closing_quote_of_a_quoted_name() {
+        // The kernel only drops the quotes around a whole argument, so a
+        // name quoted before its '=' keeps the closing quote
+        assert_eq!(split("\"a b\"=c"), vec![("a b\"", Some("c"))]);
+        assert_eq!(split("\"a b=c\""), vec![("a b", Some("c"))]);
+    }
+
+    #[test]
+    fn runs_an_unterminated_quote_to_the_end_of_the_line() {
+        assert_eq!(split("a=\"b c d"), vec![("a", Some("b c d"))]);
+        assert_eq!(split("\"quiet rw"), vec![("quiet rw", None)]);
+    }
+
+    #[test]
+    fn hands_everything_after_double_dash_to_init() {
+        let catalog = catalog();
+        let parsed = CommandLineParser::new(&catalog).parse("quiet -- single quiet").unwrap();
+        assert_eq!(parsed.parameters.len(), 1);
+        assert_eq!(parsed.init_arguments, vec!["single", "quiet"]);
+        assert_eq!(split("a=\"b -- c\" d"), vec![("a", Some("b -- c")), ("d", None)]);
     }
 
-    pub fn build(&self, _config: &crate::config::ParameterConfig) -> Result<String, crate::error::BuildError> {
-        // Build parameter string from config
-        todo!()
+    #[test]
+    fn unquotes_init_arguments_and_requotes_them_when_joined() {
+        let catalog = catalog();
+        let parsed = CommandLineParser::new(&catalog)
+            .parse("quiet -- title=\"a b\" \"c d\" \"e f=g\" plain")
+            .unwrap();
+        assert_eq!(parsed.init_arguments, ["title=a b", "c d", "e f=g", "plain"]);
+        let cmdline = parsed.to_cmdline();
+        assert_eq!(cmdline, "quiet -- title=\"a b\" \"c d\" \"e f=g\" plain");
+        let reparsed = CommandLineParser::new(&catalog).parse(&cmdline).unwrap();
+        assert_eq!(reparsed.init_arguments, parsed.init_arguments);
     }
 }
@@ -0,0 +1,96 @@
+//! Performance review of a command line: parameters that change how a
+//! system performs, such as `nosmt` or `idle=poll`, as described by the
+//! `performance_impact` field of their definitions.
+
+use serde::Serialize;
+
+use crate::error::Span;
+use crate::parameter::PerformanceRating;
+use crate::parser::ParsedParameter;
+use crate::query::glob_match;
+use crate::validators::ValidationResult;
+
+/// A parameter on a command line that changes performance.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PerformanceFinding {
+    pub parameter: String,
+    pub value: Option<String>,
+    pub rating: PerformanceRating,
+    pub effect: String,
+    pub span: Span,
+}
+
+impl PerformanceFinding {
+    /// The validation result the finding is recorded as in a performance
+    /// review profile. Performance trade-offs are often deliberate, so
+    /// every finding is informational.
+    pub fn validation_result(&self) -> ValidationResult {
+        ValidationResult::Info(format!("Performance impact ({}): {}", self.rating.name(), self.effect))
+    }
+}
+
+/// The expected performance consequences of a command line, largest
+/// first.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PerformanceReport {
+    pub cmdline: String,
+    pub findings: Vec<PerformanceFinding>,
+}
+
+impl PerformanceReport {
+    pub(crate) fn new(cmdline: &str, parameters: &[ParsedParameter]) -> Self {
+        let mut findings = performance_findings(parameters);
+        findings.sort_by(|a, b| b.rating.cmp(&a.rating).then(a.span.start.cmp(&b.span.start)));
+        Self {
+            cmdline: cmdline.to_string(),
+            findings,
+        }
+    }
+
+    /// Whether nothing on the command line is known to change performance.
+    pub fn is_empty(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// The largest impact's rating, if there are any.
+    pub fn highest_rating(&self) -> Option<PerformanceRating> {
+        self.findings.iter().map(|finding| finding.rating).max()
+    }
+
+    /// The number of findings with the given rating.
+    pub fn count(&self, rating: PerformanceRating) -> usize {
+        self.findings.iter().filter(|finding| finding.rating == rating).count()
+    }
+
+    /// One line per finding, e.g. `high: nosmt: disables simultaneous
+    /// multithreading, ...`.
+    pub fn summary(&self) -> Vec<String> {
+        self.findings.iter()
+            .map(|finding| format!("{}: {}: {}", finding.rating.name(), finding.parameter, finding.effect))
+            .collect()
+    }
+}
+
+/// The effective parameters among `parameters` with a value that their
+/// definition's `performance_impact` describes, in command line order.
+pub(crate) fn performance_findings(parameters: &[ParsedParameter]) -> Vec<PerformanceFinding> {
+    parameters.iter()
+        .filter(|param| param.effective)
+        .flat_map(|param| {
+            let impacts = param.parameter_def.iter().flat_map(|def| &def.performance_impact);
+            let value = param.value.as_deref();
+            impacts
+                .filter(move |impact| match value {
+                    None => impact.when.is_empty(),
+                    Some(value) => impact.when.is_empty() || impact.when.iter().any(|pattern| glob_match(pattern, value)),
+                })
+                .map(move |impact| PerformanceFinding {
+                    parameter: param.name.clone(),
+                    value: param.value.clone(),
+                    rating: impact.rating,
+                    effect: impact.effect.clone(),
+                    span: param.span.clone(),
+                })
+        })
+        .collect()
+}
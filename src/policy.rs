@@ -0,0 +1,118 @@
+//! Severity policies: how seriously to take each category of finding, so
+//! that one tool can fail a CI build on a deprecated parameter while
+//! another only mentions it, and whether a validated command line passes.
+
+use crate::validators::{FindingCategory, ValidationResult};
+
+/// The level a [`SeverityPolicy`] reports a category of finding at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FindingSeverity {
+    Error,
+    Warning,
+    Info,
+    /// Drop the finding from the summary.
+    Ignore,
+}
+
+/// What validation against a system does with parameters that cannot have
+/// any effect on it, such as `amd_iommu=` on an Intel machine.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ApplicabilityMode {
+    /// Do not check applicability.
+    Off,
+    /// Add an inapplicable finding for each such parameter.
+    #[default]
+    Flag,
+    /// Flag each such parameter, and report problems with it as
+    /// informational, since they cannot affect the system either.
+    Downgrade,
+}
+
+/// Reclassifies findings by category as they are added to a
+/// [`ValidationSummary`](crate::ValidationSummary). A category without a
+/// severity keeps the level its check reported.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SeverityPolicy {
+    /// Parameters missing from the catalog.
+    pub unknown: Option<FindingSeverity>,
+    /// Deprecated and renamed parameters.
+    pub deprecated: Option<FindingSeverity>,
+    /// Warnings of the other categories, such as duplicates and conflicts.
+    pub warning: Option<FindingSeverity>,
+    /// Parameters that cannot take effect on the probed system.
+    pub inapplicable: Option<FindingSeverity>,
+}
+
+impl SeverityPolicy {
+    /// Fail on anything questionable: unknown and deprecated parameters and
+    /// warnings are errors, and inapplicable parameters warnings.
+    pub fn strict() -> Self {
+        Self {
+            unknown: Some(FindingSeverity::Error),
+            deprecated: Some(FindingSeverity::Error),
+            warning: Some(FindingSeverity::Error),
+            inapplicable: Some(FindingSeverity::Warning),
+        }
+    }
+
+    /// Only report what will break the boot: unknown and deprecated
+    /// parameters are informational, and inapplicable ones ignored.
+    pub fn lenient() -> Self {
+        Self {
+            unknown: Some(FindingSeverity::Info),
+            deprecated: Some(FindingSeverity::Info),
+            warning: None,
+            inapplicable: Some(FindingSeverity::Ignore),
+        }
+    }
+
+    pub fn with_unknown(mut self, severity: FindingSeverity) -> Self {
+        self.unknown = Some(severity);
+        self
+    }
+
+    pub fn with_deprecated(mut self, severity: FindingSeverity) -> Self {
+        self.deprecated = Some(severity);
+        self
+    }
+
+    pub fn with_warning(mut self, severity: FindingSeverity) -> Self {
+        self.warning = Some(severity);
+        self
+    }
+
+    pub fn with_inapplicable(mut self, severity: FindingSeverity) -> Self {
+        self.inapplicable = Some(severity);
+        self
+    }
+
+    /// The result a finding of `category` is reported as, or `None` if it
+    /// is ignored. Valid results, and errors outside the unknown,
+    /// deprecated and inapplicable categories, are never changed.
+    pub fn apply(&self, category: FindingCategory, result: ValidationResult) -> Option<ValidationResult> {
+        let severity = match (category, &result) {
+            (_, ValidationResult::Valid) => None,
+            (FindingCategory::Unknown, _) => self.unknown,
+            (FindingCategory::Deprecated, _) => self.deprecated,
+            (FindingCategory::Inapplicable, _) => self.inapplicable,
+            (_, ValidationResult::Warning(_)) => self.warning,
+            (_, _) => None,
+        };
+        let Some(severity) = severity else {
+            return Some(result);
+        };
+        let message = match result {
+            ValidationResult::Info(message)
+            | ValidationResult::Warning(message)
+            | ValidationResult::Error(message)
+            | ValidationResult::Unknown(message) => message,
+            ValidationResult::Valid => return Some(result),
+        };
+        match severity {
+            FindingSeverity::Error => Some(ValidationResult::Error(message)),
+            FindingSeverity::Warning => Some(ValidationResult::Warning(message)),
+            FindingSeverity::Info => Some(ValidationResult::Info(message)),
+            FindingSeverity::Ignore => None,
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! The boot loaders a system is set up to boot with, from the configuration
+//! files they read, their files on the EFI system partition and the tools
+//! installed to manage them.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::rooted;
+use crate::sources::{BLS_ENTRIES_PATH, GRUBENV_PATH, GRUB_DEFAULT_PATH, ZIPL_CONF_PATH};
+
+/// Directories the EFI system partition is commonly mounted on.
+const ESP_MOUNT_POINTS: &[&str] = &["/boot/efi", "/efi", "/boot"];
+
+/// A boot loader that passes a command line to the kernel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Bootloader {
+    Grub2,
+    SystemdBoot,
+    Zipl,
+    Extlinux,
+}
+
+impl Bootloader {
+    /// The name used in `bootloader:` tags, e.g. `grub2`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Grub2 => "grub2",
+            Self::SystemdBoot => "systemd-boot",
+            Self::Zipl => "zipl",
+            Self::Extlinux => "extlinux",
+        }
+    }
+
+    /// The `bootloader:<name>` tag for this boot loader.
+    pub fn tag(self) -> String {
+        format!("bootloader:{}", self.name())
+    }
+
+    /// Configuration files and directories holding this boot loader's
+    /// command lines, most authoritative first.
+    fn config_paths(self) -> &'static [&'static str] {
+        match self {
+            Self::Grub2 => &[
+                GRUB_DEFAULT_PATH,
+                "/boot/grub2/grub.cfg",
+                "/boot/grub/grub.cfg",
+                GRUBENV_PATH,
+                "/boot/grub/grubenv",
+                BLS_ENTRIES_PATH,
+            ],
+            Self::SystemdBoot => &["/boot/loader/loader.conf", "/efi/loader/loader.conf", BLS_ENTRIES_PATH],
+            Self::Zipl => &[ZIPL_CONF_PATH],
+            Self::Extlinux => &["/boot/extlinux/extlinux.conf", "/extlinux/extlinux.conf"],
+        }
+    }
+
+    /// Programs installed with the boot loader's package.
+    fn tools(self) -> &'static [&'static str] {
+        match self {
+            Self::Grub2 => &["/usr/sbin/grub2-install", "/usr/sbin/grub-install"],
+            Self::SystemdBoot => &["/usr/bin/bootctl"],
+            Self::Zipl => &["/usr/sbin/zipl", "/sbin/zipl"],
+            Self::Extlinux => &["/usr/sbin/extlinux", "/sbin/extlinux"],
+        }
+    }
+
+    /// Whether the boot loader is installed on the EFI system partition
+    /// whose `EFI` directory is `esp_efi_dir`. Only GRUB2 and systemd-boot
+    /// boot via EFI.
+    fn is_on_esp(self, esp_efi_dir: &Path) -> bool {
+        match self {
+            Self::Grub2 => vendor_dirs(esp_efi_dir).iter().any(|dir| {
+                ["grub.cfg", "grubx64.efi", "grubaa64.efi"].iter().any(|file| dir.join(file).exists())
+            }),
+            Self::SystemdBoot => esp_efi_dir.join("systemd").is_dir(),
+            Self::Zipl | Self::Extlinux => false,
+        }
+    }
+}
+
+/// A boot loader found on a system and the paths to its configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DetectedBootloader {
+    pub bootloader: Bootloader,
+    /// The configuration files and directories that exist, under the root
+    /// the probe looked at.
+    pub config_paths: Vec<PathBuf>,
+}
+
+impl DetectedBootloader {
+    /// Detect the boot loaders of the system whose root file system is
+    /// mounted at `root`, normally `/`. A boot loader is detected by its
+    /// configuration or its files on the EFI system partition; the tools
+    /// installed with it are only used when neither is found, as on a
+    /// system whose /boot is not mounted.
+    pub fn detect<P: AsRef<Path>>(root: P) -> Vec<Self> {
+        let root = root.as_ref();
+        let esp_efi_dirs: Vec<PathBuf> = ESP_MOUNT_POINTS.iter()
+            .map(|mount| rooted(root, mount).join("EFI"))
+            .filter(|dir| dir.is_dir())
+            .collect();
+        let all = [Bootloader::Grub2, Bootloader::SystemdBoot, Bootloader::Zipl, Bootloader::Extlinux];
+
+        let mut detected: Vec<Self> = all.into_iter()
+            .filter_map(|bootloader| {
+                let mut config_paths = existing(root, bootloader.config_paths());
+                if bootloader == Bootloader::Grub2 {
+                    // Configurations written to the ESP, e.g. EFI/fedora/grub.cfg
+                    config_paths.extend(esp_efi_dirs.iter()
+                        .flat_map(|dir| vendor_dirs(dir))
+                        .map(|dir| dir.join("grub.cfg"))
+                        .filter(|path| path.exists()));
+                }
+                let on_esp = esp_efi_dirs.iter().any(|dir| bootloader.is_on_esp(dir));
+                // The BLS entries directory is shared by GRUB2 and
+                // systemd-boot, so it does not identify either
+                let configured = config_paths.iter().any(|path| !path.ends_with("loader/entries"));
+                (configured || on_esp).then_some(Self { bootloader, config_paths })
+            })
+            .collect();
+
+        if detected.is_empty() {
+            detected = all.into_iter()
+                .filter(|bootloader| !existing(root, bootloader.tools()).is_empty())
+                .map(|bootloader| Self { bootloader, config_paths: existing(root, bootloader.config_paths()) })
+                .collect();
+        }
+        detected
+    }
+}
+
+/// The paths that exist under `root`.
+fn existing(root: &Path, paths: &[&str]) -> Vec<PathBuf> {
+    paths.iter().map(|path| rooted(root, path)).filter(|path| path.exists()).collect()
+}
+
+/// The per-vendor directories of an EFI system partition, e.g. `EFI/fedora`.
+fn vendor_dirs(esp_efi_dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(esp_efi_dir)
+        .map(|entries| entries.flatten().map(|entry| entry.path()).filter(|path| path.is_dir()).collect())
+        .unwrap_or_default()
+}
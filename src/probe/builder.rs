@@ -0,0 +1,181 @@
+//! Assembling a [`SystemProbe`] by hand, for testing validation logic and
+//! asking what a command line would mean on another kind of system.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use super::{
+    Bootloader,
+    CrashKernelState,
+    DetectedBootloader,
+    DistributionInfo,
+    KernelConfigProbe,
+    ModuleState,
+    SystemProbe,
+    VulnerabilityStatus,
+};
+use crate::version::{ComponentVersion, KernelVersion};
+
+/// A [`SystemProbe`] described rather than probed. Tags are spelt as the
+/// probe records them, e.g. `cpu:vmx` or `storage:lvm`; the typed methods
+/// add the tags for common facts, so that an arm64 RHEL 9 system is
+/// `.arch("aarch64").distribution("rhel", "9.4")`.
+#[derive(Debug, Clone, Default)]
+pub struct SystemProbeBuilder {
+    hardware_tags: HashSet<String>,
+    software_tags: HashSet<String>,
+    distribution: Option<DistributionInfo>,
+    kernel_version: Option<KernelVersion>,
+    components: Vec<ComponentVersion>,
+    bootloaders: Vec<DetectedBootloader>,
+    crash_kernel: Option<CrashKernelState>,
+}
+
+impl SystemProbeBuilder {
+    /// A builder for a system with no tags on the architecture this library
+    /// was built for.
+    pub fn new() -> Self {
+        Self::default().arch(std::env::consts::ARCH)
+    }
+
+    /// Start from an existing probe, e.g. of this system, to change some of
+    /// what it found.
+    pub fn from_probe(probe: SystemProbe) -> Self {
+        Self {
+            hardware_tags: probe.hardware_tags,
+            software_tags: probe.software_tags,
+            distribution: Some(probe.distribution),
+            kernel_version: probe.kernel_version,
+            components: probe.components,
+            bootloaders: probe.bootloaders,
+            crash_kernel: probe.crash_kernel,
+        }
+    }
+
+    /// Set the architecture, named as in `arch:` selectors, e.g. `x86_64`
+    /// or `aarch64`.
+    pub fn arch(mut self, arch: &str) -> Self {
+        self.hardware_tags.retain(|tag| !tag.starts_with("arch:"));
+        self.hardware_tags.insert(format!("arch:{}", arch));
+        self
+    }
+
+    pub fn hardware_tag<S: Into<String>>(mut self, tag: S) -> Self {
+        self.hardware_tags.insert(tag.into());
+        self
+    }
+
+    pub fn software_tag<S: Into<String>>(mut self, tag: S) -> Self {
+        self.software_tags.insert(tag.into());
+        self
+    }
+
+    /// Add a CPU feature flag, e.g. `vmx`.
+    pub fn cpu_flag(self, flag: &str) -> Self {
+        self.hardware_tag(format!("cpu:{}", flag))
+    }
+
+    /// Record the kernel's assessment of a CPU vulnerability, e.g. `l1tf`.
+    pub fn vulnerability(mut self, vulnerability: &str, status: VulnerabilityStatus) -> Self {
+        let prefix = format!("cpu-vuln:{}:", vulnerability);
+        self.hardware_tags.retain(|tag| !tag.starts_with(&prefix));
+        self.hardware_tag(format!("{}{}", prefix, status.name()))
+    }
+
+    pub fn pci_device(self, vendor_id: u16, device_id: u16) -> Self {
+        self.hardware_tag(format!("pci:{:04x}", vendor_id))
+            .hardware_tag(format!("pci:{:04x}:{:04x}", vendor_id, device_id))
+    }
+
+    pub fn usb_device(self, vendor_id: u16, product_id: u16) -> Self {
+        self.hardware_tag(format!("usb:{:04x}", vendor_id))
+            .hardware_tag(format!("usb:{:04x}:{:04x}", vendor_id, product_id))
+    }
+
+    /// Add the compatible strings of a device tree, e.g.
+    /// `raspberrypi,4-model-b`.
+    pub fn devicetree_compatible<'a, I: IntoIterator<Item = &'a str>>(mut self, compatible: I) -> Self {
+        self.hardware_tags.extend(compatible.into_iter().map(|compatible| format!("dt:compatible:{}", compatible)));
+        self
+    }
+
+    /// Add a network interface and, optionally, its MAC address.
+    pub fn network_interface(self, name: &str, mac: Option<&str>) -> Self {
+        let builder = self.hardware_tag(format!("net:iface:{}", name));
+        match mac {
+            Some(mac) => builder.hardware_tag(format!("net:mac:{}", super::net::normalize_mac(mac))),
+            None => builder,
+        }
+    }
+
+    pub fn module(self, name: &str, state: ModuleState) -> Self {
+        self.software_tag(format!("{}{}", state.tag_prefix(), name.replace('-', "_")))
+    }
+
+    /// Add the `config:` tags of a kernel configuration.
+    pub fn kernel_config(mut self, config: &KernelConfigProbe) -> Self {
+        self.software_tags.extend(config.tags());
+        self
+    }
+
+    /// Set the distribution by its os-release `ID` and `VERSION_ID`.
+    pub fn distribution(self, id: &str, version_id: &str) -> Self {
+        self.distribution_info(DistributionInfo {
+            id: id.to_string(),
+            version_id: version_id.to_string(),
+            name: id.to_string(),
+            ..DistributionInfo::default()
+        })
+    }
+
+    pub fn distribution_info(mut self, distribution: DistributionInfo) -> Self {
+        self.distribution = Some(distribution);
+        self
+    }
+
+    pub fn kernel_version(mut self, version: KernelVersion) -> Self {
+        self.kernel_version = Some(version);
+        self
+    }
+
+    /// Set the version of a component such as `systemd` or `dracut`,
+    /// replacing any version set before.
+    pub fn component(mut self, name: &str, version: &str) -> Self {
+        self.components.retain(|component| component.name != name);
+        self.components.push(ComponentVersion {
+            name: name.to_string(),
+            version: version.to_string(),
+        });
+        self
+    }
+
+    /// Add a boot loader with the paths of its configuration files.
+    pub fn bootloader<I: IntoIterator<Item = PathBuf>>(mut self, bootloader: Bootloader, config_paths: I) -> Self {
+        self.software_tags.insert(bootloader.tag());
+        self.bootloaders.retain(|detected| detected.bootloader != bootloader);
+        self.bootloaders.push(DetectedBootloader {
+            bootloader,
+            config_paths: config_paths.into_iter().collect(),
+        });
+        self
+    }
+
+    /// Set the crash kernel reservation and whether kdump is active.
+    pub fn crash_kernel(mut self, reserved_bytes: u64, loaded: bool) -> Self {
+        self.crash_kernel = Some(CrashKernelState { reserved_bytes, loaded });
+        self
+    }
+
+    /// Build the probe. The distribution is left empty if none was set.
+    pub fn build(self) -> SystemProbe {
+        SystemProbe {
+            hardware_tags: self.hardware_tags,
+            software_tags: self.software_tags,
+            distribution: self.distribution.unwrap_or_default(),
+            kernel_version: self.kernel_version,
+            components: self.components,
+            bootloaders: self.bootloaders,
+            crash_kernel: self.crash_kernel,
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! A cache of the probe of the running system, so that repeated command
+//! line tool and shell completion runs do not walk sysfs each time. The
+//! cache is kept per boot, named after the kernel's random boot id, so a
+//! reboot invalidates it; changes within a boot, such as hotplugged devices
+//! or loaded modules, need an explicit [`SystemProbe::refresh`].
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{read_system_file, SystemProbe};
+use crate::database::user_cache_dir;
+use crate::error::ProbeError;
+
+/// A random id the kernel generates at each boot.
+const BOOT_ID_PATH: &str = "/proc/sys/kernel/random/boot_id";
+
+/// The default probe cache directory, `$XDG_CACHE_HOME/kcmdline/probe` or
+/// `~/.cache/kcmdline/probe`.
+pub fn default_probe_cache_dir() -> Option<PathBuf> {
+    user_cache_dir().map(|cache| cache.join("probe"))
+}
+
+impl SystemProbe {
+    /// Probe the running system, reusing the probe cached in
+    /// [`default_probe_cache_dir`] earlier in this boot. A missing,
+    /// unreadable or outdated cache is replaced by a new probe; failing to
+    /// write the cache is not an error.
+    pub fn cached() -> Result<Self, ProbeError> {
+        let Some(cache_file) = cache_file() else {
+            return Self::new();
+        };
+        if let Ok(probe) = Self::load(&cache_file) {
+            return Ok(probe);
+        }
+        Self::probe_into(&cache_file)
+    }
+
+    /// Probe the running system again and replace the cached probe, e.g.
+    /// after devices were added or modules loaded.
+    pub fn refresh() -> Result<Self, ProbeError> {
+        match cache_file() {
+            Some(cache_file) => Self::probe_into(&cache_file),
+            None => Self::new(),
+        }
+    }
+
+    fn probe_into(cache_file: &Path) -> Result<Self, ProbeError> {
+        let probe = Self::new()?;
+        let _ = write_cache(&probe, cache_file);
+        Ok(probe)
+    }
+}
+
+/// The cache file for this boot, if the boot id and cache directory are
+/// known.
+fn cache_file() -> Option<PathBuf> {
+    let boot_id = read_system_file(Path::new(BOOT_ID_PATH)).ok()?;
+    Some(default_probe_cache_dir()?.join(format!("{}.json", boot_id.trim())))
+}
+
+/// Write the cache file for this boot, replacing it atomically so that
+/// concurrent runs never read a partial probe, and remove those of earlier
+/// boots.
+fn write_cache(probe: &SystemProbe, cache_file: &Path) -> Result<(), ProbeError> {
+    let dir = cache_file.parent().ok_or_else(|| ProbeError::ParseError(format!("Invalid cache path: {:?}", cache_file)))?;
+    fs::create_dir_all(dir)?;
+
+    let temp_path = cache_file.with_extension(format!("{}.tmp", std::process::id()));
+    probe.save(&temp_path)
+        .and_then(|()| Ok(fs::rename(&temp_path, cache_file)?))
+        .inspect_err(|_| {
+            let _ = fs::remove_file(&temp_path);
+        })?;
+
+    for entry in fs::read_dir(dir)?.flatten() {
+        let path = entry.path();
+        if path != cache_file && path.extension().is_some_and(|ext| ext == "json") {
+            let _ = fs::remove_file(path);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,80 @@
+//! Platform security capabilities: TPMs, from /sys/class/tpm; IOMMUs,
+//! from the DMAR and IVRS ACPI tables that describe the hardware and the
+//! groups under /sys/kernel/iommu_groups that exist once the kernel uses
+//! it; and confidential computing, from the CPU flags and the KVM module
+//! parameters.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+/// CPU flags and the confidential computing tags they imply. The `sev` and
+/// `tdx_host_platform` flags mark a host able to run encrypted guests,
+/// `tdx_guest` a guest running as one.
+const CC_CPU_FLAGS: &[(&str, &str)] = &[
+    ("cpu:sev", "cc:sev"),
+    ("cpu:sev_es", "cc:sev-es"),
+    ("cpu:sev_snp", "cc:sev-snp"),
+    ("cpu:tdx_host_platform", "cc:tdx"),
+    ("cpu:tdx_guest", "cc:tdx-guest"),
+];
+
+/// KVM module parameters that report whether the module enabled a
+/// confidential computing technology, and the tag for each.
+const KVM_CC_PARAMETERS: &[(&str, &str)] = &[
+    ("kvm_amd/parameters/sev", "cc:sev:enabled"),
+    ("kvm_amd/parameters/sev_es", "cc:sev-es:enabled"),
+    ("kvm_amd/parameters/sev_snp", "cc:sev-snp:enabled"),
+    ("kvm_intel/parameters/tdx", "cc:tdx:enabled"),
+];
+
+/// The TPM, IOMMU and confidential computing tags for the system whose
+/// sysfs is at `sysfs_root`, given the CPU tags already probed:
+///
+/// - `tpm:present` and `tpm:version:<major>` for the first TPM;
+/// - `iommu:hw:intel` or `iommu:hw:amd` when the firmware describes an
+///   IOMMU, whether or not it is enabled, and `iommu:active` when the
+///   kernel has put devices in IOMMU groups;
+/// - `cc:sev`, `cc:sev-es`, `cc:sev-snp`, `cc:tdx` and `cc:tdx-guest` from
+///   the CPU flags, and `cc:<technology>:enabled` when KVM enabled it.
+pub(crate) fn capability_tags<'a, I>(sysfs_root: &Path, cpu_tags: I) -> Vec<String>
+where
+    I: IntoIterator<Item = &'a String>,
+{
+    let mut tags = BTreeSet::new();
+
+    let tpm = sysfs_root.join("class/tpm/tpm0");
+    if tpm.exists() {
+        tags.insert("tpm:present".to_string());
+        if let Ok(major) = fs::read_to_string(tpm.join("tpm_version_major")) {
+            tags.insert(format!("tpm:version:{}", major.trim()));
+        }
+    }
+
+    let acpi_tables = sysfs_root.join("firmware/acpi/tables");
+    for (table, tag) in [("DMAR", "iommu:hw:intel"), ("IVRS", "iommu:hw:amd")] {
+        if acpi_tables.join(table).exists() {
+            tags.insert(tag.to_string());
+        }
+    }
+    let groups = fs::read_dir(sysfs_root.join("kernel/iommu_groups"));
+    if groups.is_ok_and(|mut groups| groups.next().is_some()) {
+        tags.insert("iommu:active".to_string());
+    }
+
+    let cpu_tags: Vec<&String> = cpu_tags.into_iter().collect();
+    for (flag, tag) in CC_CPU_FLAGS {
+        if cpu_tags.iter().any(|cpu_tag| cpu_tag == flag) {
+            tags.insert(tag.to_string());
+        }
+    }
+    for (parameter, tag) in KVM_CC_PARAMETERS {
+        let enabled = fs::read_to_string(sysfs_root.join("module").join(parameter))
+            .is_ok_and(|value| matches!(value.trim(), "Y" | "1"));
+        if enabled {
+            tags.insert(tag.to_string());
+        }
+    }
+
+    tags.into_iter().collect()
+}
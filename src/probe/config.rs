@@ -0,0 +1,120 @@
+//! The build configuration of the running kernel, from /proc/config.gz
+//! when the kernel was built with CONFIG_IKCONFIG_PROC, or from the copy
+//! most distributions install as /boot/config-<release>.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::ProbeError;
+use crate::gzip::gunzip;
+
+/// The configuration of the running kernel, when built with IKCONFIG.
+const PROC_CONFIG_PATH: &str = "/proc/config.gz";
+
+/// The options a kernel was built with, such as `CONFIG_SCHED_AUTOGROUP=y`.
+/// Options may be named with or without their `CONFIG_` prefix.
+#[derive(Debug, Clone, Default)]
+pub struct KernelConfigProbe {
+    options: HashMap<String, String>,
+    source: Option<PathBuf>,
+}
+
+impl KernelConfigProbe {
+    /// Load the configuration of the running kernel from /proc/config.gz,
+    /// or /boot/config-<release> if the kernel does not provide it.
+    pub fn new() -> Result<Self, ProbeError> {
+        Self::from_sysroot("/")
+    }
+
+    /// Load the configuration of the kernel of the system whose root file
+    /// system is mounted at `root`: that of its running kernel, or of the
+    /// latest kernel installed if it is an image that is not running.
+    pub fn from_sysroot<P: AsRef<Path>>(root: P) -> Result<Self, ProbeError> {
+        let root = root.as_ref();
+        let proc_config = super::rooted(root, PROC_CONFIG_PATH);
+        if proc_config.exists() {
+            return Self::from_file(proc_config);
+        }
+        let release = super::kernel_release(root)?;
+        let boot_config = super::rooted(root, &format!("/boot/config-{}", release));
+        if boot_config.exists() {
+            return Self::from_file(boot_config);
+        }
+        Err(ProbeError::MissingSystemFile(format!("{} or {}", proc_config.display(), boot_config.display())))
+    }
+
+    /// Load a configuration file, which may be gzip compressed.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ProbeError> {
+        let path = path.as_ref();
+        let mut data = fs::read(path)?;
+        if data.starts_with(&[0x1f, 0x8b]) {
+            data = gunzip(&data).map_err(|e| ProbeError::ParseError(format!("{}: {}", path.display(), e)))?;
+        }
+        let text = String::from_utf8(data)
+            .map_err(|_| ProbeError::ParseError(format!("{}: not a text file", path.display())))?;
+        let mut config = Self::parse(&text);
+        config.source = Some(path.to_path_buf());
+        Ok(config)
+    }
+
+    /// Parse the text of a kernel `.config`. Options recorded as
+    /// `# CONFIG_X is not set` have the value `n`.
+    pub fn parse(text: &str) -> Self {
+        let options = text.lines()
+            .filter_map(|line| {
+                let line = line.trim();
+                if let Some(option) = line.strip_prefix("# ").and_then(|rest| rest.strip_suffix(" is not set")) {
+                    return Some((option.to_string(), "n".to_string()));
+                }
+                let (option, value) = line.split_once('=')?;
+                option.starts_with("CONFIG_").then(|| (option.to_string(), value.to_string()))
+            })
+            .collect();
+        Self { options, source: None }
+    }
+
+    /// The file the configuration was read from.
+    pub fn source(&self) -> Option<&Path> {
+        self.source.as_deref()
+    }
+
+    /// The value of an option as written in the configuration, e.g. `y`,
+    /// `m`, `n`, `100` or `"lz4"` with its quotes; `None` for options the
+    /// configuration does not mention.
+    pub fn get(&self, option: &str) -> Option<&str> {
+        self.options.get(&config_name(option)).map(String::as_str)
+    }
+
+    /// Whether an option is built in or built as a module.
+    pub fn is_enabled(&self, option: &str) -> bool {
+        matches!(self.get(option), Some("y" | "m"))
+    }
+
+    /// Whether an option is built in.
+    pub fn is_builtin(&self, option: &str) -> bool {
+        self.get(option) == Some("y")
+    }
+
+    /// Whether an option is built as a module.
+    pub fn is_module(&self, option: &str) -> bool {
+        self.get(option) == Some("m")
+    }
+
+    /// Tags for the boolean and tristate options, as
+    /// `config:CONFIG_X=y`, `=m` or `=n`, for matching selectors.
+    pub fn tags(&self) -> impl Iterator<Item = String> + '_ {
+        self.options.iter()
+            .filter(|(_, value)| matches!(value.as_str(), "y" | "m" | "n"))
+            .map(|(option, value)| format!("config:{}={}", option, value))
+    }
+}
+
+/// The full name of an option named with or without its `CONFIG_` prefix.
+fn config_name(option: &str) -> String {
+    if option.starts_with("CONFIG_") {
+        option.to_string()
+    } else {
+        format!("CONFIG_{}", option)
+    }
+}
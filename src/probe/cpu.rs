@@ -0,0 +1,172 @@
+//! CPU feature flags, vendor and topology from /proc/cpuinfo, which lists
+//! one block of `key : value` lines per logical CPU.
+
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Names of the arm64 `CPU implementer` codes, as listed in the kernel's
+/// arch/arm64/include/asm/cputype.h.
+const ARM_IMPLEMENTERS: &[(&str, &str)] = &[
+    ("0x41", "ARM"),
+    ("0x42", "Broadcom"),
+    ("0x43", "Cavium"),
+    ("0x46", "Fujitsu"),
+    ("0x48", "HiSilicon"),
+    ("0x4e", "NVIDIA"),
+    ("0x50", "APM"),
+    ("0x51", "Qualcomm"),
+    ("0x61", "Apple"),
+    ("0x6d", "Microsoft"),
+    ("0xc0", "Ampere"),
+];
+
+/// The tags for the CPUs described by `cpuinfo`: `cpu:<flag>` for every
+/// feature flag of any CPU (`flags` on x86, `Features` on arm64, `features`
+/// on s390x), `cpu:vendor:<vendor>`, and the number of logical CPUs, cores
+/// and sockets as `cpu:threads:<n>`, `cpu:cores:<n>` and `cpu:sockets:<n>`.
+///
+/// Cores and sockets come from the `physical id` and `core id` of each CPU
+/// where /proc/cpuinfo records them for every CPU (x86, and s390x in blocks
+/// of their own), and otherwise from the topology directories under
+/// `sysfs_cpu_dir`; they are omitted if neither has them.
+pub(crate) fn cpu_tags(cpuinfo: &str, sysfs_cpu_dir: &Path) -> Vec<String> {
+    let mut flags = BTreeSet::new();
+    let mut vendor = None;
+    let mut threads = 0;
+    let mut topology = Vec::new();
+
+    for block in cpuinfo.split("\n\n") {
+        let mut package = None;
+        let mut core = None;
+
+        for (key, value) in block.lines().filter_map(|line| line.split_once(':')) {
+            let (key, value) = (key.trim(), value.trim());
+            match key {
+                "flags" | "Features" | "features" => flags.extend(value.split_whitespace()),
+                "vendor_id" => vendor = vendor.or(Some(value.to_string())),
+                "CPU implementer" => {
+                    let name = ARM_IMPLEMENTERS.iter()
+                        .find(|(code, _)| code.eq_ignore_ascii_case(value))
+                        .map_or(value, |(_, name)| name);
+                    vendor = vendor.or(Some(name.to_string()));
+                }
+                "physical id" => package = value.parse::<u32>().ok(),
+                "core id" => core = value.parse::<u32>().ok(),
+                // s390x lists every CPU in one block, as "processor <n>"
+                _ if key == "processor" || key.starts_with("processor ") => threads += 1,
+                _ => {}
+            }
+        }
+
+        if let (Some(package), Some(core)) = (package, core) {
+            topology.push((package, core));
+        }
+    }
+    if topology.is_empty() || topology.len() != threads {
+        topology = sysfs_topology(sysfs_cpu_dir);
+    }
+
+    let mut tags: Vec<String> = flags.into_iter().map(|flag| format!("cpu:{}", flag)).collect();
+    if let Some(vendor) = vendor {
+        tags.push(format!("cpu:vendor:{}", vendor.split_whitespace().collect::<Vec<_>>().join("_")));
+    }
+    if threads > 0 {
+        tags.push(format!("cpu:threads:{}", threads));
+    }
+    if !topology.is_empty() {
+        let sockets: HashSet<u32> = topology.iter().map(|(package, _)| *package).collect();
+        let cores: HashSet<&(u32, u32)> = topology.iter().collect();
+        tags.push(format!("cpu:cores:{}", cores.len()));
+        tags.push(format!("cpu:sockets:{}", sockets.len()));
+    }
+    tags
+}
+
+/// The package and core id of every CPU with a topology directory under
+/// `sysfs_cpu_dir`, e.g. /sys/devices/system/cpu/cpu3/topology.
+fn sysfs_topology(sysfs_cpu_dir: &Path) -> Vec<(u32, u32)> {
+    let Ok(entries) = fs::read_dir(sysfs_cpu_dir) else {
+        return Vec::new();
+    };
+    let read_id = |path: &Path| fs::read_to_string(path).ok()?.trim().parse::<u32>().ok();
+
+    entries.filter_map(Result::ok)
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.strip_prefix("cpu").is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+        })
+        .filter_map(|entry| {
+            let topology = entry.path().join("topology");
+            Some((read_id(&topology.join("physical_package_id"))?, read_id(&topology.join("core_id"))?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(cpuinfo: &str) -> Vec<String> {
+        cpu_tags(cpuinfo, Path::new("/nonexistent"))
+    }
+
+    fn has(tags: &[String], tag: &str) -> bool {
+        tags.iter().any(|t| t == tag)
+    }
+
+    #[test]
+    fn parses_x86_64() {
+        let tags = tags(include_str!("../../tests/fixtures/cpuinfo/x86_64"));
+        for tag in ["cpu:vmx", "cpu:avx512f", "cpu:vendor:GenuineIntel", "cpu:threads:4", "cpu:cores:2", "cpu:sockets:1"] {
+            assert!(has(&tags, tag), "{} missing from {:?}", tag, tags);
+        }
+        // Neither the VMX capabilities nor the bugs are feature flags.
+        assert!(!has(&tags, "cpu:ept_ad"));
+        assert!(!has(&tags, "cpu:spectre_v2"));
+    }
+
+    #[test]
+    fn parses_aarch64() {
+        let tags = tags(include_str!("../../tests/fixtures/cpuinfo/aarch64"));
+        for tag in ["cpu:asimd", "cpu:atomics", "cpu:vendor:ARM", "cpu:threads:4"] {
+            assert!(has(&tags, tag), "{} missing from {:?}", tag, tags);
+        }
+        // arm64 records no topology in /proc/cpuinfo.
+        assert!(!tags.iter().any(|tag| tag.starts_with("cpu:cores:")));
+    }
+
+    #[test]
+    fn parses_s390x() {
+        let tags = tags(include_str!("../../tests/fixtures/cpuinfo/s390x"));
+        for tag in ["cpu:zarch", "cpu:sie", "cpu:vendor:IBM/S390", "cpu:threads:2", "cpu:cores:2", "cpu:sockets:1"] {
+            assert!(has(&tags, tag), "{} missing from {:?}", tag, tags);
+        }
+        assert!(!has(&tags, "cpu:168"), "facilities are not features");
+    }
+
+    #[test]
+    fn parses_ppc64le() {
+        let tags = tags(include_str!("../../tests/fixtures/cpuinfo/ppc64le"));
+        // POWER lists neither feature flags nor a vendor.
+        assert_eq!(tags, ["cpu:threads:4"]);
+    }
+
+    #[test]
+    fn topology_from_sysfs() {
+        let dir = std::env::temp_dir().join(format!("libkcmdline-cpu-{}", std::process::id()));
+        for cpu in 0..4 {
+            let topology = dir.join(format!("cpu{}/topology", cpu));
+            fs::create_dir_all(&topology).unwrap();
+            fs::write(topology.join("physical_package_id"), format!("{}\n", cpu / 2)).unwrap();
+            fs::write(topology.join("core_id"), format!("{}\n", cpu % 2)).unwrap();
+        }
+        fs::create_dir_all(dir.join("cpufreq")).unwrap();
+
+        let tags = cpu_tags(include_str!("../../tests/fixtures/cpuinfo/aarch64"), &dir);
+        assert!(has(&tags, "cpu:cores:4"));
+        assert!(has(&tags, "cpu:sockets:2"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
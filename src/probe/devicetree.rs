@@ -0,0 +1,35 @@
+//! Platform identification from the flattened device tree that ARM, RISC-V
+//! and PowerPC boards boot with, as exported under /proc/device-tree (a
+//! link to /sys/firmware/devicetree/base). Properties are files holding
+//! NUL-terminated strings.
+
+use std::fs;
+use std::path::Path;
+
+/// The tags for the root node of the device tree at `base_dir`:
+/// `dt:model:<model>`, with whitespace replaced by `_`, and
+/// `dt:compatible:<string>` for each compatible string, most specific
+/// first, e.g. `dt:compatible:raspberrypi,4-model-b` and
+/// `dt:compatible:brcm,bcm2711`. Systems booted without a device tree, such
+/// as most x86 machines, yield no tags.
+pub(crate) fn devicetree_tags(base_dir: &Path) -> Vec<String> {
+    let mut tags = Vec::new();
+    if let Some(model) = read_strings(&base_dir.join("model")).into_iter().next() {
+        tags.push(format!("dt:model:{}", model.split_whitespace().collect::<Vec<_>>().join("_")));
+    }
+    tags.extend(read_strings(&base_dir.join("compatible")).into_iter()
+        .map(|compatible| format!("dt:compatible:{}", compatible)));
+    tags
+}
+
+/// The strings of a string-list property, which are separated and
+/// terminated by NULs.
+fn read_strings(path: &Path) -> Vec<String> {
+    let Ok(bytes) = fs::read(path) else {
+        return Vec::new();
+    };
+    bytes.split(|&b| b == 0)
+        .map(|string| String::from_utf8_lossy(string).trim().to_string())
+        .filter(|string| !string.is_empty())
+        .collect()
+}
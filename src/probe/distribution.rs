@@ -0,0 +1,62 @@
+//! The installed distribution, from the os-release file described in
+//! os-release(5): shell-style `KEY=value` assignments read from
+//! /etc/os-release, or /usr/lib/os-release where /etc has none.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::ProbeError;
+use crate::sources::shell::parse_assignments;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DistributionInfo {
+    pub id: String,
+    pub version_id: String,
+    pub variant_id: Option<String>,
+    pub name: String,
+    /// The distributions this one derives from or resembles, most closely
+    /// related first, from `ID_LIKE`; e.g. `["rhel", "centos", "fedora"]`
+    /// on Rocky Linux.
+    #[serde(default)]
+    pub id_like: Vec<String>,
+}
+
+impl DistributionInfo {
+    /// Parse the text of an os-release file. `ID` defaults to `linux` and
+    /// `NAME` to `Linux`, as os-release(5) specifies.
+    pub fn parse(os_release: &str) -> Result<Self, ProbeError> {
+        let vars = parse_assignments(os_release)
+            .map_err(|e| ProbeError::ParseError(format!("os-release: {}", e)))?;
+        let get = |key: &str| vars.get(key).map(|value| value.trim()).filter(|value| !value.is_empty());
+
+        Ok(Self {
+            id: get("ID").unwrap_or("linux").to_string(),
+            version_id: get("VERSION_ID").unwrap_or_default().to_string(),
+            variant_id: get("VARIANT_ID").map(str::to_string),
+            name: get("NAME").unwrap_or("Linux").to_string(),
+            id_like: get("ID_LIKE")
+                .map(|like| like.split_whitespace().map(str::to_string).collect())
+                .unwrap_or_default(),
+        })
+    }
+
+    /// The distribution ids to look parameter support up under, in order:
+    /// this distribution's own id, then those of `ID_LIKE`.
+    pub fn resolution_order(&self) -> Vec<&str> {
+        let mut order = vec![self.id.as_str()];
+        for like in &self.id_like {
+            if !order.contains(&like.as_str()) {
+                order.push(like);
+            }
+        }
+        order
+    }
+
+    /// The first id in [`resolution_order`](Self::resolution_order) for
+    /// which `has_support` is true, and whether it is this distribution's
+    /// own id rather than one it derives from.
+    pub fn resolve<F: Fn(&str) -> bool>(&self, has_support: F) -> Option<(&str, bool)> {
+        self.resolution_order().into_iter()
+            .find(|id| has_support(id))
+            .map(|id| (id, id == self.id))
+    }
+}
@@ -0,0 +1,48 @@
+//! Platform identification from the DMI/SMBIOS tables, as exported in the
+//! files under /sys/class/dmi/id.
+
+use std::fs;
+use std::path::Path;
+
+/// The `dmi:<field>:` tag fields, named as in `DmiSpecValidator`, and the
+/// file each is read from. The serial number and UUID identify a machine
+/// rather than a platform and are only readable by root, so they are not
+/// probed.
+const DMI_FIELDS: &[(&str, &str)] = &[
+    ("vendor", "sys_vendor"),
+    ("product", "product_name"),
+    ("version", "product_version"),
+    ("sku", "product_sku"),
+    ("family", "product_family"),
+    ("board_vendor", "board_vendor"),
+    ("board_name", "board_name"),
+    ("chassis_vendor", "chassis_vendor"),
+];
+
+/// Values firmware vendors leave in fields they do not fill in.
+const PLACEHOLDERS: &[&str] = &[
+    "To be filled by O.E.M.",
+    "To Be Filled By O.E.M.",
+    "Default string",
+    "Not Applicable",
+    "System Product Name",
+    "System manufacturer",
+];
+
+/// The tags for the DMI fields under `dmi_dir`, e.g.
+/// `dmi:vendor:LENOVO` and `dmi:product:20XW0055US`. Whitespace in values
+/// is replaced by `_` so that tags can be matched by selectors. Fields that
+/// are missing, empty or placeholders are skipped; systems without DMI,
+/// such as most arm64 boards, yield no tags.
+pub(crate) fn dmi_tags(dmi_dir: &Path) -> Vec<String> {
+    DMI_FIELDS.iter()
+        .filter_map(|(field, file)| {
+            let value = fs::read_to_string(dmi_dir.join(file)).ok()?;
+            let value = value.trim();
+            if value.is_empty() || PLACEHOLDERS.contains(&value) {
+                return None;
+            }
+            Some(format!("dmi:{}:{}", field, value.split_whitespace().collect::<Vec<_>>().join("_")))
+        })
+        .collect()
+}
@@ -0,0 +1,55 @@
+//! The crash kernel memory reservation made for kdump, from
+//! /sys/kernel/kexec_crash_size and the `Crash kernel` regions of
+//! /proc/iomem, and whether a crash kernel has been loaded into it.
+
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// The crash kernel state of the running kernel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrashKernelState {
+    /// Bytes reserved by `crashkernel=`; zero when nothing was requested or
+    /// the reservation failed.
+    pub reserved_bytes: u64,
+    /// Whether a crash kernel is loaded into the reservation, i.e. kdump is
+    /// active.
+    pub loaded: bool,
+}
+
+/// The crash kernel state read from `sysfs_kernel_dir`, normally
+/// /sys/kernel, and `proc_iomem`, or `None` for kernels built without
+/// kexec. The reservation is taken from /proc/iomem when its regions are
+/// readable, as that includes the `,high` and `,low` parts of a split
+/// reservation.
+pub(crate) fn crash_kernel_state(sysfs_kernel_dir: &Path, proc_iomem: &Path) -> Option<CrashKernelState> {
+    let read = |file: &str| fs::read_to_string(sysfs_kernel_dir.join(file)).ok();
+    let crash_size = read("kexec_crash_size")?.trim().parse::<u64>().ok()?;
+    let loaded = read("kexec_crash_loaded").is_some_and(|loaded| loaded.trim() == "1");
+
+    let iomem_size = fs::read_to_string(proc_iomem).ok()
+        .map(|iomem| iomem_crash_kernel_bytes(&iomem))
+        .filter(|&size| size > 0);
+    Some(CrashKernelState {
+        reserved_bytes: iomem_size.unwrap_or(crash_size),
+        loaded,
+    })
+}
+
+/// The total size of the `Crash kernel` regions of /proc/iomem, e.g.
+/// `  2b000000-32ffffff : Crash kernel`. Unprivileged readers see every
+/// address as zero, which yields zero.
+fn iomem_crash_kernel_bytes(iomem: &str) -> u64 {
+    iomem.lines()
+        .filter_map(|line| {
+            let (range, name) = line.split_once(" : ")?;
+            if name.trim() != "Crash kernel" {
+                return None;
+            }
+            let (start, end) = range.trim().split_once('-')?;
+            let (start, end) = (u64::from_str_radix(start, 16).ok()?, u64::from_str_radix(end, 16).ok()?);
+            (end > start).then(|| end - start + 1)
+        })
+        .sum()
+}
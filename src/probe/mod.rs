@@ -0,0 +1,721 @@
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::parameter::{ParameterProcessor, DRACUT_MODULE_SELECTOR_PREFIX};
+use crate::sources::shell::split_words;
+use crate::version::{compare_versions, ComponentVersion, KernelVersion};
+
+pub use bootloader::{Bootloader, DetectedBootloader};
+pub use builder::SystemProbeBuilder;
+pub use cache::default_probe_cache_dir;
+pub use config::KernelConfigProbe;
+pub use distribution::DistributionInfo;
+pub use kdump::CrashKernelState;
+pub use modules::ModuleState;
+pub use vulnerabilities::VulnerabilityStatus;
+
+mod bootloader;
+mod builder;
+mod cache;
+mod capabilities;
+mod config;
+mod cpu;
+mod devicetree;
+mod distribution;
+mod dmi;
+mod kdump;
+mod modules;
+mod net;
+mod pci;
+mod storage;
+mod usb;
+mod versions;
+mod vulnerabilities;
+
+/// The release of the running kernel, as printed by `uname -r`.
+const OSRELEASE_PATH: &str = "/proc/sys/kernel/osrelease";
+/// The kernel module directories, one per installed kernel release.
+const MODULES_DIR: &str = "/lib/modules";
+/// The file systems to mount at boot.
+const FSTAB_PATH: &str = "/etc/fstab";
+/// The identification of the installed distribution, and the file it
+/// defaults to.
+const OS_RELEASE_PATHS: &[&str] = &["/etc/os-release", "/usr/lib/os-release"];
+/// The modules loaded into the running kernel.
+const PROC_MODULES_PATH: &str = "/proc/modules";
+/// The mounted file systems.
+const PROC_MOUNTS_PATH: &str = "/proc/mounts";
+/// The physical memory map, including the crash kernel reservation.
+const PROC_IOMEM_PATH: &str = "/proc/iomem";
+/// Text of /proc/cpuinfo.
+const CPUINFO_PATH: &str = "/proc/cpuinfo";
+/// Directory of the per-CPU sysfs entries, including their topology.
+const SYSFS_CPU_DIR: &str = "/sys/devices/system/cpu";
+/// Directory of the CPU vulnerability files, relative to the CPU directory.
+const VULNERABILITIES_DIR: &str = "vulnerabilities";
+/// Mount point of sysfs.
+const SYSFS_ROOT: &str = "/sys";
+/// Directory of the PCI devices, relative to the sysfs root.
+const PCI_DEVICES_DIR: &str = "bus/pci/devices";
+/// Directory of the USB devices, relative to the sysfs root.
+const USB_DEVICES_DIR: &str = "bus/usb/devices";
+/// Directory of the DMI identification files, relative to the sysfs root.
+const DMI_ID_DIR: &str = "class/dmi/id";
+/// Directory of the device tree, relative to the sysfs root; /proc/device-tree
+/// links to it.
+const DEVICETREE_DIR: &str = "firmware/devicetree/base";
+/// Directory of the kernel's own attributes, relative to the sysfs root.
+const SYSFS_KERNEL_DIR: &str = "kernel";
+/// Directory of the network interfaces, relative to the sysfs root.
+const CLASS_NET_DIR: &str = "class/net";
+/// Directory of the block devices and partitions, relative to the sysfs root.
+const CLASS_BLOCK_DIR: &str = "class/block";
+/// The program the kernel runs as init, usually a link to the init system.
+const INIT_PATHS: &[&str] = &["/sbin/init", "/usr/sbin/init"];
+/// Name of the running process 1.
+const PROC_INIT_COMM_PATH: &str = "/proc/1/comm";
+/// The configuration files of each initramfs generator, by the name of its
+/// processor in the database.
+const INITRAMFS_GENERATOR_PATHS: &[(&str, &[&str])] = &[
+    ("dracut", &["/etc/dracut.conf", "/etc/dracut.conf.d", "/usr/lib/dracut/dracut.conf.d"]),
+    ("initramfs-tools", &["/etc/initramfs-tools/initramfs.conf", "/usr/share/initramfs-tools"]),
+    ("mkinitcpio", &["/etc/mkinitcpio.conf", "/etc/mkinitcpio.conf.d"]),
+];
+/// Directory of the installed dracut modules, each named with its order,
+/// e.g. `90crypt`.
+const DRACUT_MODULES_DIR: &str = "/usr/lib/dracut/modules.d";
+
+/// Version of the saved probe format, bumped when a change to
+/// [`SystemProbe`] would make older saved probes load incorrectly.
+pub const PROBE_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemProbe {
+    #[serde(serialize_with = "sorted")]
+    pub hardware_tags: HashSet<String>,
+    #[serde(serialize_with = "sorted")]
+    pub software_tags: HashSet<String>,
+    pub distribution: DistributionInfo,
+    /// The release of the running kernel, if it could be read.
+    #[serde(default)]
+    pub kernel_version: Option<KernelVersion>,
+    /// The installed systemd and dracut, when found.
+    #[serde(default)]
+    pub components: Vec<ComponentVersion>,
+    /// The boot loaders found and their configuration files.
+    #[serde(default)]
+    pub bootloaders: Vec<DetectedBootloader>,
+    /// The crash kernel reservation, if the kernel supports kexec.
+    #[serde(default)]
+    pub crash_kernel: Option<CrashKernelState>,
+}
+
+/// A probe as saved, with the format version it was saved in.
+#[derive(Serialize, Deserialize)]
+struct SavedProbe<P> {
+    schema_version: u32,
+    #[serde(flatten)]
+    probe: P,
+}
+
+/// The devices described by a sysfs tree, which may be a copy of another
+/// system's rather than the running system's /sys.
+pub struct HardwareProbe {
+    tags: HashSet<String>,
+}
+
+impl SystemProbe {
+    /// Probe the running system.
+    pub fn new() -> Result<Self, crate::error::ProbeError> {
+        Self::from_sysroot("/")
+    }
+
+    /// Probe the system whose root file system is mounted at `root`, such
+    /// as an image or container being built, reading its /proc, /sys, /etc
+    /// and /boot rather than the host's. Files under /proc and /sys are
+    /// often absent from an image; what they describe is then left out
+    /// rather than taken from the host. The versions of systemd and dracut
+    /// are read from their installed files, as the image's programs may not
+    /// run on the host.
+    pub fn from_sysroot<P: AsRef<Path>>(root: P) -> Result<Self, crate::error::ProbeError> {
+        let root = root.as_ref();
+        let offline = root != Path::new("/");
+        let sysfs_root = rooted(root, SYSFS_ROOT);
+        let mut hardware_tags = HashSet::new();
+        let mut software_tags = HashSet::new();
+
+        // Architecture
+        let arch = if offline { elf_arch(root) } else { None };
+        hardware_tags.insert(format!("arch:{}", arch.unwrap_or(std::env::consts::ARCH)));
+
+        // Hardware probing
+        hardware_tags.extend(Self::probe_cpu_features(root, offline)?);
+        hardware_tags.extend(Self::probe_cpu_vulnerabilities(root));
+        hardware_tags.extend(Self::probe_pci_devices(&sysfs_root)?);
+        hardware_tags.extend(Self::probe_usb_devices(&sysfs_root)?);
+        hardware_tags.extend(Self::probe_dmi(&sysfs_root)?);
+        hardware_tags.extend(Self::probe_devicetree(&sysfs_root));
+        hardware_tags.extend(Self::probe_network(&sysfs_root));
+        let capabilities = capabilities::capability_tags(&sysfs_root, &hardware_tags);
+        hardware_tags.extend(capabilities);
+
+        // Software probing
+        software_tags.extend(Self::probe_init_system(root));
+        software_tags.extend(Self::probe_initramfs_generator(root));
+        software_tags.extend(Self::probe_dracut_modules(root));
+        software_tags.extend(Self::probe_modules(root));
+        software_tags.extend(Self::probe_storage(root, &sysfs_root));
+        let bootloaders = DetectedBootloader::detect(root);
+        software_tags.extend(bootloaders.iter().map(|detected| detected.bootloader.tag()));
+
+        let distribution = Self::probe_distribution(root)?;
+        let kernel_version = kernel_release(root).ok().and_then(|release| KernelVersion::from_release(&release));
+        let crash_kernel = kdump::crash_kernel_state(&sysfs_root.join(SYSFS_KERNEL_DIR), &rooted(root, PROC_IOMEM_PATH));
+        let components = if offline {
+            versions::installed_component_versions(root)
+        } else {
+            versions::component_versions()
+        };
+
+        Ok(Self {
+            hardware_tags,
+            software_tags,
+            distribution,
+            kernel_version,
+            components,
+            bootloaders,
+            crash_kernel,
+        })
+    }
+
+    /// A builder for a probe of a system described by hand rather than
+    /// probed.
+    pub fn builder() -> SystemProbeBuilder {
+        SystemProbeBuilder::new()
+    }
+
+    /// The probe as JSON, with its tags sorted so that saved probes can be
+    /// compared.
+    pub fn to_json(&self) -> Result<String, crate::error::ProbeError> {
+        let saved = SavedProbe { schema_version: PROBE_SCHEMA_VERSION, probe: self };
+        serde_json::to_string_pretty(&saved).map_err(|e| crate::error::ProbeError::ParseError(e.to_string()))
+    }
+
+    /// Read a probe saved by [`to_json`](Self::to_json), such as one
+    /// captured on another machine, to validate command lines against it.
+    pub fn from_json(json: &str) -> Result<Self, crate::error::ProbeError> {
+        let saved: SavedProbe<Self> = serde_json::from_str(json)
+            .map_err(|e| crate::error::ProbeError::ParseError(e.to_string()))?;
+        if saved.schema_version != PROBE_SCHEMA_VERSION {
+            return Err(crate::error::ProbeError::ParseError(format!(
+                "unsupported probe schema version {} (expected {})", saved.schema_version, PROBE_SCHEMA_VERSION,
+            )));
+        }
+        Ok(saved.probe)
+    }
+
+    /// Save the probe as JSON.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), crate::error::ProbeError> {
+        fs::write(path, self.to_json()? + "\n")?;
+        Ok(())
+    }
+
+    /// Load a probe saved with [`save`](Self::save).
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, crate::error::ProbeError> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    /// The architecture recorded in the `arch:` hardware tag.
+    pub fn arch(&self) -> Option<&str> {
+        self.hardware_tags.iter().find_map(|tag| tag.strip_prefix("arch:"))
+    }
+
+    /// The type of the root file system recorded in the `rootfs:` tag.
+    pub fn root_filesystem(&self) -> Option<&str> {
+        self.software_tags.iter().find_map(|tag| tag.strip_prefix("rootfs:"))
+    }
+
+    /// Whether the root file system is on a storage layer such as `lvm`,
+    /// `luks`, `md` or `multipath`, as recorded in the `storage:` tags.
+    pub fn has_storage(&self, layer: &str) -> bool {
+        self.software_tags.contains(&format!("storage:{}", layer))
+    }
+
+    /// Add the `config:CONFIG_X=y` tags of a kernel configuration to the
+    /// software tags, so that selectors can require kernel options.
+    pub fn with_kernel_config(mut self, config: &KernelConfigProbe) -> Self {
+        self.software_tags.extend(config.tags());
+        self
+    }
+
+    /// Whether a kernel option is built in or modular, from the tags added
+    /// by [`with_kernel_config`](Self::with_kernel_config); `None` when no
+    /// kernel configuration was added.
+    pub fn kernel_option_enabled(&self, option: &str) -> Option<bool> {
+        if !self.software_tags.iter().any(|tag| tag.starts_with("config:")) {
+            return None;
+        }
+        let option = if option.starts_with("CONFIG_") { option.to_string() } else { format!("CONFIG_{}", option) };
+        Some(["y", "m"].iter().any(|value| self.software_tags.contains(&format!("config:{}={}", option, value))))
+    }
+
+    /// Whether a kernel module is loaded, built in or installed, with its
+    /// name spelt with underscores or dashes. `None` means none of these
+    /// were found, which includes systems whose module directory could not
+    /// be read.
+    pub fn module_state(&self, module: &str) -> Option<ModuleState> {
+        let module = module.replace('-', "_");
+        [ModuleState::Loaded, ModuleState::Builtin, ModuleState::Available].into_iter()
+            .find(|state| self.software_tags.contains(&format!("{}{}", state.tag_prefix(), module)))
+    }
+
+    /// The configuration files of a boot loader, if it was found; these are
+    /// the sources to read and rewrite the boot command line in.
+    pub fn bootloader_config_paths(&self, bootloader: Bootloader) -> Option<&[PathBuf]> {
+        self.bootloaders.iter()
+            .find(|detected| detected.bootloader == bootloader)
+            .map(|detected| detected.config_paths.as_slice())
+    }
+
+    /// The version of a component such as `systemd` or `dracut`, if it is
+    /// installed.
+    pub fn component_version(&self, name: &str) -> Option<&str> {
+        self.components.iter()
+            .find(|component| component.name == name)
+            .map(|component| component.version.as_str())
+    }
+
+    /// Whether the processor of a parameter is present and recent enough to
+    /// act on it. Systemd and dracut parameters need that component at or
+    /// after their `min_version`; other processors are assumed present.
+    pub fn supports_processor(&self, processor: &ParameterProcessor) -> bool {
+        let (name, min_version) = match processor {
+            ParameterProcessor::Systemd { min_version } => ("systemd", min_version),
+            ParameterProcessor::Dracut { min_version } => ("dracut", min_version),
+            _ => return true,
+        };
+        self.component_version(name).is_some_and(|version| {
+            min_version.is_empty() || compare_versions(min_version, version) != std::cmp::Ordering::Greater
+        })
+    }
+
+    /// The compatible strings of the device tree the system booted with,
+    /// e.g. `raspberrypi,4-model-b` and `brcm,bcm2711`, sorted by name.
+    pub fn devicetree_compatible(&self) -> Vec<&str> {
+        let mut compatible: Vec<&str> = self.hardware_tags.iter()
+            .filter_map(|tag| tag.strip_prefix("dt:compatible:"))
+            .collect();
+        compatible.sort_unstable();
+        compatible
+    }
+
+    /// The kernel's assessment of a CPU vulnerability named as in
+    /// /sys/devices/system/cpu/vulnerabilities, e.g. `l1tf` or
+    /// `spectre_v2`; `None` if the kernel does not report it.
+    pub fn vulnerability_status(&self, vulnerability: &str) -> Option<VulnerabilityStatus> {
+        let prefix = format!("cpu-vuln:{}:", vulnerability);
+        self.hardware_tags.iter()
+            .find_map(|tag| VulnerabilityStatus::from_name(tag.strip_prefix(&prefix)?))
+    }
+
+    /// The vulnerabilities the kernel reports and their status, sorted by
+    /// name.
+    pub fn vulnerabilities(&self) -> Vec<(&str, VulnerabilityStatus)> {
+        let mut vulnerabilities: Vec<(&str, VulnerabilityStatus)> = self.hardware_tags.iter()
+            .filter_map(|tag| {
+                let (name, status) = tag.strip_prefix("cpu-vuln:")?.rsplit_once(':')?;
+                Some((name, VulnerabilityStatus::from_name(status)?))
+            })
+            .collect();
+        vulnerabilities.sort_by_key(|(name, _)| *name);
+        vulnerabilities
+    }
+
+    /// Whether a network interface of this name exists.
+    pub fn has_network_interface(&self, name: &str) -> bool {
+        self.hardware_tags.contains(&format!("net:iface:{}", name))
+    }
+
+    /// Whether a network interface has this MAC address, written with
+    /// colons or dashes in either case.
+    pub fn has_mac_address(&self, mac: &str) -> bool {
+        self.hardware_tags.contains(&format!("net:mac:{}", net::normalize_mac(mac)))
+    }
+
+    /// Whether any network interfaces were found; when none were, as when
+    /// probing without a sysfs, interface references cannot be checked.
+    pub fn has_network_interfaces(&self) -> bool {
+        self.hardware_tags.iter().any(|tag| tag.starts_with("net:iface:"))
+    }
+
+    /// The vendor and device ids of the PCI devices found, for use with
+    /// [`KCmdline::parameters_for_pci_device`](crate::KCmdline::parameters_for_pci_device).
+    pub fn pci_devices(&self) -> Vec<(u16, u16)> {
+        device_ids(&self.hardware_tags, "pci:")
+    }
+
+    /// The vendor and product ids of the USB devices found.
+    pub fn usb_devices(&self) -> Vec<(u16, u16)> {
+        device_ids(&self.hardware_tags, "usb:")
+    }
+
+    /// Read the command line the running kernel was booted with.
+    pub fn current_cmdline() -> Result<String, crate::error::ProbeError> {
+        let cmdline = read_system_file(Path::new("/proc/cmdline"))?;
+        Ok(cmdline.trim_end_matches('\n').to_string())
+    }
+
+    /// CPU flags, vendor and topology, e.g. `cpu:vmx`,
+    /// `cpu:vendor:GenuineIntel` and `cpu:cores:8`. /proc/cpuinfo is only
+    /// required of the running system.
+    fn probe_cpu_features(root: &Path, offline: bool) -> Result<Vec<String>, crate::error::ProbeError> {
+        let cpuinfo = match read_system_file(&rooted(root, CPUINFO_PATH)) {
+            Ok(cpuinfo) => cpuinfo,
+            Err(crate::error::ProbeError::MissingSystemFile(_)) if offline => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        Ok(cpu::cpu_tags(&cpuinfo, &rooted(root, SYSFS_CPU_DIR)))
+    }
+
+    /// The kernel's vulnerability assessment of the CPU, e.g.
+    /// `cpu-vuln:l1tf:not-affected`.
+    fn probe_cpu_vulnerabilities(root: &Path) -> Vec<String> {
+        vulnerabilities::vulnerability_tags(&rooted(root, SYSFS_CPU_DIR).join(VULNERABILITIES_DIR))
+    }
+
+    /// PCI vendor, device and class tags, e.g. `pci:8086:9a49` and
+    /// `pci-class:0300`.
+    fn probe_pci_devices(sysfs_root: &Path) -> Result<Vec<String>, crate::error::ProbeError> {
+        pci::pci_tags(&sysfs_root.join(PCI_DEVICES_DIR))
+    }
+
+    /// USB vendor and product tags, e.g. `usb:046d:c52b`.
+    fn probe_usb_devices(sysfs_root: &Path) -> Result<Vec<String>, crate::error::ProbeError> {
+        usb::usb_tags(&sysfs_root.join(USB_DEVICES_DIR))
+    }
+
+    /// Platform identification tags, e.g. `dmi:vendor:LENOVO`.
+    fn probe_dmi(sysfs_root: &Path) -> Result<Vec<String>, crate::error::ProbeError> {
+        Ok(dmi::dmi_tags(&sysfs_root.join(DMI_ID_DIR)))
+    }
+
+    /// Device tree model and compatible tags, e.g.
+    /// `dt:compatible:brcm,bcm2711`.
+    fn probe_devicetree(sysfs_root: &Path) -> Vec<String> {
+        devicetree::devicetree_tags(&sysfs_root.join(DEVICETREE_DIR))
+    }
+
+    /// Network interface, MAC address and driver tags, e.g. `net:iface:eno1`.
+    fn probe_network(sysfs_root: &Path) -> Vec<String> {
+        net::net_tags(&sysfs_root.join(CLASS_NET_DIR))
+    }
+
+    /// The init system, e.g. `init:systemd`, from the program /sbin/init
+    /// links to or, when it is not a link, the name of the running process
+    /// 1. Nothing is added when neither identifies it.
+    fn probe_init_system(root: &Path) -> Vec<String> {
+        let linked = INIT_PATHS.iter()
+            .filter_map(|path| fs::read_link(rooted(root, path)).ok())
+            .find_map(|target| init_system_name(&target.file_name()?.to_string_lossy()));
+        let running = || {
+            let comm = fs::read_to_string(rooted(root, PROC_INIT_COMM_PATH)).ok()?;
+            init_system_name(comm.trim())
+        };
+        linked.or_else(running).map(|name| format!("init:{}", name)).into_iter().collect()
+    }
+
+    /// The initramfs generators installed, e.g. `initramfs:dracut`, from
+    /// their configuration files.
+    fn probe_initramfs_generator(root: &Path) -> Vec<String> {
+        INITRAMFS_GENERATOR_PATHS.iter()
+            .filter(|(_, paths)| paths.iter().any(|path| rooted(root, path).exists()))
+            .map(|(name, _)| format!("initramfs:{}", name))
+            .collect()
+    }
+
+    /// The dracut modules an initramfs built here can include, e.g.
+    /// `dracut_module:crypt`: those installed that no dracut configuration
+    /// file leaves out with `omit_dracutmodules`.
+    fn probe_dracut_modules(root: &Path) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(rooted(root, DRACUT_MODULES_DIR)) else {
+            return Vec::new();
+        };
+        let omitted = omitted_dracut_modules(root);
+        let mut tags: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let module = name.trim_start_matches(|c: char| c.is_ascii_digit());
+                (!module.is_empty() && !omitted.contains(module)).then(|| format!("{}{}", DRACUT_MODULE_SELECTOR_PREFIX, module))
+            })
+            .collect();
+        tags.sort();
+        tags
+    }
+
+    /// Loaded, built-in and installed module tags, e.g.
+    /// `module:loaded:kvm_intel`.
+    fn probe_modules(root: &Path) -> Vec<String> {
+        let modules_dir = kernel_release(root).ok().map(|release| rooted(root, MODULES_DIR).join(release));
+        modules::module_tags(&rooted(root, PROC_MODULES_PATH), modules_dir.as_deref())
+    }
+
+    /// Root file system and storage stack tags, e.g. `rootfs:xfs`,
+    /// `storage:lvm` and `storage:luks`. An image that is not running has
+    /// no /proc/mounts, so its root file system is found in /etc/fstab.
+    fn probe_storage(root: &Path, sysfs_root: &Path) -> Vec<String> {
+        let mut mount_table = rooted(root, PROC_MOUNTS_PATH);
+        if !mount_table.exists() {
+            mount_table = rooted(root, FSTAB_PATH);
+        }
+        storage::storage_tags(&mount_table, &sysfs_root.join(CLASS_BLOCK_DIR))
+    }
+
+    /// The distribution installed, from /etc/os-release or its fallback
+    /// /usr/lib/os-release; a system with neither is generic `linux`.
+    fn probe_distribution(root: &Path) -> Result<DistributionInfo, crate::error::ProbeError> {
+        let os_release = OS_RELEASE_PATHS.iter()
+            .map(|path| fs::read_to_string(rooted(root, path)))
+            .find(|result| !matches!(result, Err(e) if e.kind() == std::io::ErrorKind::NotFound))
+            .transpose()?
+            .unwrap_or_default();
+        DistributionInfo::parse(&os_release)
+    }
+}
+
+impl HardwareProbe {
+    /// Probe the devices under `sysfs_root`, normally /sys.
+    pub fn from_sysfs<P: AsRef<Path>>(sysfs_root: P) -> Result<Self, crate::error::ProbeError> {
+        let mut tags = HashSet::new();
+        tags.extend(SystemProbe::probe_pci_devices(sysfs_root.as_ref())?);
+        tags.extend(SystemProbe::probe_usb_devices(sysfs_root.as_ref())?);
+        tags.extend(SystemProbe::probe_dmi(sysfs_root.as_ref())?);
+        tags.extend(SystemProbe::probe_devicetree(sysfs_root.as_ref()));
+        tags.extend(SystemProbe::probe_network(sysfs_root.as_ref()));
+        tags.extend(capabilities::capability_tags(sysfs_root.as_ref(), &[]));
+        Ok(Self { tags })
+    }
+
+    /// The hardware tags of the devices found, as in
+    /// [`SystemProbe::hardware_tags`].
+    pub fn tags(&self) -> &HashSet<String> {
+        &self.tags
+    }
+
+    /// The vendor and device ids of the PCI devices found.
+    pub fn pci_devices(&self) -> Vec<(u16, u16)> {
+        device_ids(&self.tags, "pci:")
+    }
+
+    /// The vendor and product ids of the USB devices found.
+    pub fn usb_devices(&self) -> Vec<(u16, u16)> {
+        device_ids(&self.tags, "usb:")
+    }
+}
+
+fn sorted<S: Serializer>(tags: &HashSet<String>, serializer: S) -> Result<S::Ok, S::Error> {
+    tags.iter().collect::<BTreeSet<_>>().serialize(serializer)
+}
+
+/// The vendor and device ids in `<prefix><vendor>:<device>` tags, sorted.
+fn device_ids<'a, I: IntoIterator<Item = &'a String>>(tags: I, prefix: &str) -> Vec<(u16, u16)> {
+    let mut ids: Vec<(u16, u16)> = tags.into_iter()
+        .filter_map(|tag| tag.strip_prefix(prefix)?.split_once(':'))
+        .filter_map(|(vendor, device)| {
+            Some((u16::from_str_radix(vendor, 16).ok()?, u16::from_str_radix(device, 16).ok()?))
+        })
+        .collect();
+    ids.sort_unstable();
+    ids
+}
+
+/// The release of the kernel running on the system at `root`, e.g.
+/// `6.8.0-45-generic`. An image that is not running has no /proc, so the
+/// latest kernel installed under /lib/modules is taken instead.
+fn kernel_release(root: &Path) -> Result<String, crate::error::ProbeError> {
+    let osrelease = rooted(root, OSRELEASE_PATH);
+    match read_system_file(&osrelease) {
+        Ok(release) if !release.trim().is_empty() => return Ok(release.trim().to_string()),
+        Err(e @ crate::error::ProbeError::IoError(_)) => return Err(e),
+        _ => {}
+    }
+    fs::read_dir(rooted(root, MODULES_DIR)).ok()
+        .into_iter()
+        .flat_map(|entries| entries.flatten())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter_map(|release| Some((KernelVersion::from_release(&release)?, release)))
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, release)| release)
+        .ok_or_else(|| crate::error::ProbeError::MissingSystemFile(osrelease.display().to_string()))
+}
+
+/// The init system a program or process name belongs to, e.g. `systemd`
+/// for `/lib/systemd/systemd`; `None` for a plain `init`, which could be
+/// any of them.
+fn init_system_name(program: &str) -> Option<&'static str> {
+    const INIT_SYSTEMS: &[&str] = &["systemd", "openrc", "runit", "s6", "upstart", "busybox", "tini"];
+    INIT_SYSTEMS.iter().copied().find(|name| program.contains(name))
+}
+
+/// An absolute path resolved under `root`.
+fn rooted(root: &Path, path: &str) -> PathBuf {
+    root.join(path.trim_start_matches('/'))
+}
+
+/// The architecture of the programs installed under `root`, named as in
+/// [`std::env::consts::ARCH`], from the ELF header of its shell.
+fn elf_arch(root: &Path) -> Option<&'static str> {
+    let mut header = [0u8; 20];
+    let mut shell = ["usr/bin/sh", "bin/sh"].iter()
+        .find_map(|shell| fs::File::open(root.join(shell)).ok())?;
+    shell.read_exact(&mut header).ok()?;
+    if &header[..4] != b"\x7fELF" {
+        return None;
+    }
+    // e_machine follows e_ident and e_type, in the file's byte order
+    let machine = [header[18], header[19]];
+    let machine = match header[5] {
+        2 => u16::from_be_bytes(machine),
+        _ => u16::from_le_bytes(machine),
+    };
+    let is_64bit = header[4] == 2;
+    Some(match (machine, is_64bit) {
+        (0x03, _) => "x86",
+        (0x3e, _) => "x86_64",
+        (0x28, _) => "arm",
+        (0xb7, _) => "aarch64",
+        (0x14, _) => "powerpc",
+        (0x15, _) => "powerpc64",
+        (0x16, _) => "s390x",
+        (0xf3, true) => "riscv64",
+        (0xf3, false) => "riscv32",
+        (0x102, _) => "loongarch64",
+        _ => return None,
+    })
+}
+
+/// The dracut modules named by `omit_dracutmodules` in any of dracut's
+/// configuration files.
+fn omitted_dracut_modules(root: &Path) -> HashSet<String> {
+    let paths = INITRAMFS_GENERATOR_PATHS.iter()
+        .filter(|(name, _)| *name == "dracut")
+        .flat_map(|(_, paths)| paths.iter())
+        .map(|path| rooted(root, path));
+    let mut files = Vec::new();
+    for path in paths {
+        match fs::read_dir(&path) {
+            Ok(entries) => files.extend(entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|file| file.extension().is_some_and(|extension| extension == "conf"))),
+            Err(_) => files.push(path),
+        }
+    }
+
+    let mut omitted = HashSet::new();
+    for text in files.iter().filter_map(|file| fs::read_to_string(file).ok()) {
+        for line in text.lines() {
+            let Some(value) = line.trim().strip_prefix("omit_dracutmodules") else {
+                continue;
+            };
+            let Some(value) = value.strip_prefix("+=").or_else(|| value.strip_prefix('=')) else {
+                continue;
+            };
+            for word in split_words(value).unwrap_or_default() {
+                omitted.extend(word.split_whitespace().map(str::to_string));
+            }
+        }
+    }
+    omitted
+}
+
+/// Read a file the probe requires, reporting it by path when it is absent.
+fn read_system_file(path: &Path) -> Result<String, crate::error::ProbeError> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(text),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Err(crate::error::ProbeError::MissingSystemFile(path.display().to_string()))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An empty system root under the temporary directory.
+    fn sysroot(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("libkcmdline-probe-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    #[test]
+    fn init_system_from_link() {
+        let root = sysroot("init-link");
+        fs::create_dir_all(root.join("sbin")).unwrap();
+        std::os::unix::fs::symlink("/lib/systemd/systemd", root.join("sbin/init")).unwrap();
+        assert_eq!(SystemProbe::probe_init_system(&root), ["init:systemd"]);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn init_system_from_process() {
+        let root = sysroot("init-comm");
+        fs::create_dir_all(root.join("proc/1")).unwrap();
+        fs::write(root.join("proc/1/comm"), "openrc-init\n").unwrap();
+        assert_eq!(SystemProbe::probe_init_system(&root), ["init:openrc"]);
+        fs::write(root.join("proc/1/comm"), "init\n").unwrap();
+        assert!(SystemProbe::probe_init_system(&root).is_empty());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn initramfs_generators_from_config() {
+        let root = sysroot("initramfs");
+        assert!(SystemProbe::probe_initramfs_generator(&root).is_empty());
+        fs::create_dir_all(root.join("etc/dracut.conf.d")).unwrap();
+        fs::write(root.join("etc/mkinitcpio.conf"), "HOOKS=(base udev)\n").unwrap();
+        assert_eq!(SystemProbe::probe_initramfs_generator(&root), ["initramfs:dracut", "initramfs:mkinitcpio"]);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn empty_sysroot_probes() {
+        let root = sysroot("empty");
+        let probe = SystemProbe::from_sysroot(&root).unwrap();
+        assert!(!probe.software_tags.iter().any(|tag| tag.starts_with("init")));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn pci_devices_from_the_sysroot() {
+        let root = sysroot("pci");
+        let device_dir = root.join("sys/bus/pci/devices/0000:00:02.0");
+        fs::create_dir_all(&device_dir).unwrap();
+        fs::write(device_dir.join("vendor"), "0x8086\n").unwrap();
+        fs::write(device_dir.join("device"), "0x9a49\n").unwrap();
+        let probe = SystemProbe::from_sysroot(&root).unwrap();
+        assert_eq!(probe.pci_devices(), [(0x8086, 0x9a49)]);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn dracut_modules_less_those_omitted() {
+        let root = sysroot("dracut-modules");
+        assert!(SystemProbe::probe_dracut_modules(&root).is_empty());
+        for module in ["90crypt", "90lvm", "95nfs", "99base"] {
+            fs::create_dir_all(root.join("usr/lib/dracut/modules.d").join(module)).unwrap();
+        }
+        fs::create_dir_all(root.join("etc/dracut.conf.d")).unwrap();
+        fs::write(root.join("etc/dracut.conf.d/omit.conf"), "omit_dracutmodules+=\" nfs lvm \"\n").unwrap();
+        fs::write(root.join("etc/dracut.conf.d/notes.txt"), "omit_dracutmodules+=\" base \"\n").unwrap();
+        assert_eq!(SystemProbe::probe_dracut_modules(&root), ["dracut_module:base", "dracut_module:crypt"]);
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,64 @@
+//! Kernel modules that are loaded, from /proc/modules, and those built into
+//! or installed for the running kernel, from the `modules.builtin` and
+//! `modules.dep` indexes under /lib/modules/<release>.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+/// Where the probe found a kernel module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModuleState {
+    /// The module is loaded.
+    Loaded,
+    /// The module is part of the kernel image and cannot be unloaded.
+    Builtin,
+    /// The module is installed and can be loaded.
+    Available,
+}
+
+impl ModuleState {
+    /// The `module:<state>:` tag prefix for modules in this state.
+    pub(crate) fn tag_prefix(self) -> &'static str {
+        match self {
+            Self::Loaded => "module:loaded:",
+            Self::Builtin => "module:builtin:",
+            Self::Available => "module:available:",
+        }
+    }
+}
+
+/// The tags for the modules listed in `proc_modules` and in the indexes
+/// under `modules_dir`, when the kernel release is known, e.g. `module:loaded:kvm_intel`,
+/// `module:builtin:ext4` and `module:available:nvme`. Module names use
+/// underscores, as the kernel does. Missing files yield no tags, as in
+/// containers without a module directory.
+pub(crate) fn module_tags(proc_modules: &Path, modules_dir: Option<&Path>) -> Vec<String> {
+    let read = |path: &Path| fs::read_to_string(path).unwrap_or_default();
+    let mut tags = BTreeSet::new();
+
+    // Each line of /proc/modules starts with the module name
+    for name in read(proc_modules).lines().filter_map(|line| line.split_whitespace().next()) {
+        tags.insert(format!("{}{}", ModuleState::Loaded.tag_prefix(), name));
+    }
+    // Both indexes list one module path per line, and modules.dep follows
+    // each with a colon and its dependencies
+    if let Some(modules_dir) = modules_dir {
+        for (index, state) in [("modules.builtin", ModuleState::Builtin), ("modules.dep", ModuleState::Available)] {
+            for line in read(&modules_dir.join(index)).lines() {
+                let path = line.split(':').next().unwrap_or(line);
+                if let Some(name) = module_name(path) {
+                    tags.insert(format!("{}{}", state.tag_prefix(), name));
+                }
+            }
+        }
+    }
+    tags.into_iter().collect()
+}
+
+/// The name of the module at a path such as `kernel/arch/x86/kvm/kvm-intel.ko.zst`.
+fn module_name(path: &str) -> Option<String> {
+    let file = path.trim().rsplit('/').next()?;
+    let (name, _) = file.split_once(".ko")?;
+    (!name.is_empty()).then(|| name.replace('-', "_"))
+}
@@ -0,0 +1,44 @@
+//! Network interfaces from the per-interface directories under
+//! /sys/class/net, each with an `address` file holding its MAC address and
+//! a `device/driver` link to the driver of physical interfaces.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+/// The tags for the interfaces under `class_net_dir`: `net:iface:<name>`,
+/// `net:mac:<address>` in lowercase and `net:driver:<driver>`, e.g.
+/// `net:iface:eno1`, `net:mac:aa:bb:cc:dd:ee:ff` and `net:driver:e1000e`.
+/// Interfaces without a hardware address, such as the loopback device, get
+/// no `net:mac:` tag and virtual interfaces no `net:driver:` tag. A missing
+/// directory yields no tags.
+pub(crate) fn net_tags(class_net_dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(class_net_dir) else {
+        return Vec::new();
+    };
+
+    let mut tags = BTreeSet::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let dir = entry.path();
+        if let Ok(address) = fs::read_to_string(dir.join("address")) {
+            let address = address.trim().to_ascii_lowercase();
+            if !address.is_empty() && address.chars().any(|c| c.is_ascii_hexdigit() && c != '0') {
+                tags.insert(format!("net:mac:{}", address));
+            }
+        }
+        if let Some(driver) = fs::read_link(dir.join("device/driver")).ok()
+            .and_then(|link| link.file_name().map(|driver| driver.to_string_lossy().into_owned()))
+        {
+            tags.insert(format!("net:driver:{}", driver));
+        }
+        tags.insert(format!("net:iface:{}", name));
+    }
+    tags.into_iter().collect()
+}
+
+/// A MAC address in the lowercase, colon separated form of the `net:mac:`
+/// tags; dracut also accepts dashes as separators.
+pub(crate) fn normalize_mac(mac: &str) -> String {
+    mac.trim().replace('-', ":").to_ascii_lowercase()
+}
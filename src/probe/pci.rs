@@ -0,0 +1,88 @@
+//! PCI devices from the per-device directories under /sys/bus/pci/devices,
+//! each with `vendor`, `device` and `class` files holding hex ids.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use crate::error::ProbeError;
+
+/// The tags for the PCI devices under `devices_dir`: `pci:<vendor>` and
+/// `pci:<vendor>:<device>` for each device, so that selectors with and
+/// without a device id both match, and `pci-class:<class><subclass>`, all
+/// as lowercase hex, e.g. `pci:8086:9a49` and `pci-class:0300`. A missing
+/// directory, as on systems without PCI, yields no tags; devices whose ids
+/// cannot be read are skipped.
+pub(crate) fn pci_tags(devices_dir: &Path) -> Result<Vec<String>, ProbeError> {
+    let entries = match fs::read_dir(devices_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut tags = BTreeSet::new();
+    for entry in entries {
+        let device_dir = entry?.path();
+        let read_id = |file: &str| {
+            let text = fs::read_to_string(device_dir.join(file)).ok()?;
+            u32::from_str_radix(text.trim().trim_start_matches("0x"), 16).ok()
+        };
+        let (Some(vendor), Some(device)) = (read_id("vendor"), read_id("device")) else {
+            continue;
+        };
+        tags.insert(format!("pci:{:04x}", vendor));
+        tags.insert(format!("pci:{:04x}:{:04x}", vendor, device));
+        // The class file holds class, subclass and programming interface
+        if let Some(class) = read_id("class") {
+            tags.insert(format!("pci-class:{:04x}", class >> 8));
+        }
+    }
+    Ok(tags.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A sysfs root under the temporary directory holding the given PCI
+    /// devices as (vendor, device, class) file contents.
+    fn sysfs(name: &str, devices: &[(&str, &str, &str)]) -> std::path::PathBuf {
+        let root = std::env::temp_dir().join(format!("libkcmdline-pci-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        for (i, (vendor, device, class)) in devices.iter().enumerate() {
+            let device_dir = root.join(format!("bus/pci/devices/0000:00:{:02x}.0", i));
+            fs::create_dir_all(&device_dir).unwrap();
+            fs::write(device_dir.join("vendor"), vendor).unwrap();
+            fs::write(device_dir.join("device"), device).unwrap();
+            fs::write(device_dir.join("class"), class).unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn tags_devices_under_an_alternate_root() {
+        let root = sysfs("devices", &[("0x8086\n", "0x9A49\n", "0x030000\n"), ("0x10de\n", "0x1eb8\n", "0x030200\n")]);
+        assert_eq!(pci_tags(&root.join("bus/pci/devices")).unwrap(), [
+            "pci-class:0300",
+            "pci-class:0302",
+            "pci:10de",
+            "pci:10de:1eb8",
+            "pci:8086",
+            "pci:8086:9a49",
+        ]);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn skips_devices_with_unreadable_ids() {
+        let root = sysfs("unreadable", &[("0x8086\n", "bogus\n", "0x030000\n"), ("0x1af4\n", "0x1000\n", "")]);
+        assert_eq!(pci_tags(&root.join("bus/pci/devices")).unwrap(), ["pci:1af4", "pci:1af4:1000"]);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn no_tags_without_pci() {
+        let root = sysfs("missing", &[]);
+        assert!(pci_tags(&root.join("bus/pci/devices")).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,110 @@
+//! The storage stack under the root file system, from its entry in
+//! /proc/mounts and the device-mapper, md and partition information under
+//! /sys/class/block, without running lvs, cryptsetup or mdadm.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Device-mapper uuid prefixes and the tags of the targets they identify.
+const DM_UUID_PREFIXES: &[(&str, &str)] = &[
+    ("LVM-", "storage:lvm"),
+    ("CRYPT-LUKS", "storage:luks"),
+    ("CRYPT-", "storage:dm-crypt"),
+    ("mpath-", "storage:multipath"),
+];
+
+/// The tags for the root file system mounted according to `proc_mounts`:
+/// `rootfs:<type>`, e.g. `rootfs:xfs`, and a `storage:` tag for each layer
+/// beneath it, e.g. `storage:luks` and `storage:lvm` for LVM on LUKS, or
+/// `storage:md` for software RAID. Missing files yield no tags, and a root
+/// that is not a block device, such as an overlay in a container, yields
+/// only its file system type.
+pub(crate) fn storage_tags(proc_mounts: &Path, class_block_dir: &Path) -> Vec<String> {
+    let mounts = fs::read_to_string(proc_mounts).unwrap_or_default();
+    // Later mounts on / hide earlier ones, such as the initramfs rootfs
+    let Some((device, fstype)) = mounts.lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            (fields.len() >= 3 && fields[1] == "/").then(|| (fields[0], fields[2]))
+        })
+        .next_back()
+    else {
+        return Vec::new();
+    };
+
+    let mut tags = BTreeSet::new();
+    tags.insert(format!("rootfs:{}", fstype));
+    if let Some(name) = block_device_name(device, class_block_dir) {
+        stack_tags(&name, class_block_dir, &mut tags, 0);
+    }
+    tags.into_iter().collect()
+}
+
+/// The kernel name of the block device at `device`, e.g. `dm-0` for
+/// `/dev/mapper/rhel-root` and `sda2` for `/dev/sda2`.
+fn block_device_name(device: &str, class_block_dir: &Path) -> Option<String> {
+    let name = device.strip_prefix("/dev/")?;
+    if let Some(dm_name) = name.strip_prefix("mapper/") {
+        return fs::read_dir(class_block_dir).ok()?
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .filter(|name| name.starts_with("dm-"))
+            .find(|name| read_trimmed(&class_block_dir.join(name).join("dm/name")).as_deref() == Some(dm_name));
+    }
+    if class_block_dir.join(name).exists() {
+        return Some(name.to_string());
+    }
+    // Links such as /dev/disk/by-uuid/<uuid> or /dev/vg/lv on the running system
+    let target = fs::canonicalize(device).ok()?;
+    let name = target.file_name()?.to_string_lossy().into_owned();
+    class_block_dir.join(&name).exists().then_some(name)
+}
+
+/// Add the tags for the block device `name` and the devices it is built on:
+/// the `slaves` of device-mapper and md devices, and the disk holding a
+/// partition.
+fn stack_tags(name: &str, class_block_dir: &Path, tags: &mut BTreeSet<String>, depth: usize) {
+    // Stacks are shallow; the limit guards against a malformed sysfs copy
+    if depth > 16 {
+        return;
+    }
+    let dir = class_block_dir.join(name);
+
+    if name.starts_with("dm-") {
+        if let Some(uuid) = read_trimmed(&dir.join("dm/uuid")) {
+            if let Some((_, tag)) = DM_UUID_PREFIXES.iter().find(|(prefix, _)| uuid.starts_with(prefix)) {
+                tags.insert(tag.to_string());
+            }
+        }
+    } else if dir.join("md").is_dir() {
+        tags.insert("storage:md".to_string());
+        if let Some(level) = read_trimmed(&dir.join("md/level")) {
+            tags.insert(format!("storage:md:{}", level));
+        }
+    }
+
+    for lower in lower_devices(&dir) {
+        stack_tags(&lower, class_block_dir, tags, depth + 1);
+    }
+}
+
+/// The devices a block device is built on.
+fn lower_devices(dir: &Path) -> Vec<String> {
+    let mut lower: Vec<String> = fs::read_dir(dir.join("slaves"))
+        .map(|entries| entries.flatten().map(|entry| entry.file_name().to_string_lossy().into_owned()).collect())
+        .unwrap_or_default();
+    // A partition's sysfs directory is inside that of its disk
+    if dir.join("partition").exists() {
+        let disk = fs::canonicalize(dir).ok()
+            .and_then(|path| path.parent().map(PathBuf::from))
+            .and_then(|path| path.file_name().map(|name| name.to_string_lossy().into_owned()));
+        lower.extend(disk);
+    }
+    lower
+}
+
+/// The value of a sysfs attribute, without its trailing newline.
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|text| text.trim().to_string())
+}
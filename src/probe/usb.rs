@@ -0,0 +1,35 @@
+//! USB devices from /sys/bus/usb/devices, where each device directory has
+//! `idVendor` and `idProduct` files holding hex ids. Interface directories
+//! such as `1-1:1.0` have neither and are skipped.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use crate::error::ProbeError;
+
+/// The tags for the USB devices under `devices_dir`: `usb:<vendor>` and
+/// `usb:<vendor>:<product>` for each device, as lowercase hex, e.g.
+/// `usb:046d:c52b`. A missing directory yields no tags.
+pub(crate) fn usb_tags(devices_dir: &Path) -> Result<Vec<String>, ProbeError> {
+    let entries = match fs::read_dir(devices_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut tags = BTreeSet::new();
+    for entry in entries {
+        let device_dir = entry?.path();
+        let read_id = |file: &str| {
+            let text = fs::read_to_string(device_dir.join(file)).ok()?;
+            u16::from_str_radix(text.trim(), 16).ok()
+        };
+        let (Some(vendor), Some(product)) = (read_id("idVendor"), read_id("idProduct")) else {
+            continue;
+        };
+        tags.insert(format!("usb:{:04x}", vendor));
+        tags.insert(format!("usb:{:04x}:{:04x}", vendor, product));
+    }
+    Ok(tags.into_iter().collect())
+}
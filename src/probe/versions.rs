@@ -0,0 +1,83 @@
+//! Versions of the components that consume kernel command line parameters:
+//! the running kernel, from its release as printed by `uname -r`, and the
+//! systemd and dracut installed, from their `--version` output or, for a
+//! system that is not running, from the files they install.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use crate::version::ComponentVersion;
+
+const SYSTEMCTL: &str = "systemctl";
+const DRACUT: &str = "dracut";
+
+/// Directories holding systemd's shared library, which is named after its
+/// version, e.g. `libsystemd-shared-252.so`.
+const SYSTEMD_LIBRARY_DIRS: &[&str] = &["usr/lib/systemd", "usr/lib64/systemd", "lib/systemd"];
+/// The script recording the dracut version, as `DRACUT_VERSION=<version>`.
+const DRACUT_VERSION_SCRIPTS: &[&str] = &["usr/lib/dracut/dracut-version.sh", "usr/share/dracut/dracut-version.sh"];
+
+/// The installed systemd and dracut, omitting those that are not installed
+/// or whose version could not be read.
+pub(crate) fn component_versions() -> Vec<ComponentVersion> {
+    let mut components = Vec::new();
+    components.extend(run_version(SYSTEMCTL).as_deref().and_then(parse_systemctl_version));
+    components.extend(run_version(DRACUT).as_deref().and_then(parse_dracut_version));
+    components
+}
+
+/// The systemd and dracut installed under `root`, found without running
+/// them.
+pub(crate) fn installed_component_versions(root: &Path) -> Vec<ComponentVersion> {
+    let systemd = SYSTEMD_LIBRARY_DIRS.iter()
+        .filter_map(|dir| fs::read_dir(root.join(dir)).ok())
+        .flat_map(|entries| entries.flatten())
+        .find_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let version = name.strip_prefix("libsystemd-shared-")?.strip_suffix(".so")?;
+            parse_systemctl_version(&format!("systemd {}", version))
+        });
+    let dracut = DRACUT_VERSION_SCRIPTS.iter()
+        .filter_map(|script| fs::read_to_string(root.join(script)).ok())
+        .find_map(|script| {
+            let version = script.lines().find_map(|line| line.trim().strip_prefix("DRACUT_VERSION="))?;
+            parse_dracut_version(&format!("dracut {}", version.trim_matches(['"', '\''])))
+        });
+    systemd.into_iter().chain(dracut).collect()
+}
+
+/// The standard output of `<program> --version`, if it ran successfully.
+fn run_version(program: &str) -> Option<String> {
+    let output = Command::new(program).arg("--version").output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Parse `systemctl --version`, whose first line is e.g.
+/// `systemd 252 (252-18.el9)`. The version is the upstream release number.
+fn parse_systemctl_version(output: &str) -> Option<ComponentVersion> {
+    let version = first_line_version(output, "systemd")?;
+    Some(ComponentVersion {
+        name: "systemd".to_string(),
+        version: version.split(['~', '-']).next().unwrap_or(version).to_string(),
+    })
+}
+
+/// Parse `dracut --version`, e.g. `dracut 057-53.git20231121.el9`. The
+/// distribution release after the dash is dropped, leaving `057`.
+fn parse_dracut_version(output: &str) -> Option<ComponentVersion> {
+    let version = first_line_version(output, "dracut")?;
+    Some(ComponentVersion {
+        name: "dracut".to_string(),
+        version: version.split('-').next().unwrap_or(version).to_string(),
+    })
+}
+
+/// The word after `name` on the first line of `output`.
+fn first_line_version<'a>(output: &'a str, name: &str) -> Option<&'a str> {
+    let mut words = output.lines().next()?.split_whitespace();
+    if words.next()? != name {
+        return None;
+    }
+    words.next().filter(|version| version.starts_with(|c: char| c.is_ascii_digit()))
+}
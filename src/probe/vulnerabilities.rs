@@ -0,0 +1,71 @@
+//! The kernel's assessment of the CPU's exposure to speculative execution
+//! and other hardware vulnerabilities, from the files under
+//! /sys/devices/system/cpu/vulnerabilities, one per vulnerability.
+
+use std::fs;
+use std::path::Path;
+
+/// Whether the CPU is affected by a vulnerability and, if so, whether the
+/// kernel mitigates it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VulnerabilityStatus {
+    /// The CPU is not affected, so mitigations only cost performance.
+    NotAffected,
+    /// The CPU is affected and the kernel mitigates it.
+    Mitigated,
+    /// The CPU is affected and not, or only partly, mitigated.
+    Vulnerable,
+    /// The kernel cannot tell, e.g. for lack of microcode information.
+    Unknown,
+}
+
+impl VulnerabilityStatus {
+    /// The status reported in a vulnerability file, such as `Not affected`,
+    /// `Mitigation: PTE Inversion` or `Vulnerable: Clear CPU buffers
+    /// attempted, no microcode`.
+    pub fn parse(text: &str) -> Self {
+        let text = text.trim();
+        if text.starts_with("Not affected") {
+            Self::NotAffected
+        } else if text.starts_with("Mitigation") {
+            Self::Mitigated
+        } else if text.starts_with("Vulnerable") {
+            Self::Vulnerable
+        } else {
+            Self::Unknown
+        }
+    }
+
+    /// The name of the status in `cpu-vuln:<vulnerability>:<status>` tags.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::NotAffected => "not-affected",
+            Self::Mitigated => "mitigated",
+            Self::Vulnerable => "vulnerable",
+            Self::Unknown => "unknown",
+        }
+    }
+
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        [Self::NotAffected, Self::Mitigated, Self::Vulnerable, Self::Unknown].into_iter()
+            .find(|status| status.name() == name)
+    }
+}
+
+/// The tags for the vulnerability files under `vulnerabilities_dir`, as
+/// `cpu-vuln:<vulnerability>:<status>`, e.g. `cpu-vuln:l1tf:not-affected`
+/// and `cpu-vuln:spectre_v2:mitigated`. Kernels too old to report
+/// vulnerabilities, and other architectures without them, yield no tags.
+pub(crate) fn vulnerability_tags(vulnerabilities_dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(vulnerabilities_dir) else {
+        return Vec::new();
+    };
+    let mut tags: Vec<String> = entries.flatten()
+        .filter_map(|entry| {
+            let status = VulnerabilityStatus::parse(&fs::read_to_string(entry.path()).ok()?);
+            Some(format!("cpu-vuln:{}:{}", entry.file_name().to_string_lossy(), status.name()))
+        })
+        .collect();
+    tags.sort();
+    tags
+}
@@ -0,0 +1,106 @@
+//! Named sets of parameters that serve a purpose together, such as
+//! debugging over a serial console, shipped in the database under
+//! `profiles/<name>/profile.toml` so they need not be copied between
+//! command lines by hand.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::compose::CommandLineBuilder;
+use crate::error::{BuildError, DatabaseError};
+use crate::parser::split_args;
+
+/// A named set of arguments. Arguments may contain `{variable}`
+/// placeholders, e.g. for the CPUs a profile dedicates, to be given values
+/// with [`with_variable`](Self::with_variable) before the profile is applied.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    pub description: String,
+    /// The arguments the profile adds, as written on a command line.
+    pub parameters: Vec<String>,
+    /// What each variable of the arguments stands for, by name.
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+    #[serde(skip)]
+    values: HashMap<String, String>,
+}
+
+impl Profile {
+    /// Every profile shipped in the database, by name.
+    pub fn builtin_profiles() -> Result<Vec<Profile>, DatabaseError> {
+        let documents: HashMap<&str, &str> = include!(concat!(env!("OUT_DIR"), "/compiled_profiles.rs"));
+        let mut profiles = documents.values()
+            .map(|document| Self::from_toml(document))
+            .collect::<Result<Vec<_>, _>>()?;
+        profiles.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(profiles)
+    }
+
+    /// The profile shipped in the database as `name`, if any.
+    pub fn builtin(name: &str) -> Result<Option<Profile>, DatabaseError> {
+        Ok(Self::builtin_profiles()?.into_iter().find(|profile| profile.name == name))
+    }
+
+    /// Read a profile document.
+    pub fn from_toml(document: &str) -> Result<Self, DatabaseError> {
+        let profile: Profile = toml::from_str(document)
+            .map_err(|e| DatabaseError::FormatError(format!("invalid profile: {}", e)))?;
+        for argument in &profile.parameters {
+            if let Some(variable) = placeholders(argument).find(|name| !profile.variables.contains_key(*name)) {
+                return Err(DatabaseError::FormatError(format!(
+                    "profile {} uses undeclared variable '{}' in '{}'", profile.name, variable, argument
+                )));
+            }
+        }
+        Ok(profile)
+    }
+
+    /// Read a profile document from a file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, DatabaseError> {
+        Self::from_toml(&fs::read_to_string(path)?)
+    }
+
+    /// Give the variable `name` a value.
+    pub fn with_variable(mut self, name: &str, value: &str) -> Self {
+        self.values.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// The arguments of the profile with its variables replaced by their
+    /// values, as names and values.
+    pub fn arguments(&self) -> Result<Vec<(String, Option<String>)>, BuildError> {
+        let mut arguments = Vec::new();
+        for argument in &self.parameters {
+            let mut expanded = argument.clone();
+            for variable in placeholders(argument) {
+                let value = self.values.get(variable).ok_or_else(|| {
+                    BuildError::MissingConfig(format!("{} for profile {}", variable, self.name))
+                })?;
+                expanded = expanded.replace(&format!("{{{}}}", variable), value);
+            }
+            arguments.extend(split_args(&expanded).iter().map(|arg| (arg.param.to_string(), arg.value.map(str::to_string))));
+        }
+        Ok(arguments)
+    }
+
+    /// Add the profile's arguments to `builder`. Fails without changing
+    /// the builder if a variable has no value, or if an argument would
+    /// conflict with, replace or be overridden by one already given.
+    pub fn apply(&self, builder: &mut CommandLineBuilder) -> Result<(), BuildError> {
+        let arguments = self.arguments()?;
+        builder.check_additions(&arguments)?;
+        for (name, value) in arguments {
+            builder.insert(&name, value);
+        }
+        Ok(())
+    }
+}
+
+/// The names of the `{variable}` placeholders in `argument`.
+fn placeholders(argument: &str) -> impl Iterator<Item = &str> {
+    argument.split('{').skip(1).filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+}
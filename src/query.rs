@@ -1,38 +1,76 @@
+use std::cmp::Ordering;
+use std::fmt;
+
 use regex::Regex;
-use crate::parameter::ParameterProcessor;
+use serde::{Deserialize, Serialize};
+use crate::parameter::{is_descriptive_selector, Parameter, ParameterProcessor, FLAG_SELECTOR_PREFIX};
+use crate::probe::SystemProbe;
+use crate::catalog::VersionInfo;
+use crate::error::QueryError;
+use crate::version::{compare_versions, KernelVersion};
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct QueryParameters {
     pub query_mode: QueryMode,
+    #[serde(with = "serde_regex", skip_serializing_if = "Option::is_none")]
     pub name: Option<Regex>,
+    /// Shell-style pattern for the parameter name, e.g. `rd.luks.*`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name_glob: Option<String>,
+    /// Words to search for in names, descriptions, formats and documentation
+    /// links. Results are ranked by relevance.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub processor: Option<ParameterProcessor>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub pci_ids: Vec<(u16, u16)>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub usb_ids: Vec<(u16, u16)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub arch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub applicable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub distribution: Option<DistributionQuery>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub deprecated: Option<bool>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub flags: Vec<String>,
+    /// Selector patterns such as `arch:x86_64` or `pci:8086:*`; each must
+    /// match a selector of the parameter.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub selectors: Vec<String>,
+    /// Only parameters applicable to a system with these probe tags.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub applicable_tags: Option<Vec<String>>,
+    /// Also match subparameters, such as `nomio` under `pci=`, rather than
+    /// only top-level parameters.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub include_subparameters: bool,
+    /// Only parameters first released in a mainline kernel later than this.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub introduced_after: Option<KernelVersion>,
+    /// Only parameters first released in a mainline kernel earlier than this.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub introduced_before: Option<KernelVersion>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum QueryMode {
+    #[default]
     And,
     Or,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DistributionQuery {
     pub id: String,
     pub version: Option<String>,
 }
 
-impl Default for QueryMode {
-    fn default() -> Self {
-        QueryMode::And
-    }
-}
-
 impl QueryParameters {
     pub fn new() -> Self {
         Self::default()
@@ -43,13 +81,727 @@ impl QueryParameters {
         Ok(self)
     }
 
+    pub fn with_name_glob(mut self, pattern: &str) -> Self {
+        self.name_glob = Some(pattern.to_string());
+        self
+    }
+
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.text = Some(text.to_string());
+        self
+    }
+
     pub fn with_processor(mut self, processor: ParameterProcessor) -> Self {
         self.processor = Some(processor);
         self
     }
 
+    /// Restrict to parameters introduced after `after` and before `before`,
+    /// e.g. the knobs added since a distribution's last kernel rebase.
+    pub fn introduced_between(mut self, after: KernelVersion, before: KernelVersion) -> Self {
+        self.introduced_after = Some(after);
+        self.introduced_before = Some(before);
+        self
+    }
+
+    pub fn with_selector(mut self, pattern: &str) -> Self {
+        self.selectors.push(pattern.to_string());
+        self
+    }
+
+    /// Restrict to parameters applicable to the system described by `probe`,
+    /// which need not be the system the query runs on.
+    pub fn applicable_to(mut self, probe: &SystemProbe) -> Self {
+        let tags = probe.hardware_tags.iter().chain(&probe.software_tags).cloned().collect();
+        self.applicable_tags = Some(tags);
+        self
+    }
+
+    pub fn with_subparameters(mut self) -> Self {
+        self.include_subparameters = true;
+        self
+    }
+
     pub fn applicable_only(mut self) -> Self {
         self.applicable = Some(true);
         self
     }
+
+    /// Lower the query to an expression with one condition per field the
+    /// caller set, combined according to `query_mode`. The hardware fields
+    /// (PCI ids, USB ids and architecture) always combine with AND, and
+    /// unless `include_subparameters` is set the result is restricted to
+    /// top-level parameters.
+    pub fn to_expr(&self) -> QueryExpr {
+        let expr = self.filter_expr();
+        if self.include_subparameters {
+            return expr;
+        }
+        QueryExpr::from(QueryCondition::Subparameter(false)).and(expr)
+    }
+
+    /// The caller's conditions alone, applying to parameters and
+    /// subparameters alike.
+    pub fn filter_expr(&self) -> QueryExpr {
+        let mut conditions: Vec<QueryExpr> = Vec::new();
+        let mut push = |condition: QueryCondition| conditions.push(condition.into());
+
+        if let Some(regex) = &self.name {
+            push(QueryCondition::Name(regex.clone()));
+        }
+        if let Some(glob) = &self.name_glob {
+            push(QueryCondition::NameGlob(glob.clone()));
+        }
+        if let Some(processor) = &self.processor {
+            push(QueryCondition::Processor(processor.clone()));
+        }
+
+        let mut hardware: Vec<QueryExpr> = Vec::new();
+        if !self.pci_ids.is_empty() {
+            hardware.push(QueryCondition::PciIds(self.pci_ids.clone()).into());
+        }
+        if !self.usb_ids.is_empty() {
+            hardware.push(QueryCondition::UsbIds(self.usb_ids.clone()).into());
+        }
+        if let Some(arch) = &self.arch {
+            hardware.push(QueryCondition::Arch(arch.clone()).into());
+        }
+        match hardware.len() {
+            0 => {}
+            1 => conditions.extend(hardware),
+            _ => conditions.push(QueryExpr::And(hardware)),
+        }
+
+        let mut push = |condition: QueryCondition| conditions.push(condition.into());
+        if let Some(applicable) = self.applicable {
+            push(QueryCondition::Applicable(applicable));
+        }
+        if let Some(distribution) = &self.distribution {
+            push(QueryCondition::Distribution(distribution.clone()));
+        }
+        if let Some(deprecated) = self.deprecated {
+            push(QueryCondition::Deprecated(deprecated));
+        }
+        if !self.flags.is_empty() {
+            push(QueryCondition::Flags(self.flags.clone()));
+        }
+        for pattern in &self.selectors {
+            push(QueryCondition::Selector(pattern.clone()));
+        }
+        if let Some(tags) = &self.applicable_tags {
+            push(QueryCondition::ApplicableTo(tags.clone()));
+        }
+        if let Some(version) = &self.introduced_after {
+            push(QueryCondition::IntroducedAfter(version.clone()));
+        }
+        if let Some(version) = &self.introduced_before {
+            push(QueryCondition::IntroducedBefore(version.clone()));
+        }
+        if let Some(text) = &self.text {
+            push(QueryCondition::Text(text.clone()));
+        }
+
+        match self.query_mode {
+            _ if conditions.is_empty() => QueryExpr::all(),
+            QueryMode::And => QueryExpr::And(conditions),
+            QueryMode::Or => QueryExpr::Or(conditions),
+        }
+    }
+}
+
+/// Version of the serialized form of saved queries and query results.
+/// Readers accept documents up to this version; fields added in later
+/// versions of the same major schema are optional with defaults.
+pub const QUERY_SCHEMA_VERSION: u32 = 1;
+
+/// (De)serialize an optional regex through its pattern string.
+mod serde_regex {
+    use regex::Regex;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(regex: &Option<Regex>, serializer: S) -> Result<S::Ok, S::Error> {
+        match regex {
+            Some(regex) => serializer.serialize_some(regex.as_str()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Regex>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|pattern| Regex::new(&pattern).map_err(D::Error::custom))
+            .transpose()
+    }
+}
+
+fn check_schema_version(version: u32) -> Result<(), QueryError> {
+    if version > QUERY_SCHEMA_VERSION {
+        return Err(QueryError::UnsupportedSchemaVersion {
+            found: version,
+            supported: QUERY_SCHEMA_VERSION,
+        });
+    }
+    Ok(())
+}
+
+/// A query stored for later use, e.g. in a configuration file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedQuery {
+    pub schema_version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub query: QueryParameters,
+}
+
+impl SavedQuery {
+    pub fn new(query: QueryParameters) -> Self {
+        Self {
+            schema_version: QUERY_SCHEMA_VERSION,
+            name: None,
+            query,
+        }
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn to_json(&self) -> Result<String, QueryError> {
+        serde_json::to_string_pretty(self).map_err(|e| QueryError::SerializationError(e.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, QueryError> {
+        let saved: Self = serde_json::from_str(json).map_err(|e| QueryError::SerializationError(e.to_string()))?;
+        check_schema_version(saved.schema_version)?;
+        Ok(saved)
+    }
+
+    pub fn to_toml(&self) -> Result<String, QueryError> {
+        toml::to_string(self).map_err(|e| QueryError::SerializationError(e.to_string()))
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, QueryError> {
+        let saved: Self = toml::from_str(text).map_err(|e| QueryError::SerializationError(e.to_string()))?;
+        check_schema_version(saved.schema_version)?;
+        Ok(saved)
+    }
+}
+
+/// The parameters matching a query, in result order, for returning from
+/// services.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryResults {
+    pub schema_version: u32,
+    pub query: QueryParameters,
+    pub parameters: Vec<Parameter>,
+}
+
+impl QueryResults {
+    pub fn new(query: QueryParameters, parameters: Vec<Parameter>) -> Self {
+        Self {
+            schema_version: QUERY_SCHEMA_VERSION,
+            query,
+            parameters,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, QueryError> {
+        serde_json::to_string_pretty(self).map_err(|e| QueryError::SerializationError(e.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, QueryError> {
+        let results: Self = serde_json::from_str(json).map_err(|e| QueryError::SerializationError(e.to_string()))?;
+        check_schema_version(results.schema_version)?;
+        Ok(results)
+    }
+}
+
+/// A single test against a parameter definition, the leaf of a QueryExpr.
+#[derive(Debug, Clone)]
+pub enum QueryCondition {
+    Name(Regex),
+    NameGlob(String),
+    /// Every word occurs in the name, description, format or documentation.
+    Text(String),
+    Processor(ParameterProcessor),
+    PciIds(Vec<(u16, u16)>),
+    UsbIds(Vec<(u16, u16)>),
+    Arch(String),
+    Applicable(bool),
+    Distribution(DistributionQuery),
+    Deprecated(bool),
+    Flags(Vec<String>),
+    /// The parameter has a selector matching the pattern.
+    Selector(String),
+    /// The parameter's selectors are satisfied by the tags.
+    ApplicableTo(Vec<String>),
+    IntroducedAfter(KernelVersion),
+    IntroducedBefore(KernelVersion),
+    /// The parameter is (or is not) a subparameter of another.
+    Subparameter(bool),
+}
+
+/// A boolean combination of query conditions, e.g.
+/// `(processor=dracut OR processor=systemd) AND NOT deprecated`.
+///
+/// An empty `And` matches every parameter and an empty `Or` none.
+#[derive(Debug, Clone)]
+pub enum QueryExpr {
+    And(Vec<QueryExpr>),
+    Or(Vec<QueryExpr>),
+    Not(Box<QueryExpr>),
+    Leaf(QueryCondition),
+}
+
+impl QueryExpr {
+    pub fn all() -> Self {
+        QueryExpr::And(Vec::new())
+    }
+
+    pub fn and(self, other: QueryExpr) -> Self {
+        match self {
+            QueryExpr::And(mut exprs) => {
+                exprs.push(other);
+                QueryExpr::And(exprs)
+            }
+            expr => QueryExpr::And(vec![expr, other]),
+        }
+    }
+
+    pub fn or(self, other: QueryExpr) -> Self {
+        match self {
+            QueryExpr::Or(mut exprs) => {
+                exprs.push(other);
+                QueryExpr::Or(exprs)
+            }
+            expr => QueryExpr::Or(vec![expr, other]),
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Self {
+        QueryExpr::Not(Box::new(self))
+    }
+
+    /// Every condition in the expression, depth first.
+    pub fn conditions(&self) -> Vec<&QueryCondition> {
+        match self {
+            QueryExpr::And(exprs) | QueryExpr::Or(exprs) => {
+                exprs.iter().flat_map(|expr| expr.conditions()).collect()
+            }
+            QueryExpr::Not(expr) => expr.conditions(),
+            QueryExpr::Leaf(condition) => vec![condition],
+        }
+    }
+
+    /// Evaluate the expression, deciding each leaf with `leaf`.
+    pub fn evaluate(&self, leaf: &mut impl FnMut(&QueryCondition) -> bool) -> bool {
+        match self {
+            QueryExpr::And(exprs) => exprs.iter().all(|expr| expr.evaluate(leaf)),
+            QueryExpr::Or(exprs) => exprs.iter().any(|expr| expr.evaluate(leaf)),
+            QueryExpr::Not(expr) => !expr.evaluate(leaf),
+            QueryExpr::Leaf(condition) => leaf(condition),
+        }
+    }
+}
+
+impl From<QueryCondition> for QueryExpr {
+    fn from(condition: QueryCondition) -> Self {
+        QueryExpr::Leaf(condition)
+    }
+}
+
+impl QueryCondition {
+    /// Whether `param`, with its version history if known, satisfies the
+    /// condition. Text and subparameter conditions need the catalog and
+    /// always return false here; version conditions never match parameters
+    /// whose mainline release is unknown.
+    pub fn matches(&self, param: &Parameter, probe: &SystemProbe, versions: Option<&VersionInfo>) -> bool {
+        let introduced = || versions.and_then(VersionInfo::introduced_version);
+        match self {
+            QueryCondition::Name(regex) => regex.is_match(&param.name),
+            QueryCondition::NameGlob(glob) => glob_match(glob, &param.name),
+            QueryCondition::Text(_) => false,
+            QueryCondition::Processor(processor) => processor_matches(param, processor),
+            QueryCondition::PciIds(ids) => ids_match(param, "pci:", ids),
+            QueryCondition::UsbIds(ids) => ids_match(param, "usb:", ids),
+            QueryCondition::Arch(arch) => arch_matches(param, arch),
+            QueryCondition::Applicable(applicable) => param.is_applicable(probe) == *applicable,
+            QueryCondition::Distribution(distribution) => param.is_available_in_distribution(
+                &distribution.id,
+                distribution.version.as_deref().unwrap_or(""),
+            ),
+            QueryCondition::Deprecated(deprecated) => param.deprecated == *deprecated,
+            QueryCondition::Flags(flags) => flags_match(param, flags),
+            QueryCondition::Selector(pattern) => param.has_selector(pattern),
+            QueryCondition::ApplicableTo(tags) => param.is_applicable_to_tags(tags),
+            QueryCondition::IntroducedAfter(version) => introduced().is_some_and(|v| v > *version),
+            QueryCondition::IntroducedBefore(version) => introduced().is_some_and(|v| v < *version),
+            QueryCondition::Subparameter(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for QueryCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ids = |ids: &[(u16, u16)]| {
+            ids.iter().map(|(v, d)| format!("{:04x}:{:04x}", v, d)).collect::<Vec<_>>().join(", ")
+        };
+        match self {
+            QueryCondition::Name(regex) => write!(f, "name matches /{}/", regex.as_str()),
+            QueryCondition::NameGlob(glob) => write!(f, "name matches '{}'", glob),
+            QueryCondition::Text(text) => write!(f, "text contains '{}'", text),
+            QueryCondition::Processor(processor) => write!(f, "processor is {:?}", processor),
+            QueryCondition::PciIds(pci_ids) => write!(f, "PCI device in [{}]", ids(pci_ids)),
+            QueryCondition::UsbIds(usb_ids) => write!(f, "USB device in [{}]", ids(usb_ids)),
+            QueryCondition::Arch(arch) => write!(f, "architecture is {}", arch),
+            QueryCondition::Applicable(applicable) => write!(f, "applicable to this system is {}", applicable),
+            QueryCondition::Distribution(distribution) => match &distribution.version {
+                Some(version) => write!(f, "available in {} {}", distribution.id, version),
+                None => write!(f, "available in {}", distribution.id),
+            },
+            QueryCondition::Deprecated(deprecated) => write!(f, "deprecated is {}", deprecated),
+            QueryCondition::Flags(flags) => write!(f, "has flags [{}]", flags.join(", ")),
+            QueryCondition::Selector(pattern) => write!(f, "has selector '{}'", pattern),
+            QueryCondition::ApplicableTo(tags) => write!(f, "applicable to tags [{}]", tags.join(", ")),
+            QueryCondition::IntroducedAfter(version) => write!(f, "introduced after {}", version),
+            QueryCondition::IntroducedBefore(version) => write!(f, "introduced before {}", version),
+            QueryCondition::Subparameter(true) => write!(f, "is a subparameter"),
+            QueryCondition::Subparameter(false) => write!(f, "is a top-level parameter"),
+        }
+    }
+}
+
+/// The outcome of one condition of a query for one parameter.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConditionExplanation {
+    pub condition: String,
+    pub matched: bool,
+    pub reason: String,
+}
+
+/// Why a parameter was or was not returned by a query: the overall result
+/// and the outcome of every condition, including those a short-circuiting
+/// evaluation would have skipped.
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryExplanation {
+    pub parameter: String,
+    pub matched: bool,
+    pub conditions: Vec<ConditionExplanation>,
+}
+
+impl fmt::Display for QueryExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}: {}", self.parameter, if self.matched { "matched" } else { "not matched" })?;
+        for condition in &self.conditions {
+            let mark = if condition.matched { '+' } else { '-' };
+            writeln!(f, "  {} {}: {}", mark, condition.condition, condition.reason)?;
+        }
+        Ok(())
+    }
+}
+
+impl QueryCondition {
+    /// Explain the outcome of `matches` for `param`. Text and subparameter
+    /// conditions are explained by the caller, which has the catalog.
+    pub fn explain(&self, param: &Parameter, probe: &SystemProbe, versions: Option<&VersionInfo>) -> ConditionExplanation {
+        let matched = self.matches(param, probe, versions);
+        let selectors = |prefix: &str| param.selector_values(prefix).collect::<Vec<_>>().join(", ");
+        let reason = match self {
+            QueryCondition::Name(_) | QueryCondition::NameGlob(_) => {
+                format!("name '{}' {}", param.name, if matched { "matches" } else { "does not match" })
+            }
+            QueryCondition::Text(_) | QueryCondition::Subparameter(_) => String::new(),
+            QueryCondition::Processor(_) => format!("parameter processor is {:?}", param.processor),
+            QueryCondition::PciIds(_) | QueryCondition::UsbIds(_) => {
+                let prefix = if matches!(self, QueryCondition::PciIds(_)) { "pci:" } else { "usb:" };
+                match param.selector_values(prefix).next() {
+                    None => format!("parameter has no {} selectors", prefix),
+                    Some(_) if matched => format!("{} selectors [{}] include a queried device", prefix, selectors(prefix)),
+                    Some(_) => format!("{} selectors [{}] match no queried device", prefix, selectors(prefix)),
+                }
+            }
+            QueryCondition::Arch(_) => match param.selector_values("arch:").next() {
+                None => "parameter has no arch: selectors and applies to every architecture".to_string(),
+                Some(_) => format!("arch: selectors are [{}]", selectors("arch:")),
+            },
+            QueryCondition::Applicable(_) => {
+                let tags: Vec<&String> = probe.hardware_tags.iter().chain(&probe.software_tags).collect();
+                explain_applicability(param, &tags)
+            }
+            QueryCondition::ApplicableTo(tags) => explain_applicability(param, &tags.iter().collect::<Vec<_>>()),
+            QueryCondition::Distribution(distribution) => match param.distributions.get(&distribution.id) {
+                _ if param.distributions.is_empty() => "parameter has no distribution data".to_string(),
+                None => format!("parameter lists no support for {}", distribution.id),
+                Some(support) => format!(
+                    "{} supports it from {} to {}",
+                    distribution.id,
+                    support.min_version.as_deref().unwrap_or("any release"),
+                    support.max_version.as_deref().unwrap_or("the latest release"),
+                ),
+            },
+            QueryCondition::Deprecated(_) => {
+                format!("parameter is {}", if param.deprecated { "deprecated" } else { "not deprecated" })
+            }
+            QueryCondition::Flags(_) => format!("parameter flags are [{}]", selectors(FLAG_SELECTOR_PREFIX)),
+            QueryCondition::Selector(pattern) => {
+                match param.selectors.iter().find(|selector| glob_match(pattern, selector)) {
+                    Some(selector) => format!("selector {} matched", selector),
+                    None => format!("no selector matches among [{}]", param.selectors.join(", ")),
+                }
+            }
+            QueryCondition::IntroducedAfter(_) | QueryCondition::IntroducedBefore(_) => {
+                match versions.and_then(VersionInfo::introduced_version) {
+                    Some(version) => format!("introduced in {}", version),
+                    None => "mainline release is unknown".to_string(),
+                }
+            }
+        };
+
+        ConditionExplanation {
+            condition: self.to_string(),
+            matched,
+            reason,
+        }
+    }
+}
+
+/// Describe, per selector, which tag satisfied it, e.g.
+/// "selector pci:10de:* matched probe tag pci:10de:2204".
+fn explain_applicability(param: &Parameter, tags: &[&String]) -> String {
+    let reasons: Vec<String> = param.selectors.iter()
+        .filter(|selector| !is_descriptive_selector(selector))
+        .map(|selector| match tags.iter().find(|tag| glob_match(selector, tag)) {
+            Some(tag) => format!("selector {} matched probe tag {}", selector, tag),
+            None => format!("selector {} matched no probe tag", selector),
+        })
+        .collect();
+
+    if reasons.is_empty() {
+        "parameter has no selectors and applies everywhere".to_string()
+    } else {
+        reasons.join("; ")
+    }
+}
+
+/// Systemd and dracut parameters match a queried component version when
+/// they were introduced in or before it; an empty version matches any.
+pub(crate) fn processor_matches(param: &Parameter, wanted: &ParameterProcessor) -> bool {
+    let introduced_by = |min_version: &str, version: &str| {
+        min_version.is_empty()
+            || version.is_empty()
+            || compare_versions(min_version, version) != Ordering::Greater
+    };
+    match (&param.processor, wanted) {
+        (ParameterProcessor::Systemd { min_version }, ParameterProcessor::Systemd { min_version: version })
+        | (ParameterProcessor::Dracut { min_version }, ParameterProcessor::Dracut { min_version: version }) => {
+            introduced_by(min_version, version)
+        }
+        (actual, wanted) => actual == wanted,
+    }
+}
+
+/// PCI and USB ids match `pci:<vendor>[:<device>]` and
+/// `usb:<vendor>[:<product>]` selectors, where an omitted or `*` device
+/// matches any device of the vendor.
+pub(crate) fn ids_match(param: &Parameter, prefix: &str, ids: &[(u16, u16)]) -> bool {
+    param.selector_values(prefix).any(|selector| {
+        let (vendor, device) = match selector.split_once(':') {
+            Some((vendor, device)) => (vendor, device),
+            None => (selector, "*"),
+        };
+        let Ok(vendor) = u16::from_str_radix(vendor, 16) else {
+            return false;
+        };
+        ids.iter().any(|&(v, d)| {
+            v == vendor && (device == "*" || u16::from_str_radix(device, 16) == Ok(d))
+        })
+    })
+}
+
+/// Parameters without `arch:` selectors match any architecture.
+pub(crate) fn arch_matches(param: &Parameter, arch: &str) -> bool {
+    let mut arches = param.selector_values("arch:").peekable();
+    arches.peek().is_none() || arches.any(|a| a == arch)
+}
+
+/// Every flag must be present as a `flag:` selector; flags are compared
+/// case-insensitively.
+pub(crate) fn flags_match(param: &Parameter, flags: &[String]) -> bool {
+    flags.iter().all(|flag| {
+        param.selector_values(FLAG_SELECTOR_PREFIX).any(|f| f.eq_ignore_ascii_case(flag))
+    })
+}
+
+/// Match `text` against a shell-style glob: `*` matches any run of
+/// characters, `?` any single character, `[...]` a character class (with
+/// ranges and `!` or `^` negation) and a backslash escapes the next character.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position after the last '*' and the text position it is matched up to
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        let step = match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p + 1, t));
+                p += 1;
+                continue;
+            }
+            Some('?') => Some(p + 1),
+            Some('[') => match_class(&pattern, p, text[t]),
+            Some('\\') if p + 1 < pattern.len() => (pattern[p + 1] == text[t]).then_some(p + 2),
+            Some(&c) => (c == text[t]).then_some(p + 1),
+            None => None,
+        };
+
+        match (step, backtrack) {
+            (Some(next), _) => {
+                p = next;
+                t += 1;
+            }
+            (None, Some((star, matched))) => {
+                p = star;
+                t = matched + 1;
+                backtrack = Some((star, matched + 1));
+            }
+            (None, None) => return false,
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Match `c` against the class starting at `pattern[start]`, returning the
+/// position after the class on a match. An unterminated class matches a
+/// literal '['.
+fn match_class(pattern: &[char], start: usize, c: char) -> Option<usize> {
+    let mut i = start + 1;
+    let negated = matches!(pattern.get(i), Some('!' | '^'));
+    if negated {
+        i += 1;
+    }
+
+    let mut matched = false;
+    let mut first = true;
+    while i < pattern.len() && (first || pattern[i] != ']') {
+        first = false;
+        let low = pattern[i];
+        if pattern.get(i + 1) == Some(&'-') && pattern.get(i + 2).is_some_and(|&h| h != ']') {
+            matched |= (low..=pattern[i + 2]).contains(&c);
+            i += 3;
+        } else {
+            matched |= low == c;
+            i += 1;
+        }
+    }
+
+    if i >= pattern.len() {
+        return (c == '[').then_some(start + 1);
+    }
+    (matched != negated).then_some(i + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::{CatalogBuilder, ParameterCatalog};
+
+    fn catalog() -> ParameterCatalog {
+        let mut deprecated = Parameter::new("noexec", ParameterProcessor::Kernel);
+        deprecated.deprecated = true;
+        CatalogBuilder::new()
+            .add_parameter(Parameter::new("quiet", ParameterProcessor::Kernel))
+            .add_parameter(Parameter::new("rd.luks.uuid", ParameterProcessor::Dracut { min_version: String::new() }))
+            .add_parameter(Parameter::new("rd.debug", ParameterProcessor::Dracut { min_version: String::new() }))
+            .add_parameter(deprecated)
+            .add_parameter(Parameter::new("pci", ParameterProcessor::Kernel))
+            .add_subparameter("pci", Parameter::new("rd.nomio", ParameterProcessor::Kernel))
+            .build()
+            .unwrap()
+    }
+
+    fn names(query: &QueryParameters) -> Vec<String> {
+        let probe = SystemProbe::builder().build();
+        let mut names: Vec<String> = catalog().query_parameters_on(query, &probe)
+            .into_iter()
+            .map(|param| param.name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn query(mode: QueryMode) -> QueryParameters {
+        QueryParameters {
+            query_mode: mode,
+            name_glob: Some("rd.*".to_string()),
+            deprecated: Some(true),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn and_mode_requires_every_condition() {
+        assert!(names(&query(QueryMode::And)).is_empty());
+        let not_deprecated = QueryParameters { deprecated: Some(false), ..query(QueryMode::And) };
+        assert_eq!(names(&not_deprecated), ["rd.debug", "rd.luks.uuid"]);
+    }
+
+    #[test]
+    fn or_mode_requires_any_condition() {
+        assert_eq!(names(&query(QueryMode::Or)), ["noexec", "rd.debug", "rd.luks.uuid"]);
+    }
+
+    #[test]
+    fn or_mode_matches_subparameters_when_asked() {
+        let query = QueryParameters { include_subparameters: true, ..query(QueryMode::Or) };
+        assert_eq!(names(&query), ["noexec", "rd.debug", "rd.luks.uuid", "rd.nomio"]);
+    }
+
+    #[test]
+    fn hardware_conditions_combine_with_and_in_or_mode() {
+        let query = QueryParameters {
+            query_mode: QueryMode::Or,
+            pci_ids: vec![(0x8086, 0x1234)],
+            arch: Some("x86_64".to_string()),
+            ..Default::default()
+        };
+        let QueryExpr::Or(conditions) = query.filter_expr() else {
+            panic!("expected an OR expression");
+        };
+        assert!(matches!(conditions.as_slice(), [QueryExpr::And(hardware)] if hardware.len() == 2));
+    }
+
+    #[test]
+    fn empty_query_matches_top_level_parameters() {
+        for mode in [QueryMode::And, QueryMode::Or] {
+            let query = QueryParameters { query_mode: mode, ..Default::default() };
+            assert_eq!(names(&query), ["noexec", "pci", "quiet", "rd.debug", "rd.luks.uuid"]);
+        }
+    }
+
+    #[test]
+    fn queries_without_a_probe() {
+        let catalog = catalog();
+        let mut names: Vec<&str> = catalog.query_parameters(&query(QueryMode::Or).with_name_glob("rd.l*"))
+            .into_iter()
+            .map(|param| param.name.as_str())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["noexec", "rd.luks.uuid"]);
+    }
+
+    #[test]
+    fn checks_single_conditions() {
+        let kcmdline = crate::KCmdline::new(catalog(), SystemProbe::builder().build());
+        let noexec = kcmdline.catalog().get_parameter("noexec").unwrap();
+        let query = query(QueryMode::And);
+        assert!(!kcmdline.check_name_condition(noexec, &query));
+        assert!(kcmdline.check_deprecated_condition(noexec, &query));
+        assert!(kcmdline.check_processor_condition(noexec, &query.clone().with_processor(ParameterProcessor::Kernel)));
+        assert!(kcmdline.check_hardware_condition(noexec, &query));
+        assert!(kcmdline.check_flags_condition(noexec, &query));
+    }
 }
@@ -0,0 +1,473 @@
+//! A parameter source fetched from a URL, so that distributions can publish
+//! database updates more often than library releases.
+//!
+//! The URL serves a JSON bundle written by
+//! [`FilesystemDatabase::write_bundle`](crate::FilesystemDatabase::write_bundle),
+//! optionally with a detached signature at `<url>.sig`. Both are cached
+//! under `$XDG_CACHE_HOME/kcmdline/remote`, revalidated with `ETag` and
+//! `Last-Modified`, and the cached copy is used when the URL cannot be
+//! fetched.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::database::{ParameterDefinitionRaw, ParameterSource, ParameterVersionsRaw, BUNDLE_SCHEMA_VERSION};
+use crate::error::{DatabaseError, DocumentContext, SourceKind};
+use crate::schema;
+use crate::snapshot::checksum;
+use crate::trust::SignatureVerifier;
+
+const BUNDLE_FILE: &str = "bundle.json";
+const SIGNATURE_FILE: &str = "bundle.json.sig";
+const METADATA_FILE: &str = "metadata.json";
+
+/// A conditional GET of a URL.
+#[derive(Debug, Clone, Copy)]
+pub struct FetchRequest<'a> {
+    pub url: &'a str,
+    /// `ETag` of the cached copy, sent as `If-None-Match`.
+    pub etag: Option<&'a str>,
+    /// `Last-Modified` of the cached copy, sent as `If-Modified-Since`.
+    pub last_modified: Option<&'a str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FetchResponse {
+    /// The cached copy is current (HTTP 304).
+    NotModified,
+    Body {
+        body: Vec<u8>,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    },
+}
+
+/// Performs HTTP requests for a [`RemoteDatabase`]. The built-in
+/// [`UreqFetcher`] speaks HTTP and HTTPS; other implementations can route
+/// requests through a client the application already uses.
+pub trait HttpFetcher {
+    /// Fetch the URL, returning an error for anything but success or
+    /// "not modified".
+    fn fetch(&self, request: &FetchRequest<'_>) -> Result<FetchResponse, String>;
+}
+
+/// An HTTP and HTTPS client built on `ureq`, verifying servers against the
+/// Mozilla root certificates and following up to five redirects. Bodies
+/// larger than [`DEFAULT_MAX_RESPONSE_SIZE`] are refused.
+#[derive(Debug, Clone)]
+pub struct UreqFetcher {
+    timeout: Duration,
+    max_size: u64,
+}
+
+/// The time allowed for connecting, and for a whole request, by default.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The largest response body accepted by default.
+pub const DEFAULT_MAX_RESPONSE_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Redirects followed before a request fails.
+const MAX_REDIRECTS: u32 = 5;
+
+impl Default for UreqFetcher {
+    fn default() -> Self {
+        Self {
+            timeout: DEFAULT_TIMEOUT,
+            max_size: DEFAULT_MAX_RESPONSE_SIZE,
+        }
+    }
+}
+
+impl UreqFetcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Give up on connecting, or on the whole request, after `timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Refuse response bodies larger than `max_size` bytes.
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    fn agent(&self) -> ureq::Agent {
+        ureq::AgentBuilder::new()
+            .timeout_connect(self.timeout)
+            .timeout(self.timeout)
+            .redirects(MAX_REDIRECTS)
+            .user_agent(concat!("libkcmdline/", env!("CARGO_PKG_VERSION")))
+            .build()
+    }
+}
+
+impl HttpFetcher for UreqFetcher {
+    fn fetch(&self, request: &FetchRequest<'_>) -> Result<FetchResponse, String> {
+        let mut get = self.agent().get(request.url);
+        if let Some(etag) = request.etag {
+            get = get.set("If-None-Match", etag);
+        }
+        if let Some(last_modified) = request.last_modified {
+            get = get.set("If-Modified-Since", last_modified);
+        }
+
+        let response = match get.call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(code, response)) => {
+                return Err(format!("HTTP error: {} {}", code, response.status_text()));
+            }
+            Err(e) => return Err(e.to_string()),
+        };
+        if response.status() == 304 {
+            return Ok(FetchResponse::NotModified);
+        }
+        if !(200..300).contains(&response.status()) {
+            return Err(format!("HTTP error: {} {}", response.status(), response.status_text()));
+        }
+
+        let too_large = || format!("response from {} is larger than {} bytes", request.url, self.max_size);
+        if response.header("content-length").and_then(|length| length.parse::<u64>().ok()).is_some_and(|length| length > self.max_size) {
+            return Err(too_large());
+        }
+        let etag = response.header("etag").map(str::to_string);
+        let last_modified = response.header("last-modified").map(str::to_string);
+
+        let mut body = Vec::new();
+        response.into_reader()
+            .take(self.max_size + 1)
+            .read_to_end(&mut body)
+            .map_err(|e| e.to_string())?;
+        if body.len() as u64 > self.max_size {
+            return Err(too_large());
+        }
+        Ok(FetchResponse::Body { body, etag, last_modified })
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CacheMetadata {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+/// A bundle written by [`FilesystemDatabase::write_bundle`]; its schema
+/// version is checked before it is deserialized.
+///
+/// [`FilesystemDatabase::write_bundle`]: crate::FilesystemDatabase::write_bundle
+#[derive(Deserialize)]
+struct Bundle {
+    parameters: BTreeMap<String, BundleEntry>,
+}
+
+#[derive(Deserialize)]
+struct BundleEntry {
+    definition: Option<serde_json::Value>,
+    versions: Option<serde_json::Value>,
+}
+
+/// The default cache directory, `$XDG_CACHE_HOME/kcmdline/remote` or
+/// `~/.cache/kcmdline/remote`.
+pub fn default_cache_dir() -> Option<PathBuf> {
+    crate::database::user_cache_dir().map(|cache| cache.join("remote"))
+}
+
+/// A database bundle fetched from a URL.
+pub struct RemoteDatabase {
+    url: String,
+    bundle: Bundle,
+    content: Vec<u8>,
+    signature: Option<Vec<u8>>,
+    stale: bool,
+}
+
+impl RemoteDatabase {
+    /// Fetch an `http://` or `https://` URL with [`UreqFetcher`], caching
+    /// it in [`default_cache_dir`].
+    pub fn fetch(url: &str) -> Result<Self, DatabaseError> {
+        Self::fetch_with(url, &UreqFetcher::new(), default_cache_dir().as_deref())
+    }
+
+    /// Fetch a URL with `fetcher`, revalidating and updating the copy cached
+    /// in `cache_dir`, or falling back to it if the fetch fails.
+    pub fn fetch_with<F: HttpFetcher + ?Sized>(
+        url: &str,
+        fetcher: &F,
+        cache_dir: Option<&Path>,
+    ) -> Result<Self, DatabaseError> {
+        let cache = cache_dir.map(|dir| dir.join(format!("{:016x}", checksum(url.as_bytes()))));
+        let cached = cache.as_deref().and_then(Self::read_cache);
+
+        let metadata = cached.as_ref().map(|(metadata, _, _)| metadata);
+        let request = FetchRequest {
+            url,
+            etag: metadata.and_then(|m| m.etag.as_deref()),
+            last_modified: metadata.and_then(|m| m.last_modified.as_deref()),
+        };
+
+        let (content, signature, stale) = match (fetcher.fetch(&request), cached) {
+            (Ok(FetchResponse::Body { body, etag, last_modified }), _) => {
+                let signature_url = format!("{}.sig", url);
+                let signature = match fetcher.fetch(&FetchRequest { url: &signature_url, etag: None, last_modified: None }) {
+                    Ok(FetchResponse::Body { body, .. }) => Some(body),
+                    _ => None,
+                };
+                // Parse before caching so that a broken upload does not
+                // replace a working cached copy
+                Self::parse_bundle(url, &body)?;
+                if let Some(cache) = &cache {
+                    let metadata = CacheMetadata { url: url.to_string(), etag, last_modified };
+                    // The cache is an optimisation; failing to update it is
+                    // not an error
+                    let _ = Self::write_cache(cache, &metadata, &body, signature.as_deref());
+                }
+                (body, signature, false)
+            }
+            (Ok(FetchResponse::NotModified), Some((_, content, signature))) => (content, signature, false),
+            (Ok(FetchResponse::NotModified), None) => {
+                return Err(DatabaseError::LoadError(format!("{} not modified, but nothing is cached", url)));
+            }
+            (Err(_), Some((_, content, signature))) => (content, signature, true),
+            (Err(e), None) => {
+                return Err(DatabaseError::LoadError(format!("Failed to fetch {}: {}", url, e)));
+            }
+        };
+
+        Ok(Self {
+            url: url.to_string(),
+            bundle: Self::parse_bundle(url, &content)?,
+            content,
+            signature,
+            stale,
+        })
+    }
+
+    /// Whether the URL could not be fetched and the cached copy is used.
+    pub fn is_stale(&self) -> bool {
+        self.stale
+    }
+
+    fn parse_bundle(url: &str, content: &[u8]) -> Result<Bundle, DatabaseError> {
+        let format_error = |e: String| DatabaseError::FormatError(format!("Invalid bundle from {}: {}", url, e));
+        let value: serde_json::Value = serde_json::from_slice(content).map_err(|e| format_error(e.to_string()))?;
+        let version = value.get("schema_version").and_then(|v| v.as_u64());
+        if version != Some(u64::from(BUNDLE_SCHEMA_VERSION)) {
+            return Err(format_error(format!(
+                "unsupported schema version {:?} (supported {})", version, BUNDLE_SCHEMA_VERSION
+            )));
+        }
+        serde_json::from_value(value).map_err(|e| format_error(e.to_string()))
+    }
+
+    fn context(&self, name: &str) -> DocumentContext {
+        DocumentContext::new(SourceKind::Remote)
+            .with_path(self.url.clone())
+            .with_parameter(name)
+    }
+
+    fn read_cache(dir: &Path) -> Option<(CacheMetadata, Vec<u8>, Option<Vec<u8>>)> {
+        let metadata = serde_json::from_slice(&fs::read(dir.join(METADATA_FILE)).ok()?).ok()?;
+        let content = fs::read(dir.join(BUNDLE_FILE)).ok()?;
+        let signature = fs::read(dir.join(SIGNATURE_FILE)).ok();
+        Some((metadata, content, signature))
+    }
+
+    fn write_cache(
+        dir: &Path,
+        metadata: &CacheMetadata,
+        content: &[u8],
+        signature: Option<&[u8]>,
+    ) -> Result<(), DatabaseError> {
+        fs::create_dir_all(dir)?;
+        fs::write(dir.join(BUNDLE_FILE), content)?;
+        match signature {
+            Some(signature) => fs::write(dir.join(SIGNATURE_FILE), signature)?,
+            None => {
+                let _ = fs::remove_file(dir.join(SIGNATURE_FILE));
+            }
+        }
+        let metadata = serde_json::to_vec(metadata).map_err(|e| DatabaseError::LoadError(e.to_string()))?;
+        fs::write(dir.join(METADATA_FILE), metadata)?;
+        Ok(())
+    }
+}
+
+impl ParameterSource for RemoteDatabase {
+    fn source_name(&self) -> String {
+        self.url.clone()
+    }
+
+    fn list_parameters(&self) -> Result<Vec<String>, DatabaseError> {
+        Ok(self.bundle.parameters.iter()
+            .filter(|(_, entry)| entry.definition.is_some())
+            .map(|(name, _)| name.clone())
+            .collect())
+    }
+
+    /// Fetched once, so never changes.
+    fn parameter_stamp(&self, _name: &str) -> Option<u64> {
+        Some(0)
+    }
+
+    fn get_parameter_definition(&self, name: &str) -> Result<Option<ParameterDefinitionRaw>, DatabaseError> {
+        self.bundle.parameters.get(name)
+            .and_then(|entry| entry.definition.clone())
+            .map(|definition| schema::parse_definition(&self.context(name), definition))
+            .transpose()
+    }
+
+    fn get_parameter_versions(&self, name: &str) -> Result<Option<ParameterVersionsRaw>, DatabaseError> {
+        self.bundle.parameters.get(name)
+            .and_then(|entry| entry.versions.clone())
+            .map(|versions| schema::parse_versions(&self.context(name), versions))
+            .transpose()
+    }
+
+    fn get_subparameters(&self, parent: &str) -> Result<Vec<String>, DatabaseError> {
+        let prefix = format!("{}/", parent);
+        Ok(self.list_parameters()?
+            .into_iter()
+            .filter(|name| name.strip_prefix(&prefix).is_some_and(|rest| !rest.contains('/')))
+            .collect())
+    }
+
+    /// The signature is made over the bundle as served.
+    fn verify(&self, verifier: &dyn SignatureVerifier) -> Result<(), DatabaseError> {
+        let untrusted = |reason: String| DatabaseError::UntrustedSource {
+            name: self.source_name(),
+            reason,
+        };
+        let signature = self.signature.as_deref()
+            .ok_or_else(|| untrusted(format!("no signature at {}.sig", self.url)))?;
+        verifier.verify(&self.content, signature).map_err(untrusted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Serve `connections` requests on a local port, answering each with the
+    /// response `respond` gives for its path and headers, and return the
+    /// base URL.
+    fn serve<F>(connections: usize, respond: F) -> String
+    where
+        F: Fn(&str, &[String]) -> Vec<u8> + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for stream in listener.incoming().take(connections) {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut headers = Vec::new();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    headers.push(line.trim().to_ascii_lowercase());
+                }
+                let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+                let _ = stream.write_all(&respond(path, &headers));
+            }
+        });
+        url
+    }
+
+    fn response(status: &str, headers: &[&str], body: &[u8]) -> Vec<u8> {
+        let mut response = format!("HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n", status, body.len());
+        for header in headers {
+            response.push_str(header);
+            response.push_str("\r\n");
+        }
+        response.push_str("\r\n");
+        let mut response = response.into_bytes();
+        response.extend_from_slice(body);
+        response
+    }
+
+    fn get(fetcher: &UreqFetcher, url: &str, etag: Option<&str>) -> Result<FetchResponse, String> {
+        fetcher.fetch(&FetchRequest { url, etag, last_modified: None })
+    }
+
+    #[test]
+    fn fetches_body_and_validators() {
+        let url = serve(1, |_, _| response("200 OK", &["ETag: \"v1\"", "Last-Modified: Mon, 05 Oct 2026 10:00:00 GMT"], b"{}"));
+        let fetched = get(&UreqFetcher::new(), &url, None).unwrap();
+        assert_eq!(fetched, FetchResponse::Body {
+            body: b"{}".to_vec(),
+            etag: Some("\"v1\"".to_string()),
+            last_modified: Some("Mon, 05 Oct 2026 10:00:00 GMT".to_string()),
+        });
+    }
+
+    #[test]
+    fn revalidates_with_etag() {
+        let url = serve(1, |_, headers| {
+            if headers.iter().any(|header| header == "if-none-match: \"v1\"") {
+                response("304 Not Modified", &[], b"")
+            } else {
+                response("200 OK", &[], b"{}")
+            }
+        });
+        assert_eq!(get(&UreqFetcher::new(), &url, Some("\"v1\"")), Ok(FetchResponse::NotModified));
+    }
+
+    #[test]
+    fn follows_redirects() {
+        let url = serve(2, |path, _| match path {
+            "/old" => response("301 Moved Permanently", &["Location: /new"], b""),
+            _ => response("200 OK", &[], b"moved"),
+        });
+        let fetched = get(&UreqFetcher::new(), &format!("{}/old", url), None).unwrap();
+        assert!(matches!(fetched, FetchResponse::Body { body, .. } if body == b"moved"));
+    }
+
+    #[test]
+    fn reports_http_errors() {
+        let url = serve(1, |_, _| response("404 Not Found", &[], b"missing"));
+        let error = get(&UreqFetcher::new(), &url, None).unwrap_err();
+        assert!(error.contains("404"), "{}", error);
+    }
+
+    #[test]
+    fn refuses_oversized_bodies() {
+        let url = serve(1, |_, _| response("200 OK", &[], &[b'x'; 1024]));
+        let error = get(&UreqFetcher::new().with_max_size(100), &url, None).unwrap_err();
+        assert!(error.contains("larger than 100 bytes"), "{}", error);
+
+        // Without a Content-Length, the limit applies to what is read.
+        let url = serve(1, |_, _| {
+            let mut response = b"HTTP/1.1 200 OK\r\nConnection: close\r\n\r\n".to_vec();
+            response.extend_from_slice(&[b'x'; 1024]);
+            response
+        });
+        let error = get(&UreqFetcher::new().with_max_size(100), &url, None).unwrap_err();
+        assert!(error.contains("larger than 100 bytes"), "{}", error);
+    }
+
+    #[test]
+    fn times_out() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let fetcher = UreqFetcher::new().with_timeout(Duration::from_millis(200));
+        assert!(get(&fetcher, &url, None).is_err());
+        drop(listener);
+    }
+}
@@ -0,0 +1,114 @@
+//! Validation summaries as machine-readable reports, so that CI systems
+//! and web interfaces can consume findings without scraping their display
+//! form. Field names are part of the format: renaming or removing one
+//! needs a new [`VALIDATION_REPORT_SCHEMA_VERSION`].
+
+use serde::Serialize;
+
+use crate::error::{ReportError, Span};
+use crate::fixes::Fix;
+use crate::validators::{FindingCategory, ValidationResult, ValidationSummary};
+
+/// The version of the validation report format.
+pub const VALIDATION_REPORT_SCHEMA_VERSION: u32 = 1;
+
+/// The level of a finding in a report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportSeverity {
+    Valid,
+    Info,
+    Warning,
+    Error,
+    Unknown,
+}
+
+/// One finding of a report.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReportFinding {
+    pub parameter: String,
+    pub severity: ReportSeverity,
+    /// What is wrong; absent for valid parameters.
+    pub message: Option<String>,
+    pub category: FindingCategory,
+    /// The name and code of the check that reported the finding; built-in
+    /// checks are listed in [`BUILTIN_RULES`](crate::BUILTIN_RULES).
+    pub rule: Option<String>,
+    pub code: Option<String>,
+    pub span: Option<Span>,
+    pub suggestions: Vec<String>,
+    pub fix: Option<Fix>,
+}
+
+/// The number of findings at each level, and of parameters checked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ReportCounts {
+    pub parameters: usize,
+    pub valid: usize,
+    pub info: usize,
+    pub warning: usize,
+    pub error: usize,
+    pub unknown: usize,
+    /// Findings left out by a suppression, counted in no other field.
+    pub suppressed: usize,
+}
+
+/// A validation summary in a stable, serializable form.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ValidationReport {
+    pub schema_version: u32,
+    /// Whether no finding is an error.
+    pub passed: bool,
+    pub exit_code: i32,
+    pub counts: ReportCounts,
+    /// Every finding in the order it was reported: the result of each
+    /// parameter in command line order, then those of the other checks.
+    pub findings: Vec<ReportFinding>,
+}
+
+impl ValidationReport {
+    pub fn new(summary: &ValidationSummary) -> Self {
+        let findings = summary.details.iter()
+            .map(|finding| {
+                let (severity, message) = match &finding.result {
+                    ValidationResult::Valid => (ReportSeverity::Valid, None),
+                    ValidationResult::Info(message) => (ReportSeverity::Info, Some(message)),
+                    ValidationResult::Warning(message) => (ReportSeverity::Warning, Some(message)),
+                    ValidationResult::Error(message) => (ReportSeverity::Error, Some(message)),
+                    ValidationResult::Unknown(message) => (ReportSeverity::Unknown, Some(message)),
+                };
+                ReportFinding {
+                    parameter: finding.parameter.clone(),
+                    severity,
+                    message: message.cloned(),
+                    category: finding.category,
+                    rule: finding.rule.clone(),
+                    code: finding.code().map(str::to_string),
+                    span: finding.span.clone(),
+                    suggestions: finding.suggestions.clone(),
+                    fix: finding.fix.clone(),
+                }
+            })
+            .collect();
+
+        Self {
+            schema_version: VALIDATION_REPORT_SCHEMA_VERSION,
+            passed: summary.is_valid(),
+            exit_code: summary.exit_code(),
+            counts: ReportCounts {
+                parameters: summary.total_parameters,
+                valid: summary.valid_count,
+                info: summary.info_count,
+                warning: summary.warning_count,
+                error: summary.error_count,
+                unknown: summary.unknown_count,
+                suppressed: summary.suppressed.len(),
+            },
+            findings,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, ReportError> {
+        serde_json::to_string_pretty(self).map_err(|e| ReportError::SerializationError(e.to_string()))
+    }
+}
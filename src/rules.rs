@@ -0,0 +1,274 @@
+//! Lint rules over a whole parsed command line, for problems that only
+//! show in how parameters combine: options that contradict each other, an
+//! encrypted root nothing unlocks, and the like. A [`LintRuleRegistry`]
+//! holds the rules a parser runs; the built-in rules can be extended with
+//! third-party ones implementing [`CmdlineLintRule`].
+
+use crate::catalog::ParameterCatalog;
+use crate::error::RegistryError;
+use crate::parser::{ParsedCommandLine, ParsedParameter};
+use crate::probe::SystemProbe;
+use crate::query::glob_match;
+use crate::taxonomy::{self, rule_info};
+use crate::validators::{Finding, FindingCategory, ValidationResult};
+
+/// What a lint rule checks: the parsed command line, the catalog it was
+/// parsed with and, when validating against a system, its probe.
+pub struct LintContext<'a> {
+    pub parsed: &'a ParsedCommandLine,
+    pub catalog: &'a ParameterCatalog,
+    pub probe: Option<&'a SystemProbe>,
+}
+
+impl<'a> LintContext<'a> {
+    /// The effective parameters named `name`, in command line order.
+    pub fn effective(&self, name: &'a str) -> impl Iterator<Item = &'a ParsedParameter> + 'a {
+        self.parsed.parameters.iter().filter(move |p| p.effective && p.name == name)
+    }
+
+    /// Every occurrence of `name`, effective or not.
+    pub fn occurrences(&self, name: &'a str) -> impl Iterator<Item = &'a ParsedParameter> + 'a {
+        self.parsed.parameters.iter().filter(move |p| p.name == name)
+    }
+
+    pub fn has(&self, name: &str) -> bool {
+        self.parsed.parameters.iter().any(|p| p.effective && p.name == name)
+    }
+}
+
+/// A check of a whole command line.
+pub trait CmdlineLintRule: Send + Sync {
+    /// A short, unique, kebab-case name for the rule, e.g. `quiet-debug`.
+    fn id(&self) -> &str;
+    fn description(&self) -> &str;
+    fn check(&self, context: &LintContext) -> Vec<Finding>;
+}
+
+/// The lint rules a parser runs, in registration order.
+pub struct LintRuleRegistry {
+    rules: Vec<Box<dyn CmdlineLintRule>>,
+}
+
+impl LintRuleRegistry {
+    /// A registry without any rules.
+    pub fn new() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    /// A registry with the built-in rules.
+    pub fn with_builtin_rules() -> Self {
+        Self {
+            rules: vec![
+                Box::new(DuplicateRootRule),
+                Box::new(ConsoleOrderRule),
+                Box::new(QuietDebugRule),
+                Box::new(EncryptedRootRule),
+            ],
+        }
+    }
+
+    /// Add a rule, failing if one with the same id is registered.
+    pub fn register(&mut self, rule: Box<dyn CmdlineLintRule>) -> Result<(), RegistryError> {
+        if self.get(rule.id()).is_some() {
+            return Err(RegistryError::NameError(rule.id().to_string()));
+        }
+        self.rules.push(rule);
+        Ok(())
+    }
+
+    /// Remove the rule with `id`, returning it if it was registered.
+    pub fn unregister(&mut self, id: &str) -> Option<Box<dyn CmdlineLintRule>> {
+        let index = self.rules.iter().position(|rule| rule.id() == id)?;
+        Some(self.rules.remove(index))
+    }
+
+    pub fn get(&self, id: &str) -> Option<&dyn CmdlineLintRule> {
+        self.rules.iter().find(|rule| rule.id() == id).map(|rule| rule.as_ref())
+    }
+
+    pub fn rules(&self) -> impl Iterator<Item = &dyn CmdlineLintRule> {
+        self.rules.iter().map(|rule| rule.as_ref())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// The findings of every rule, rule by rule, each attributed to the
+    /// rule that reported it unless the rule named another. Findings of
+    /// built-in rules are in the rule's category.
+    pub fn check(&self, context: &LintContext) -> Vec<Finding> {
+        self.rules.iter()
+            .flat_map(|rule| {
+                rule.check(context).into_iter().map(|mut finding| {
+                    let id = finding.rule.get_or_insert_with(|| rule.id().to_string());
+                    if let (FindingCategory::General, Some(info)) = (finding.category, rule_info(id)) {
+                        finding.category = info.category;
+                    }
+                    finding
+                })
+            })
+            .collect()
+    }
+}
+
+impl Default for LintRuleRegistry {
+    fn default() -> Self {
+        Self::with_builtin_rules()
+    }
+}
+
+/// `root=` given more than once. The kernel mounts the last one, which is
+/// rarely what whoever added the first meant.
+pub struct DuplicateRootRule;
+
+impl CmdlineLintRule for DuplicateRootRule {
+    fn id(&self) -> &str {
+        taxonomy::DUPLICATE_ROOT.name
+    }
+
+    fn description(&self) -> &str {
+        taxonomy::DUPLICATE_ROOT.description
+    }
+
+    fn check(&self, context: &LintContext) -> Vec<Finding> {
+        let roots: Vec<&ParsedParameter> = context.occurrences("root").collect();
+        let Some(last) = roots.last().filter(|_| roots.len() > 1) else {
+            return Vec::new();
+        };
+        let message = format!(
+            "root= is given {} times; the root file system is mounted from the last, '{}'",
+            roots.len(),
+            last.value.as_deref().unwrap_or(""),
+        );
+        vec![Finding::new(&last.name, ValidationResult::Warning(message), Some(last.span.clone()))]
+    }
+}
+
+/// Several `console=` devices. Kernel messages go to all of them, but the
+/// last becomes /dev/console, which init writes to and reads passwords
+/// from, so a serial console followed by `tty0` leaves the serial line
+/// without boot prompts.
+pub struct ConsoleOrderRule;
+
+impl CmdlineLintRule for ConsoleOrderRule {
+    fn id(&self) -> &str {
+        taxonomy::CONSOLE_ORDER.name
+    }
+
+    fn description(&self) -> &str {
+        taxonomy::CONSOLE_ORDER.description
+    }
+
+    fn check(&self, context: &LintContext) -> Vec<Finding> {
+        let consoles: Vec<&ParsedParameter> = context.effective("console").filter(|p| p.value.is_some()).collect();
+        let device = |param: &ParsedParameter| {
+            let value = param.value.as_deref().unwrap_or("");
+            value.split_once(',').map_or(value, |(device, _)| device).to_string()
+        };
+        let Some(last) = consoles.last() else {
+            return Vec::new();
+        };
+        let last_device = device(last);
+        let serial_earlier = consoles[..consoles.len() - 1].iter()
+            .map(|param| device(param))
+            .find(|earlier| *earlier != last_device && !is_virtual_terminal(earlier));
+        match serial_earlier {
+            Some(serial) if is_virtual_terminal(&last_device) => {
+                let message = format!(
+                    "console={} is last, so it becomes /dev/console and init's output and password prompts \
+                     will not appear on {}; put console={} last to use it interactively",
+                    last_device, serial, serial,
+                );
+                vec![Finding::new(&last.name, ValidationResult::Warning(message), Some(last.span.clone()))]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Whether a console device is a virtual terminal rather than a serial or
+/// network console.
+fn is_virtual_terminal(device: &str) -> bool {
+    device.strip_prefix("tty").is_some_and(|n| n.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// `quiet` and `debug` together: each sets the console log level, so the
+/// later one wins and the other has no effect.
+pub struct QuietDebugRule;
+
+impl CmdlineLintRule for QuietDebugRule {
+    fn id(&self) -> &str {
+        taxonomy::QUIET_DEBUG.name
+    }
+
+    fn description(&self) -> &str {
+        taxonomy::QUIET_DEBUG.description
+    }
+
+    fn check(&self, context: &LintContext) -> Vec<Finding> {
+        let parameters = &context.parsed.parameters;
+        let last = |name: &str| parameters.iter().rposition(|p| p.effective && p.name == name);
+        let (Some(quiet), Some(debug)) = (last("quiet"), last("debug")) else {
+            return Vec::new();
+        };
+        let (earlier, later) = (&parameters[quiet.min(debug)], &parameters[quiet.max(debug)]);
+        let message = format!(
+            "'quiet' and 'debug' both set the console log level; '{}' comes later, so '{}' has no effect",
+            later.name, earlier.name,
+        );
+        vec![Finding::new(&earlier.name, ValidationResult::Warning(message), Some(earlier.span.clone()))]
+    }
+}
+
+/// Device names under which an encrypted root file system appears once
+/// unlocked.
+const ENCRYPTED_ROOT_PATTERNS: &[&str] = &["/dev/mapper/luks-*", "/dev/disk/by-id/dm-uuid-CRYPT-*"];
+
+/// Parameters that tell the initramfs which LUKS devices to unlock.
+const LUKS_PARAMETERS: &[&str] = &["rd.luks.uuid", "rd.luks.name", "luks.uuid", "luks.name"];
+
+/// A `root=` on an unlocked LUKS device with no parameter saying which
+/// device to unlock. The initramfs may still unlock it from an
+/// /etc/crypttab entry, but `rd.luks=0` rules that out.
+pub struct EncryptedRootRule;
+
+impl CmdlineLintRule for EncryptedRootRule {
+    fn id(&self) -> &str {
+        taxonomy::ENCRYPTED_ROOT_LUKS.name
+    }
+
+    fn description(&self) -> &str {
+        taxonomy::ENCRYPTED_ROOT_LUKS.description
+    }
+
+    fn check(&self, context: &LintContext) -> Vec<Finding> {
+        let Some(root) = context.effective("root").last() else {
+            return Vec::new();
+        };
+        let value = root.value.as_deref().unwrap_or("");
+        if !ENCRYPTED_ROOT_PATTERNS.iter().any(|pattern| glob_match(pattern, value)) {
+            return Vec::new();
+        }
+        if LUKS_PARAMETERS.iter().any(|name| context.has(name)) {
+            return Vec::new();
+        }
+
+        let suggestion = value.strip_prefix("/dev/mapper/luks-")
+            .map_or_else(|| "rd.luks.uuid=<uuid>".to_string(), |uuid| format!("rd.luks.uuid={}", uuid));
+        let disabled = context.effective("rd.luks").any(|p| p.value.as_deref() == Some("0"));
+        let result = if disabled {
+            ValidationResult::Error(format!(
+                "root= is on an encrypted device but rd.luks=0 stops the initramfs unlocking it; add e.g. '{}'",
+                suggestion,
+            ))
+        } else {
+            ValidationResult::Warning(format!(
+                "root= is on an encrypted device but no rd.luks.uuid= or rd.luks.name= says which device to \
+                 unlock; unless the initramfs has an /etc/crypttab entry for it, add e.g. '{}'",
+                suggestion,
+            ))
+        };
+        vec![Finding::new(&root.name, result, Some(root.span.clone()))]
+    }
+}
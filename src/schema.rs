@@ -0,0 +1,168 @@
+//! Versioning of the parameter definition schema.
+//!
+//! Definition documents carry a `schema_version`; documents without one
+//! predate versioning and are version 1. Documents are upgraded one version
+//! at a time to [`DEFINITION_SCHEMA_VERSION`] before they are deserialized,
+//! so that overlays written for older releases keep loading as the format
+//! evolves, while documents written for a newer release are rejected rather
+//! than misread.
+//!
+//! Fields the schema does not know are recorded on the parsed document and
+//! rejected by the loader unless its [`SchemaPolicy`] is lenient. Top-level
+//! fields named `x-<namespace>` are extensions for downstream consumers and
+//! are always accepted; their content is not checked.
+
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use crate::database::{ParameterDefinitionRaw, ParameterVersionsRaw};
+use crate::error::{DatabaseError, DocumentContext};
+
+/// Version of the definition document schema, bumped with a migration
+/// whenever fields are renamed, removed or change meaning.
+pub const DEFINITION_SCHEMA_VERSION: u32 = 2;
+
+/// How the loader treats fields that the definition schema does not know.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub enum SchemaPolicy {
+    /// Reject documents with unknown fields, which are usually misspelt.
+    #[default]
+    Strict,
+    /// Ignore unknown fields.
+    Lenient,
+}
+
+type Migration = fn(&mut Map<String, Value>);
+
+/// `MIGRATIONS[n]` upgrades a definition from version `n + 1` to `n + 2`.
+const MIGRATIONS: &[Migration] = &[drop_title];
+
+const _: () = assert!(MIGRATIONS.len() as u32 + 1 == DEFINITION_SCHEMA_VERSION);
+
+/// Version 1 definitions generated from the kernel documentation name the
+/// file they were generated into in a `title`, which version 2 drops.
+fn drop_title(definition: &mut Map<String, Value>) {
+    definition.remove("title");
+}
+
+/// The fields of each table of a document, by path from the document root;
+/// `*` matches any key. Tables not listed, such as the validator config in
+/// `syntax`, are open.
+type Fields = &'static [(&'static str, &'static [&'static str])];
+
+const DEFINITION_FIELDS: Fields = &[
+    ("", &[
+        "schema_version", "name", "processor", "description", "deprecated", "deprecated_since",
+        "replacement", "aliases", "selectors", "syntax", "inherit_syntax", "arch_syntax",
+        "distributions", "examples", "documentation", "duplicate_policy", "conflicts_with",
+        "requires", "controls", "security_impact", "performance_impact", "required_config",
+    ]),
+    ("distributions.*", &["min_version", "max_version", "component_version", "notes"]),
+    ("examples", &["valid", "invalid"]),
+    ("documentation", &["kernel_org", "man_pages", "distribution_docs"]),
+];
+
+const VERSIONS_FIELDS: Fields = &[
+    ("", &["mainline", "vendors", "components"]),
+    ("mainline", &["introduced", "commit", "last_modified", "last_modified_commit"]),
+    ("vendors.*.*", &["introduced", "commit", "notes"]),
+    ("components.*", &["name", "version"]),
+];
+
+/// Upgrade a definition document to the current schema and deserialize it.
+pub(crate) fn parse_definition(
+    context: &DocumentContext,
+    value: Value,
+) -> Result<ParameterDefinitionRaw, DatabaseError> {
+    let mut value = value;
+    let definition = value.as_object_mut()
+        .ok_or_else(|| invalid(context, "expected a table".to_string()))?;
+
+    let version = match definition.get("schema_version") {
+        None => 1,
+        Some(version) => version.as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .filter(|&version| version > 0)
+            .ok_or_else(|| invalid(context, format!("invalid schema_version {}", version)))?,
+    };
+    if version > DEFINITION_SCHEMA_VERSION {
+        return Err(DatabaseError::UnsupportedSchemaVersion {
+            context: Box::new(context.clone()),
+            found: version,
+            supported: DEFINITION_SCHEMA_VERSION,
+        });
+    }
+    for migration in &MIGRATIONS[version as usize - 1..] {
+        migration(definition);
+    }
+    definition.insert("schema_version".to_string(), DEFINITION_SCHEMA_VERSION.into());
+
+    // Unknown fields are reported by their path from the root, so only
+    // top-level fields can name an extension.
+    let unknown_fields = unknown_fields(&value, DEFINITION_FIELDS).into_iter()
+        .filter(|field| !is_extension(field))
+        .collect();
+    let mut definition: ParameterDefinitionRaw = deserialize(context, value)?;
+    definition.extra.retain(|key, _| is_extension(key));
+    definition.unknown_fields = unknown_fields;
+    Ok(definition)
+}
+
+/// Deserialize a versions document, which has no migrations yet.
+pub(crate) fn parse_versions(context: &DocumentContext, value: Value) -> Result<ParameterVersionsRaw, DatabaseError> {
+    let unknown_fields = unknown_fields(&value, VERSIONS_FIELDS);
+    let mut versions: ParameterVersionsRaw = deserialize(context, value)?;
+    versions.unknown_fields = unknown_fields;
+    Ok(versions)
+}
+
+/// Whether a top-level field of a definition is an extension namespace.
+fn is_extension(key: &str) -> bool {
+    key.strip_prefix("x-").is_some_and(|namespace| !namespace.is_empty())
+}
+
+fn deserialize<T: DeserializeOwned>(context: &DocumentContext, value: Value) -> Result<T, DatabaseError> {
+    serde_json::from_value(value).map_err(|e| invalid(context, e.to_string()))
+}
+
+fn invalid(context: &DocumentContext, message: String) -> DatabaseError {
+    DatabaseError::InvalidDocument {
+        context: Box::new(context.clone()),
+        message,
+    }
+}
+
+/// The dotted paths of the fields of `value` not in `fields`.
+fn unknown_fields(value: &Value, fields: Fields) -> Vec<String> {
+    let mut unknown = Vec::new();
+    collect_unknown(value, &mut Vec::new(), fields, &mut unknown);
+    unknown
+}
+
+fn collect_unknown<'a>(value: &'a Value, path: &mut Vec<&'a str>, fields: Fields, unknown: &mut Vec<String>) {
+    let Some(table) = value.as_object() else {
+        return;
+    };
+
+    let matches = |pattern: &str| {
+        let segments: Vec<&str> = pattern.split('.').filter(|s| !s.is_empty()).collect();
+        segments.len() == path.len()
+            && segments.iter().zip(path.iter()).all(|(segment, key)| *segment == "*" || segment == key)
+    };
+    let known = fields.iter().find(|(pattern, _)| matches(pattern)).map(|(_, known)| *known);
+
+    for (key, child) in table {
+        if known.is_some_and(|known| !known.contains(&key.as_str())) {
+            let mut field = path.join(".");
+            if !field.is_empty() {
+                field.push('.');
+            }
+            field.push_str(key);
+            unknown.push(field);
+            continue;
+        }
+        path.push(key);
+        collect_unknown(child, path, fields, unknown);
+        path.pop();
+    }
+}
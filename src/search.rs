@@ -0,0 +1,140 @@
+//! Full-text search over parameter names, descriptions, syntax formats and
+//! documentation links.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::parameter::Parameter;
+
+/// The part of a parameter definition a search term was found in. Hits are
+/// ranked by the weight of the field they occur in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SearchField {
+    Documentation,
+    Format,
+    Description,
+    Name,
+}
+
+impl SearchField {
+    pub fn weight(self) -> u32 {
+        match self {
+            SearchField::Name => 8,
+            SearchField::Description => 4,
+            SearchField::Format => 2,
+            SearchField::Documentation => 1,
+        }
+    }
+}
+
+/// Split text into lowercase alphanumeric search terms.
+pub fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(|term| term.to_lowercase())
+}
+
+/// An inverted index from search terms to the parameters containing them.
+#[derive(Debug, Clone, Default)]
+pub struct TextIndex {
+    keys: Vec<String>,
+    /// term -> (key index, best field the term occurs in), sorted by key index
+    postings: BTreeMap<String, Vec<(usize, SearchField)>>,
+}
+
+impl TextIndex {
+    /// Index parameters under the keys they are catalogued by.
+    pub fn new<'a, I>(parameters: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a str, &'a Parameter)>,
+    {
+        let mut index = Self::default();
+        for (key, param) in parameters {
+            index.insert(key, param);
+        }
+        index
+    }
+
+    pub fn insert(&mut self, key: &str, param: &Parameter) {
+        let id = self.keys.len();
+        self.keys.push(key.to_string());
+
+        let mut fields = vec![
+            (SearchField::Name, param.name.clone()),
+            (SearchField::Description, param.description.clone()),
+            (SearchField::Format, param.syntax.format.clone()),
+        ];
+        if let Some(docs) = &param.documentation {
+            let links = docs.kernel_org.iter()
+                .chain(&docs.man_pages)
+                .chain(docs.distribution_docs.values());
+            fields.extend(links.map(|link| (SearchField::Documentation, link.clone())));
+        }
+
+        let mut best: HashMap<String, SearchField> = HashMap::new();
+        for (field, text) in &fields {
+            for term in tokenize(text) {
+                let entry = best.entry(term).or_insert(*field);
+                *entry = (*entry).max(*field);
+            }
+        }
+        for (term, field) in best {
+            self.postings.entry(term).or_default().push((id, field));
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Find the parameters containing every term of `text`, best match first.
+    /// The last term also matches as a prefix, so partially typed words find
+    /// results. A hit scores the weight of the best field it occurs in,
+    /// summed over terms.
+    pub fn search(&self, text: &str) -> Vec<(&str, u32)> {
+        let terms: Vec<String> = tokenize(text).collect();
+        let Some((last, rest)) = terms.split_last() else {
+            return Vec::new();
+        };
+
+        let mut scores: Option<HashMap<usize, u32>> = None;
+        for (term, prefix) in rest.iter().map(|t| (t, false)).chain([(last, true)]) {
+            let hits = self.term_hits(term, prefix);
+            scores = Some(match scores {
+                None => hits,
+                Some(scores) => scores.into_iter()
+                    .filter_map(|(id, score)| hits.get(&id).map(|hit| (id, score + hit)))
+                    .collect(),
+            });
+        }
+
+        let mut results: Vec<(&str, u32)> = scores.unwrap_or_default()
+            .into_iter()
+            .map(|(id, score)| (self.keys[id].as_str(), score))
+            .collect();
+        results.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        results
+    }
+
+    fn term_hits(&self, term: &str, prefix: bool) -> HashMap<usize, u32> {
+        let mut hits: HashMap<usize, u32> = HashMap::new();
+        let postings: Box<dyn Iterator<Item = &Vec<(usize, SearchField)>>> = if prefix {
+            Box::new(self.postings.range(term.to_string()..)
+                .take_while(|(t, _)| t.starts_with(term))
+                .map(|(_, postings)| postings))
+        } else {
+            Box::new(self.postings.get(term).into_iter())
+        };
+
+        for posting in postings {
+            for &(id, field) in posting {
+                let hit = hits.entry(id).or_insert(0);
+                *hit = (*hit).max(field.weight());
+            }
+        }
+        hits
+    }
+}
@@ -0,0 +1,193 @@
+//! What-if simulation of edits to a command line: the findings an edit
+//! would introduce or resolve and the parameters whose effect it changes,
+//! without validating both command lines and comparing them by hand.
+
+use indexmap::IndexMap;
+use serde::Serialize;
+
+use crate::editor::CommandLineEditor;
+use crate::parser::{render_argument, ParsedCommandLine};
+use crate::report::{ReportFinding, ValidationReport};
+
+/// An edit to simulate, applied the way [`CommandLineEditor`] applies it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "edit")]
+pub enum CmdlineEdit {
+    /// Add an occurrence of a parameter, even if it is already present.
+    Add { name: String, value: Option<String> },
+    /// Set a parameter, replacing its first occurrence and removing later
+    /// ones, or adding it if it is absent.
+    Set { name: String, value: Option<String> },
+    /// Remove every occurrence of a parameter.
+    Remove { name: String },
+}
+
+impl CmdlineEdit {
+    pub fn add(name: &str, value: Option<&str>) -> Self {
+        Self::Add {
+            name: name.to_string(),
+            value: value.map(str::to_string),
+        }
+    }
+
+    pub fn set(name: &str, value: Option<&str>) -> Self {
+        Self::Set {
+            name: name.to_string(),
+            value: value.map(str::to_string),
+        }
+    }
+
+    pub fn remove(name: &str) -> Self {
+        Self::Remove { name: name.to_string() }
+    }
+
+    fn apply(&self, editor: &mut CommandLineEditor) {
+        match self {
+            Self::Add { name, value } => {
+                editor.append(name, value.as_deref());
+            }
+            Self::Set { name, value } => {
+                editor.set(name, value.as_deref());
+            }
+            Self::Remove { name } => {
+                editor.remove(name);
+            }
+        }
+    }
+}
+
+/// Apply `edits` to `cmdline` in order.
+pub(crate) fn apply_edits(cmdline: &str, edits: &[CmdlineEdit]) -> String {
+    let mut editor = CommandLineEditor::new(cmdline);
+    for edit in edits {
+        edit.apply(&mut editor);
+    }
+    editor.to_string()
+}
+
+/// A parameter whose effect differs between the two command lines: the
+/// occurrences that take effect are different, or take effect with
+/// different conflicts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BehaviorChange {
+    pub parameter: String,
+    /// The arguments that take effect before the edits, in command line
+    /// order; empty if the parameter is absent.
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+    /// The parameters it conflicts with before and after the edits.
+    pub conflicts_before: Vec<String>,
+    pub conflicts_after: Vec<String>,
+}
+
+/// The consequences of editing a command line.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImpactReport {
+    /// The command line before and after the edits.
+    pub before: String,
+    pub after: String,
+    /// Whether each command line passes validation.
+    pub passed_before: bool,
+    pub passed_after: bool,
+    /// Findings reported only after the edits.
+    pub introduced: Vec<ReportFinding>,
+    /// Findings reported only before the edits.
+    pub resolved: Vec<ReportFinding>,
+    /// Parameters whose effect the edits change, in the order they first
+    /// appear.
+    pub behavior_changes: Vec<BehaviorChange>,
+}
+
+impl ImpactReport {
+    pub(crate) fn new(before: &ParsedCommandLine, after: &ParsedCommandLine, cmdlines: (String, String)) -> Self {
+        let (report_before, report_after) = (before.validation_summary.report(), after.validation_summary.report());
+        let introduced = unmatched_findings(&report_after, &report_before);
+        let resolved = unmatched_findings(&report_before, &report_after);
+
+        let (effects_before, effects_after) = (effects(before), effects(after));
+        let mut names: Vec<&String> = effects_before.keys().collect();
+        names.extend(effects_after.keys().filter(|name| !effects_before.contains_key(*name)));
+        let behavior_changes = names.into_iter()
+            .filter_map(|name| {
+                let before = effects_before.get(name).cloned().unwrap_or_default();
+                let after = effects_after.get(name).cloned().unwrap_or_default();
+                (before != after).then(|| BehaviorChange {
+                    parameter: name.clone(),
+                    before: before.arguments,
+                    after: after.arguments,
+                    conflicts_before: before.conflicts,
+                    conflicts_after: after.conflicts,
+                })
+            })
+            .collect();
+
+        Self {
+            before: cmdlines.0,
+            after: cmdlines.1,
+            passed_before: report_before.passed,
+            passed_after: report_after.passed,
+            introduced,
+            resolved,
+            behavior_changes,
+        }
+    }
+
+    /// Whether the edits change any finding or the effect of any parameter.
+    pub fn is_empty(&self) -> bool {
+        self.introduced.is_empty() && self.resolved.is_empty() && self.behavior_changes.is_empty()
+    }
+}
+
+/// The effective arguments of a parameter and what they conflict with.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Effect {
+    arguments: Vec<String>,
+    conflicts: Vec<String>,
+}
+
+/// The effect of each parameter of a command line, by current name.
+fn effects(parsed: &ParsedCommandLine) -> IndexMap<String, Effect> {
+    let name_of = |index: usize| {
+        let param = &parsed.parameters[index];
+        param.parameter_def.as_ref().map_or(param.name.clone(), |def| def.name.clone())
+    };
+
+    let mut effects: IndexMap<String, Effect> = IndexMap::new();
+    for (index, param) in parsed.parameters.iter().enumerate().filter(|(_, p)| p.effective) {
+        effects.entry(name_of(index)).or_default().arguments.push(render_argument(&param.name, param.value.as_deref()));
+    }
+    for conflict in &parsed.conflicts {
+        for (this, other) in [(conflict.first, conflict.second), (conflict.second, conflict.first)] {
+            let other = name_of(other);
+            let effect = effects.entry(name_of(this)).or_default();
+            if !effect.conflicts.contains(&other) {
+                effect.conflicts.push(other);
+            }
+        }
+    }
+    for effect in effects.values_mut() {
+        effect.conflicts.sort();
+    }
+    effects
+}
+
+/// The findings of `report` other than valid results that `other` does not
+/// report too. Findings are matched regardless of where they are reported,
+/// as edits move the arguments after them.
+fn unmatched_findings(report: &ValidationReport, other: &ValidationReport) -> Vec<ReportFinding> {
+    let same = |a: &ReportFinding, b: &ReportFinding| {
+        a.parameter == b.parameter && a.severity == b.severity && a.message == b.message && a.rule == b.rule
+    };
+    let mut remaining: Vec<&ReportFinding> = other.findings.iter().collect();
+    report.findings.iter()
+        .filter(|finding| finding.message.is_some())
+        .filter(|finding| match remaining.iter().position(|candidate| same(finding, candidate)) {
+            Some(index) => {
+                remaining.remove(index);
+                false
+            }
+            None => true,
+        })
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,138 @@
+//! Binary snapshots of a loaded catalog, so that long-running services and
+//! command line tools can start without parsing the TOML database.
+//!
+//! A snapshot is a fixed header followed by the catalog in CBOR:
+//!
+//! ```text
+//! magic     8 bytes   "KCMDSNAP"
+//! version   u32 LE    SNAPSHOT_FORMAT_VERSION
+//! checksum  u64 LE    FNV-1a of the payload
+//! length    u64 LE    payload length in bytes
+//! payload   CBOR      parameters, subparameter index, version history and
+//!                     provenance
+//! ```
+//!
+//! Snapshots are a cache rather than an interchange format: any change to
+//! the catalog types bumps the format version, and older snapshots are
+//! rejected so that they are rebuilt from the database.
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::catalog::VersionInfo;
+use crate::database::Provenance;
+use crate::error::DatabaseError;
+use crate::parameter::Parameter;
+use crate::trust::{snapshot_signature_path, TrustPolicy};
+
+/// Version of the snapshot format, bumped whenever the header or the
+/// serialized catalog types change.
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 4;
+
+const MAGIC: &[u8; 8] = b"KCMDSNAP";
+const HEADER_LEN: usize = MAGIC.len() + 4 + 8 + 8;
+
+/// The catalog state a snapshot carries; derived indexes are rebuilt on
+/// load.
+#[derive(Deserialize)]
+pub(crate) struct SnapshotData {
+    pub(crate) parameters: HashMap<String, Parameter>,
+    pub(crate) subparameter_index: HashMap<String, Vec<String>>,
+    pub(crate) version_info: HashMap<String, VersionInfo>,
+    pub(crate) provenance: HashMap<String, Provenance>,
+}
+
+/// [`SnapshotData`] borrowed from a catalog for saving.
+#[derive(Serialize)]
+pub(crate) struct SnapshotRef<'a> {
+    pub(crate) parameters: &'a HashMap<String, Parameter>,
+    pub(crate) subparameter_index: &'a HashMap<String, Vec<String>>,
+    pub(crate) version_info: &'a HashMap<String, VersionInfo>,
+    pub(crate) provenance: &'a HashMap<String, Provenance>,
+}
+
+/// Write `data` to `path`, replacing any existing snapshot atomically.
+pub(crate) fn save_snapshot(data: &SnapshotRef<'_>, path: &Path) -> Result<(), DatabaseError> {
+    let mut payload = Vec::new();
+    ciborium::into_writer(data, &mut payload)
+        .map_err(|e| DatabaseError::SnapshotError(format!("Failed to encode snapshot: {}", e)))?;
+
+    // Write alongside the destination and rename over it, so that readers
+    // never see a partial snapshot.
+    let mut temp_name = path.file_name()
+        .ok_or_else(|| DatabaseError::SnapshotError(format!("Invalid snapshot path: {:?}", path)))?
+        .to_os_string();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+
+    let write = || -> Result<(), DatabaseError> {
+        let mut writer = BufWriter::new(File::create(&temp_path)?);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&SNAPSHOT_FORMAT_VERSION.to_le_bytes())?;
+        writer.write_all(&checksum(&payload).to_le_bytes())?;
+        writer.write_all(&(payload.len() as u64).to_le_bytes())?;
+        writer.write_all(&payload)?;
+        writer.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    };
+    write().inspect_err(|_| {
+        let _ = fs::remove_file(&temp_path);
+    })
+}
+
+/// Read a snapshot written by [`save_snapshot`], checking its format
+/// version and checksum, and its detached signature if the policy requires
+/// one.
+pub(crate) fn load_snapshot(path: &Path, policy: &TrustPolicy) -> Result<SnapshotData, DatabaseError> {
+    let bytes = fs::read(path)?;
+    if let TrustPolicy::RequireSigned(verifier) = policy {
+        let untrusted = |reason: String| DatabaseError::UntrustedSource {
+            name: path.display().to_string(),
+            reason,
+        };
+        let signature_path = snapshot_signature_path(path);
+        let signature = fs::read(&signature_path)
+            .map_err(|e| untrusted(format!("cannot read {:?}: {}", signature_path, e)))?;
+        verifier.verify(&bytes, &signature).map_err(untrusted)?;
+    }
+
+    if bytes.len() < HEADER_LEN || &bytes[..MAGIC.len()] != MAGIC {
+        return Err(DatabaseError::SnapshotError(format!("{:?} is not a catalog snapshot", path)));
+    }
+
+    let field = |offset: usize, len: usize| &bytes[offset..offset + len];
+    let version = u32::from_le_bytes(field(8, 4).try_into().unwrap());
+    if version != SNAPSHOT_FORMAT_VERSION {
+        return Err(DatabaseError::UnsupportedSnapshotVersion {
+            found: version,
+            supported: SNAPSHOT_FORMAT_VERSION,
+        });
+    }
+    let expected = u64::from_le_bytes(field(12, 8).try_into().unwrap());
+    let length = u64::from_le_bytes(field(20, 8).try_into().unwrap());
+
+    let payload = &bytes[HEADER_LEN..];
+    if payload.len() as u64 != length {
+        return Err(DatabaseError::SnapshotError(format!(
+            "Truncated snapshot {:?}: expected {} bytes of data, found {}", path, length, payload.len()
+        )));
+    }
+    if checksum(payload) != expected {
+        return Err(DatabaseError::SnapshotError(format!("Checksum mismatch in snapshot {:?}", path)));
+    }
+
+    ciborium::from_reader(payload)
+        .map_err(|e| DatabaseError::SnapshotError(format!("Failed to decode snapshot {:?}: {}", path, e)))
+}
+
+/// 64-bit FNV-1a, enough to catch truncated or corrupted snapshots.
+pub(crate) fn checksum(data: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    data.iter().fold(OFFSET_BASIS, |hash, &byte| (hash ^ u64::from(byte)).wrapping_mul(PRIME))
+}
@@ -0,0 +1,121 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{CommandLineSource, GrubEnv, SourcedCommandLine};
+use crate::error::ParseError;
+
+pub const BLS_ENTRIES_PATH: &str = "/boot/loader/entries";
+
+/// A Boot Loader Specification type #1 entry.
+#[derive(Debug, Clone, Default)]
+pub struct BlsEntry {
+    pub path: Option<PathBuf>,
+    pub title: Option<String>,
+    pub version: Option<String>,
+    pub linux: Option<String>,
+    pub options: Vec<String>,
+}
+
+impl BlsEntry {
+    pub fn parse(content: &str) -> Self {
+        let mut entry = Self::default();
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = match line.split_once(|c: char| c.is_ascii_whitespace()) {
+                Some((key, value)) => (key, value.trim()),
+                None => (line, ""),
+            };
+
+            match key {
+                "title" => entry.title = Some(value.to_string()),
+                "version" => entry.version = Some(value.to_string()),
+                "linux" => entry.linux = Some(value.to_string()),
+                "options" => entry.options.push(value.to_string()),
+                _ => {}
+            }
+        }
+
+        entry
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        let mut entry = Self::parse(&fs::read_to_string(path.as_ref())?);
+        entry.path = Some(path.as_ref().to_path_buf());
+        Ok(entry)
+    }
+
+    /// The kernel command line for this entry. Like systemd-boot, multiple
+    /// `options` lines are joined with a single space.
+    pub fn cmdline(&self) -> String {
+        self.options.join(" ")
+    }
+}
+
+/// Command line source for Boot Loader Specification entries, reading either
+/// a single entry file or every `*.conf` file in an entries directory.
+pub struct BlsEntrySource {
+    path: PathBuf,
+    grubenv: Option<PathBuf>,
+}
+
+impl BlsEntrySource {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            grubenv: None,
+        }
+    }
+
+    pub fn system() -> Self {
+        Self::new(BLS_ENTRIES_PATH)
+    }
+
+    /// Expand variable references such as `$kernelopts` in entry options from
+    /// a GRUB environment block, as GRUB's blscfg command does.
+    pub fn with_grubenv<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.grubenv = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn entries(&self) -> Result<Vec<BlsEntry>, ParseError> {
+        if !self.path.is_dir() {
+            return Ok(vec![BlsEntry::load(&self.path)?]);
+        }
+
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(&self.path)? {
+            let path = entry?.path();
+            if path.is_file() && path.extension().is_some_and(|ext| ext == "conf") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        paths.iter().map(BlsEntry::load).collect()
+    }
+}
+
+impl CommandLineSource for BlsEntrySource {
+    fn command_lines(&self) -> Result<Vec<SourcedCommandLine>, ParseError> {
+        let grubenv = self.grubenv.as_ref().map(GrubEnv::load).transpose()?;
+
+        Ok(self.entries()?
+            .into_iter()
+            .map(|entry| SourcedCommandLine {
+                source: entry.path
+                    .as_ref()
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_default(),
+                cmdline: match &grubenv {
+                    Some(env) => env.expand(&entry.cmdline()),
+                    None => entry.cmdline(),
+                },
+            })
+            .collect())
+    }
+}
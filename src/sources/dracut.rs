@@ -0,0 +1,97 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{CommandLineSource, SourcedCommandLine};
+use crate::error::ParseError;
+
+pub const DRACUT_CMDLINE_D_PATH: &str = "/etc/cmdline.d";
+
+/// Command line source for dracut's /etc/cmdline.d fragments.
+///
+/// Dracut's getcmdline() joins the lines of each `*.conf` fragment in glob
+/// order, then appends /etc/cmdline and finally the kernel command line, so
+/// later fragments take precedence for parameters where the last value wins.
+pub struct DracutCmdlineSource {
+    cmdline_d: PathBuf,
+    etc_cmdline: Option<PathBuf>,
+    kernel_cmdline: Option<String>,
+}
+
+impl DracutCmdlineSource {
+    pub fn new<P: AsRef<Path>>(cmdline_d: P) -> Self {
+        Self {
+            cmdline_d: cmdline_d.as_ref().to_path_buf(),
+            etc_cmdline: None,
+            kernel_cmdline: None,
+        }
+    }
+
+    pub fn system() -> Self {
+        Self::new(DRACUT_CMDLINE_D_PATH)
+    }
+
+    /// Also merge a single-file /etc/cmdline after the fragments.
+    pub fn with_etc_cmdline<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.etc_cmdline = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Also merge the kernel command line after all configuration files.
+    pub fn with_kernel_cmdline<S: Into<String>>(mut self, cmdline: S) -> Self {
+        self.kernel_cmdline = Some(cmdline.into());
+        self
+    }
+
+    /// The fragment files in the order dracut reads them.
+    pub fn fragment_paths(&self) -> Result<Vec<PathBuf>, ParseError> {
+        let mut paths = Vec::new();
+
+        if self.cmdline_d.is_dir() {
+            for entry in fs::read_dir(&self.cmdline_d)? {
+                let path = entry?.path();
+                if path.is_file() && path.extension().is_some_and(|ext| ext == "conf") {
+                    paths.push(path);
+                }
+            }
+        }
+        paths.sort();
+
+        if let Some(etc_cmdline) = &self.etc_cmdline {
+            if etc_cmdline.is_file() {
+                paths.push(etc_cmdline.clone());
+            }
+        }
+
+        Ok(paths)
+    }
+
+    fn read_fragment(path: &Path) -> Result<SourcedCommandLine, ParseError> {
+        let content = fs::read_to_string(path)?;
+        Ok(SourcedCommandLine {
+            source: path.display().to_string(),
+            cmdline: content.lines().collect::<Vec<_>>().join(" "),
+        })
+    }
+}
+
+impl CommandLineSource for DracutCmdlineSource {
+    fn command_lines(&self) -> Result<Vec<SourcedCommandLine>, ParseError> {
+        let mut fragments = self.fragment_paths()?
+            .iter()
+            .map(|path| Self::read_fragment(path))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if let Some(cmdline) = &self.kernel_cmdline {
+            fragments.push(SourcedCommandLine {
+                source: "/proc/cmdline".to_string(),
+                cmdline: cmdline.clone(),
+            });
+        }
+
+        Ok(fragments)
+    }
+
+    fn is_fragmented(&self) -> bool {
+        true
+    }
+}
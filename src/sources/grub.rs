@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{shell, CommandLineSource, SourcedCommandLine};
+use crate::error::ParseError;
+
+pub const GRUB_DEFAULT_PATH: &str = "/etc/default/grub";
+
+/// The variables assigned in a GRUB defaults file such as /etc/default/grub.
+#[derive(Debug, Clone)]
+pub struct GrubDefaults {
+    variables: HashMap<String, String>,
+}
+
+impl GrubDefaults {
+    pub fn parse(content: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            variables: shell::parse_assignments(content)?,
+        })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.variables.get(name).map(String::as_str)
+    }
+
+    pub fn cmdline_linux(&self) -> Option<&str> {
+        self.get("GRUB_CMDLINE_LINUX")
+    }
+
+    pub fn cmdline_linux_default(&self) -> Option<&str> {
+        self.get("GRUB_CMDLINE_LINUX_DEFAULT")
+    }
+
+    /// The command line grub-mkconfig generates for normal (non-recovery)
+    /// entries: GRUB_CMDLINE_LINUX followed by GRUB_CMDLINE_LINUX_DEFAULT.
+    pub fn cmdline(&self) -> String {
+        [self.cmdline_linux(), self.cmdline_linux_default()]
+            .into_iter()
+            .flatten()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Command line source for the GRUB defaults file used by grub-mkconfig.
+pub struct GrubDefaultSource {
+    path: PathBuf,
+}
+
+impl GrubDefaultSource {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn system() -> Self {
+        Self::new(GRUB_DEFAULT_PATH)
+    }
+}
+
+impl CommandLineSource for GrubDefaultSource {
+    fn command_lines(&self) -> Result<Vec<SourcedCommandLine>, ParseError> {
+        let defaults = GrubDefaults::load(&self.path)?;
+        Ok(vec![SourcedCommandLine {
+            source: self.path.display().to_string(),
+            cmdline: defaults.cmdline(),
+        }])
+    }
+}
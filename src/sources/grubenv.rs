@@ -0,0 +1,143 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexMap;
+
+use super::{expand_variables, CommandLineSource, SourcedCommandLine};
+use crate::error::ParseError;
+
+pub const GRUBENV_PATH: &str = "/boot/grub2/grubenv";
+
+const GRUBENV_SIGNATURE: &str = "# GRUB Environment Block\n";
+const GRUBENV_SIZE: usize = 1024;
+
+/// The variables of a GRUB environment block, as written by grub2-editenv.
+#[derive(Debug, Clone, Default)]
+pub struct GrubEnv {
+    variables: IndexMap<String, String>,
+}
+
+impl GrubEnv {
+    /// Parse a GRUB environment block: a signature line, `name=value` lines
+    /// with backslash escapes, padded with '#' to exactly 1024 bytes.
+    pub fn parse(block: &[u8]) -> Result<Self, ParseError> {
+        if block.len() < GRUBENV_SIZE {
+            return Err(ParseError::TruncatedEnvironmentBlock(format!(
+                "{} bytes, expected {}", block.len(), GRUBENV_SIZE
+            )));
+        }
+        if block.len() > GRUBENV_SIZE {
+            return Err(ParseError::CorruptEnvironmentBlock(format!(
+                "{} bytes, expected {}", block.len(), GRUBENV_SIZE
+            )));
+        }
+
+        let text = std::str::from_utf8(block)
+            .map_err(|e| ParseError::CorruptEnvironmentBlock(format!("not valid UTF-8: {}", e)))?;
+        let body = text.strip_prefix(GRUBENV_SIGNATURE)
+            .ok_or_else(|| ParseError::CorruptEnvironmentBlock("missing signature".to_string()))?;
+
+        // Variables end at the first line of padding; everything after must be '#'
+        let mut variables = IndexMap::new();
+        let mut rest = body;
+        while !rest.is_empty() && !rest.starts_with('#') {
+            let (line, remainder) = Self::split_line(rest).ok_or_else(|| {
+                ParseError::CorruptEnvironmentBlock("unterminated variable".to_string())
+            })?;
+            let (name, value) = line.split_once('=').ok_or_else(|| {
+                ParseError::CorruptEnvironmentBlock(format!("malformed line '{}'", line))
+            })?;
+            variables.insert(name.to_string(), Self::unescape(value));
+            rest = remainder;
+        }
+
+        if !rest.bytes().all(|b| b == b'#') {
+            return Err(ParseError::CorruptEnvironmentBlock("missing or damaged padding".to_string()));
+        }
+
+        Ok(Self { variables })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        Self::parse(&fs::read(path)?)
+    }
+
+    /// Split at the first newline not escaped by a backslash.
+    fn split_line(text: &str) -> Option<(&str, &str)> {
+        let bytes = text.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\\' => i += 2,
+                b'\n' => return Some((&text[..i], &text[i + 1..])),
+                _ => i += 1,
+            }
+        }
+        None
+    }
+
+    fn unescape(value: &str) -> String {
+        let mut unescaped = String::with_capacity(value.len());
+        let mut chars = value.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => unescaped.extend(chars.next()),
+                c => unescaped.push(c),
+            }
+        }
+        unescaped
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.variables.get(name).map(String::as_str)
+    }
+
+    pub fn variables(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.variables.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// The default kernel command line used by BLS entries on Fedora and RHEL 8.
+    pub fn kernelopts(&self) -> Option<&str> {
+        self.get("kernelopts")
+    }
+
+    pub fn saved_entry(&self) -> Option<&str> {
+        self.get("saved_entry")
+    }
+
+    /// Expand `$name` and `${name}` references to environment variables, as
+    /// GRUB does for the options of BLS entries.
+    pub fn expand(&self, text: &str) -> String {
+        expand_variables(text, |name| self.get(name))
+    }
+}
+
+/// Command line source for the `kernelopts` variable of a GRUB environment block.
+pub struct GrubEnvSource {
+    path: PathBuf,
+}
+
+impl GrubEnvSource {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn system() -> Self {
+        Self::new(GRUBENV_PATH)
+    }
+}
+
+impl CommandLineSource for GrubEnvSource {
+    fn command_lines(&self) -> Result<Vec<SourcedCommandLine>, ParseError> {
+        let env = GrubEnv::load(&self.path)?;
+        Ok(env.kernelopts()
+            .map(|kernelopts| SourcedCommandLine {
+                source: format!("{}:kernelopts", self.path.display()),
+                cmdline: kernelopts.to_string(),
+            })
+            .into_iter()
+            .collect())
+    }
+}
@@ -0,0 +1,62 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{shell, strip_arguments, CommandLineSource, SourcedCommandLine, NETBOOT_LOADER_ARGUMENTS};
+use crate::error::ParseError;
+
+/// Command line source for the kernel arguments a kickstart file installs,
+/// taken from `bootloader --append`.
+pub struct KickstartSource {
+    path: PathBuf,
+}
+
+impl KickstartSource {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Extract the `--append` arguments from kickstart content. Only the last
+    /// `bootloader` command takes effect, as in anaconda.
+    pub fn parse(content: &str) -> Result<Option<String>, ParseError> {
+        let mut append = None;
+
+        for line in content.replace("\\\n", " ").lines() {
+            let line = line.trim();
+            if !line.starts_with("bootloader") {
+                continue;
+            }
+
+            let words = shell::split_words(line)?;
+            if words.first().map(String::as_str) != Some("bootloader") {
+                continue;
+            }
+
+            append = Some(String::new());
+            let mut words = words.iter().skip(1);
+            while let Some(word) = words.next() {
+                if let Some(value) = word.strip_prefix("--append=") {
+                    append = Some(value.to_string());
+                } else if word == "--append" {
+                    append = words.next().cloned();
+                }
+            }
+        }
+
+        Ok(append.map(|args| strip_arguments(&args, NETBOOT_LOADER_ARGUMENTS)))
+    }
+}
+
+impl CommandLineSource for KickstartSource {
+    fn command_lines(&self) -> Result<Vec<SourcedCommandLine>, ParseError> {
+        let content = fs::read_to_string(&self.path)?;
+        Ok(Self::parse(&content)?
+            .map(|cmdline| SourcedCommandLine {
+                source: format!("{}:bootloader --append", self.path.display()),
+                cmdline,
+            })
+            .into_iter()
+            .collect())
+    }
+}
@@ -0,0 +1,115 @@
+//! Front-ends that extract kernel command lines from boot configuration
+//! files so that they can be fed into the CommandLineParser.
+
+use crate::error::ParseError;
+use crate::validators::Finding;
+
+pub mod bls;
+pub mod dracut;
+pub mod grub;
+pub mod grubenv;
+pub mod kickstart;
+pub mod pxe;
+pub mod rpi;
+pub(crate) mod shell;
+pub mod uboot;
+pub mod uki;
+pub mod zipl;
+
+pub use bls::*;
+pub use dracut::*;
+pub use grub::*;
+pub use grubenv::*;
+pub use kickstart::*;
+pub use pxe::*;
+pub use rpi::*;
+pub use uboot::*;
+pub use uki::*;
+pub use zipl::*;
+
+/// Remove the arguments called `names` from `cmdline`, keeping the original
+/// text of every other argument.
+pub(crate) fn strip_arguments(cmdline: &str, names: &[&str]) -> String {
+    crate::parser::split_args(cmdline)
+        .iter()
+        .filter(|arg| !names.contains(&arg.param))
+        .map(|arg| arg.text)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Expand `$name` and `${name}` references in `text` using `lookup`.
+/// Undefined variables expand to nothing; a `$` that does not start a
+/// reference is kept.
+pub(crate) fn expand_variables<'a>(text: &str, lookup: impl Fn(&str) -> Option<&'a str>) -> String {
+    let mut expanded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(dollar) = rest.find('$') {
+        expanded.push_str(&rest[..dollar]);
+        let after = &rest[dollar + 1..];
+
+        let (name, remainder) = if let Some(braced) = after.strip_prefix('{') {
+            match braced.split_once('}') {
+                Some((name, remainder)) => (name, remainder),
+                None => ("", after),
+            }
+        } else {
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            (&after[..end], &after[end..])
+        };
+
+        if name.is_empty() {
+            expanded.push('$');
+            rest = after;
+        } else {
+            expanded.push_str(lookup(name).unwrap_or(""));
+            rest = remainder;
+        }
+    }
+    expanded.push_str(rest);
+
+    expanded
+}
+
+/// A command line together with a description of where it came from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourcedCommandLine {
+    pub source: String,
+    pub cmdline: String,
+}
+
+pub trait CommandLineSource {
+    /// Extract the command line(s) described by this source.
+    fn command_lines(&self) -> Result<Vec<SourcedCommandLine>, ParseError>;
+
+    /// Whether the command lines are fragments that combine, in order, into a
+    /// single effective command line rather than independent alternatives.
+    fn is_fragmented(&self) -> bool {
+        false
+    }
+
+    /// The longest command line the boot loader accepts, when it imposes a
+    /// limit of its own; longer ones are reported by
+    /// [`CommandLineParser::parse_source`](crate::CommandLineParser::parse_source).
+    fn length_limit(&self) -> Option<usize> {
+        None
+    }
+
+    /// The longest command line older versions of the boot loader or the
+    /// kernel accept, when the current ones accept more; longer ones are
+    /// warned of.
+    fn legacy_length_limit(&self) -> Option<usize> {
+        None
+    }
+
+    /// Problems with the source itself rather than the arguments of its
+    /// command lines, such as content the boot loader ignores;
+    /// [`CommandLineParser::parse_source`](crate::CommandLineParser::parse_source)
+    /// adds them to each command line it parses from the source.
+    fn source_findings(&self) -> Result<Vec<Finding>, ParseError> {
+        Ok(Vec::new())
+    }
+}
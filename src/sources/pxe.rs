@@ -0,0 +1,144 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{strip_arguments, CommandLineSource, SourcedCommandLine};
+use crate::error::ParseError;
+
+/// Arguments consumed by network boot loaders rather than the kernel:
+/// `initrd=` names the initramfs to load and `BOOTIF=` is injected by
+/// PXELINUX's IPAPPEND.
+pub const NETBOOT_LOADER_ARGUMENTS: &[&str] = &["initrd", "BOOTIF"];
+
+/// A boot label from a PXELINUX configuration file.
+#[derive(Debug, Clone, Default)]
+pub struct PxelinuxLabel {
+    pub name: String,
+    pub kernel: Option<String>,
+    pub append: Option<String>,
+}
+
+/// Labels and global settings of a pxelinux.cfg file.
+#[derive(Debug, Clone, Default)]
+pub struct PxelinuxConfig {
+    pub default: Option<String>,
+    /// The global APPEND line, used by labels without their own.
+    pub append: Option<String>,
+    pub labels: Vec<PxelinuxLabel>,
+}
+
+impl PxelinuxConfig {
+    pub fn parse(content: &str) -> Self {
+        let mut config = Self::default();
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (keyword, value) = match line.split_once(char::is_whitespace) {
+                Some((keyword, value)) => (keyword, value.trim()),
+                None => (line, ""),
+            };
+
+            let label = config.labels.last_mut();
+            match (keyword.to_ascii_uppercase().as_str(), label) {
+                ("DEFAULT", _) => config.default = Some(value.to_string()),
+                ("LABEL", _) => config.labels.push(PxelinuxLabel {
+                    name: value.to_string(),
+                    ..Default::default()
+                }),
+                ("KERNEL" | "LINUX", Some(label)) => label.kernel = Some(value.to_string()),
+                ("APPEND", Some(label)) => label.append = Some(value.to_string()),
+                ("APPEND", None) => config.append = Some(value.to_string()),
+                _ => {}
+            }
+        }
+
+        config
+    }
+
+    /// The kernel command line for `label`, with loader arguments removed.
+    /// `APPEND -` explicitly clears the global APPEND line.
+    pub fn cmdline(&self, label: &PxelinuxLabel) -> String {
+        match label.append.as_deref().or(self.append.as_deref()) {
+            Some("-") | None => String::new(),
+            Some(append) => strip_arguments(append, NETBOOT_LOADER_ARGUMENTS),
+        }
+    }
+}
+
+/// Command line source for PXELINUX configuration files, yielding one
+/// command line per label.
+pub struct PxelinuxConfigSource {
+    path: PathBuf,
+}
+
+impl PxelinuxConfigSource {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+}
+
+impl CommandLineSource for PxelinuxConfigSource {
+    fn command_lines(&self) -> Result<Vec<SourcedCommandLine>, ParseError> {
+        let config = PxelinuxConfig::parse(&fs::read_to_string(&self.path)?);
+        Ok(config.labels
+            .iter()
+            .map(|label| SourcedCommandLine {
+                source: format!("{}[{}]", self.path.display(), label.name),
+                cmdline: config.cmdline(label),
+            })
+            .collect())
+    }
+}
+
+/// Command line source for iPXE scripts, extracting the arguments that follow
+/// the image name on `kernel`, `chain` and `imgargs` commands.
+pub struct IpxeScriptSource {
+    path: PathBuf,
+}
+
+impl IpxeScriptSource {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Extract the kernel command line from a single script line, if it
+    /// loads or configures a kernel image.
+    pub fn parse_line(line: &str) -> Option<String> {
+        let line = line.trim();
+        let (command, rest) = line.split_once(char::is_whitespace)?;
+        if !matches!(command, "kernel" | "chain" | "imgargs") {
+            return None;
+        }
+
+        // Skip command options such as --name and --autofree, then the image
+        let mut rest = rest.trim_start();
+        while rest.starts_with("--") {
+            rest = rest.split_once(char::is_whitespace).map_or("", |(_, r)| r.trim_start());
+        }
+        let (_image, args) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+
+        Some(strip_arguments(args.trim(), NETBOOT_LOADER_ARGUMENTS))
+    }
+}
+
+impl CommandLineSource for IpxeScriptSource {
+    fn command_lines(&self) -> Result<Vec<SourcedCommandLine>, ParseError> {
+        let content = fs::read_to_string(&self.path)?;
+        Ok(content.lines()
+            .enumerate()
+            .filter_map(|(number, line)| {
+                Self::parse_line(line).map(|cmdline| SourcedCommandLine {
+                    source: format!("{}:{}", self.path.display(), number + 1),
+                    cmdline,
+                })
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,138 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{CommandLineSource, SourcedCommandLine};
+use crate::error::ParseError;
+use crate::taxonomy;
+use crate::validators::{Finding, ValidationResult, COMMAND_LINE_FINDING};
+
+/// Location of cmdline.txt on Raspberry Pi OS since Bookworm.
+pub const RPI_FIRMWARE_CMDLINE_PATH: &str = "/boot/firmware/cmdline.txt";
+/// Location of cmdline.txt on older releases and most other distributions.
+pub const RPI_CMDLINE_PATH: &str = "/boot/cmdline.txt";
+
+/// The contents of a Raspberry Pi cmdline.txt file.
+///
+/// The firmware passes only the first line to the kernel, so arguments
+/// split across several lines are silently lost.
+#[derive(Debug, Clone, Default)]
+pub struct RaspberryPiCmdline {
+    pub cmdline: String,
+    /// Non-empty lines after the first, which the firmware ignores.
+    pub ignored_lines: Vec<String>,
+}
+
+impl RaspberryPiCmdline {
+    pub fn parse(content: &str) -> Self {
+        let mut lines = content.lines();
+        let cmdline = lines.next().unwrap_or("").trim().to_string();
+        let ignored_lines = lines
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .map(|line| line.to_string())
+            .collect();
+
+        Self { cmdline, ignored_lines }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        Ok(Self::parse(&fs::read_to_string(path)?))
+    }
+
+    pub fn is_single_line(&self) -> bool {
+        self.ignored_lines.is_empty()
+    }
+
+    /// Check the single-line constraint, reporting any content the firmware
+    /// would drop.
+    pub fn check_single_line(&self) -> ValidationResult {
+        match self.ignored_lines.len() {
+            0 => ValidationResult::Valid,
+            n => ValidationResult::Error(format!(
+                "cmdline.txt must be a single line; {} further line(s) are ignored by the firmware: '{}'",
+                n,
+                self.ignored_lines.join(" ")
+            )),
+        }
+    }
+}
+
+/// Command line source for the cmdline.txt file read by the Raspberry Pi
+/// firmware.
+pub struct RaspberryPiCmdlineSource {
+    path: PathBuf,
+}
+
+impl RaspberryPiCmdlineSource {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Use the firmware partition location if present, otherwise the
+    /// traditional one.
+    pub fn system() -> Self {
+        if Path::new(RPI_FIRMWARE_CMDLINE_PATH).exists() {
+            Self::new(RPI_FIRMWARE_CMDLINE_PATH)
+        } else {
+            Self::new(RPI_CMDLINE_PATH)
+        }
+    }
+}
+
+impl CommandLineSource for RaspberryPiCmdlineSource {
+    fn command_lines(&self) -> Result<Vec<SourcedCommandLine>, ParseError> {
+        let cmdline = RaspberryPiCmdline::load(&self.path)?;
+        Ok(vec![SourcedCommandLine {
+            source: self.path.display().to_string(),
+            cmdline: cmdline.cmdline,
+        }])
+    }
+
+    /// Lines after the first, which the firmware drops.
+    fn source_findings(&self) -> Result<Vec<Finding>, ParseError> {
+        let cmdline = RaspberryPiCmdline::load(&self.path)?;
+        Ok(match cmdline.check_single_line() {
+            ValidationResult::Valid => Vec::new(),
+            result => vec![
+                Finding::new(COMMAND_LINE_FINDING, result, None)
+                    .with_rule_info(&taxonomy::IGNORED_SOURCE_CONTENT)
+            ],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::ParameterCatalog;
+    use crate::parser::CommandLineParser;
+
+    fn ignored_content(name: &str, content: &str) -> Vec<ValidationResult> {
+        let path = std::env::temp_dir().join(format!("libkcmdline-rpi-{}-{}.txt", name, std::process::id()));
+        fs::write(&path, content).unwrap();
+        let catalog = ParameterCatalog::load_embedded().unwrap();
+        let parsed = CommandLineParser::new(&catalog).parse_source(&RaspberryPiCmdlineSource::new(&path)).unwrap();
+        fs::remove_file(&path).unwrap();
+        parsed[0].validation_summary.details.iter()
+            .filter(|finding| finding.rule.as_deref() == Some(taxonomy::IGNORED_SOURCE_CONTENT.name))
+            .map(|finding| finding.result.clone())
+            .collect()
+    }
+
+    #[test]
+    fn reports_lines_the_firmware_ignores() {
+        let findings = ignored_content("split", "console=serial0,115200 root=/dev/mmcblk0p2
+quiet splash
+");
+        assert!(matches!(findings.as_slice(), [ValidationResult::Error(message)] if message.contains("quiet splash")));
+    }
+
+    #[test]
+    fn accepts_a_single_line() {
+        assert!(ignored_content("single", "console=serial0,115200 root=/dev/mmcblk0p2 quiet
+
+").is_empty());
+    }
+}
@@ -0,0 +1,306 @@
+//! Minimal POSIX shell parsing for configuration files that are sourced by
+//! shell scripts, such as /etc/default/grub.
+//!
+//! Only assignments are evaluated. Quoting, backslash escapes and references
+//! to previously assigned variables, including `${NAME:-default}` and
+//! `${NAME-default}` defaults, are expanded; command substitutions are
+//! skipped and expand to nothing.
+
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::error::ParseError;
+
+struct Lexer<'s> {
+    chars: Peekable<Chars<'s>>,
+    line: usize,
+}
+
+impl<'s> Lexer<'s> {
+    fn new(input: &'s str) -> Self {
+        Self {
+            chars: input.chars().peekable(),
+            line: 1,
+        }
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next();
+        if c == Some('\n') {
+            self.line += 1;
+        }
+        c
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().copied()
+    }
+
+    fn error(&self, msg: &str) -> ParseError {
+        ParseError::SyntaxError(format!("line {}: {}", self.line, msg))
+    }
+
+    fn skip_comment(&mut self) {
+        while let Some(c) = self.peek() {
+            if c == '\n' {
+                break;
+            }
+            self.next();
+        }
+    }
+
+    fn read_name(&mut self) -> String {
+        let mut name = String::new();
+        while let Some(c) = self.peek() {
+            if !(c.is_ascii_alphanumeric() || c == '_') {
+                break;
+            }
+            name.push(c);
+            self.next();
+        }
+        name
+    }
+
+    /// Read one word, removing quotes and expanding variable references.
+    fn read_word(&mut self, vars: &HashMap<String, String>, word: &mut String) -> Result<(), ParseError> {
+        self.read_until(vars, word, |c| matches!(c, ' ' | '\t' | '\n' | ';'))
+    }
+
+    /// Read up to the first unquoted character matching `end`, removing
+    /// quotes and expanding variable references.
+    fn read_until(&mut self, vars: &HashMap<String, String>, word: &mut String, end: fn(char) -> bool) -> Result<(), ParseError> {
+        while let Some(c) = self.peek() {
+            match c {
+                c if end(c) => break,
+                '\\' => {
+                    self.next();
+                    match self.next() {
+                        Some('\n') => {}
+                        Some(escaped) => word.push(escaped),
+                        None => word.push('\\'),
+                    }
+                }
+                '\'' => {
+                    self.next();
+                    loop {
+                        match self.next() {
+                            Some('\'') => break,
+                            Some(c) => word.push(c),
+                            None => return Err(self.error("unterminated single quote")),
+                        }
+                    }
+                }
+                '"' => {
+                    self.next();
+                    self.read_double_quoted(vars, word)?;
+                }
+                '$' => {
+                    self.next();
+                    self.expand(vars, word)?;
+                }
+                '`' => {
+                    self.next();
+                    self.skip_backquoted()?;
+                }
+                _ => {
+                    word.push(c);
+                    self.next();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn read_double_quoted(&mut self, vars: &HashMap<String, String>, word: &mut String) -> Result<(), ParseError> {
+        loop {
+            match self.next() {
+                Some('"') => return Ok(()),
+                Some('\\') => match self.next() {
+                    Some('\n') => {}
+                    Some(c @ ('$' | '`' | '"' | '\\')) => word.push(c),
+                    Some(c) => {
+                        word.push('\\');
+                        word.push(c);
+                    }
+                    None => return Err(self.error("unterminated double quote")),
+                },
+                Some('$') => self.expand(vars, word)?,
+                Some('`') => self.skip_backquoted()?,
+                Some(c) => word.push(c),
+                None => return Err(self.error("unterminated double quote")),
+            }
+        }
+    }
+
+    /// Expand the parameter reference following a '$'.
+    fn expand(&mut self, vars: &HashMap<String, String>, word: &mut String) -> Result<(), ParseError> {
+        match self.peek() {
+            Some('{') => {
+                self.next();
+                let name = self.read_name();
+                let value = vars.get(&name).map(String::as_str);
+                match self.next() {
+                    Some('}') => word.push_str(value.unwrap_or("")),
+                    // ${name:-word} also uses the default for an empty value
+                    Some(':') if self.peek() == Some('-') => {
+                        self.next();
+                        self.expand_default(vars, value.filter(|value| !value.is_empty()), word)?;
+                    }
+                    Some('-') => self.expand_default(vars, value, word)?,
+                    _ => return Err(self.error(&format!("bad substitution for '{}'", name))),
+                }
+            }
+            Some('(') => {
+                self.next();
+                self.skip_command_substitution()?;
+            }
+            Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+                let name = self.read_name();
+                word.push_str(vars.get(&name).map(String::as_str).unwrap_or(""));
+            }
+            _ => word.push('$'),
+        }
+        Ok(())
+    }
+
+    /// Expand the default word of a `${name-word}` reference, up to its
+    /// closing brace, in place of `value` if that is unset.
+    fn expand_default(&mut self, vars: &HashMap<String, String>, value: Option<&str>, word: &mut String) -> Result<(), ParseError> {
+        let mut default = String::new();
+        self.read_until(vars, &mut default, |c| c == '}')?;
+        if self.next() != Some('}') {
+            return Err(self.error("unterminated parameter default"));
+        }
+        word.push_str(value.unwrap_or(&default));
+        Ok(())
+    }
+
+    fn skip_command_substitution(&mut self) -> Result<(), ParseError> {
+        let mut depth = 1;
+        let mut quote = None;
+        while depth > 0 {
+            match (self.next(), quote) {
+                (None, _) => return Err(self.error("unterminated command substitution")),
+                (Some('\\'), _) => {
+                    self.next();
+                }
+                (Some(c @ ('\'' | '"')), None) => quote = Some(c),
+                (Some(c), Some(q)) if c == q => quote = None,
+                (Some('('), None) => depth += 1,
+                (Some(')'), None) => depth -= 1,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn skip_backquoted(&mut self) -> Result<(), ParseError> {
+        loop {
+            match self.next() {
+                Some('`') => return Ok(()),
+                Some('\\') => {
+                    self.next();
+                }
+                Some(_) => {}
+                None => return Err(self.error("unterminated command substitution")),
+            }
+        }
+    }
+}
+
+/// Split a shell command line into words, removing quoting and escapes.
+/// Variable references expand to nothing.
+pub(crate) fn split_words(input: &str) -> Result<Vec<String>, ParseError> {
+    let vars = HashMap::new();
+    let mut words = Vec::new();
+    let mut lexer = Lexer::new(input);
+
+    while let Some(c) = lexer.peek() {
+        match c {
+            ' ' | '\t' | '\n' | ';' => {
+                lexer.next();
+            }
+            '#' => lexer.skip_comment(),
+            _ => {
+                let mut word = String::new();
+                lexer.read_word(&vars, &mut word)?;
+                words.push(word);
+            }
+        }
+    }
+
+    Ok(words)
+}
+
+/// Evaluate the `NAME=value` assignments in shell source, returning the
+/// final value of every assigned variable.
+pub(crate) fn parse_assignments(input: &str) -> Result<HashMap<String, String>, ParseError> {
+    let mut vars = HashMap::new();
+    let mut lexer = Lexer::new(input);
+
+    while let Some(c) = lexer.peek() {
+        match c {
+            ' ' | '\t' | '\n' | ';' => {
+                lexer.next();
+            }
+            '#' => lexer.skip_comment(),
+            _ => {
+                let name = if c.is_ascii_alphabetic() || c == '_' {
+                    lexer.read_name()
+                } else {
+                    String::new()
+                };
+
+                if !name.is_empty() && lexer.peek() == Some('=') {
+                    lexer.next();
+                    let mut value = String::new();
+                    lexer.read_word(&vars, &mut value)?;
+                    vars.insert(name, value);
+                } else {
+                    // Any other word (export, conditionals, commands) is skipped
+                    let mut word = name;
+                    lexer.read_word(&vars, &mut word)?;
+                }
+            }
+        }
+    }
+
+    Ok(vars)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_defaults_of_unset_variables() {
+        let vars = parse_assignments("A=\"${UNSET:-quiet splash}\"\nB=${UNSET-rw}\n").unwrap();
+        assert_eq!(vars["A"], "quiet splash");
+        assert_eq!(vars["B"], "rw");
+    }
+
+    #[test]
+    fn expands_defaults_of_empty_variables_only_with_a_colon() {
+        let vars = parse_assignments("EMPTY=\nA=${EMPTY:-quiet}\nB=${EMPTY-quiet}\n").unwrap();
+        assert_eq!(vars["A"], "quiet");
+        assert_eq!(vars["B"], "");
+    }
+
+    #[test]
+    fn prefers_values_of_set_variables() {
+        let vars = parse_assignments("BASE=ro\nA=\"${BASE:-rw} quiet\"\n").unwrap();
+        assert_eq!(vars["A"], "ro quiet");
+    }
+
+    #[test]
+    fn expands_references_in_defaults() {
+        let vars = parse_assignments("BASE=ro\nA=${UNSET:-\"$BASE ${OTHER:-quiet}\"}\n").unwrap();
+        assert_eq!(vars["A"], "ro quiet");
+    }
+
+    #[test]
+    fn rejects_unterminated_defaults() {
+        assert!(parse_assignments("A=${UNSET:-quiet\n").is_err());
+    }
+}
@@ -0,0 +1,110 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use indexmap::IndexMap;
+
+use super::{expand_variables, CommandLineSource, SourcedCommandLine};
+use crate::error::ParseError;
+
+const FW_PRINTENV: &str = "fw_printenv";
+
+/// The variables of a u-boot environment, as printed by `fw_printenv` or
+/// written in a uEnv.txt file.
+#[derive(Debug, Clone, Default)]
+pub struct UbootEnv {
+    variables: IndexMap<String, String>,
+}
+
+impl UbootEnv {
+    /// Parse `name=value` lines. Blank lines and `#` comments, which appear
+    /// in uEnv.txt but not in `fw_printenv` output, are skipped.
+    pub fn parse(content: &str) -> Result<Self, ParseError> {
+        let mut variables = IndexMap::new();
+
+        for line in content.lines() {
+            let line = line.trim_end_matches('\r');
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (name, value) = line.split_once('=').ok_or_else(|| {
+                ParseError::InvalidFormat(format!("malformed u-boot environment line '{}'", line))
+            })?;
+            variables.insert(name.trim().to_string(), value.to_string());
+        }
+
+        Ok(Self { variables })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Read the environment of the running system with `fw_printenv`.
+    pub fn from_fw_printenv() -> Result<Self, ParseError> {
+        let output = Command::new(FW_PRINTENV).output()?;
+        if !output.status.success() {
+            return Err(ParseError::InvalidFormat(format!(
+                "{} failed: {}",
+                FW_PRINTENV,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Self::parse(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.variables.get(name).map(String::as_str)
+    }
+
+    pub fn variables(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.variables.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// The kernel command line u-boot passes to the kernel.
+    pub fn bootargs(&self) -> Option<&str> {
+        self.get("bootargs")
+    }
+
+    /// Expand `$name` and `${name}` references to environment variables, as
+    /// u-boot's shell does when a boot script sets `bootargs`.
+    pub fn expand(&self, text: &str) -> String {
+        expand_variables(text, |name| self.get(name))
+    }
+}
+
+/// Command line source for the `bootargs` variable of a u-boot environment,
+/// read from a dump file or from `fw_printenv`.
+pub struct UbootEnvSource {
+    path: Option<PathBuf>,
+}
+
+impl UbootEnvSource {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: Some(path.as_ref().to_path_buf()),
+        }
+    }
+
+    pub fn system() -> Self {
+        Self { path: None }
+    }
+}
+
+impl CommandLineSource for UbootEnvSource {
+    fn command_lines(&self) -> Result<Vec<SourcedCommandLine>, ParseError> {
+        let (env, origin) = match &self.path {
+            Some(path) => (UbootEnv::load(path)?, path.display().to_string()),
+            None => (UbootEnv::from_fw_printenv()?, FW_PRINTENV.to_string()),
+        };
+
+        Ok(env.bootargs()
+            .map(|bootargs| SourcedCommandLine {
+                source: format!("{}:bootargs", origin),
+                cmdline: env.expand(bootargs),
+            })
+            .into_iter()
+            .collect())
+    }
+}
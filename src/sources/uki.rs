@@ -0,0 +1,142 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{CommandLineSource, SourcedCommandLine};
+use crate::error::ParseError;
+
+const DOS_MAGIC: &[u8] = b"MZ";
+const PE_MAGIC: &[u8] = b"PE\0\0";
+const COFF_HEADER_SIZE: usize = 20;
+const SECTION_HEADER_SIZE: usize = 40;
+
+/// A section header from the PE section table.
+#[derive(Debug, Clone)]
+struct Section {
+    name: String,
+    virtual_size: u32,
+    raw_size: u32,
+    raw_offset: u32,
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn truncated() -> ParseError {
+    ParseError::InvalidFormat("Truncated PE image".to_string())
+}
+
+/// Extracts the embedded `.cmdline` section from a Unified Kernel Image.
+///
+/// Only the PE headers needed to locate sections are parsed, so no object
+/// file library is required.
+pub struct UkiCmdlineExtractor {
+    path: PathBuf,
+}
+
+impl UkiCmdlineExtractor {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Read the `.cmdline` section from the image file, returning `None` if
+    /// the image does not embed a command line.
+    pub fn extract(&self) -> Result<Option<String>, ParseError> {
+        Self::extract_from_bytes(&fs::read(&self.path)?)
+    }
+
+    pub fn extract_from_bytes(image: &[u8]) -> Result<Option<String>, ParseError> {
+        let section = match Self::sections(image)?.into_iter().find(|s| s.name == ".cmdline") {
+            Some(section) => section,
+            None => return Ok(None),
+        };
+
+        // VirtualSize is the real length; raw data is padded to file alignment
+        let size = if section.virtual_size == 0 {
+            section.raw_size
+        } else {
+            section.virtual_size.min(section.raw_size)
+        } as usize;
+        let start = section.raw_offset as usize;
+        let data = image.get(start..start + size).ok_or_else(truncated)?;
+
+        let data = match data.iter().position(|&b| b == 0) {
+            Some(nul) => &data[..nul],
+            None => data,
+        };
+        let cmdline = std::str::from_utf8(data)
+            .map_err(|e| ParseError::InvalidFormat(format!("UKI .cmdline is not valid UTF-8: {}", e)))?;
+
+        Ok(Some(cmdline.trim_end().to_string()))
+    }
+
+    /// The architecture the image was built for, from the COFF machine type.
+    pub fn architecture(image: &[u8]) -> Result<Option<&'static str>, ParseError> {
+        let pe_offset = Self::pe_header_offset(image)?;
+        let machine = read_u16(image, pe_offset + PE_MAGIC.len()).ok_or_else(truncated)?;
+
+        Ok(match machine {
+            0x014c => Some("x86"),
+            0x8664 => Some("x86_64"),
+            0x01c2 | 0x01c4 => Some("arm"),
+            0xaa64 => Some("aarch64"),
+            0x5064 => Some("riscv64"),
+            0x6264 => Some("loongarch64"),
+            _ => None,
+        })
+    }
+
+    fn pe_header_offset(image: &[u8]) -> Result<usize, ParseError> {
+        if !image.starts_with(DOS_MAGIC) {
+            return Err(ParseError::InvalidFormat("Not a PE image: missing MZ header".to_string()));
+        }
+
+        let pe_offset = read_u32(image, 0x3c).ok_or_else(truncated)? as usize;
+        if image.get(pe_offset..pe_offset + PE_MAGIC.len()) != Some(PE_MAGIC) {
+            return Err(ParseError::InvalidFormat("Not a PE image: missing PE signature".to_string()));
+        }
+
+        Ok(pe_offset)
+    }
+
+    fn sections(image: &[u8]) -> Result<Vec<Section>, ParseError> {
+        let coff = Self::pe_header_offset(image)? + PE_MAGIC.len();
+        let section_count = read_u16(image, coff + 2).ok_or_else(truncated)? as usize;
+        let optional_header_size = read_u16(image, coff + 16).ok_or_else(truncated)? as usize;
+        let table = coff + COFF_HEADER_SIZE + optional_header_size;
+
+        (0..section_count)
+            .map(|i| {
+                let header = table + i * SECTION_HEADER_SIZE;
+                let raw_name = image.get(header..header + 8).ok_or_else(truncated)?;
+                let name_len = raw_name.iter().position(|&b| b == 0).unwrap_or(8);
+
+                Ok(Section {
+                    name: String::from_utf8_lossy(&raw_name[..name_len]).into_owned(),
+                    virtual_size: read_u32(image, header + 8).ok_or_else(truncated)?,
+                    raw_size: read_u32(image, header + 16).ok_or_else(truncated)?,
+                    raw_offset: read_u32(image, header + 20).ok_or_else(truncated)?,
+                })
+            })
+            .collect()
+    }
+}
+
+impl CommandLineSource for UkiCmdlineExtractor {
+    fn command_lines(&self) -> Result<Vec<SourcedCommandLine>, ParseError> {
+        let cmdline = self.extract()?.ok_or_else(|| {
+            ParseError::MissingValue(format!("{}: no .cmdline section", self.path.display()))
+        })?;
+
+        Ok(vec![SourcedCommandLine {
+            source: format!("{}:.cmdline", self.path.display()),
+            cmdline,
+        }])
+    }
+}
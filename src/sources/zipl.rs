@@ -0,0 +1,279 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{BlsEntrySource, CommandLineSource, SourcedCommandLine};
+use crate::error::ParseError;
+use crate::validators::command_line_size;
+
+pub const ZIPL_CONF_PATH: &str = "/etc/zipl.conf";
+
+/// Longest `parameters=` value zipl accepts for kernels that do not record
+/// a larger limit in their parameter area (the historical s390
+/// COMMAND_LINE_SIZE of 896 bytes, less the terminating NUL).
+pub const ZIPL_LEGACY_PARAMETERS_LIMIT: usize = 895;
+
+/// A boot section of a zipl configuration file.
+#[derive(Debug, Clone, Default)]
+pub struct ZiplSection {
+    pub name: String,
+    pub image: Option<String>,
+    pub ramdisk: Option<String>,
+    pub parameters: Option<String>,
+}
+
+impl ZiplSection {
+    /// Whether the section's `parameters=` value is longer than `limit`,
+    /// such as [`ZIPL_LEGACY_PARAMETERS_LIMIT`].
+    pub fn exceeds_parameters_limit(&self, limit: usize) -> bool {
+        self.parameters.as_ref().is_some_and(|p| p.len() > limit)
+    }
+}
+
+/// The boot sections of a zipl.conf file.
+#[derive(Debug, Clone, Default)]
+pub struct ZiplConfig {
+    pub default: Option<String>,
+    pub sections: Vec<ZiplSection>,
+}
+
+/// Join physical lines into logical ones: a trailing backslash or an
+/// unterminated double quote continues the value onto the next line.
+fn logical_lines(content: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if current.is_empty() && line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(continued) = line.strip_suffix('\\') {
+            current.push_str(continued.trim_end());
+            current.push(' ');
+            continue;
+        }
+
+        current.push_str(line);
+        if current.matches('"').count() % 2 == 1 {
+            current.push(' ');
+            continue;
+        }
+        lines.push(std::mem::take(&mut current));
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+
+    lines
+}
+
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    value.strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+impl ZiplConfig {
+    pub fn parse(content: &str) -> Result<Self, ParseError> {
+        let mut config = Self::default();
+        let mut section: Option<ZiplSection> = None;
+        let mut in_defaultboot = false;
+
+        for line in logical_lines(content) {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                config.sections.extend(section.take().filter(|s| s.image.is_some()));
+                in_defaultboot = name == "defaultboot";
+                if !in_defaultboot {
+                    section = Some(ZiplSection {
+                        name: name.to_string(),
+                        ..Default::default()
+                    });
+                }
+                continue;
+            }
+
+            // Menu sections start with ':' and only reference boot sections
+            if line.starts_with(':') {
+                config.sections.extend(section.take().filter(|s| s.image.is_some()));
+                in_defaultboot = false;
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = unquote(value).to_string();
+
+            match (key.trim(), section.as_mut()) {
+                ("default", _) if in_defaultboot => config.default = Some(value),
+                ("image", Some(section)) => section.image = Some(value),
+                ("ramdisk", Some(section)) => section.ramdisk = Some(value),
+                ("parameters", Some(section)) => section.parameters = Some(value),
+                _ => {}
+            }
+        }
+        config.sections.extend(section.filter(|s| s.image.is_some()));
+
+        Ok(config)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn section(&self, name: &str) -> Option<&ZiplSection> {
+        self.sections.iter().find(|s| s.name == name)
+    }
+
+    pub fn default_section(&self) -> Option<&ZiplSection> {
+        self.default.as_deref().and_then(|name| self.section(name))
+    }
+}
+
+/// Command line source for zipl, the s390x boot loader installer.
+///
+/// Reads the boot sections of zipl.conf and, when an entries directory is
+/// configured, Boot Loader Specification entries as used by zipl's BLS
+/// support.
+pub struct ZiplConfigSource {
+    path: PathBuf,
+    bls_entries: Option<PathBuf>,
+    legacy_limit: bool,
+}
+
+impl ZiplConfigSource {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            bls_entries: None,
+            legacy_limit: false,
+        }
+    }
+
+    pub fn system() -> Self {
+        Self::new(ZIPL_CONF_PATH).with_bls_entries(super::BLS_ENTRIES_PATH)
+    }
+
+    pub fn with_bls_entries<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.bls_entries = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Report command lines over [`ZIPL_LEGACY_PARAMETERS_LIMIT`] as errors,
+    /// for kernels that do not record a larger limit. By default they are
+    /// warned of and only those over s390x's COMMAND_LINE_SIZE are errors.
+    pub fn with_legacy_limit(mut self) -> Self {
+        self.legacy_limit = true;
+        self
+    }
+}
+
+impl CommandLineSource for ZiplConfigSource {
+    fn command_lines(&self) -> Result<Vec<SourcedCommandLine>, ParseError> {
+        let mut command_lines = Vec::new();
+
+        if self.path.exists() {
+            let config = ZiplConfig::load(&self.path)?;
+            for section in config.sections {
+                command_lines.push(SourcedCommandLine {
+                    source: format!("{}[{}]", self.path.display(), section.name),
+                    cmdline: section.parameters.unwrap_or_default(),
+                });
+            }
+        }
+
+        if let Some(entries) = self.bls_entries.as_ref().filter(|p| p.is_dir()) {
+            command_lines.extend(BlsEntrySource::new(entries).command_lines()?);
+        }
+
+        Ok(command_lines)
+    }
+
+    fn length_limit(&self) -> Option<usize> {
+        if self.legacy_limit {
+            Some(ZIPL_LEGACY_PARAMETERS_LIMIT)
+        } else {
+            command_line_size("s390x").map(|size| size - 1)
+        }
+    }
+
+    fn legacy_length_limit(&self) -> Option<usize> {
+        (!self.legacy_limit).then_some(ZIPL_LEGACY_PARAMETERS_LIMIT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::ParameterCatalog;
+    use crate::parser::CommandLineParser;
+    use crate::validators::{ValidationResult, COMMAND_LINE_FINDING};
+
+    fn zipl_conf(name: &str, parameters: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("libkcmdline-zipl-{}-{}.conf", name, std::process::id()));
+        let content = format!(
+            "[defaultboot]\ndefault=linux\n\n[linux]\nimage=/boot/vmlinuz\nparameters=\"{}\"\n",
+            parameters,
+        );
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn length_findings(source: ZiplConfigSource, path: &Path) -> Vec<ValidationResult> {
+        let catalog = ParameterCatalog::load_embedded().unwrap();
+        let parsed = CommandLineParser::new(&catalog).parse_source(&source).unwrap();
+        fs::remove_file(path).unwrap();
+        parsed[0].validation_summary.details.iter()
+            .filter(|finding| finding.parameter == COMMAND_LINE_FINDING)
+            .map(|finding| finding.result.clone())
+            .collect()
+    }
+
+    fn long_parameters(length: usize) -> String {
+        let long = format!("root=/dev/dasda1 {}", "quiet ".repeat(length / 6).trim_end());
+        assert!(long.len() > length);
+        long
+    }
+
+    #[test]
+    fn section_limit() {
+        let config = ZiplConfig::parse("[linux]\nimage=/boot/vmlinuz\nparameters=\"root=/dev/dasda1 quiet\"\n").unwrap();
+        let section = config.section("linux").unwrap();
+        assert!(!section.exceeds_parameters_limit(ZIPL_LEGACY_PARAMETERS_LIMIT));
+        assert!(section.exceeds_parameters_limit(10));
+    }
+
+    #[test]
+    fn warns_of_parameters_over_legacy_limit() {
+        let path = zipl_conf("long", &long_parameters(ZIPL_LEGACY_PARAMETERS_LIMIT));
+        let findings = length_findings(ZiplConfigSource::new(&path), &path);
+        assert!(matches!(findings.as_slice(), [ValidationResult::Warning(_)]));
+    }
+
+    #[test]
+    fn reports_parameters_over_legacy_limit_in_legacy_mode() {
+        let path = zipl_conf("legacy", &long_parameters(ZIPL_LEGACY_PARAMETERS_LIMIT));
+        let findings = length_findings(ZiplConfigSource::new(&path).with_legacy_limit(), &path);
+        assert!(matches!(findings.as_slice(), [ValidationResult::Error(_)]));
+    }
+
+    #[test]
+    fn reports_parameters_over_command_line_size() {
+        let path = zipl_conf("huge", &long_parameters(4096));
+        let findings = length_findings(ZiplConfigSource::new(&path), &path);
+        assert!(matches!(findings.as_slice(), [ValidationResult::Error(_)]));
+    }
+
+    #[test]
+    fn accepts_parameters_within_legacy_limit() {
+        let path = zipl_conf("short", "root=/dev/dasda1 quiet");
+        assert!(length_findings(ZiplConfigSource::new(&path), &path).is_empty());
+    }
+}
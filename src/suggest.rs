@@ -0,0 +1,265 @@
+//! Approximate name matching used to suggest corrections for misspelt
+//! parameter names and for as-you-type lookup.
+
+/// Optimal string alignment distance: the number of single character
+/// insertions, deletions, substitutions and adjacent transpositions needed
+/// to turn `a` into `b`.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let mut prev_prev: Vec<usize> = vec![0; b.len() + 1];
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut current: Vec<usize> = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        current[0] = i;
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            current[j] = (prev[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(prev[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(prev_prev[j - 2] + 1);
+            }
+        }
+        std::mem::swap(&mut prev_prev, &mut prev);
+        std::mem::swap(&mut prev, &mut current);
+    }
+
+    prev[b.len()]
+}
+
+/// The shortest name for which misspellings are suggested; shorter names
+/// are within an edit or two of too many others.
+pub(crate) const MIN_SUGGESTION_LENGTH: usize = 3;
+
+/// The largest edit distance at which a name is still considered a likely
+/// misspelling of `term`: short names allow fewer edits so that unrelated
+/// short names are not offered, and no name is as far from `term` as its
+/// length, which would replace every character.
+pub(crate) fn max_edit_distance(term: &str) -> usize {
+    let length = term.chars().count();
+    let distance = if length <= 4 { 1 } else { 2 };
+    distance.min(length.saturating_sub(1))
+}
+
+/// Characters that separate the words of a parameter name.
+fn is_word_boundary(c: char) -> bool {
+    matches!(c, '.' | '_' | '-' | '/')
+}
+
+/// Score how well `candidate` matches a partially typed `term`, ignoring
+/// case, or `None` if it does not match at all. Each kind of match has its
+/// own band of scores, so that no score of one kind reaches another's
+/// whatever the length of the name; in decreasing order: exact matches
+/// (1000), prefixes (801-899), substrings starting a word (701-799), other
+/// substrings (601-699), subsequences (201-599, e.g. "rdluuid" for
+/// "rd.luks.uuid") and names within a small edit distance (100-150).
+pub fn fuzzy_score(term: &str, candidate: &str) -> Option<u32> {
+    let term = term.to_lowercase();
+    let candidate = candidate.to_lowercase();
+    if term.is_empty() {
+        return None;
+    }
+    // Never zero below an exact match, as the names then differ in length
+    let extra = (candidate.chars().count().saturating_sub(term.chars().count())).clamp(1, 99) as u32;
+
+    if candidate == term {
+        return Some(1000);
+    }
+    if candidate.starts_with(&term) {
+        return Some(900 - extra);
+    }
+    if let Some(position) = candidate.find(&term) {
+        // Matches starting a word ("luks" in "rd.luks.uuid") rank higher
+        let at_word = candidate[..position].ends_with(is_word_boundary);
+        return Some(if at_word { 800 } else { 700 } - extra);
+    }
+    if let Some(score) = subsequence_score(&term, &candidate) {
+        return Some(score);
+    }
+
+    let distance = edit_distance(&term, &candidate);
+    if distance <= max_edit_distance(&term) {
+        return Some(200 - 50 * distance as u32);
+    }
+    None
+}
+
+/// Score an in-order, possibly gapped match of `term` in `candidate`,
+/// rewarding characters that continue a run or start a word, and
+/// penalising skipped characters and matches that jump into the middle of
+/// a later word.
+fn subsequence_score(term: &str, candidate: &str) -> Option<u32> {
+    let mut score: i64 = 400;
+    let mut chars = candidate.chars();
+    let mut previous: Option<char> = None;
+    let mut in_run = false;
+    let mut crossed_word = false;
+
+    for (i, t) in term.chars().enumerate() {
+        loop {
+            let c = chars.next()?;
+            let starts_word = previous.is_none_or(is_word_boundary);
+            previous = Some(c);
+            if c == t {
+                if in_run {
+                    score += 3;
+                } else if starts_word {
+                    score += 2;
+                } else if crossed_word {
+                    score -= 3;
+                }
+                in_run = true;
+                crossed_word = false;
+                break;
+            }
+            if i > 0 {
+                score -= 1;
+            }
+            crossed_word |= is_word_boundary(c);
+            in_run = false;
+        }
+    }
+    // Keep subsequence matches within their band
+    Some(score.clamp(201, 599) as u32)
+}
+
+#[derive(Debug, Clone)]
+struct BkNode {
+    word: String,
+    children: Vec<(usize, usize)>,
+}
+
+/// A BK-tree over a set of names, answering "which names are within edit
+/// distance N of this one" without comparing against every entry.
+///
+/// Optimal string alignment distance is not strictly a metric, so a match
+/// that depends on overlapping transpositions can occasionally be missed.
+/// That is an acceptable trade for catching swapped characters in typos.
+#[derive(Debug, Clone, Default)]
+pub struct SimilarityIndex {
+    nodes: Vec<BkNode>,
+}
+
+impl SimilarityIndex {
+    pub fn new<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut index = Self::default();
+        for word in words {
+            index.insert(word);
+        }
+        index
+    }
+
+    pub fn insert<S: Into<String>>(&mut self, word: S) {
+        let word = word.into();
+        if self.nodes.is_empty() {
+            self.nodes.push(BkNode { word, children: Vec::new() });
+            return;
+        }
+
+        let mut node = 0;
+        loop {
+            let distance = edit_distance(&word, &self.nodes[node].word);
+            if distance == 0 {
+                return;
+            }
+            match self.nodes[node].children.iter().find(|(d, _)| *d == distance) {
+                Some(&(_, child)) => node = child,
+                None => {
+                    let child = self.nodes.len();
+                    self.nodes.push(BkNode { word, children: Vec::new() });
+                    self.nodes[node].children.push((distance, child));
+                    return;
+                }
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// All indexed names within `max_distance` of `word`, closest first.
+    pub fn find(&self, word: &str, max_distance: usize) -> Vec<(&str, usize)> {
+        let mut matches = Vec::new();
+        if self.nodes.is_empty() {
+            return matches;
+        }
+
+        let mut pending = vec![0];
+        while let Some(node) = pending.pop() {
+            let node = &self.nodes[node];
+            let distance = edit_distance(word, &node.word);
+            if distance <= max_distance {
+                matches.push((node.word.as_str(), distance));
+            }
+            // Triangle inequality: only subtrees in this band can match
+            for &(child_distance, child) in &node.children {
+                if child_distance + max_distance >= distance && child_distance <= distance + max_distance {
+                    pending.push(child);
+                }
+            }
+        }
+
+        matches.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(b.0)));
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(edit_distance("quiet", "quiet"), 0);
+        assert_eq!(edit_distance("quite", "quiet"), 1);
+        assert_eq!(edit_distance("nosmp", "nosmt"), 1);
+        assert_eq!(edit_distance("", "rw"), 2);
+    }
+
+    #[test]
+    fn fuzzy_score_bands_are_disjoint() {
+        let long = "x".repeat(200);
+        assert_eq!(fuzzy_score("Quiet", "quiet"), Some(1000));
+        let prefix = fuzzy_score("rd", &format!("rd{}", long)).unwrap();
+        let word = fuzzy_score("luks", "rd.luks.uuid").unwrap();
+        let at_word = fuzzy_score("luks", &format!("rd.luks{}", long)).unwrap();
+        let inner = fuzzy_score("uks", "rd.luks").unwrap();
+        let substring = fuzzy_score("uks", &format!("rd.luks{}", long)).unwrap();
+        let subsequence = fuzzy_score("rdluuid", "rd.luks.uuid").unwrap();
+        let typo = fuzzy_score("quite", "quiet").unwrap();
+        assert!(prefix > 800 && word < 800 && at_word > 700);
+        assert!(inner < 700 && substring > 600);
+        assert!(subsequence < 600 && subsequence > 200);
+        assert!(typo <= 200);
+        assert_eq!(fuzzy_score("xyz", "quiet"), None);
+        assert_eq!(fuzzy_score("", "quiet"), None);
+    }
+
+    #[test]
+    fn fuzzy_score_penalises_gaps_and_crossing_words() {
+        // Skipping characters costs, as does landing mid-word past a boundary
+        assert!(fuzzy_score("abc", "a_b_c") > fuzzy_score("abc", "axxbxxc"));
+        assert!(fuzzy_score("ab", "axb") > fuzzy_score("ab", "axxxb"));
+        assert!(fuzzy_score("ab", "axb") > fuzzy_score("ab", "a_xb"));
+    }
+
+    #[test]
+    fn max_edit_distance_is_below_term_length() {
+        assert_eq!(max_edit_distance("r"), 0);
+        assert_eq!(max_edit_distance("ro"), 1);
+        assert_eq!(max_edit_distance("rw0"), 1);
+        assert_eq!(max_edit_distance("quiet"), 2);
+    }
+}
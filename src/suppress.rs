@@ -0,0 +1,171 @@
+//! Suppression of accepted findings, by rule and parameter or from a
+//! baseline recording the findings a fleet has today, so that validation
+//! can gate CI on new problems without first fixing every old one.
+
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::ReportError;
+use crate::fleet::FleetReport;
+use crate::validators::{Finding, ValidationResult, ValidationSummary};
+
+/// The version of the baseline file format.
+pub const BASELINE_SCHEMA_VERSION: u32 = 1;
+
+/// Findings to leave out of a validation summary: those of a check, given
+/// by rule code or name, optionally only about one parameter or with one
+/// message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Suppression {
+    pub rule: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parameter: Option<String>,
+    /// The exact message, as recorded by a baseline.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Why the findings are accepted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl Suppression {
+    /// Suppress every finding of the check with the code or name `rule`.
+    pub fn new(rule: &str) -> Self {
+        Self {
+            rule: rule.to_string(),
+            parameter: None,
+            message: None,
+            reason: None,
+        }
+    }
+
+    pub fn for_parameter(mut self, parameter: &str) -> Self {
+        self.parameter = Some(parameter.to_string());
+        self
+    }
+
+    pub fn with_message(mut self, message: &str) -> Self {
+        self.message = Some(message.to_string());
+        self
+    }
+
+    pub fn with_reason(mut self, reason: &str) -> Self {
+        self.reason = Some(reason.to_string());
+        self
+    }
+
+    /// Whether the suppression applies to `finding`; valid results are
+    /// never suppressed.
+    pub fn matches(&self, finding: &Finding) -> bool {
+        let Some(message) = message_of(&finding.result) else {
+            return false;
+        };
+        finding.is_rule(&self.rule)
+            && self.parameter.as_ref().is_none_or(|parameter| *parameter == finding.parameter)
+            && self.message.as_ref().is_none_or(|expected| expected == message)
+    }
+}
+
+/// A set of suppressions, which may be saved as a baseline file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Suppressions {
+    pub suppressions: Vec<Suppression>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedSuppressions<S> {
+    schema_version: u32,
+    suppressions: S,
+}
+
+impl Suppressions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, suppression: Suppression) -> Self {
+        self.suppressions.push(suppression);
+        self
+    }
+
+    /// A baseline accepting every finding of `summary` that a check
+    /// reported, matched by rule, parameter and message but not position,
+    /// so that it still applies when arguments move.
+    pub fn baseline(summary: &ValidationSummary) -> Self {
+        let mut baseline = Self::new();
+        for finding in &summary.details {
+            let (Some(rule), Some(message)) = (&finding.rule, message_of(&finding.result)) else {
+                continue;
+            };
+            baseline.accept(Suppression::new(rule).for_parameter(&finding.parameter).with_message(message));
+        }
+        baseline
+    }
+
+    /// A baseline accepting every finding of a batch of command lines.
+    pub fn fleet_baseline(report: &FleetReport) -> Self {
+        let mut baseline = Self::new();
+        for rollup in &report.findings {
+            let Some(rule) = &rollup.rule else {
+                continue;
+            };
+            baseline.accept(Suppression::new(rule).for_parameter(&rollup.parameter).with_message(&rollup.message));
+        }
+        baseline
+    }
+
+    fn accept(&mut self, suppression: Suppression) {
+        if !self.suppressions.contains(&suppression) {
+            self.suppressions.push(suppression);
+        }
+    }
+
+    /// The first suppression matching `finding`, if any.
+    pub fn matching(&self, finding: &Finding) -> Option<&Suppression> {
+        self.suppressions.iter().find(|suppression| suppression.matches(finding))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.suppressions.is_empty()
+    }
+
+    pub fn to_json(&self) -> Result<String, ReportError> {
+        let saved = SavedSuppressions { schema_version: BASELINE_SCHEMA_VERSION, suppressions: &self.suppressions };
+        serde_json::to_string_pretty(&saved).map_err(|e| ReportError::SerializationError(e.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, ReportError> {
+        let saved: SavedSuppressions<Vec<Suppression>> = serde_json::from_str(json)
+            .map_err(|e| ReportError::SerializationError(e.to_string()))?;
+        if saved.schema_version != BASELINE_SCHEMA_VERSION {
+            return Err(ReportError::UnsupportedSchemaVersion {
+                found: saved.schema_version,
+                supported: BASELINE_SCHEMA_VERSION,
+            });
+        }
+        Ok(Self { suppressions: saved.suppressions })
+    }
+
+    /// Save the suppressions as a baseline file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ReportError> {
+        fs::write(path, self.to_json()? + "\n")?;
+        Ok(())
+    }
+
+    /// Load a baseline file saved with [`save`](Self::save).
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ReportError> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+}
+
+fn message_of(result: &ValidationResult) -> Option<&str> {
+    match result {
+        ValidationResult::Valid => None,
+        ValidationResult::Info(message)
+        | ValidationResult::Warning(message)
+        | ValidationResult::Error(message)
+        | ValidationResult::Unknown(message) => Some(message),
+    }
+}
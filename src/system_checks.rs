@@ -0,0 +1,389 @@
+//! Checks of a parsed command line against the probed system rather than
+//! the catalog: devices and addresses it names, the crash kernel
+//! reservation it asks for, the kernel options and CPU features parameters
+//! need, their support by the installed distribution and components, the
+//! dracut modules that read them, the parameters of its modules, and the
+//! findings of the [`mitigations`] and
+//! [`hardware_support`] checks.
+//!
+//! [`mitigations`]: crate::mitigations
+//! [`hardware_support`]: crate::hardware_support
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::config::{format_size, CrashKernelPlacement, CrashKernelSpec, IpConfig};
+use crate::hardware_support::hardware_support_findings;
+use crate::mitigations::mitigation_findings;
+use crate::modinfo::ModuleParameterSource;
+use crate::parameter::{ParameterProcessor, DRACUT_MODULE_SELECTOR_PREFIX};
+use crate::parser::ParsedParameter;
+use crate::probe::SystemProbe;
+use crate::query::glob_match;
+use crate::suggest::edit_distance;
+use crate::taxonomy;
+use crate::validators::{Finding, ModuleParameterValidator, ParameterValidator, ValidationResult};
+use crate::version::compare_versions;
+
+/// Every finding about `parameters` that depends on the probed system.
+pub(crate) fn system_findings(parameters: &[ParsedParameter], probe: &SystemProbe) -> Vec<Finding> {
+    let mut findings = network_reference_findings(parameters, probe);
+    findings.extend(mitigation_findings(parameters, probe));
+    findings.extend(crash_kernel_findings(parameters, probe));
+    findings.extend(hardware_support_findings(parameters, probe));
+    findings.extend(required_config_findings(parameters, probe));
+    findings.extend(distribution_findings(parameters, probe));
+    findings.extend(dracut_module_findings(parameters, probe));
+    findings
+}
+
+/// Flag parameters whose selectors rule this system out, such as
+/// `amd_iommu=` on an Intel machine, saying which selector it fails.
+/// Selector categories the probe has no tags for, e.g. PCI devices in a
+/// container without sysfs, are taken to match.
+pub(crate) fn applicability_findings(parameters: &[ParsedParameter], probe: &SystemProbe) -> Vec<Finding> {
+    let tags: Vec<&String> = probe.hardware_tags.iter().chain(&probe.software_tags).collect();
+    let probed = |category: &str| tags.iter().any(|tag| tag.starts_with(&format!("{}:", category)));
+    let mut findings = Vec::new();
+
+    for param in parameters.iter().filter(|p| p.effective) {
+        let Some(def) = &param.parameter_def else {
+            continue;
+        };
+        let unmatched = def.unmatched_selectors(tags.iter().copied());
+        let Some((_, selectors)) = unmatched.into_iter().find(|(category, _)| probed(category)) else {
+            continue;
+        };
+        let targets: Vec<String> = selectors.iter().map(|selector| describe_selector(selector)).collect();
+        let message = format!("Has no effect on this system: it only applies to {}", targets.join(" or "));
+        let finding = Finding::new(&param.name, ValidationResult::Warning(message), Some(param.span.clone()));
+        findings.push(finding.with_rule_info(&taxonomy::INAPPLICABLE_PARAMETER));
+    }
+    findings
+}
+
+/// The systems a selector matches, in words, e.g. `s390x systems` for
+/// `arch:s390x`.
+fn describe_selector(selector: &str) -> String {
+    let (category, value) = selector.split_once(':').unwrap_or((selector, ""));
+    match (category, value.split_once(':')) {
+        ("arch", _) => format!("{} systems", value),
+        ("cpu", Some(("vendor", vendor))) => format!("{} CPUs", vendor),
+        ("cpu", _) => format!("CPUs with the {} flag", value),
+        ("pci", Some((vendor, "*"))) => format!("systems with a PCI device from vendor {}", vendor),
+        ("pci", None) => format!("systems with a PCI device from vendor {}", value),
+        ("pci", Some(_)) => format!("systems with PCI device {}", value),
+        ("pci-class", _) => format!("systems with a PCI device of class {}", value),
+        ("usb", _) => format!("systems with USB device {}", value),
+        ("module", Some((_, module))) => format!("systems with the {} module", module),
+        ("config", _) => format!("kernels built with {}", value),
+        ("dt", _) => format!("devicetree systems compatible with {}", value),
+        _ => format!("systems matching '{}'", selector),
+    }
+}
+
+/// Check the parameters of modules present on this system against what
+/// `modinfo` reports for them: a parameter the module does not have is a
+/// warning, as the kernel ignores it, and a value that does not match the
+/// declared type an error. Modules `modules` does not describe, and
+/// parameters whose catalog definitions already check the type, are
+/// skipped.
+pub(crate) fn module_parameter_findings(
+    parameters: &[ParsedParameter],
+    modules: &ModuleParameterSource,
+    probe: &SystemProbe,
+) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    for param in parameters.iter().filter(|p| p.effective) {
+        let (Some(module), Some((_, name))) = (&param.module, param.name.split_once('.')) else {
+            continue;
+        };
+        if probe.module_state(module).is_none() || !modules.has_module(module) {
+            continue;
+        }
+        if param.parameter_def.as_ref().is_some_and(|def| def.syntax.validator_type == "module_param") {
+            continue;
+        }
+
+        let span = Some(param.diagnostic_span());
+        let Some((declared, parmtype)) = modules.parameter(module, name) else {
+            let suggestions: Vec<String> = modules.parameters_of(module).into_iter()
+                .filter(|candidate| edit_distance(candidate, name) <= 2)
+                .map(|candidate| format!("{}.{}", module, candidate))
+                .collect();
+            let message = format!("Module '{}' has no parameter '{}'; the kernel will ignore it", module, name);
+            let finding = Finding::new(&param.name, ValidationResult::Warning(message), span)
+                .with_rule_info(&taxonomy::UNKNOWN_MODULE_PARAMETER)
+                .with_suggestions(suggestions);
+            findings.push(finding);
+            continue;
+        };
+        let Some(parmtype) = parmtype else {
+            continue;
+        };
+        let config = HashMap::from([("parmtype".to_string(), toml::Value::String(parmtype.to_string()))]);
+        if let ValidationResult::Error(error) = ModuleParameterValidator.validate(param.value.as_deref().unwrap_or(""), &config) {
+            let message = format!("{}; {}.{} is declared as {}", error, module, declared, parmtype);
+            findings.push(Finding::new(&param.name, ValidationResult::Error(message), span).with_rule_info(&taxonomy::INVALID_MODULE_PARAMETER));
+        }
+    }
+    findings
+}
+
+/// Warn about parameters that the installed distribution release does not
+/// support, or that need a newer kernel, systemd or dracut than the one
+/// installed. A distribution's `component_version` takes precedence over
+/// the processor's upstream minimum, as distributions backport parameters
+/// into older releases.
+fn distribution_findings(parameters: &[ParsedParameter], probe: &SystemProbe) -> Vec<Finding> {
+    let distribution = &probe.distribution;
+    let kernel_release = probe.kernel_version.as_ref().map(|kernel| match &kernel.branch {
+        Some(branch) => format!("{}-{}", kernel.version, branch),
+        None => kernel.version.clone(),
+    });
+    let mut findings = Vec::new();
+
+    for param in parameters.iter().filter(|p| p.effective) {
+        let Some(def) = &param.parameter_def else {
+            continue;
+        };
+        let mut warn = |message: String| {
+            let finding = Finding::new(&param.name, ValidationResult::Warning(message), Some(param.span.clone()));
+            findings.push(finding.with_rule_info(&taxonomy::UNSUPPORTED_RELEASE));
+        };
+
+        let resolved = (!distribution.id.is_empty())
+            .then(|| distribution.resolve(|id| def.distributions.contains_key(id)))
+            .flatten();
+        let support = resolved.map(|(id, own)| (&def.distributions[id], own));
+        if let Some((support, true)) = support {
+            if !def.is_available_on(distribution) {
+                let mut message = format!("Not supported on {} {}", distribution.name, distribution.version_id);
+                match (&support.min_version, &support.max_version) {
+                    (Some(min), Some(max)) => message.push_str(&format!("; supported from {} to {}", min, max)),
+                    (Some(min), None) => message.push_str(&format!("; supported from {}", min)),
+                    (None, Some(max)) => message.push_str(&format!("; supported up to {}", max)),
+                    (None, None) => {}
+                }
+                if let Some(notes) = &support.notes {
+                    message.push_str(&format!(" ({})", notes));
+                }
+                warn(message);
+                continue;
+            }
+        }
+
+        let (component, installed, upstream_min) = match &def.processor {
+            ParameterProcessor::Systemd { min_version } => ("systemd", probe.component_version("systemd"), Some(min_version)),
+            ParameterProcessor::Dracut { min_version } => ("dracut", probe.component_version("dracut"), Some(min_version)),
+            ParameterProcessor::Kernel => ("the kernel", kernel_release.as_deref(), None),
+            _ => continue,
+        };
+        let distribution_min = support
+            .and_then(|(support, own)| support.component_version.as_ref().filter(|_| own));
+        let Some(required) = distribution_min.or(upstream_min).filter(|version| !version.is_empty()) else {
+            continue;
+        };
+        if let Some(installed) = installed.filter(|installed| compare_versions(required, installed) == Ordering::Greater) {
+            warn(format!("Requires {} {} or later; this system has {}", component, required, installed));
+        }
+    }
+    findings
+}
+
+/// Warn about parameters read only by dracut modules that an initramfs
+/// built on this system will not include. Only checked when the probe
+/// found the installed dracut modules.
+fn dracut_module_findings(parameters: &[ParsedParameter], probe: &SystemProbe) -> Vec<Finding> {
+    let included: Vec<&str> = probe.software_tags.iter()
+        .filter_map(|tag| tag.strip_prefix(DRACUT_MODULE_SELECTOR_PREFIX))
+        .collect();
+    if included.is_empty() {
+        return Vec::new();
+    }
+    let mut findings = Vec::new();
+
+    for param in parameters.iter().filter(|p| p.effective) {
+        let Some(def) = &param.parameter_def else {
+            continue;
+        };
+        let modules: Vec<&str> = def.dracut_modules().collect();
+        if modules.is_empty() || modules.iter().any(|module| included.contains(module)) {
+            continue;
+        }
+        let message = format!(
+            "Has no effect: the initramfs will not include the dracut module {} that reads it",
+            modules.join(" or ")
+        );
+        let finding = Finding::new(&param.name, ValidationResult::Warning(message), Some(param.span.clone()));
+        findings.push(finding.with_rule_info(&taxonomy::MISSING_DRACUT_MODULE));
+    }
+    findings
+}
+
+/// Warn about parameters that the kernel will ignore because it was built
+/// without an option they need, or the CPU lacks a flag they need. Options
+/// are only checked when the probe has a kernel configuration, and flags
+/// when it has read the CPU's.
+fn required_config_findings(parameters: &[ParsedParameter], probe: &SystemProbe) -> Vec<Finding> {
+    let cpu_known = probe.hardware_tags.iter().any(|tag| tag.starts_with("cpu:vendor:"));
+    let mut findings = Vec::new();
+
+    for param in parameters.iter().filter(|p| p.effective) {
+        let Some(def) = &param.parameter_def else {
+            continue;
+        };
+        let value = param.value.as_deref().unwrap_or("");
+        let on_arch = |arches: &[String]| arches.is_empty() || probe.arch().is_some_and(|arch| arches.iter().any(|a| a == arch));
+        let for_value = |when: &[String]| when.is_empty() || when.iter().any(|pattern| glob_match(pattern, value));
+        let requirements = def.required_config.iter()
+            .filter(|requirement| on_arch(&requirement.arch) && for_value(&requirement.when));
+        for requirement in requirements {
+            let missing_options = requirement.options.iter()
+                .filter(|option| probe.kernel_option_enabled(option) == Some(false))
+                .map(|option| format!("kernel built without {}", option));
+            let missing_flags = requirement.cpu_flags.iter()
+                .filter(|flag| cpu_known && !probe.hardware_tags.contains(&format!("cpu:{}", flag)))
+                .map(|flag| format!("CPU lacks the {} flag", flag));
+            for missing in missing_options.chain(missing_flags) {
+                let result = ValidationResult::Warning(format!("Parameter will be ignored: {}", missing));
+                findings.push(Finding::new(&param.name, result, Some(param.span.clone())).with_rule_info(&taxonomy::MISSING_KERNEL_SUPPORT));
+            }
+        }
+    }
+    findings
+}
+
+/// Warn about interfaces and MAC addresses named by `ip=`, `ifname=` and
+/// `bootdev=` that this system does not have. Interfaces that `ifname=`
+/// creates are taken to exist.
+fn network_reference_findings(parameters: &[ParsedParameter], probe: &SystemProbe) -> Vec<Finding> {
+    if !probe.has_network_interfaces() {
+        return Vec::new();
+    }
+
+    let effective: Vec<&ParsedParameter> = parameters.iter().filter(|p| p.effective).collect();
+    let renamed: Vec<&str> = effective.iter()
+        .filter(|p| p.name == "ifname")
+        .filter_map(|p| p.value.as_deref()?.split_once(':').map(|(name, _)| name))
+        .collect();
+    let has_interface = |name: &str| renamed.contains(&name) || probe.has_network_interface(name);
+
+    let mut findings = Vec::new();
+    for param in effective {
+        let Some(value) = param.value.as_deref() else {
+            continue;
+        };
+        let (interface, mac) = match param.name.as_str() {
+            "ip" => match IpConfig::parse(value) {
+                Some(ip) => (ip.interface, ip.macaddr),
+                None => continue,
+            },
+            "ifname" => (None, value.split_once(':').map(|(_, mac)| mac.to_string())),
+            "bootdev" => (Some(value.to_string()), None),
+            _ => continue,
+        };
+        let mut warn = |message: String| {
+            let finding = Finding::new(&param.name, ValidationResult::Warning(message), Some(param.span.clone()));
+            findings.push(finding.with_rule_info(&taxonomy::MISSING_NETWORK_DEVICE));
+        };
+        if let Some(interface) = interface.filter(|interface| !has_interface(interface)) {
+            warn(format!("Network interface '{}' does not exist on this system", interface));
+        }
+        if let Some(mac) = mac.filter(|mac| !probe.has_mac_address(mac)) {
+            warn(format!("No network interface has MAC address '{}'", mac));
+        }
+    }
+    findings
+}
+
+/// Cross-check `crashkernel=` against the running kernel's reservation:
+/// a valid value can still fail to reserve memory, e.g. when it asks for
+/// more than is free at boot.
+fn crash_kernel_findings(parameters: &[ParsedParameter], probe: &SystemProbe) -> Vec<Finding> {
+    let Some(state) = probe.crash_kernel else {
+        return Vec::new();
+    };
+    let requested: Vec<&ParsedParameter> = parameters.iter()
+        .filter(|p| p.effective && p.name == "crashkernel" && p.value.is_some())
+        .collect();
+    let Some(first) = requested.first() else {
+        return Vec::new();
+    };
+
+    let result = if state.reserved_bytes == 0 {
+        ValidationResult::Warning(
+            "The running kernel has no crash kernel reservation; if it was booted with this value, \
+             the reservation failed".to_string(),
+        )
+    } else if let Some(size) = requested_crash_kernel_bytes(&requested).filter(|&size| size != state.reserved_bytes) {
+        ValidationResult::Warning(format!(
+            "crashkernel= requests {} but the running kernel reserved {}",
+            format_size(size), format_size(state.reserved_bytes),
+        ))
+    } else if !state.loaded {
+        ValidationResult::Info("Memory is reserved for a crash kernel but none is loaded; kdump is not active".to_string())
+    } else {
+        return Vec::new();
+    };
+    vec![Finding::new(&first.name, result, Some(first.span.clone())).with_rule_info(&taxonomy::CRASH_KERNEL_RESERVATION)]
+}
+
+/// The bytes a set of `crashkernel=<size>[@<offset>][,high|,low]` values
+/// reserve in total, or `None` if any uses the memory-dependent range
+/// syntax, which needs the installed memory to evaluate, or a `,high`
+/// reservation without `,low`, which the kernel pairs with a low one of its
+/// own choosing. `,cma` reservations are not crash kernel regions and are
+/// left out.
+fn requested_crash_kernel_bytes(requested: &[&ParsedParameter]) -> Option<u64> {
+    let specs = requested.iter()
+        .map(|p| CrashKernelSpec::parse(p.value.as_deref()?))
+        .collect::<Option<Vec<_>>>()?;
+    let placed = |wanted: CrashKernelPlacement| specs.iter().any(|spec| {
+        matches!(spec, CrashKernelSpec::Placed { placement, .. } if *placement == wanted)
+    });
+    if placed(CrashKernelPlacement::High) && !placed(CrashKernelPlacement::Low) {
+        return None;
+    }
+    let mut total = 0;
+    for spec in specs {
+        total += match spec {
+            CrashKernelSpec::Ranges { .. } => return None,
+            CrashKernelSpec::Placed { placement: CrashKernelPlacement::Cma, .. } => 0,
+            CrashKernelSpec::Size { size, .. } | CrashKernelSpec::Placed { size, .. } => size,
+        };
+    }
+    Some(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::CatalogBuilder;
+    use crate::parser::CommandLineParser;
+
+    fn parameters(probe: &SystemProbe) -> Vec<ParsedParameter> {
+        let mut luks = crate::Parameter::new("rd.luks.uuid", ParameterProcessor::Dracut { min_version: String::new() });
+        luks.selectors.push("dracut_module:crypt".to_string());
+        let catalog = CatalogBuilder::new().add_parameter(luks).build().unwrap();
+        assert!(catalog.get_parameter("rd.luks.uuid").unwrap().is_applicable(probe));
+        CommandLineParser::new(&catalog).parse("rd.luks.uuid=1234").unwrap().parameters
+    }
+
+    #[test]
+    fn warns_of_parameters_whose_dracut_module_is_left_out() {
+        let probe = SystemProbe::builder().software_tag("dracut_module:lvm").build();
+        let findings = dracut_module_findings(&parameters(&probe), &probe);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule.as_deref(), Some(taxonomy::MISSING_DRACUT_MODULE.name));
+    }
+
+    #[test]
+    fn accepts_parameters_whose_dracut_module_is_included() {
+        let probe = SystemProbe::builder().software_tag("dracut_module:crypt").build();
+        assert!(dracut_module_findings(&parameters(&probe), &probe).is_empty());
+        let unprobed = SystemProbe::builder().build();
+        assert!(dracut_module_findings(&parameters(&unprobed), &unprobed).is_empty());
+    }
+}
@@ -0,0 +1,124 @@
+//! Stable identifiers for the checks that report findings, so that tools
+//! can suppress, gate on or document a check without matching the text of
+//! its messages. Each built-in check has a code, e.g. `KC0103`, and a
+//! kebab-case name, e.g. `deprecated`; findings carry the name in
+//! [`Finding::rule`](crate::Finding::rule). Codes are grouped by the kind
+//! of check:
+//!
+//! - `KC00xx`: each parameter on its own
+//! - `KC01xx`: module parameters and deprecations
+//! - `KC02xx`: how parameters combine
+//! - `KC03xx`: the probed system
+//! - `KC04xx`: security and performance profiles
+//! - `KC05xx`: built-in lint rules
+//!
+//! Codes and names are never reused for another check.
+
+use serde::Serialize;
+
+use crate::validators::FindingCategory;
+
+/// A check that reports findings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub struct RuleInfo {
+    pub code: &'static str,
+    pub name: &'static str,
+    pub category: FindingCategory,
+    pub description: &'static str,
+}
+
+const fn rule(code: &'static str, name: &'static str, category: FindingCategory, description: &'static str) -> RuleInfo {
+    RuleInfo { code, name, category, description }
+}
+
+pub(crate) const UNKNOWN_PARAMETER: RuleInfo =
+    rule("KC0001", "unknown-parameter", FindingCategory::Unknown, "the parameter is not in the catalog");
+pub(crate) const INVALID_VALUE: RuleInfo =
+    rule("KC0002", "invalid-value", FindingCategory::Syntax, "the value does not match the parameter's syntax");
+pub(crate) const COMMAND_LINE_LENGTH: RuleInfo =
+    rule("KC0003", "command-line-length", FindingCategory::Syntax, "the command line is longer than the architecture allows");
+pub(crate) const IGNORED_SOURCE_CONTENT: RuleInfo =
+    rule("KC0004", "ignored-source-content", FindingCategory::Syntax, "the boot loader ignores part of the file holding the command line");
+pub(crate) const UNCATALOGUED_MODULE_PARAMETER: RuleInfo =
+    rule("KC0101", "uncatalogued-module-parameter", FindingCategory::General, "a module parameter is not in the catalog");
+pub(crate) const UNKNOWN_MODULE_PARAMETER: RuleInfo =
+    rule("KC0102", "unknown-module-parameter", FindingCategory::Unknown, "modinfo does not list the module parameter");
+pub(crate) const DEPRECATED: RuleInfo =
+    rule("KC0103", "deprecated", FindingCategory::Deprecated, "the parameter is deprecated");
+pub(crate) const RENAMED_PARAMETER: RuleInfo =
+    rule("KC0104", "renamed-parameter", FindingCategory::Deprecated, "the parameter is given under a former name");
+pub(crate) const INVALID_MODULE_PARAMETER: RuleInfo =
+    rule("KC0105", "invalid-module-parameter", FindingCategory::Syntax, "the value does not match the modinfo type");
+pub(crate) const DUPLICATE_PARAMETER: RuleInfo =
+    rule("KC0201", "duplicate-parameter", FindingCategory::Duplicate, "the parameter is given more than once");
+pub(crate) const CONFLICTING_PARAMETERS: RuleInfo =
+    rule("KC0202", "conflicting-parameters", FindingCategory::Conflict, "two parameters contradict each other");
+pub(crate) const OVERRIDDEN_PARAMETER: RuleInfo =
+    rule("KC0203", "overridden-parameter", FindingCategory::Override, "a later parameter nullifies the effect of this one");
+pub(crate) const MISSING_REQUIREMENT: RuleInfo =
+    rule("KC0204", "missing-requirement", FindingCategory::Dependency, "a parameter this one needs is not given");
+pub(crate) const UNSUPPORTED_HARDWARE: RuleInfo =
+    rule("KC0301", "unsupported-hardware", FindingCategory::Inapplicable, "the hardware the parameter is for is not present");
+pub(crate) const MISSING_KERNEL_SUPPORT: RuleInfo =
+    rule("KC0302", "missing-kernel-support", FindingCategory::Inapplicable, "the kernel or CPU lacks what the parameter needs");
+pub(crate) const UNSUPPORTED_RELEASE: RuleInfo =
+    rule("KC0303", "unsupported-release", FindingCategory::Inapplicable, "the installed distribution or components do not support the parameter");
+pub(crate) const INAPPLICABLE_PARAMETER: RuleInfo =
+    rule("KC0304", "inapplicable-parameter", FindingCategory::Inapplicable, "the parameter's selectors rule out the system");
+pub(crate) const MISSING_NETWORK_DEVICE: RuleInfo =
+    rule("KC0305", "missing-network-device", FindingCategory::System, "a network interface or address named does not exist");
+pub(crate) const MITIGATION_MISMATCH: RuleInfo =
+    rule("KC0306", "mitigation-mismatch", FindingCategory::System, "a mitigation setting does not suit the CPU's vulnerabilities");
+pub(crate) const CRASH_KERNEL_RESERVATION: RuleInfo =
+    rule("KC0307", "crash-kernel-reservation", FindingCategory::System, "the crash kernel reservation differs from the one requested");
+pub(crate) const MISSING_DRACUT_MODULE: RuleInfo =
+    rule("KC0308", "missing-dracut-module", FindingCategory::Inapplicable, "the initramfs lacks the dracut module that reads the parameter");
+pub(crate) const WEAKENS_SECURITY: RuleInfo =
+    rule("KC0401", "weakens-security", FindingCategory::Security, "the parameter weakens the security of the system");
+pub(crate) const PERFORMANCE_IMPACT: RuleInfo =
+    rule("KC0402", "performance-impact", FindingCategory::Performance, "the parameter changes the performance of the system");
+pub(crate) const DUPLICATE_ROOT: RuleInfo =
+    rule("KC0501", "duplicate-root", FindingCategory::Lint, "root= is given more than once");
+pub(crate) const CONSOLE_ORDER: RuleInfo =
+    rule("KC0502", "console-order", FindingCategory::Lint, "only the last of several console= devices becomes /dev/console");
+pub(crate) const QUIET_DEBUG: RuleInfo =
+    rule("KC0503", "quiet-debug", FindingCategory::Lint, "quiet and debug cancel each other out");
+pub(crate) const ENCRYPTED_ROOT_LUKS: RuleInfo =
+    rule("KC0504", "encrypted-root-luks", FindingCategory::Lint, "root= is on a LUKS device that nothing unlocks");
+
+/// Every built-in check, by code.
+pub const BUILTIN_RULES: &[RuleInfo] = &[
+    UNKNOWN_PARAMETER,
+    INVALID_VALUE,
+    COMMAND_LINE_LENGTH,
+    IGNORED_SOURCE_CONTENT,
+    UNCATALOGUED_MODULE_PARAMETER,
+    UNKNOWN_MODULE_PARAMETER,
+    DEPRECATED,
+    RENAMED_PARAMETER,
+    INVALID_MODULE_PARAMETER,
+    DUPLICATE_PARAMETER,
+    CONFLICTING_PARAMETERS,
+    OVERRIDDEN_PARAMETER,
+    MISSING_REQUIREMENT,
+    UNSUPPORTED_HARDWARE,
+    MISSING_KERNEL_SUPPORT,
+    UNSUPPORTED_RELEASE,
+    INAPPLICABLE_PARAMETER,
+    MISSING_NETWORK_DEVICE,
+    MITIGATION_MISMATCH,
+    CRASH_KERNEL_RESERVATION,
+    MISSING_DRACUT_MODULE,
+    WEAKENS_SECURITY,
+    PERFORMANCE_IMPACT,
+    DUPLICATE_ROOT,
+    CONSOLE_ORDER,
+    QUIET_DEBUG,
+    ENCRYPTED_ROOT_LUKS,
+];
+
+/// The built-in check with the code or name `id`, e.g. `KC0103` or
+/// `deprecated`. Codes match case-insensitively.
+pub fn rule_info(id: &str) -> Option<&'static RuleInfo> {
+    BUILTIN_RULES.iter().find(|rule| rule.name == id || rule.code.eq_ignore_ascii_case(id))
+}
@@ -0,0 +1,217 @@
+//! Verification of detached signatures over parameter databases, so that
+//! only vetted definitions influence validation on locked-down systems.
+//!
+//! Callers supply a [`SignatureVerifier`] holding the vendor's public keys;
+//! with the `ed25519` feature, [`Ed25519Verifier`] checks ed25519
+//! signatures against keys loaded from files. Signatures are detached:
+//! a filesystem database is signed over its [`signing payload`] in
+//! `SIGNATURE_FILE` at its root, and a snapshot in `<snapshot>.sig`.
+//!
+//! [`signing payload`]: crate::FilesystemDatabase::signing_payload
+
+use std::fmt;
+#[cfg(feature = "ed25519")]
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+#[cfg(feature = "ed25519")]
+use ed25519_dalek::{Signature, VerifyingKey};
+
+#[cfg(feature = "ed25519")]
+use crate::error::DatabaseError;
+
+/// Detached signature of a filesystem database or overlay directory.
+pub const SIGNATURE_FILE: &str = "parameters.sig";
+
+/// First line of the signing payload of a filesystem database.
+pub(crate) const PAYLOAD_HEADER: &[u8] = b"kcmdline-signed-database-v1\n";
+
+/// Checks a detached signature, e.g. ed25519 against a set of trusted keys.
+pub trait SignatureVerifier: Send + Sync {
+    /// Check `signature` over `message`, returning why it is not trusted
+    /// if not.
+    fn verify(&self, message: &[u8], signature: &[u8]) -> Result<(), String>;
+}
+
+/// Which parameter databases a [`DatabaseLoader`](crate::DatabaseLoader)
+/// accepts.
+#[derive(Clone, Default)]
+pub enum TrustPolicy {
+    /// Load every source without verification.
+    #[default]
+    Unverified,
+    /// Require every source to carry a signature accepted by the verifier.
+    /// The embedded database is part of the library and always trusted;
+    /// sources with no signature, such as harvested module parameters, are
+    /// rejected.
+    RequireSigned(Arc<dyn SignatureVerifier>),
+}
+
+impl TrustPolicy {
+    pub fn require_signed<V: SignatureVerifier + 'static>(verifier: V) -> Self {
+        Self::RequireSigned(Arc::new(verifier))
+    }
+}
+
+impl fmt::Debug for TrustPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unverified => write!(f, "Unverified"),
+            Self::RequireSigned(_) => write!(f, "RequireSigned"),
+        }
+    }
+}
+
+/// The detached signature file of a snapshot.
+pub fn snapshot_signature_path(snapshot: &Path) -> PathBuf {
+    let mut name = snapshot.as_os_str().to_os_string();
+    name.push(".sig");
+    PathBuf::from(name)
+}
+
+/// Verifies ed25519 signatures, accepting a signature made with any of its
+/// trusted public keys.
+#[cfg(feature = "ed25519")]
+#[derive(Debug, Clone, Default)]
+pub struct Ed25519Verifier {
+    keys: Vec<VerifyingKey>,
+}
+
+#[cfg(feature = "ed25519")]
+impl Ed25519Verifier {
+    /// A verifier trusting the given 32-byte public keys.
+    pub fn from_public_keys<'a, I: IntoIterator<Item = &'a [u8; 32]>>(keys: I) -> Result<Self, DatabaseError> {
+        let keys = keys.into_iter()
+            .map(|key| VerifyingKey::from_bytes(key).map_err(|e| {
+                DatabaseError::FormatError(format!("invalid ed25519 public key: {}", e))
+            }))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { keys })
+    }
+
+    /// A verifier trusting the public keys in a key file, or in every file
+    /// of a directory of them. A key file holds one hex-encoded key per
+    /// line; blank lines and lines starting with `#` are ignored.
+    pub fn load_keys<P: AsRef<Path>>(path: P) -> Result<Self, DatabaseError> {
+        let path = path.as_ref();
+        let mut files = vec![path.to_path_buf()];
+        if path.is_dir() {
+            files = fs::read_dir(path)?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<Vec<_>, _>>()?;
+            files.retain(|file| file.is_file());
+            files.sort();
+        }
+
+        let mut keys = Vec::new();
+        for file in files {
+            for (number, line) in fs::read_to_string(&file)?.lines().enumerate() {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                keys.push(parse_hex_key(line).ok_or_else(|| DatabaseError::FormatError(format!(
+                    "{}:{}: expected a hex-encoded 32-byte ed25519 public key", file.display(), number + 1,
+                )))?);
+            }
+        }
+        Self::from_public_keys(&keys)
+    }
+
+    /// The number of trusted keys.
+    pub fn key_count(&self) -> usize {
+        self.keys.len()
+    }
+}
+
+#[cfg(feature = "ed25519")]
+impl SignatureVerifier for Ed25519Verifier {
+    fn verify(&self, message: &[u8], signature: &[u8]) -> Result<(), String> {
+        let signature = Signature::from_slice(signature)
+            .map_err(|_| format!("expected a {}-byte ed25519 signature, found {} bytes", Signature::BYTE_SIZE, signature.len()))?;
+        if self.keys.is_empty() {
+            return Err("no trusted keys".to_string());
+        }
+        if self.keys.iter().any(|key| key.verify_strict(message, &signature).is_ok()) {
+            Ok(())
+        } else {
+            Err("signature does not match any trusted key".to_string())
+        }
+    }
+}
+
+/// A 32-byte key written as 64 hex digits.
+#[cfg(feature = "ed25519")]
+fn parse_hex_key(text: &str) -> Option<[u8; 32]> {
+    if text.len() != 64 || !text.is_ascii() {
+        return None;
+    }
+    let mut key = [0u8; 32];
+    for (byte, pair) in key.iter_mut().zip(text.as_bytes().chunks(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()?;
+    }
+    Some(key)
+}
+
+#[cfg(all(test, feature = "ed25519"))]
+mod tests {
+    use super::*;
+
+    // Test 1 of RFC 8032 section 7.1: the empty message.
+    const PUBLIC_KEY: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+    const SIGNATURE: &str = "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b";
+    // Test 2: the message 0x72.
+    const OTHER_PUBLIC_KEY: &str = "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c";
+
+    fn bytes(hex: &str) -> Vec<u8> {
+        (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()).collect()
+    }
+
+    fn key_file(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("libkcmdline-keys-{}-{}", name, std::process::id()));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn accepts_good_signature() {
+        let verifier = Ed25519Verifier::from_public_keys([&parse_hex_key(PUBLIC_KEY).unwrap()]).unwrap();
+        assert_eq!(verifier.verify(b"", &bytes(SIGNATURE)), Ok(()));
+    }
+
+    #[test]
+    fn rejects_bad_signature() {
+        let verifier = Ed25519Verifier::from_public_keys([&parse_hex_key(PUBLIC_KEY).unwrap()]).unwrap();
+        assert!(verifier.verify(b"tampered", &bytes(SIGNATURE)).is_err());
+
+        let mut signature = bytes(SIGNATURE);
+        signature[0] ^= 1;
+        assert!(verifier.verify(b"", &signature).is_err());
+        assert!(verifier.verify(b"", &signature[..63]).is_err());
+    }
+
+    #[test]
+    fn rejects_signature_of_untrusted_key() {
+        let verifier = Ed25519Verifier::from_public_keys([&parse_hex_key(OTHER_PUBLIC_KEY).unwrap()]).unwrap();
+        assert!(verifier.verify(b"", &bytes(SIGNATURE)).is_err());
+        assert!(Ed25519Verifier::default().verify(b"", &bytes(SIGNATURE)).is_err());
+    }
+
+    #[test]
+    fn loads_key_file() {
+        let path = key_file("good", &format!("# vendor keys\n{}\n\n{}\n", OTHER_PUBLIC_KEY, PUBLIC_KEY));
+        let verifier = Ed25519Verifier::load_keys(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(verifier.key_count(), 2);
+        assert_eq!(verifier.verify(b"", &bytes(SIGNATURE)), Ok(()));
+    }
+
+    #[test]
+    fn rejects_malformed_key_file() {
+        let path = key_file("bad", "d75a9801\n");
+        let error = Ed25519Verifier::load_keys(&path).unwrap_err();
+        fs::remove_file(&path).unwrap();
+        assert!(error.to_string().contains(":1: expected a hex-encoded"), "{}", error);
+    }
+}
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use super::{ParameterValidator, ValidationResult};
+use crate::config::{split_ip_config, IP_AUTOCONF_METHODS};
 use regex::Regex;
 
 #[derive(Clone)]
@@ -91,17 +92,19 @@ impl ParameterValidator for DracutNetworkValidator {
     fn validate(&self, value: &str, _config: &HashMap<String, toml::Value>) -> ValidationResult {
         // Basic ip= parameter validation for dracut
         // Format is complex: ip=<client-IP>:[<peer>]:<gateway-IP>:<netmask>:<hostname>:<interface>:{none|off|dhcp|on|any|dhcp6|auto6|ibft}[:[<mtu>][:<macaddr>]]
-        let parts: Vec<&str> = value.split(':').collect();
-        if parts.len() < 7 {
-            return ValidationResult::Error("IP configuration requires at least 7 colon-separated fields".to_string());
-        }
-
-        // Validate the boot protocol field (7th field)
-        let boot_proto = parts[6];
-        let valid_protos = ["none", "off", "dhcp", "on", "any", "dhcp6", "auto6", "ibft"];
-        if !valid_protos.contains(&boot_proto) {
+        // or the short forms ip=<autoconf> and ip=<interface>:<autoconf>[:[<mtu>][:<macaddr>]]
+        let (parts, autoconf) = match split_ip_config(value) {
+            Some(split) => split,
+            None => return ValidationResult::Error(
+                "IP configuration requires an autoconf method or at least 7 colon-separated fields".to_string()
+            ),
+        };
+
+        // Validate the boot protocol field
+        let boot_proto = parts[autoconf].as_str();
+        if !IP_AUTOCONF_METHODS.contains(&boot_proto) {
             return ValidationResult::Error(format!(
-                "Invalid boot protocol: '{}'. Valid: {:?}", boot_proto, valid_protos
+                "Invalid boot protocol: '{}'. Valid: {:?}", boot_proto, IP_AUTOCONF_METHODS
             ));
         }
 
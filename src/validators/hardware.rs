@@ -9,10 +9,9 @@ impl ParameterValidator for PciDeviceSpecValidator {
     fn validate(&self, value: &str, _config: &HashMap<String, toml::Value>) -> ValidationResult {
         // Format: [<domain>:]<bus>:<dev>.<func>[/<dev>.<func>]*
         // or pci:<vendor>:<device>[:<subvendor>:<subdevice>]
-        if value.starts_with("pci:") {
-            self.validate_pci_id_format(&value[4..])
-        } else {
-            self.validate_pci_address_format(value)
+        match value.strip_prefix("pci:") {
+            Some(id) => self.validate_pci_id_format(id),
+            None => self.validate_pci_address_format(value),
         }
     }
 
@@ -50,10 +49,6 @@ impl PciDeviceSpecValidator {
             ValidationResult::Error(format!("Invalid PCI address format: '{}'", value))
         }
     }
-
-    fn clone_boxed(&self) -> Box<dyn ParameterValidator> {
-        Box::new(self.clone())
-    }
 }
 
 #[derive(Clone)]
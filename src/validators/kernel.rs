@@ -3,6 +3,10 @@ use regex::Regex;
 
 use super::{ParameterValidator, ValidationResult};
 
+/// The largest NR_CPUS any architecture can be configured with. The kernel
+/// rejects CPU lists naming a CPU at or past its own limit.
+pub const MAX_NR_CPUS: u32 = 8192;
+
 #[derive(Clone)]
 pub struct CpuListValidator;
 
@@ -56,17 +60,28 @@ impl CpuListValidator {
 
             if let Some(captures) = cpu_regex.captures(trimmed) {
                 let exclusion = captures.get(1).map_or("", |m| m.as_str());
-                let start: u32 = captures[2].parse().unwrap();
 
                 if exclusion == "^" && !supports_exclusion {
                     return ValidationResult::Error("CPU exclusion (^) not supported".to_string());
                 }
 
-                if let Some(end_match) = captures.get(4) {
-                    let end: u32 = end_match.as_str().parse().unwrap();
-                    if end <= start {
-                        return ValidationResult::Error(format!("Invalid CPU range: {}-{}", start, end));
+                // Numbers too large for a u32 are past the limit too
+                let start: u32 = captures[2].parse().unwrap_or(u32::MAX);
+                let end = match captures.get(4) {
+                    Some(end_match) => {
+                        let end: u32 = end_match.as_str().parse().unwrap_or(u32::MAX);
+                        if end <= start {
+                            return ValidationResult::Error(format!("Invalid CPU range: {}-{}", start, end));
+                        }
+                        end
                     }
+                    None => start,
+                };
+                if end >= MAX_NR_CPUS {
+                    let last = captures.get(4).unwrap_or_else(|| captures.get(2).unwrap()).as_str();
+                    return ValidationResult::Error(format!(
+                        "CPU {} is past the largest possible CPU number, {}", last, MAX_NR_CPUS - 1
+                    ));
                 }
             } else {
                 return ValidationResult::Error(format!("Invalid CPU specification: '{}'", trimmed));
@@ -111,8 +126,8 @@ impl ParameterValidator for IoRangeValidator {
             //
             // Validate base and size are valid numbers (hex or decimal)
             for part in parts {
-                if part.starts_with("0x") {
-                    if u64::from_str_radix(&part[2..], 16).is_err() {
+                if let Some(hex) = part.strip_prefix("0x") {
+                    if u64::from_str_radix(hex, 16).is_err() {
                         return ValidationResult::Error(format!("Invalid hex number: '{}'", part));
                     }
                 } else if part.parse::<u64>().is_err() {
@@ -130,3 +145,138 @@ impl ParameterValidator for IoRangeValidator {
         Box::new(self.clone())
     }
 }
+
+/// Validates loadable module parameters by the `parmtype` modinfo declares
+/// for them (`int`, `bool`, `charp`, `array of uint`, ...), following the
+/// kernel's own parsers: integers accept a `0x` or `0` prefix for hex and
+/// octal, and arrays are comma-separated.
+#[derive(Clone)]
+pub struct ModuleParameterValidator;
+
+impl ParameterValidator for ModuleParameterValidator {
+    fn validate(&self, value: &str, config: &HashMap<String, toml::Value>) -> ValidationResult {
+        let Some(parmtype) = config.get("parmtype").and_then(|v| v.as_str()) else {
+            return ValidationResult::Valid;
+        };
+
+        match parmtype.strip_prefix("array of ") {
+            Some(element_type) => {
+                for element in value.split(',') {
+                    let result = Self::validate_scalar(element, element_type);
+                    if result != ValidationResult::Valid {
+                        return result;
+                    }
+                }
+                ValidationResult::Valid
+            }
+            None => Self::validate_scalar(value, parmtype),
+        }
+    }
+
+    fn clone_boxed(&self) -> Box<dyn ParameterValidator> {
+        Box::new(self.clone())
+    }
+}
+
+impl ModuleParameterValidator {
+    fn validate_scalar(value: &str, parmtype: &str) -> ValidationResult {
+        let range: (i128, i128) = match parmtype {
+            "bool" | "invbool" => return Self::validate_bool(value),
+            "charp" | "string" => return ValidationResult::Valid,
+            "byte" => (0, u8::MAX.into()),
+            "short" => (i16::MIN.into(), i16::MAX.into()),
+            "ushort" => (0, u16::MAX.into()),
+            "int" => (i32::MIN.into(), i32::MAX.into()),
+            "uint" | "hexint" => (0, u32::MAX.into()),
+            "long" | "llong" => (i64::MIN.into(), i64::MAX.into()),
+            "ulong" | "ullong" => (0, u64::MAX.into()),
+            other => return ValidationResult::Warning(format!("Unknown module parameter type '{}'", other)),
+        };
+
+        match Self::parse_integer(value) {
+            Some(n) if n >= range.0 && n <= range.1 => ValidationResult::Valid,
+            Some(n) => ValidationResult::Error(format!(
+                "Value {} out of range for {} [{}, {}]", n, parmtype, range.0, range.1
+            )),
+            None => ValidationResult::Error(format!("Invalid {} value: '{}'", parmtype, value)),
+        }
+    }
+
+    /// kstrtobool(): the first character decides, with "on" and "off"
+    /// spelled out. An empty value sets the parameter.
+    fn validate_bool(value: &str) -> ValidationResult {
+        let valid = match value.as_bytes() {
+            [] => true,
+            [b'o' | b'O', second, ..] => matches!(second, b'n' | b'N' | b'f' | b'F'),
+            [first, ..] => b"yYtTeE1nNfFdD0".contains(first),
+        };
+        if valid {
+            ValidationResult::Valid
+        } else {
+            ValidationResult::Error(format!("Invalid boolean value: '{}'", value))
+        }
+    }
+
+    /// kstrtoll() with base 0: an optional sign, then hex with `0x`, octal
+    /// with a leading `0`, or decimal.
+    fn parse_integer(value: &str) -> Option<i128> {
+        let (negative, digits) = match value.as_bytes().first()? {
+            b'-' => (true, &value[1..]),
+            b'+' => (false, &value[1..]),
+            _ => (false, value),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_alphanumeric()) {
+            return None;
+        }
+        let magnitude = if let Some(hex) = digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+            u64::from_str_radix(hex, 16).ok()?
+        } else if digits.len() > 1 && digits.starts_with('0') {
+            u64::from_str_radix(&digits[1..], 8).ok()?
+        } else {
+            digits.parse::<u64>().ok()?
+        };
+        let magnitude = i128::from(magnitude);
+        Some(if negative { -magnitude } else { magnitude })
+    }
+}
+
+/// The kernel's COMMAND_LINE_SIZE for an architecture, as named by Rust's
+/// `std::env::consts::ARCH` or `uname -m`. The limit includes the
+/// terminating NUL, so the longest usable command line is one byte shorter.
+pub fn command_line_size(arch: &str) -> Option<usize> {
+    match arch {
+        "x86_64" | "x86" | "i386" | "i686" => Some(2048),
+        "aarch64" | "arm64" => Some(2048),
+        "arm" | "armv7l" => Some(1024),
+        "powerpc" | "powerpc64" | "ppc64" | "ppc64le" => Some(2048),
+        "s390x" | "s390" => Some(4096),
+        "riscv64" | "riscv32" => Some(1024),
+        "loongarch64" => Some(4096),
+        "mips" | "mips64" => Some(4096),
+        "sparc64" => Some(2048),
+        _ => None,
+    }
+}
+
+/// Check that a complete command line fits within the architecture's
+/// COMMAND_LINE_SIZE, warning when it is within 10% of the limit.
+pub fn validate_command_line_length(cmdline: &str, arch: &str) -> ValidationResult {
+    let size = match command_line_size(arch) {
+        Some(size) => size,
+        None => return ValidationResult::Unknown(format!("Unknown COMMAND_LINE_SIZE for architecture '{}'", arch)),
+    };
+    let max_len = size - 1;
+    let len = cmdline.len();
+
+    if len > max_len {
+        ValidationResult::Error(format!(
+            "Command line is {} bytes; {} allows at most {} and the kernel will truncate it", len, arch, max_len
+        ))
+    } else if len * 10 > max_len * 9 {
+        ValidationResult::Warning(format!(
+            "Command line is {} bytes, within 10% of the {} byte limit on {}", len, max_len, arch
+        ))
+    } else {
+        ValidationResult::Valid
+    }
+}
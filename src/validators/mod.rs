@@ -1,7 +1,14 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
 
 use crate::parameter::ParameterProcessor;
-use crate::error::RegistryError;
+use crate::error::{ReportError, Span};
+use crate::fixes::Fix;
+use crate::policy::SeverityPolicy;
+use crate::report::ValidationReport;
+use crate::suppress::Suppressions;
+use crate::taxonomy::{rule_info, RuleInfo};
 
 pub mod common;
 pub mod kernel;
@@ -9,11 +16,10 @@ pub mod systemd;
 pub mod dracut;
 pub mod hardware;
 
-pub use common::*;
 pub use kernel::*;
-pub use systemd::*;
-pub use dracut::*;
-pub use hardware::*;
+
+/// The name findings about the command line as a whole are recorded under.
+pub const COMMAND_LINE_FINDING: &str = "(command line)";
 
 pub trait ParameterValidator: Send + Sync {
     fn validate(&self, value: &str, config: &HashMap<String, toml::Value>) -> ValidationResult;
@@ -25,25 +31,305 @@ pub trait ParameterValidator: Send + Sync {
 
 pub trait ValidatorRegistry: Send + Sync {
     fn get_validator(&self, processor: &ParameterProcessor, name: &str) -> Option<Box<dyn ParameterValidator>>;
-    fn register_validator(&mut self, name: String, validator: Box<dyn ParameterValidator>) -> Result<(), RegistryError>;
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ValidationResult {
     Valid,
+    Info(String),
     Warning(String),
     Error(String),
     Unknown(String),
 }
 
+/// What a finding is about, for a [`SeverityPolicy`] to decide how seriously
+/// to take it and for tools to group findings by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingCategory {
+    /// Any finding not in another category, such as one of a third-party
+    /// lint rule.
+    #[default]
+    General,
+    /// A malformed value, or a command line too long to boot.
+    Syntax,
+    /// A parameter missing from the catalog.
+    Unknown,
+    /// A deprecated parameter, or one given under a former name.
+    Deprecated,
+    /// A parameter given more than once.
+    Duplicate,
+    /// Parameters that contradict each other.
+    Conflict,
+    /// A parameter whose effect a later one nullifies.
+    Override,
+    /// A parameter without a companion it needs.
+    Dependency,
+    /// A parameter that cannot have any effect on the probed system.
+    Inapplicable,
+    /// A parameter at odds with the probed system, e.g. naming a network
+    /// interface it does not have.
+    System,
+    /// A parameter that weakens security.
+    Security,
+    /// A parameter that changes performance.
+    Performance,
+    /// A finding of a built-in lint rule.
+    Lint,
+}
+
+/// A validation result for a named parameter, with the location it refers to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub parameter: String,
+    pub result: ValidationResult,
+    pub span: Option<Span>,
+    pub category: FindingCategory,
+    /// The name of the check that reported the finding: a built-in one
+    /// listed in [`BUILTIN_RULES`](crate::BUILTIN_RULES) or the id of a
+    /// lint rule. Valid results have none.
+    pub rule: Option<String>,
+    /// Arguments that would fix the problem, or names the parameter may
+    /// have been meant as.
+    pub suggestions: Vec<String>,
+    /// A correction that can be applied without review.
+    pub fix: Option<Fix>,
+}
+
+impl Finding {
+    /// A finding in the category its result implies: unknown for
+    /// [`ValidationResult::Unknown`], general otherwise.
+    pub fn new(parameter: &str, result: ValidationResult, span: Option<Span>) -> Self {
+        let category = match result {
+            ValidationResult::Unknown(_) => FindingCategory::Unknown,
+            _ => FindingCategory::General,
+        };
+        Self {
+            parameter: parameter.to_string(),
+            result,
+            span,
+            category,
+            rule: None,
+            suggestions: Vec::new(),
+            fix: None,
+        }
+    }
+
+    pub fn with_category(mut self, category: FindingCategory) -> Self {
+        self.category = category;
+        self
+    }
+
+    pub fn with_rule(mut self, rule: &str) -> Self {
+        self.rule = Some(rule.to_string());
+        self
+    }
+
+    /// Attribute the finding to a built-in check, in the check's category.
+    pub(crate) fn with_rule_info(mut self, rule: &RuleInfo) -> Self {
+        self.rule = Some(rule.name.to_string());
+        self.category = rule.category;
+        self
+    }
+
+    /// The code of the built-in check that reported the finding, e.g.
+    /// `KC0103`.
+    pub fn code(&self) -> Option<&'static str> {
+        self.rule.as_deref().and_then(rule_info).map(|rule| rule.code)
+    }
+
+    /// Whether the finding was reported by the check with the code or name
+    /// `rule`.
+    pub fn is_rule(&self, rule: &str) -> bool {
+        self.rule.as_deref() == Some(rule) || self.code().is_some_and(|code| code.eq_ignore_ascii_case(rule))
+    }
+
+    pub fn with_suggestions(mut self, suggestions: Vec<String>) -> Self {
+        self.suggestions = suggestions;
+        self
+    }
+
+    pub fn with_fix(mut self, fix: Fix) -> Self {
+        self.fix = Some(fix);
+        self
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ValidationSummary {
     pub total_parameters: usize,
     pub valid_count: usize,
+    pub info_count: usize,
     pub warning_count: usize,
     pub error_count: usize,
     pub unknown_count: usize,
-    pub details: Vec<(String, ValidationResult)>,
+    pub details: Vec<Finding>,
+    /// How findings are reclassified as they are added.
+    pub policy: SeverityPolicy,
+    /// Which findings are left out as they are added.
+    pub suppressions: Suppressions,
+    /// The findings left out by a suppression, which are not counted.
+    pub suppressed: Vec<Finding>,
+}
+
+impl ValidationSummary {
+    pub fn new() -> Self {
+        Self::with_policy(SeverityPolicy::default())
+    }
+
+    /// A summary that reclassifies the findings added to it by `policy`.
+    pub fn with_policy(policy: SeverityPolicy) -> Self {
+        Self {
+            total_parameters: 0,
+            valid_count: 0,
+            info_count: 0,
+            warning_count: 0,
+            error_count: 0,
+            unknown_count: 0,
+            details: Vec::new(),
+            policy,
+            suppressions: Suppressions::default(),
+            suppressed: Vec::new(),
+        }
+    }
+
+    /// Leave the findings `suppressions` match out of the summary.
+    pub fn with_suppressions(mut self, suppressions: Suppressions) -> Self {
+        self.suppressions = suppressions;
+        self
+    }
+
+    /// Record the validation result for one parameter and update the counts.
+    pub fn record(&mut self, name: &str, result: ValidationResult) {
+        self.record_at(name, result, None);
+    }
+
+    pub fn record_at(&mut self, name: &str, result: ValidationResult, span: Option<Span>) {
+        self.total_parameters += 1;
+        self.add_finding_at(name, result, span);
+    }
+
+    /// Record an additional finding about a parameter that has already been
+    /// counted, such as a problem spanning several occurrences.
+    pub fn add_finding(&mut self, name: &str, result: ValidationResult) {
+        self.add_finding_at(name, result, None);
+    }
+
+    pub fn add_finding_at(&mut self, name: &str, result: ValidationResult, span: Option<Span>) {
+        self.push(Finding::new(name, result, span));
+    }
+
+    /// The findings reported by the check with the code or name `rule`.
+    pub fn findings_for_rule<'a>(&'a self, rule: &'a str) -> impl Iterator<Item = &'a Finding> + 'a {
+        self.details.iter().filter(move |finding| finding.is_rule(rule))
+    }
+
+    /// The number of findings other than valid results reported by each
+    /// check, by rule name.
+    pub fn rule_counts(&self) -> BTreeMap<&str, usize> {
+        let mut counts = BTreeMap::new();
+        for rule in self.details.iter().filter(|finding| finding.result != ValidationResult::Valid).filter_map(|finding| finding.rule.as_deref()) {
+            *counts.entry(rule).or_default() += 1;
+        }
+        counts
+    }
+
+    /// Record the finding for one parameter, like [`record_at`](Self::record_at),
+    /// keeping its category.
+    pub fn record_finding(&mut self, finding: Finding) {
+        self.total_parameters += 1;
+        self.push(finding);
+    }
+
+    /// Add a finding, reclassified by the summary's policy, and update the
+    /// counts. Findings the policy ignores are dropped, and those a
+    /// suppression matches set aside.
+    pub fn push(&mut self, finding: Finding) {
+        if self.suppressions.matching(&finding).is_some() {
+            self.suppressed.push(finding);
+            return;
+        }
+        let Some(result) = self.policy.apply(finding.category, finding.result) else {
+            return;
+        };
+        match result {
+            ValidationResult::Valid => self.valid_count += 1,
+            ValidationResult::Info(_) => self.info_count += 1,
+            ValidationResult::Warning(_) => self.warning_count += 1,
+            ValidationResult::Error(_) => self.error_count += 1,
+            ValidationResult::Unknown(_) => self.unknown_count += 1,
+        }
+        self.details.push(Finding { result, ..finding });
+    }
+
+    /// Report the warnings and errors whose spans lie within `span` as
+    /// informational, noting `reason`, e.g. for a parameter that has no
+    /// effect.
+    pub fn downgrade_within(&mut self, span: &Span, reason: &str) {
+        for finding in &mut self.details {
+            let within = finding.span.as_ref().is_some_and(|inner| {
+                inner.source == span.source && inner.start >= span.start && inner.end <= span.end
+            });
+            if !within || finding.category == FindingCategory::Inapplicable {
+                continue;
+            }
+            if let ValidationResult::Warning(message) | ValidationResult::Error(message) = &finding.result {
+                finding.result = ValidationResult::Info(format!("{} ({})", message, reason));
+            }
+        }
+        self.recount();
+    }
+
+    fn recount(&mut self) {
+        (self.valid_count, self.info_count, self.warning_count, self.error_count, self.unknown_count) = (0, 0, 0, 0, 0);
+        for finding in &self.details {
+            match finding.result {
+                ValidationResult::Valid => self.valid_count += 1,
+                ValidationResult::Info(_) => self.info_count += 1,
+                ValidationResult::Warning(_) => self.warning_count += 1,
+                ValidationResult::Error(_) => self.error_count += 1,
+                ValidationResult::Unknown(_) => self.unknown_count += 1,
+            }
+        }
+    }
+
+    /// The names of the parameters that were not recognised, in command
+    /// line order, kept apart from those whose values failed validation.
+    pub fn unknown_parameters(&self) -> Vec<&str> {
+        self.details.iter()
+            .filter(|finding| finding.category == FindingCategory::Unknown)
+            .map(|finding| finding.parameter.as_str())
+            .collect()
+    }
+
+    /// Whether no parameter failed validation. Warnings and unknown
+    /// parameters do not count as failures.
+    pub fn is_valid(&self) -> bool {
+        self.error_count == 0
+    }
+
+    /// The exit code for a command line tool to report the summary with:
+    /// 0 when no finding is an error, 1 otherwise.
+    pub fn exit_code(&self) -> i32 {
+        if self.is_valid() { 0 } else { 1 }
+    }
+
+    /// The summary as a report with stable field names, for tools that
+    /// consume validation results rather than display them.
+    pub fn report(&self) -> ValidationReport {
+        ValidationReport::new(self)
+    }
+
+    /// The [`report`](Self::report) as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String, ReportError> {
+        self.report().to_json()
+    }
+}
+
+impl Default for ValidationSummary {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 pub struct StandardValidatorRegistry {
@@ -65,8 +351,16 @@ impl StandardValidatorRegistry {
         // Only truly unique validators that can't be handled by common ones
         processor_specific.insert("cpu_list".to_string(), Box::new(kernel::CpuListValidator) as Box<dyn ParameterValidator>);
         processor_specific.insert("memory_range".to_string(), Box::new(kernel::MemoryRangeValidator));
+        processor_specific.insert("module_param".to_string(), Box::new(kernel::ModuleParameterValidator));
+        processor_specific.insert("io_range".to_string(), Box::new(kernel::IoRangeValidator));
         processor_specific.insert("pci_device".to_string(), Box::new(hardware::PciDeviceSpecValidator));
+        processor_specific.insert("usb_device".to_string(), Box::new(hardware::UsbDeviceSpecValidator));
+        processor_specific.insert("dmi".to_string(), Box::new(hardware::DmiSpecValidator));
+        processor_specific.insert("systemd_unit".to_string(), Box::new(systemd::SystemdUnitValidator));
         processor_specific.insert("dracut_luks_name".to_string(), Box::new(dracut::DracutLuksNameValidator));
+        processor_specific.insert("dracut_lvm_lv".to_string(), Box::new(dracut::DracutLvmLvValidator));
+        processor_specific.insert("dracut_break".to_string(), Box::new(dracut::DracutBreakValidator));
+        processor_specific.insert("dracut_network".to_string(), Box::new(dracut::DracutNetworkValidator));
 
         Self {
             common_validators: common,
@@ -84,12 +378,35 @@ impl ValidatorRegistry for StandardValidatorRegistry {
         // Fall back to common validators
         self.common_validators.get(name).map(|v| v.clone_boxed())
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registers_every_validator_by_type() {
+        let registry = StandardValidatorRegistry::new();
+        let cases = [
+            ("io_range", "0x3f8,8"),
+            ("usb_device", "usb:046d:c52b"),
+            ("dmi", "dmi:vendor:LENOVO"),
+            ("systemd_unit", "rescue.target"),
+            ("dracut_lvm_lv", "vg0/root"),
+            ("dracut_break", "pre-mount"),
+        ];
+        for (validator_type, value) in cases {
+            let validator = registry.get_validator(&ParameterProcessor::Kernel, validator_type).unwrap();
+            assert_eq!(validator.validate(value, &HashMap::new()), ValidationResult::Valid, "{}", validator_type);
+        }
+    }
 
-    fn register_validator(&mut self, name: String, validator: Box<dyn ParameterValidator>) -> Result<(), RegistryError> {
-        //if let Some(validator) = self.processor_validators.get::Box<dyn ParameterValidator>(name.as_ref()) {
-        //    return Err(RegistryError::NameError(name));
-        //}
-        self.processor_validators.insert(name.to_string(), validator);
-        Ok(())
+    #[test]
+    fn rejects_cpu_lists_past_the_largest_nr_cpus() {
+        let validator = kernel::CpuListValidator;
+        let config = HashMap::new();
+        assert_eq!(validator.validate("0-8191", &config), ValidationResult::Valid);
+        assert!(matches!(validator.validate("0-4000000000", &config), ValidationResult::Error(_)));
+        assert!(matches!(validator.validate("99999999999", &config), ValidationResult::Error(_)));
     }
 }
@@ -1,10 +1,141 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Debug, Clone)]
 pub struct KernelVersion {
     pub version: String,
     pub branch: Option<String>,
 }
 
+impl KernelVersion {
+    /// Parse a kernel release such as "6.5", "v5.10.12" or "6.8-rc3".
+    /// Anything after the release and release candidate, such as a
+    /// distribution suffix in "5.14.0-284.el9", is ignored for ordering.
+    pub fn parse(version: &str) -> Option<Self> {
+        let version = version.trim().trim_start_matches('v');
+        Self::sort_key_of(version)?;
+        Some(Self {
+            version: version.to_string(),
+            branch: None,
+        })
+    }
+
+    /// Parse a kernel release as printed by `uname -r`, such as
+    /// "5.14.0-362.el9.x86_64" or "6.8.0-45-generic", recording the
+    /// distribution suffix after the first dash as the branch.
+    pub fn from_release(release: &str) -> Option<Self> {
+        let mut version = Self::parse(release)?;
+        version.branch = version.version.split_once('-')
+            .map(|(_, suffix)| suffix)
+            .filter(|suffix| !suffix.starts_with("rc"))
+            .map(str::to_string);
+        Some(version)
+    }
+
+    /// Release numbers with trailing zeros removed, so that "6.5" and
+    /// "6.5.0" are equal, and the release candidate number, if any.
+    fn sort_key_of(version: &str) -> Option<(Vec<u64>, Option<u64>)> {
+        let (release, suffix) = match version.split_once('-') {
+            Some((release, suffix)) => (release, Some(suffix)),
+            None => (version, None),
+        };
+
+        let mut numbers = release.split('.')
+            .map(|part| part.parse::<u64>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        while numbers.last() == Some(&0) {
+            numbers.pop();
+        }
+
+        let rc = suffix
+            .and_then(|suffix| suffix.strip_prefix("rc"))
+            .and_then(|rc| rc.split(|c: char| !c.is_ascii_digit()).next())
+            .and_then(|rc| rc.parse::<u64>().ok());
+        Some((numbers, rc))
+    }
+
+    fn sort_key(&self) -> (Vec<u64>, Option<u64>) {
+        Self::sort_key_of(&self.version).unwrap_or_default()
+    }
+}
+
+impl PartialEq for KernelVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.sort_key() == other.sort_key()
+    }
+}
+
+impl Eq for KernelVersion {}
+
+impl PartialOrd for KernelVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for KernelVersion {
+    /// Release candidates sort before the release they lead up to.
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (numbers, rc) = self.sort_key();
+        let (other_numbers, other_rc) = other.sort_key();
+        numbers.cmp(&other_numbers).then_with(|| match (rc, other_rc) {
+            (Some(rc), Some(other_rc)) => rc.cmp(&other_rc),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        })
+    }
+}
+
+impl Serialize for KernelVersion {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.version)
+    }
+}
+
+impl<'de> Deserialize<'de> for KernelVersion {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let version = String::deserialize(deserializer)?;
+        Self::parse(&version)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid kernel version '{}'", version)))
+    }
+}
+
+impl std::fmt::Display for KernelVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.version)
+    }
+}
+
+/// The version of a userspace component that consumes parameters, such as
+/// systemd "252" or dracut "057".
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ComponentVersion {
     pub name: String,
     pub version: String,
 }
 
+/// Compare two dotted version strings such as "6.5.0", "252" or "059".
+/// Components are compared numerically where both are numbers and as text
+/// otherwise; a version with extra components sorts after its prefix.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let split = |v: &str| -> Vec<String> {
+        v.split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|part| !part.is_empty())
+            .map(|part| part.to_string())
+            .collect()
+    };
+    let (a, b) = (split(a), split(b));
+
+    for (x, y) in a.iter().zip(&b) {
+        let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            _ => x.cmp(y),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    a.len().cmp(&b.len())
+}
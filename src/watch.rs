@@ -0,0 +1,156 @@
+//! Reloading a catalog at runtime when the definitions in its filesystem
+//! sources and overlay directories change, using inotify.
+
+use std::ffi::CString;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::os::fd::{AsRawFd, FromRawFd};
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::catalog::ParameterCatalog;
+use crate::database::DatabaseLoader;
+use crate::error::DatabaseError;
+
+/// Events that change the definitions under a watched directory. Writes are
+/// seen on close so that a file is not read while it is being written.
+const WATCH_MASK: u32 = libc::IN_CREATE
+    | libc::IN_DELETE
+    | libc::IN_CLOSE_WRITE
+    | libc::IN_MOVED_FROM
+    | libc::IN_MOVED_TO
+    | libc::IN_DELETE_SELF
+    | libc::IN_MOVE_SELF;
+
+/// A catalog that follows changes to the directories its loader reads.
+///
+/// inotify watches are not recursive, so every directory below the loader's
+/// [`watch_paths`](DatabaseLoader::watch_paths) is watched, and directories
+/// created later are picked up on the next reload. Directories that do not
+/// exist when the watcher is created are not watched.
+pub struct CatalogWatcher {
+    loader: DatabaseLoader,
+    catalog: ParameterCatalog,
+    inotify: File,
+}
+
+impl CatalogWatcher {
+    /// Load a catalog from `loader` and start watching its sources.
+    pub fn new(loader: DatabaseLoader) -> Result<Self, DatabaseError> {
+        // SAFETY: inotify_init1 takes no pointers; a non-negative result is
+        // a new descriptor that the File takes ownership of.
+        let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        let inotify = unsafe { File::from_raw_fd(fd) };
+
+        let mut loader = loader;
+        let catalog = ParameterCatalog::from(loader.load()?.clone());
+        let watcher = Self { loader, catalog, inotify };
+        watcher.add_watches()?;
+        Ok(watcher)
+    }
+
+    pub fn catalog(&self) -> &ParameterCatalog {
+        &self.catalog
+    }
+
+    pub fn into_catalog(self) -> ParameterCatalog {
+        self.catalog
+    }
+
+    /// Reload the catalog if anything changed since the last check, without
+    /// blocking. Returns whether the catalog was reloaded.
+    ///
+    /// If the changed definitions fail to load, e.g. a file that does not
+    /// parse, the error is returned and the previous catalog is kept.
+    pub fn check(&mut self) -> Result<bool, DatabaseError> {
+        if !self.drain_events()? {
+            return Ok(false);
+        }
+        self.reload()?;
+        Ok(true)
+    }
+
+    /// Wait up to `timeout`, or indefinitely, for a change and reload the
+    /// catalog. Returns whether the catalog was reloaded.
+    pub fn wait(&mut self, timeout: Option<Duration>) -> Result<bool, DatabaseError> {
+        let timeout_ms = timeout
+            .map(|timeout| timeout.as_millis().min(libc::c_int::MAX as u128) as libc::c_int)
+            .unwrap_or(-1);
+        let mut pollfd = libc::pollfd {
+            fd: self.inotify.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+
+        // SAFETY: pollfd is a valid array of one element for the call.
+        let ready = unsafe { libc::poll(&mut pollfd, 1, timeout_ms) };
+        if ready < 0 {
+            let error = io::Error::last_os_error();
+            if error.kind() == io::ErrorKind::Interrupted {
+                return Ok(false);
+            }
+            return Err(error.into());
+        }
+        self.check()
+    }
+
+    /// Reload the changed definitions; the loader keeps the unchanged ones.
+    fn reload(&mut self) -> Result<(), DatabaseError> {
+        self.catalog = ParameterCatalog::from(self.loader.reload()?.clone());
+        self.add_watches()
+    }
+
+    /// Read all pending events, returning whether there were any.
+    fn drain_events(&mut self) -> Result<bool, DatabaseError> {
+        let mut buffer = [0u8; 4096];
+        let mut changed = false;
+        loop {
+            match self.inotify.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(_) => changed = true,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Watch every directory below the loader's watch paths. Watching a
+    /// directory again keeps its existing watch.
+    fn add_watches(&self) -> Result<(), DatabaseError> {
+        for path in self.loader.watch_paths() {
+            if path.is_dir() {
+                self.watch_tree(&path)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn watch_tree(&self, dir: &Path) -> Result<(), DatabaseError> {
+        let path = CString::new(dir.as_os_str().as_bytes())
+            .map_err(|_| DatabaseError::LoadError(format!("Invalid path: {:?}", dir)))?;
+        // SAFETY: path is a NUL-terminated string that outlives the call.
+        let wd = unsafe { libc::inotify_add_watch(self.inotify.as_raw_fd(), path.as_ptr(), WATCH_MASK) };
+        if wd < 0 {
+            let error = io::Error::last_os_error();
+            // Removed between listing and watching; its parent saw it go
+            if error.kind() == io::ErrorKind::NotFound {
+                return Ok(());
+            }
+            return Err(DatabaseError::LoadError(format!("Failed to watch {:?}: {}", dir, error)));
+        }
+
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                self.watch_tree(&entry.path())?;
+            }
+        }
+        Ok(())
+    }
+}
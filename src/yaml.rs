@@ -0,0 +1,99 @@
+//! Reading of parameter definition files written in YAML, producing the same
+//! `serde_json::Value` that JSON definitions parse to. Plain scalars are
+//! typed by the YAML 1.2 core schema, so `yes` and `on` stay strings.
+
+use std::fmt;
+
+use serde_json::Value;
+
+/// A syntax error, at a 1-based line and column when known.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct YamlError {
+    pub(crate) line: Option<usize>,
+    pub(crate) column: Option<usize>,
+    pub(crate) message: String,
+}
+
+impl fmt::Display for YamlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "line {} column {}: {}", line, column, self.message),
+            (Some(line), None) => write!(f, "line {}: {}", line, self.message),
+            _ => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Parse a YAML document, reporting errors with their position.
+#[cfg(feature = "yaml")]
+pub(crate) fn parse_yaml(text: &str) -> Result<Value, YamlError> {
+    serde_yaml_ng::from_str(text).map_err(|e| {
+        let location = e.location();
+        // The position is reported separately, so drop it from the message
+        let mut message = e.to_string();
+        if let Some(location) = &location {
+            let position = format!(" at line {} column {}", location.line(), location.column());
+            message = message.replacen(&position, "", 1);
+        }
+        YamlError {
+            line: location.as_ref().map(|location| location.line()),
+            column: location.as_ref().map(|location| location.column()),
+            message,
+        }
+    })
+}
+
+/// YAML documents cannot be read without the `yaml` feature.
+#[cfg(not(feature = "yaml"))]
+pub(crate) fn parse_yaml(_text: &str) -> Result<Value, YamlError> {
+    Err(YamlError {
+        line: None,
+        column: None,
+        message: "YAML documents need libkcmdline's yaml feature".to_string(),
+    })
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn block_and_flow_collections() {
+        let value = parse_yaml("name: quiet\nselectors:\n  - arch:x86_64\n  - arch:aarch64\nsyntax: {type: enum, choices: [on, off]}\n").unwrap();
+        assert_eq!(value, json!({
+            "name": "quiet",
+            "selectors": ["arch:x86_64", "arch:aarch64"],
+            "syntax": {"type": "enum", "choices": ["on", "off"]},
+        }));
+    }
+
+    #[test]
+    fn scalars_follow_the_core_schema() {
+        let value = parse_yaml("a: yes\nb: true\nc: 42\nd: 5.10\ne: '5.10'\nf: ~\ng: \"tab\\there\"\nh: 'it''s'\n").unwrap();
+        assert_eq!(value, json!({
+            "a": "yes", "b": true, "c": 42, "d": 5.1, "e": "5.10", "f": null, "g": "tab\there", "h": "it's",
+        }));
+    }
+
+    #[test]
+    fn block_scalars() {
+        let value = parse_yaml("literal: |\n  one\n  two\nfolded: >-\n  one\n  two\n").unwrap();
+        assert_eq!(value, json!({"literal": "one\ntwo\n", "folded": "one two"}));
+    }
+
+    #[test]
+    fn reports_error_position() {
+        let error = parse_yaml("name: quiet\nselectors: [a, b\nprocessor: kernel\n").unwrap_err();
+        assert!(error.line.is_some_and(|line| line >= 2), "{}", error);
+        assert!(!error.message.starts_with("did not find expected ',' or ']' at"), "{}", error);
+
+        let error = parse_yaml("name: quiet\n  bad: indent\n").unwrap_err();
+        assert_eq!(error.line, Some(2), "{}", error);
+    }
+
+    #[test]
+    fn empty_document_is_null() {
+        assert_eq!(parse_yaml("").unwrap(), Value::Null);
+    }
+}
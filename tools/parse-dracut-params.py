@@ -0,0 +1,252 @@
+#!/usr/bin/python3
+from tomlkit import array, comment, document, nl, parse as tkparse, string as tkstring, table
+from dataclasses import dataclass, field
+from argparse import ArgumentParser
+from os import makedirs, fdatasync
+from typing import Dict, List, Optional
+from os.path import basename
+from pathlib import Path
+import logging
+import re
+import sys
+
+log = logging.getLogger("__name__")
+
+_log_debug = log.debug
+_log_info = log.info
+_log_warn = log.warning
+
+formatter = logging.Formatter("%(levelname)s - %(message)s")
+console_handler = logging.StreamHandler()
+console_handler.setLevel(logging.INFO)
+console_handler.setFormatter(formatter)
+log.addHandler(console_handler)
+
+_DB_TOP_DIR = Path("parameters/dracut")
+
+# Selector naming the dracut module that reads a parameter; matches
+# DRACUT_MODULE_SELECTOR_PREFIX in src/parameter.rs.
+_MODULE_SELECTOR = "dracut_module:"
+
+# A parameter entry in dracut.cmdline.7.asc, e.g.
+#   **rd.luks.uuid=**__<luks uuid>__::
+#   **rd.luks=0**::
+_ENTRY_RE = re.compile(r"^\*\*([A-Za-z0-9_.\-]+)(=?)([^*]*)\*\*(.*)::\s*$")
+
+# Parameter names as they appear in getarg calls of module scripts
+_RD_NAME_RE = re.compile(r"\brd\.[A-Za-z0-9_.\-]*[A-Za-z0-9_]")
+
+
+@dataclass
+class Param:
+    name: str = "INVALID"
+    desc: str = ""
+    fmt: str = ""
+    modules: List[str] = field(default_factory=list)
+
+
+def strip_markup(text: str) -> str:
+    text = re.sub(r"\*\*|__|`", "", text)
+    # _emphasis_, leaving underscores within names alone
+    return re.sub(r"(?<!\w)_(\S[^_]*?)_(?!\w)", r"\1", text).strip()
+
+
+def process_man_page(man_path: Path) -> Dict[str, Param]:
+    _log_info("Proccessing path: %s", man_path)
+    params = {}
+    current: List[Param] = []
+    in_desc = False
+    continued = False
+
+    for line in man_path.read_text(encoding="utf8").splitlines():
+        match = _ENTRY_RE.match(line)
+        if match:
+            # Consecutive entries share the description that follows them
+            if in_desc:
+                current = []
+                in_desc = False
+            name, eq, value, fmt = match.groups()
+            fmt = strip_markup(value + fmt)
+            if eq and not fmt:
+                fmt = "<value>"
+            param = params.setdefault(name, Param(name=name, fmt=fmt))
+            current.append(param)
+            _log_debug("New PARAMETER: %s, fmt=%s", name, fmt)
+            continue
+
+        if not current:
+            continue
+        if line.strip() == "+":
+            # List continuation: the next paragraph need not be indented
+            continued = True
+            for param in current:
+                param.desc += "\n\n"
+        elif not line.strip():
+            continued = False
+        elif line.startswith((" ", "\t")) or continued:
+            in_desc = True
+            for param in current:
+                param.desc += ("" if param.desc.endswith("\n") or not param.desc else " ") + strip_markup(line)
+        else:
+            current = []
+            in_desc = False
+
+    for param in params.values():
+        param.desc = param.desc.strip()
+    return params
+
+
+def module_name(module_dir: Path) -> str:
+    # modules.d/90crypt is the "crypt" module
+    return re.sub(r"^\d+", "", module_dir.name)
+
+
+def process_source_tree(tree: Path, params: Dict[str, Param]):
+    modules_dir = tree / "modules.d"
+    _log_info("Scanning modules: %s", modules_dir)
+    names = set(params)
+
+    for module_dir in sorted(p for p in modules_dir.iterdir() if p.is_dir()):
+        module = module_name(module_dir)
+        for script in sorted(module_dir.glob("*.sh")):
+            for line in script.read_text(encoding="utf8", errors="replace").splitlines():
+                if "getarg" not in line:
+                    continue
+                found = set(_RD_NAME_RE.findall(line))
+                found |= {n for n in names if re.search(rf"(?<![\w.]){re.escape(n)}(?![\w.])", line)}
+                for name in found:
+                    if name not in params:
+                        _log_debug("Undocumented PARAMETER %s in %s", name, script)
+                        params[name] = Param(name=name)
+                    if module not in params[name].modules:
+                        params[name].modules.append(module)
+
+
+def write_definition(param: Param, param_dir: Path):
+    param_file = param_dir / "definition.toml"
+    selectors = [_MODULE_SELECTOR + module for module in sorted(param.modules)]
+
+    if param_file.exists():
+        # Update what the documentation owns; keep hand-written syntax
+        doc = tkparse(param_file.read_text(encoding="utf8"))
+        if param.desc:
+            doc["description"] = tkstring(param.desc, literal=True, multiline=True)
+        kept = [s for s in doc.get("selectors", []) if not s.startswith(_MODULE_SELECTOR)]
+        if kept or selectors:
+            doc["selectors"] = array(kept + selectors)
+        _log_debug("Updating parameter '%s'", param.name)
+    else:
+        doc = document()
+        doc.add(comment("This is a libKCmdline definition document."))
+        doc.add(nl())
+        doc.add("title", f"{param.name} - definition.toml")
+        doc.add("name", param.name)
+        doc.add("processor", "dracut")
+        doc.add("description", tkstring(param.desc, literal=True, multiline=True))
+        if selectors:
+            doc.add("selectors", selectors)
+        syntax = table()
+        syntax.add("type", "")
+        syntax.add("format", param.fmt)
+        syntax.add("allow_empty", not param.fmt)
+        doc.add("syntax", syntax)
+        _log_debug("Writing parameter '%s'", param.name)
+
+    with open(param_file, "w", encoding="utf8") as fd:
+        fd.write(doc.as_string())
+        fd.flush()
+        fdatasync(fd.fileno())
+
+
+def write_dracut_parameters(params: Dict[str, Param], db_dir: Path):
+    kernel_dir = db_dir.parent / "kernel"
+    for param in params.values():
+        # Kernel parameters dracut also reads (root, ip, ...) belong to the
+        # kernel/ database; a second definition would shadow them.
+        if (kernel_dir / param.name / "definition.toml").exists():
+            _log_info("Skipping kernel parameter '%s'", param.name)
+            continue
+        param_dir = db_dir / param.name
+        makedirs(param_dir, exist_ok=True)
+        write_definition(param, param_dir)
+
+
+def dump_dracut_parameters(params: Dict[str, Param]):
+    for param in params.values():
+        print(f"   Name: {param.name}")
+        print(f" Format: {param.fmt}")
+        print(f"Modules: {', '.join(param.modules)}")
+        print(f"   Desc: {param.desc}\n")
+
+
+def main() -> int:
+    parser = ArgumentParser(
+        basename(sys.argv[0]), description="Import dracut/ database entries"
+    )
+    parser.add_argument(
+        "manpage",
+        metavar="MANPAGE",
+        type=Path,
+        help="Path to dracut's man/dracut.cmdline.7.asc",
+    )
+    parser.add_argument(
+        "outdir",
+        metavar="OUTDIR",
+        type=Path,
+        nargs="?",
+        help="Output directory",
+        default="database/",
+    )
+    parser.add_argument(
+        "--source",
+        "-s",
+        metavar="TREE",
+        type=Path,
+        help="dracut source tree to attribute parameters to modules",
+    )
+    parser.add_argument(
+        "--dump-parameters",
+        "--dump",
+        action="store_true",
+        help="Dump parameter definitions to stdout",
+    )
+    parser.add_argument(
+        "--dry-run",
+        "-n",
+        action="store_true",
+        help="Parse the documentation without writing definitions",
+    )
+    parser.add_argument(
+        "--verbose",
+        "-v",
+        action="store_true",
+        help="Enable debug logging",
+    )
+    args = parser.parse_args()
+
+    man_path = args.manpage.expanduser()
+    db_dir = args.outdir.expanduser() / _DB_TOP_DIR
+    source: Optional[Path] = args.source.expanduser() if args.source else None
+
+    assert man_path.exists()
+    assert source is None or (source / "modules.d").is_dir()
+
+    if args.verbose:
+        log.setLevel(logging.DEBUG)
+        console_handler.setLevel(logging.DEBUG)
+
+    params = process_man_page(man_path)
+    if source:
+        process_source_tree(source, params)
+    _log_info("Found %d parameters", len(params))
+    if args.dump_parameters:
+        dump_dracut_parameters(params)
+
+    if not args.dry_run:
+        makedirs(db_dir, exist_ok=True)
+        write_dracut_parameters(params, db_dir)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,264 @@
+#!/usr/bin/python3
+from tomlkit import comment, document, nl, parse as tkparse, string as tkstring, table
+from dataclasses import dataclass
+from argparse import ArgumentParser
+from os import makedirs, fdatasync
+from typing import Dict, Optional
+from os.path import basename
+from pathlib import Path
+import xml.etree.ElementTree as ET
+import logging
+import re
+import sys
+
+log = logging.getLogger("__name__")
+
+_log_debug = log.debug
+_log_info = log.info
+_log_warn = log.warning
+
+formatter = logging.Formatter("%(levelname)s - %(message)s")
+console_handler = logging.StreamHandler()
+console_handler.setLevel(logging.INFO)
+console_handler.setFormatter(formatter)
+log.addHandler(console_handler)
+
+_DB_TOP_DIR = Path("parameters/systemd")
+
+_XINCLUDE = "{http://www.w3.org/2001/XInclude}include"
+
+# Entities predefined by XML; everything else comes from systemd's
+# custom-entities.ent, which is generated at build time.
+_XML_ENTITIES = {"lt", "gt", "amp", "quot", "apos"}
+
+
+@dataclass
+class Param:
+    name: str = "INVALID"
+    desc: str = ""
+    fmt: str = ""
+    min_version: Optional[int] = None
+
+
+def strip_entities(text: str) -> str:
+    """Replace custom entity references with their name so that the
+    document can be parsed without systemd's generated entity file."""
+    def replace(match):
+        name = match.group(1)
+        return match.group(0) if name in _XML_ENTITIES else name
+    text = re.sub(r"<!DOCTYPE[^\[>]*(\[.*?\])?\s*>", "", text, flags=re.S)
+    return re.sub(r"&([A-Za-z_][A-Za-z0-9_.-]*);", replace, text)
+
+
+def element_text(elem: ET.Element) -> str:
+    return " ".join("".join(elem.itertext()).split())
+
+
+def describe(listitem: ET.Element) -> str:
+    paragraphs = []
+    for para in listitem.iter("para"):
+        text = element_text(para)
+        if text:
+            paragraphs.append(text)
+    return "\n\n".join(paragraphs)
+
+
+def min_version(listitem: ET.Element) -> Optional[int]:
+    versions = []
+    for include in listitem.iter(_XINCLUDE):
+        if include.get("href") != "version-info.xml":
+            continue
+        match = re.fullmatch(r"v(\d+)", include.get("xpointer", ""))
+        if match:
+            versions.append(int(match.group(1)))
+    return min(versions) if versions else None
+
+
+def parse_term(term: ET.Element) -> Optional[Param]:
+    varname = term.find("varname")
+    if varname is None:
+        return None
+    text = element_text(varname)
+    name, eq, fmt = text.partition("=")
+    if not name:
+        return None
+    fmt = fmt.strip()
+    if eq and not fmt:
+        fmt = "<value>"
+    return Param(name=name, fmt=fmt)
+
+
+def process_systemd_parameters(xml_path: Path) -> Dict[str, Param]:
+    _log_info("Proccessing path: %s", xml_path)
+    root = ET.fromstring(strip_entities(xml_path.read_text(encoding="utf8")))
+    params = {}
+
+    for entry in root.iter("varlistentry"):
+        listitem = entry.find("listitem")
+        if listitem is None:
+            continue
+        desc = describe(listitem)
+        version = min_version(listitem)
+
+        for term in entry.findall("term"):
+            param = parse_term(term)
+            if param is None:
+                continue
+            param.desc = desc
+            param.min_version = version
+            if param.name in params:
+                _log_debug("Merging duplicate entry for %s", param.name)
+                existing = params[param.name]
+                existing.fmt = existing.fmt or param.fmt
+                versions = [v for v in (existing.min_version, version) if v is not None]
+                existing.min_version = min(versions) if versions else None
+                continue
+            _log_debug(
+                "New PARAMETER: %s, fmt=%s, min_version=%s",
+                param.name,
+                param.fmt,
+                param.min_version,
+            )
+            params[param.name] = param
+
+    return params
+
+
+def parameter_dir(name: str) -> str:
+    # systemd.log_level lives in systemd/log_level; rd.* names are kept whole
+    return name.removeprefix("systemd.")
+
+
+def write_definition(param: Param, param_dir: Path):
+    param_file = param_dir / "definition.toml"
+
+    if param_file.exists():
+        # Update what the documentation owns; keep hand-written syntax
+        doc = tkparse(param_file.read_text(encoding="utf8"))
+        doc["description"] = tkstring(param.desc, literal=True, multiline=True)
+        _log_debug("Updating parameter '%s'", param.name)
+    else:
+        doc = document()
+        doc.add(comment("This is a libKCmdline definition document."))
+        doc.add(nl())
+        doc.add("title", f"{param.name} - definition.toml")
+        doc.add("name", param.name)
+        doc.add("processor", "systemd")
+        doc.add("description", tkstring(param.desc, literal=True, multiline=True))
+        syntax = table()
+        syntax.add("type", "")
+        syntax.add("format", param.fmt)
+        syntax.add("allow_empty", not param.fmt)
+        doc.add("syntax", syntax)
+        _log_debug("Writing parameter '%s'", param.name)
+
+    with open(param_file, "w", encoding="utf8") as fd:
+        fd.write(doc.as_string())
+        fd.flush()
+        fdatasync(fd.fileno())
+
+
+def write_versions(param: Param, param_dir: Path):
+    if param.min_version is None:
+        return
+    versions_file = param_dir / "versions.toml"
+    if versions_file.exists():
+        doc = tkparse(versions_file.read_text(encoding="utf8"))
+    else:
+        doc = document()
+        doc.add(comment("This is a libKCmdline versions document."))
+        doc.add(nl())
+    components = doc.setdefault("components", table())
+    component = table()
+    component.add("name", "systemd")
+    component.add("version", str(param.min_version))
+    components["systemd"] = component
+
+    with open(versions_file, "w", encoding="utf8") as fd:
+        fd.write(doc.as_string())
+        fd.flush()
+        fdatasync(fd.fileno())
+
+
+def write_systemd_parameters(params: Dict[str, Param], db_dir: Path):
+    kernel_dir = db_dir.parent / "kernel"
+    for param in params.values():
+        # Kernel parameters systemd also reads (quiet, debug, ...) belong to
+        # the kernel/ database; a second definition would shadow them.
+        if (kernel_dir / param.name / "definition.toml").exists():
+            _log_info("Skipping kernel parameter '%s'", param.name)
+            continue
+        param_dir = db_dir / parameter_dir(param.name)
+        makedirs(param_dir, exist_ok=True)
+        write_definition(param, param_dir)
+        write_versions(param, param_dir)
+
+
+def dump_systemd_parameters(params: Dict[str, Param]):
+    for param in params.values():
+        print(f"       Name: {param.name}")
+        print(f"     Format: {param.fmt}")
+        print(f"Min version: {param.min_version or ''}")
+        print(f"       Desc: {param.desc}\n")
+
+
+def main() -> int:
+    parser = ArgumentParser(
+        basename(sys.argv[0]), description="Import systemd/ database entries"
+    )
+    parser.add_argument(
+        "xml",
+        metavar="XML",
+        type=Path,
+        help="Path to systemd's man/kernel-command-line.xml",
+    )
+    parser.add_argument(
+        "outdir",
+        metavar="OUTDIR",
+        type=Path,
+        nargs="?",
+        help="Output directory",
+        default="database/",
+    )
+    parser.add_argument(
+        "--dump-parameters",
+        "--dump",
+        action="store_true",
+        help="Dump parameter definitions to stdout",
+    )
+    parser.add_argument(
+        "--dry-run",
+        "-n",
+        action="store_true",
+        help="Parse the documentation without writing definitions",
+    )
+    parser.add_argument(
+        "--verbose",
+        "-v",
+        action="store_true",
+        help="Enable debug logging",
+    )
+    args = parser.parse_args()
+
+    xml_path = args.xml.expanduser()
+    db_dir = args.outdir.expanduser() / _DB_TOP_DIR
+
+    assert xml_path.exists()
+
+    if args.verbose:
+        log.setLevel(logging.DEBUG)
+        console_handler.setLevel(logging.DEBUG)
+
+    params = process_systemd_parameters(xml_path)
+    _log_info("Found %d parameters", len(params))
+    if args.dump_parameters:
+        dump_systemd_parameters(params)
+
+    if not args.dry_run:
+        makedirs(db_dir, exist_ok=True)
+        write_systemd_parameters(params, db_dir)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())